@@ -8,6 +8,7 @@ use {
         TransactionSimulationDetails, TransactionStatus,
     },
     solana_client::connection_cache::ConnectionCache,
+    solana_program_runtime::instruction_tracer::InstructionTracing,
     solana_runtime::{
         bank::{Bank, TransactionExecutionResult, TransactionSimulationResult},
         bank_forks::BankForks,
@@ -159,32 +160,33 @@ fn verify_transaction(
     Ok(())
 }
 
-fn simulate_transaction(
+fn sanitize_transaction(
     bank: &Bank,
     transaction: VersionedTransaction,
-) -> BanksTransactionResultWithSimulation {
-    let sanitized_transaction = match SanitizedTransaction::try_create(
+) -> transaction::Result<SanitizedTransaction> {
+    SanitizedTransaction::try_create(
         transaction,
         MessageHash::Compute,
         Some(false), // is_simple_vote_tx
         bank,
         true, // require_static_program_ids
-    ) {
-        Err(err) => {
-            return BanksTransactionResultWithSimulation {
-                result: Some(Err(err)),
-                simulation_details: None,
-            };
-        }
-        Ok(tx) => tx,
-    };
+    )
+}
+
+fn banks_transaction_result_with_simulation(
+    simulation_result: TransactionSimulationResult,
+) -> BanksTransactionResultWithSimulation {
     let TransactionSimulationResult {
         result,
         logs,
+        pre_simulation_accounts: _,
         post_simulation_accounts: _,
         units_consumed,
         return_data,
-    } = bank.simulate_transaction_unchecked(sanitized_transaction);
+        inner_instructions: _,
+        instruction_trace: _,
+        written_accounts: _,
+    } = simulation_result;
     let simulation_details = TransactionSimulationDetails {
         logs,
         units_consumed,
@@ -196,6 +198,60 @@ fn simulate_transaction(
     }
 }
 
+fn simulate_transaction(
+    bank: &Bank,
+    transaction: VersionedTransaction,
+) -> BanksTransactionResultWithSimulation {
+    let sanitized_transaction = match sanitize_transaction(bank, transaction) {
+        Err(err) => {
+            return BanksTransactionResultWithSimulation {
+                result: Some(Err(err)),
+                simulation_details: None,
+            };
+        }
+        Ok(tx) => tx,
+    };
+    banks_transaction_result_with_simulation(bank.simulate_transaction_unchecked(
+        sanitized_transaction,
+        false,
+        InstructionTracing::Disabled,
+    ))
+}
+
+/// If a transaction of the bundle fails to sanitize, none are simulated and only that one has a
+/// result
+fn simulate_bundle(
+    bank: &Bank,
+    transactions: Vec<VersionedTransaction>,
+) -> Vec<BanksTransactionResultWithSimulation> {
+    let number_of_transactions = transactions.len();
+    let sanitized_transactions = match transactions
+        .into_iter()
+        .enumerate()
+        .map(|(index, transaction)| {
+            sanitize_transaction(bank, transaction).map_err(|err| (index, err))
+        })
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Err((index, err)) => {
+            let mut results = vec![
+                BanksTransactionResultWithSimulation {
+                    result: None,
+                    simulation_details: None,
+                };
+                number_of_transactions
+            ];
+            results[index].result = Some(Err(err));
+            return results;
+        }
+        Ok(sanitized_transactions) => sanitized_transactions,
+    };
+    bank.simulate_bundle_unchecked(sanitized_transactions, false, InstructionTracing::Disabled)
+        .into_iter()
+        .map(banks_transaction_result_with_simulation)
+        .collect()
+}
+
 #[tarpc::server]
 impl Banks for BanksServer {
     async fn send_transaction_with_context(self, _: Context, transaction: VersionedTransaction) {
@@ -308,6 +364,15 @@ impl Banks for BanksServer {
         simulate_transaction(&self.bank(commitment), transaction)
     }
 
+    async fn simulate_bundle_with_commitment_and_context(
+        self,
+        _: Context,
+        transactions: Vec<VersionedTransaction>,
+        commitment: CommitmentLevel,
+    ) -> Vec<BanksTransactionResultWithSimulation> {
+        simulate_bundle(&self.bank(commitment), transactions)
+    }
+
     async fn process_transaction_with_commitment_and_context(
         self,
         _: Context,
@@ -5,6 +5,7 @@ use {
         bucket_stats::BucketMapStats,
         bucket_storage::{BucketStorage, Uid, DEFAULT_CAPACITY_POW2},
         index_entry::IndexEntry,
+        restart::{RestartableBucket, RestartableStorage},
         MaxSearch, RefCount,
     },
     rand::{thread_rng, Rng},
@@ -13,6 +14,7 @@ use {
     std::{
         collections::hash_map::DefaultHasher,
         hash::{Hash, Hasher},
+        io,
         marker::PhantomData,
         ops::RangeBounds,
         path::PathBuf,
@@ -102,6 +104,62 @@ impl<T: Clone + Copy> Bucket<T> {
         }
     }
 
+    /// reopen the files of a bucket that was persisted by a previous run
+    pub(crate) fn load_on_restart(
+        drives: Arc<Vec<PathBuf>>,
+        max_search: MaxSearch,
+        stats: Arc<BucketMapStats>,
+        count: Arc<AtomicU64>,
+        restart: &RestartableBucket,
+    ) -> Option<Self> {
+        let index = BucketStorage::load_on_restart(
+            &restart.index,
+            1,
+            std::mem::size_of::<IndexEntry>() as u64,
+            max_search,
+            Arc::clone(&stats.index),
+            count,
+        )?;
+        let data = restart
+            .data
+            .iter()
+            .enumerate()
+            .map(|(ix, storage)| {
+                BucketStorage::load_on_restart(
+                    storage,
+                    1 << ix,
+                    Self::elem_size(),
+                    max_search,
+                    Arc::clone(&stats.data),
+                    Arc::default(),
+                )
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            random: restart.random,
+            drives,
+            index,
+            data,
+            _phantom: PhantomData::default(),
+            stats,
+            reallocated: Reallocated::default(),
+        })
+    }
+
+    /// flush all files of this bucket and keep them after the bucket is dropped
+    pub(crate) fn persist(&mut self) -> io::Result<RestartableBucket> {
+        self.handle_delayed_grows();
+        Ok(RestartableBucket {
+            random: self.random,
+            index: self.index.persist()?,
+            data: self
+                .data
+                .iter_mut()
+                .map(BucketStorage::persist)
+                .collect::<io::Result<Vec<RestartableStorage>>>()?,
+        })
+    }
+
     pub fn keys(&self) -> Vec<Pubkey> {
         let mut rv = vec![];
         for i in 0..self.index.capacity() {
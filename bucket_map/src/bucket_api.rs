@@ -1,10 +1,11 @@
 use {
     crate::{
         bucket::Bucket, bucket_item::BucketItem, bucket_map::BucketMapError,
-        bucket_stats::BucketMapStats, MaxSearch, RefCount,
+        bucket_stats::BucketMapStats, restart::RestartableBucket, MaxSearch, RefCount,
     },
     solana_sdk::pubkey::Pubkey,
     std::{
+        io,
         ops::RangeBounds,
         path::PathBuf,
         sync::{
@@ -40,6 +41,52 @@ impl<T: Clone + Copy> BucketApi<T> {
         }
     }
 
+    /// reopen a bucket persisted by a previous run.
+    /// 'restart' is None if the bucket was never written to.
+    pub(crate) fn load_on_restart(
+        drives: Arc<Vec<PathBuf>>,
+        max_search: MaxSearch,
+        stats: Arc<BucketMapStats>,
+        restart: Option<&RestartableBucket>,
+    ) -> Option<Self> {
+        let count = Arc::default();
+        let bucket = match restart {
+            Some(restart) => Some(Bucket::load_on_restart(
+                Arc::clone(&drives),
+                max_search,
+                Arc::clone(&stats),
+                Arc::clone(&count),
+                restart,
+            )?),
+            None => None,
+        };
+        Some(Self {
+            drives,
+            max_search,
+            stats,
+            bucket: RwLock::new(bucket),
+            count,
+        })
+    }
+
+    /// flush the files of this bucket and keep them after it is dropped.
+    /// Returns None if the bucket was never written to.
+    pub(crate) fn persist(&self) -> io::Result<Option<RestartableBucket>> {
+        self.bucket
+            .write()
+            .unwrap()
+            .as_mut()
+            .map(Bucket::persist)
+            .transpose()
+    }
+
+    /// remove all items, deleting the files of this bucket
+    pub(crate) fn clear(&self) {
+        let mut bucket = self.bucket.write().unwrap();
+        *bucket = None;
+        self.count.store(0, Ordering::Relaxed);
+    }
+
     /// Get the items for bucket
     pub fn items_in_range<R>(&self, range: &Option<&R>) -> Vec<BucketItem<T>>
     where
@@ -1,9 +1,22 @@
 //! BucketMap is a mostly contention free concurrent map backed by MmapMut
 
 use {
-    crate::{bucket_api::BucketApi, bucket_stats::BucketMapStats, MaxSearch, RefCount},
+    crate::{
+        bucket_api::BucketApi, bucket_stats::BucketMapStats, index_entry::IndexEntry,
+        restart::Restart, MaxSearch, RefCount,
+    },
+    log::*,
     solana_sdk::pubkey::Pubkey,
-    std::{convert::TryInto, fmt::Debug, fs, path::PathBuf, sync::Arc},
+    std::{
+        convert::TryInto,
+        fmt::Debug,
+        fs, io,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    },
     tempfile::TempDir,
 };
 
@@ -12,6 +25,10 @@ pub struct BucketMapConfig {
     pub max_buckets: usize,
     pub drives: Option<Vec<PathBuf>>,
     pub max_search: Option<MaxSearch>,
+    /// File describing the bucket files kept by `BucketMap::persist`.
+    /// If it exists when the map is created, the files it describes are reopened instead of erasing `drives`.
+    /// Only used when `drives` is specified.
+    pub restart_config_file: Option<PathBuf>,
 }
 
 impl BucketMapConfig {
@@ -31,11 +48,16 @@ pub struct BucketMap<T: Clone + Copy + Debug> {
     max_buckets_pow2: u8,
     pub stats: Arc<BucketMapStats>,
     pub temp_dir: Option<TempDir>,
+    restart_config_file: Option<PathBuf>,
+    /// true if the contents were reopened from the files of a previous run
+    restored: bool,
+    /// true once `persist` has succeeded, so the drives must not be erased on drop
+    persisted: AtomicBool,
 }
 
 impl<T: Clone + Copy + Debug> Drop for BucketMap<T> {
     fn drop(&mut self) {
-        if self.temp_dir.is_none() {
+        if self.temp_dir.is_none() && !self.persisted.load(Ordering::Acquire) {
             BucketMap::<T>::erase_previous_drives(&self.drives);
         }
     }
@@ -68,10 +90,10 @@ impl<T: Clone + Copy + Debug> BucketMap<T> {
         // this should be <= 1 << DEFAULT_CAPACITY or we end up searching the same items over and over - probably not a big deal since it is so small anyway
         const MAX_SEARCH: MaxSearch = 32;
         let max_search = config.max_search.unwrap_or(MAX_SEARCH);
+        let restart_config_file = config
+            .restart_config_file
+            .filter(|_| config.drives.is_some());
 
-        if let Some(drives) = config.drives.as_ref() {
-            Self::erase_previous_drives(drives);
-        }
         let mut temp_dir = None;
         let drives = config.drives.unwrap_or_else(|| {
             temp_dir = Some(TempDir::new().unwrap());
@@ -80,15 +102,32 @@ impl<T: Clone + Copy + Debug> BucketMap<T> {
         let drives = Arc::new(drives);
 
         let stats = Arc::default();
-        let buckets = (0..config.max_buckets)
-            .map(|_| {
-                Arc::new(BucketApi::new(
-                    Arc::clone(&drives),
+        let restored_buckets = restart_config_file
+            .as_ref()
+            .and_then(|restart_config_file| {
+                Self::load_buckets_on_restart(
+                    restart_config_file,
+                    config.max_buckets,
+                    &drives,
                     max_search,
-                    Arc::clone(&stats),
-                ))
-            })
-            .collect();
+                    &stats,
+                )
+            });
+        let restored = restored_buckets.is_some();
+        let buckets = restored_buckets.unwrap_or_else(|| {
+            if temp_dir.is_none() {
+                Self::erase_previous_drives(&drives);
+            }
+            (0..config.max_buckets)
+                .map(|_| {
+                    Arc::new(BucketApi::new(
+                        Arc::clone(&drives),
+                        max_search,
+                        Arc::clone(&stats),
+                    ))
+                })
+                .collect()
+        });
 
         // A simple log2 function that is correct if x is a power of two
         let log2 = |x: usize| usize::BITS - x.leading_zeros() - 1;
@@ -99,7 +138,90 @@ impl<T: Clone + Copy + Debug> BucketMap<T> {
             max_buckets_pow2: log2(config.max_buckets) as u8,
             stats,
             temp_dir,
+            restart_config_file,
+            restored,
+            persisted: AtomicBool::default(),
+        }
+    }
+
+    /// reopen the buckets described by 'restart_config_file'.
+    /// The file is removed so the same files are never reopened twice.
+    /// Returns None if there is nothing to reopen or it does not match this map.
+    fn load_buckets_on_restart(
+        restart_config_file: &Path,
+        max_buckets: usize,
+        drives: &Arc<Vec<PathBuf>>,
+        max_search: MaxSearch,
+        stats: &Arc<BucketMapStats>,
+    ) -> Option<Vec<Arc<BucketApi<T>>>> {
+        if !restart_config_file.exists() {
+            return None;
+        }
+        let restart = Restart::read(restart_config_file);
+        let _ = fs::remove_file(restart_config_file);
+        let restart = restart
+            .map_err(|err| warn!("unable to read bucket map restart file: {err}"))
+            .ok()?;
+        if restart.buckets.len() != max_buckets
+            || restart.elem_size != std::mem::size_of::<T>() as u64
+            || restart.index_entry_size != std::mem::size_of::<IndexEntry>() as u64
+            || restart.key_uid_check != IndexEntry::key_uid(&Pubkey::default())
+        {
+            warn!("bucket map restart file does not match, starting empty");
+            return None;
+        }
+        let buckets = restart
+            .buckets
+            .iter()
+            .map(|bucket| {
+                BucketApi::load_on_restart(
+                    Arc::clone(drives),
+                    max_search,
+                    Arc::clone(stats),
+                    bucket.as_ref(),
+                )
+                .map(Arc::new)
+            })
+            .collect::<Option<Vec<_>>>();
+        if buckets.is_none() {
+            warn!("unable to reopen bucket map files, starting empty");
         }
+        buckets
+    }
+
+    /// true if the contents of this map were reopened from the files kept by `persist` on a previous run
+    pub fn is_restored(&self) -> bool {
+        self.restored
+    }
+
+    /// Flush all buckets to disk and record their files in the restart config file,
+    /// so the next `BucketMap::new` with the same config reopens them.
+    /// The map must not be modified once this has been called.
+    pub fn persist(&self) -> io::Result<()> {
+        let restart_config_file = self.restart_config_file.as_ref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "bucket map has no restart config file",
+            )
+        })?;
+        let restart = Restart {
+            elem_size: std::mem::size_of::<T>() as u64,
+            index_entry_size: std::mem::size_of::<IndexEntry>() as u64,
+            key_uid_check: IndexEntry::key_uid(&Pubkey::default()),
+            buckets: self
+                .buckets
+                .iter()
+                .map(|bucket| bucket.persist())
+                .collect::<io::Result<Vec<_>>>()?,
+        };
+        // keep the bucket files even if writing the restart file fails; they are erased on the next start
+        self.persisted.store(true, Ordering::Release);
+        restart.write(restart_config_file)
+    }
+
+    /// Remove all items from all buckets
+    pub fn clear(&self) {
+        self.buckets.iter().for_each(|bucket| bucket.clear());
     }
 
     fn erase_previous_drives(drives: &[PathBuf]) {
@@ -305,6 +427,93 @@ mod tests {
         }
     }
 
+    #[test]
+    fn bucket_map_test_restart() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let drives = vec![tmpdir.path().join("a"), tmpdir.path().join("b")];
+        let config = BucketMapConfig {
+            max_buckets: 1 << 2,
+            drives: Some(drives),
+            restart_config_file: Some(tmpdir.path().join("restart")),
+            ..BucketMapConfig::default()
+        };
+        let keys = (0..1000u64)
+            .map(|i| (Pubkey::new_unique(), i))
+            .collect::<Vec<_>>();
+        {
+            let index = BucketMap::new(config.clone());
+            assert!(!index.is_restored());
+            for (key, i) in &keys {
+                // vary the slot list lengths so several data buckets are used
+                index.update(key, |_| Some(((0..i % 5).collect::<Vec<_>>(), *i)));
+            }
+            index.persist().unwrap();
+        }
+
+        let index = BucketMap::<u64>::new(config.clone());
+        assert!(index.is_restored());
+        assert!(!tmpdir.path().join("restart").exists());
+        for (key, i) in &keys {
+            assert_eq!(
+                index.read_value(key),
+                Some(((0..i % 5).collect::<Vec<_>>(), *i))
+            );
+        }
+        let key = Pubkey::new_unique();
+        index.update(&key, |_| Some((vec![1], 1)));
+        assert_eq!(index.read_value(&key), Some((vec![1], 1)));
+        drop(index);
+
+        // not persisted, so the next map starts empty
+        let index = BucketMap::<u64>::new(config.clone());
+        assert!(!index.is_restored());
+        assert_eq!(index.read_value(&keys[0].0), None);
+        index.update(&key, |_| Some((vec![1], 1)));
+        index.persist().unwrap();
+        drop(index);
+
+        // a different element type cannot reuse the files
+        let index = BucketMap::<u32>::new(config.clone());
+        assert!(!index.is_restored());
+        assert_eq!(index.read_value(&key), None);
+        index.update(&key, |_| Some((vec![1], 1)));
+        index.persist().unwrap();
+        drop(index);
+
+        // neither can a different number of buckets
+        let index = BucketMap::<u32>::new(BucketMapConfig {
+            max_buckets: 1 << 3,
+            ..config.clone()
+        });
+        assert!(!index.is_restored());
+        assert_eq!(index.read_value(&key), None);
+        index.update(&key, |_| Some((vec![1], 1)));
+        index.persist().unwrap();
+        drop(index);
+
+        let index = BucketMap::<u32>::new(BucketMapConfig {
+            max_buckets: 1 << 3,
+            ..config
+        });
+        assert!(index.is_restored());
+        index.clear();
+        assert_eq!(index.read_value(&key), None);
+        assert_eq!(
+            index
+                .buckets
+                .iter()
+                .map(|bucket| bucket.bucket_len())
+                .sum::<u64>(),
+            0
+        );
+    }
+
+    #[test]
+    fn bucket_map_test_persist_without_restart_config_file() {
+        let index = BucketMap::<u64>::new(BucketMapConfig::new(1 << 2));
+        assert!(index.persist().is_err());
+    }
+
     #[test]
     fn bucket_map_test_n_drives() {
         let config = BucketMapConfig::new(1 << 2);
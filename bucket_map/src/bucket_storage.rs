@@ -1,11 +1,11 @@
 use {
-    crate::{bucket_stats::BucketStats, MaxSearch},
+    crate::{bucket_stats::BucketStats, restart::RestartableStorage, MaxSearch},
     memmap2::MmapMut,
     rand::{thread_rng, Rng},
     solana_measure::measure::Measure,
     std::{
         fs::{remove_file, OpenOptions},
-        io::{Seek, SeekFrom, Write},
+        io::{self, Seek, SeekFrom, Write},
         path::PathBuf,
         sync::{
             atomic::{AtomicU64, Ordering},
@@ -82,6 +82,8 @@ pub struct BucketStorage {
     pub count: Arc<AtomicU64>,
     pub stats: Arc<BucketStats>,
     pub max_search: MaxSearch,
+    /// true once the file has been flushed for reuse on restart, so it must outlive this storage
+    persisted: bool,
 }
 
 #[derive(Debug)]
@@ -91,7 +93,9 @@ pub enum BucketStorageError {
 
 impl Drop for BucketStorage {
     fn drop(&mut self) {
-        let _ = remove_file(&self.path);
+        if !self.persisted {
+            let _ = remove_file(&self.path);
+        }
     }
 }
 
@@ -115,9 +119,58 @@ impl BucketStorage {
             capacity_pow2,
             stats,
             max_search,
+            persisted: false,
         }
     }
 
+    /// reopen the file of a storage that was persisted by a previous run.
+    /// Returns None if the file is missing or its size does not match 'storage'.
+    pub(crate) fn load_on_restart(
+        storage: &RestartableStorage,
+        num_elems: u64,
+        elem_size: u64,
+        max_search: MaxSearch,
+        stats: Arc<BucketStats>,
+        count: Arc<AtomicU64>,
+    ) -> Option<Self> {
+        let cell_size = elem_size * num_elems + std::mem::size_of::<Header>() as u64;
+        let expected_len = (1u64 << storage.capacity_pow2) * cell_size;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&storage.path)
+            .ok()?;
+        if file.metadata().ok()?.len() != expected_len {
+            return None;
+        }
+        let mmap = unsafe { MmapMut::map_mut(&file).ok()? };
+        count.store(storage.count, Ordering::Relaxed);
+        let storage = Self {
+            path: storage.path.clone(),
+            mmap,
+            cell_size,
+            count,
+            capacity_pow2: storage.capacity_pow2,
+            stats,
+            max_search,
+            persisted: false,
+        };
+        storage.update_max_size();
+        Some(storage)
+    }
+
+    /// flush the mmapped file to disk and keep it when this storage is dropped.
+    /// Returns what is needed to reopen the file with `load_on_restart`.
+    pub(crate) fn persist(&mut self) -> io::Result<RestartableStorage> {
+        self.mmap.flush()?;
+        self.persisted = true;
+        Ok(RestartableStorage {
+            capacity_pow2: self.capacity_pow2,
+            count: self.count.load(Ordering::Relaxed),
+            path: self.path.clone(),
+        })
+    }
+
     pub fn max_search(&self) -> u64 {
         self.max_search as u64
     }
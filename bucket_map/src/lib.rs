@@ -6,6 +6,7 @@ pub mod bucket_map;
 mod bucket_stats;
 mod bucket_storage;
 mod index_entry;
+mod restart;
 
 pub type MaxSearch = u8;
 pub type RefCount = u64;
@@ -0,0 +1,279 @@
+//! Description of the files backing a `BucketMap`, written on a clean shutdown so the next run can
+//! reopen the files instead of starting from an empty map.
+//!
+//! The format is line based text:
+//! ```text
+//! solana-bucket-map-restart v1
+//! elem_size <size of one data element>
+//! index_entry_size <size of one index entry>
+//! key_uid_check <uid of the default pubkey>
+//! buckets <number of buckets>
+//! bucket <random> | empty
+//! index <capacity_pow2> <count> <path>
+//! data <capacity_pow2> <count> <path>
+//! ...
+//! ```
+//! Each `bucket` line is followed by its `index` line and one `data` line per data bucket, in order.
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+const HEADER: &str = "solana-bucket-map-restart v1";
+
+/// what is needed to reopen one `BucketStorage` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RestartableStorage {
+    pub(crate) capacity_pow2: u8,
+    /// number of allocated cells
+    pub(crate) count: u64,
+    pub(crate) path: PathBuf,
+}
+
+/// what is needed to reopen one `Bucket`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RestartableBucket {
+    pub(crate) random: u64,
+    pub(crate) index: RestartableStorage,
+    pub(crate) data: Vec<RestartableStorage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Restart {
+    pub(crate) elem_size: u64,
+    pub(crate) index_entry_size: u64,
+    /// `IndexEntry::key_uid` of the default pubkey.
+    /// Entries are placed by hash, so the files can only be reused if hashing is unchanged.
+    pub(crate) key_uid_check: u64,
+    /// None for buckets that were never written to
+    pub(crate) buckets: Vec<Option<RestartableBucket>>,
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn parse<T: FromStr>(value: Option<&str>, line: &str) -> io::Result<T> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| invalid_data(format!("invalid restart line: {line}")))
+}
+
+impl Restart {
+    /// write to 'path', replacing any previous file only once the new contents are complete
+    pub(crate) fn write(&self, path: &Path) -> io::Result<()> {
+        let temp_path = path.with_extension("tmp");
+        {
+            let mut file = BufWriter::new(File::create(&temp_path)?);
+            writeln!(file, "{HEADER}")?;
+            writeln!(file, "elem_size {}", self.elem_size)?;
+            writeln!(file, "index_entry_size {}", self.index_entry_size)?;
+            writeln!(file, "key_uid_check {}", self.key_uid_check)?;
+            writeln!(file, "buckets {}", self.buckets.len())?;
+            for bucket in &self.buckets {
+                match bucket {
+                    None => writeln!(file, "bucket empty")?,
+                    Some(bucket) => {
+                        writeln!(file, "bucket {}", bucket.random)?;
+                        Self::write_storage(&mut file, "index", &bucket.index)?;
+                        for data in &bucket.data {
+                            Self::write_storage(&mut file, "data", data)?;
+                        }
+                    }
+                }
+            }
+            file.into_inner()
+                .map_err(|err| err.into_error())?
+                .sync_all()?;
+        }
+        fs::rename(temp_path, path)
+    }
+
+    fn write_storage(
+        file: &mut impl Write,
+        kind: &str,
+        storage: &RestartableStorage,
+    ) -> io::Result<()> {
+        let path = storage
+            .path
+            .to_str()
+            .ok_or_else(|| invalid_data("bucket file path is not valid utf-8"))?;
+        writeln!(
+            file,
+            "{kind} {} {} {path}",
+            storage.capacity_pow2, storage.count
+        )
+    }
+
+    pub(crate) fn read(path: &Path) -> io::Result<Self> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let mut next_line = || {
+            lines
+                .next()
+                .unwrap_or_else(|| Err(invalid_data("restart file is truncated")))
+        };
+        if next_line()? != HEADER {
+            return Err(invalid_data("unknown restart file version"));
+        }
+        let mut header_value = |name: &str| -> io::Result<u64> {
+            let line = next_line()?;
+            match line.split_once(' ') {
+                Some((key, value)) if key == name => parse(Some(value), &line),
+                _ => Err(invalid_data(format!("expected {name}, found: {line}"))),
+            }
+        };
+        let elem_size = header_value("elem_size")?;
+        let index_entry_size = header_value("index_entry_size")?;
+        let key_uid_check = header_value("key_uid_check")?;
+        let num_buckets = header_value("buckets")?;
+
+        let mut buckets: Vec<Option<RestartableBucket>> = Vec::new();
+        let mut storages = 0;
+        for line in lines {
+            let line = line?;
+            let mut fields = line.splitn(4, ' ');
+            match fields.next() {
+                Some("bucket") => match fields.next() {
+                    Some("empty") => buckets.push(None),
+                    random => buckets.push(Some(RestartableBucket {
+                        random: parse(random, &line)?,
+                        index: RestartableStorage {
+                            capacity_pow2: 0,
+                            count: 0,
+                            path: PathBuf::default(),
+                        },
+                        data: Vec::new(),
+                    })),
+                },
+                Some(kind @ ("index" | "data")) => {
+                    let storage = RestartableStorage {
+                        capacity_pow2: parse(fields.next(), &line)?,
+                        count: parse(fields.next(), &line)?,
+                        path: PathBuf::from(
+                            fields
+                                .next()
+                                .ok_or_else(|| invalid_data(format!("missing path: {line}")))?,
+                        ),
+                    };
+                    let bucket = buckets
+                        .last_mut()
+                        .and_then(Option::as_mut)
+                        .ok_or_else(|| invalid_data(format!("storage without bucket: {line}")))?;
+                    if kind == "index" {
+                        if storages != 0 {
+                            return Err(invalid_data(format!("unexpected index: {line}")));
+                        }
+                        bucket.index = storage;
+                    } else {
+                        if storages == 0 {
+                            return Err(invalid_data(format!("data before index: {line}")));
+                        }
+                        bucket.data.push(storage);
+                    }
+                    storages += 1;
+                    continue;
+                }
+                _ => return Err(invalid_data(format!("invalid restart line: {line}"))),
+            }
+            // a new bucket line was read
+            storages = 0;
+        }
+        if buckets.len() as u64 != num_buckets {
+            return Err(invalid_data(format!(
+                "expected {num_buckets} buckets, found {}",
+                buckets.len()
+            )));
+        }
+        if buckets
+            .iter()
+            .flatten()
+            .any(|bucket| bucket.index.path.as_os_str().is_empty())
+        {
+            return Err(invalid_data("bucket without index"));
+        }
+        Ok(Self {
+            elem_size,
+            index_entry_size,
+            key_uid_check,
+            buckets,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::tempdir};
+
+    fn storage(capacity_pow2: u8, count: u64, path: &str) -> RestartableStorage {
+        RestartableStorage {
+            capacity_pow2,
+            count,
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn test_restart_write_read() {
+        let tmpdir = tempdir().unwrap();
+        let path = tmpdir.path().join("restart");
+        let restart = Restart {
+            elem_size: 16,
+            index_entry_size: 56,
+            key_uid_check: 12345,
+            buckets: vec![
+                None,
+                Some(RestartableBucket {
+                    random: u64::MAX,
+                    index: storage(5, 3, "/drive a/1"),
+                    data: vec![storage(5, 0, "/drive a/2"), storage(6, 2, "/drive b/3")],
+                }),
+                Some(RestartableBucket {
+                    random: 0,
+                    index: storage(7, 1, "/drive/4"),
+                    data: vec![],
+                }),
+            ],
+        };
+        restart.write(&path).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+        assert_eq!(Restart::read(&path).unwrap(), restart);
+    }
+
+    #[test]
+    fn test_restart_read_invalid() {
+        let tmpdir = tempdir().unwrap();
+        let path = tmpdir.path().join("restart");
+        assert!(Restart::read(&path).is_err());
+
+        let valid_header =
+            format!("{HEADER}\nelem_size 16\nindex_entry_size 56\nkey_uid_check 1\nbuckets 1\n");
+        for contents in [
+            "unknown header\n".to_string(),
+            // truncated
+            format!("{HEADER}\nelem_size 16\n"),
+            // missing buckets
+            valid_header.clone(),
+            // bucket without index
+            format!("{valid_header}bucket 1\n"),
+            // data before index
+            format!("{valid_header}bucket 1\ndata 5 0 /a\nindex 5 0 /b\n"),
+            // storage without bucket
+            format!("{valid_header}index 5 0 /b\n"),
+            // bad values
+            format!("{valid_header}bucket 1\nindex 500 0 /b\n"),
+            format!("{valid_header}bucket x\n"),
+        ] {
+            fs::write(&path, contents.as_bytes()).unwrap();
+            assert!(Restart::read(&path).is_err(), "{contents}");
+        }
+
+        fs::write(
+            &path,
+            format!("{valid_header}bucket 1\nindex 5 0 /b\n").as_bytes(),
+        )
+        .unwrap();
+        assert!(Restart::read(&path).is_ok());
+    }
+}
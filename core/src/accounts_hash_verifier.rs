@@ -190,6 +190,8 @@ impl AccountsHashVerifier {
 
         Self::save_epoch_accounts_hash(&accounts_package, accounts_hash);
 
+        Self::save_snapshot_accounts_hash_bins(&accounts_package);
+
         Self::push_accounts_hashes_to_cluster(
             &accounts_package,
             cluster_info,
@@ -325,6 +327,47 @@ impl AccountsHashVerifier {
         }
     }
 
+    /// Keep the accounts hash bins summary of a snapshot, so nodes that load the snapshot can compare
+    /// their accounts with ours
+    fn save_snapshot_accounts_hash_bins(accounts_package: &AccountsPackage) {
+        let full_snapshot_slot = match accounts_package.package_type {
+            AccountsPackageType::Snapshot(SnapshotType::FullSnapshot) => accounts_package.slot,
+            AccountsPackageType::Snapshot(SnapshotType::IncrementalSnapshot(base_slot)) => {
+                base_slot
+            }
+            _ => return,
+        };
+        let accounts_db = &accounts_package.accounts.accounts_db;
+        let num_bins = match accounts_db.num_snapshot_accounts_hash_bins() {
+            Some(num_bins) => num_bins,
+            None => return,
+        };
+
+        let mut measure = Measure::start("accounts_hash_bins");
+        // the storages were just scanned for the accounts hash, so this reuses the hash cache
+        let accounts_hash_bins = accounts_db
+            .calculate_accounts_hash_bins_from_storages(
+                accounts_package.slot,
+                &CalcAccountsHashConfig {
+                    use_bg_thread_pool: true,
+                    check_hash: false,
+                    ancestors: None,
+                    epoch_schedule: &accounts_package.epoch_schedule,
+                    rent_collector: &accounts_package.rent_collector,
+                    store_detailed_debug_info_on_failure: false,
+                },
+                &SortedStorages::new(&accounts_package.snapshot_storages),
+                num_bins,
+            )
+            .unwrap(); // unwrap here will never fail since check_hash = false
+        accounts_db.add_snapshot_accounts_hash_bins(accounts_hash_bins, full_snapshot_slot);
+        measure.stop();
+        datapoint_info!(
+            "accounts_hash_verifier",
+            ("calculate_hash_bins", measure.as_us(), i64),
+        );
+    }
+
     fn generate_fault_hash(original_hash: &Hash) -> Hash {
         use {
             rand::{thread_rng, Rng},
@@ -0,0 +1,528 @@
+//! The `banking_simulation` module replays the packet batches recorded by
+//! [`BankingTracer`](crate::banking_trace::BankingTracer) back into a
+//! [`BankingStage`] running on top of a bank loaded from a snapshot.
+//!
+//! The original inter-arrival timing of the recorded batches is preserved, so that leader-slot
+//! scheduling and block-packing behavior can be reproduced offline. Each simulated block's
+//! blockhash and bank hash are reported alongside the ones which were recorded by the traced
+//! node, if any.
+use {
+    crate::{
+        banking_stage::BankingStage,
+        banking_trace::{
+            BankingPacketBatch, BankingPacketSender, BankingTracer, ChannelLabel, TimedTracedEvent,
+            TracedEvent, BASENAME, TRACE_FILE_ROTATE_COUNT,
+        },
+    },
+    crossbeam_channel::{unbounded, Receiver, RecvTimeoutError},
+    solana_client::connection_cache::ConnectionCache,
+    solana_gossip::{
+        cluster_info::ClusterInfo, legacy_contact_info::LegacyContactInfo as ContactInfo,
+    },
+    solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
+    solana_poh::{
+        poh_recorder::{PohRecorder, WorkingBankEntry},
+        poh_service::{PohService, DEFAULT_HASHES_PER_BATCH, DEFAULT_PINNED_CPU_CORE},
+    },
+    solana_runtime::{bank::Bank, bank_forks::BankForks},
+    solana_sdk::{
+        clock::Slot,
+        genesis_config::GenesisConfig,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        timing::timestamp,
+    },
+    solana_streamer::socket::SocketAddrSpace,
+    std::{
+        collections::BTreeMap,
+        fs::File,
+        io::{self, BufReader},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, sleep, JoinHandle},
+        time::{Duration, Instant, SystemTime},
+    },
+    thiserror::Error,
+};
+
+/// How long before the first simulated slot recorded packet batches start to be replayed, so that
+/// banking stage has buffered packets just like the traced node had at the start of its leader
+/// slot.
+const WARMUP_DURATION: Duration = Duration::from_millis(13 * 400);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Error, Debug)]
+pub enum SimulateError {
+    #[error("IO Error: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("Deserialization Error: {0}")]
+    DeserializeError(#[from] bincode::Error),
+
+    #[error("No banking trace events are found under {0:?}")]
+    NoEvents(PathBuf),
+
+    #[error("Parent slot {0} of the first simulated slot wasn't frozen in the banking trace")]
+    MissingParentFreeze(Slot),
+
+    #[error("Leader of the first simulated slot {0} couldn't be determined")]
+    UnknownLeader(Slot),
+
+    #[error("First simulated slot {0} must be a child of the working bank at slot {1}")]
+    InvalidFirstSimulatedSlot(Slot, Slot),
+}
+
+/// All events which are recorded under a banking trace directory, ordered by their recorded time.
+#[derive(Default)]
+pub struct BankingTraceEvents {
+    packet_batches: Vec<(SystemTime, ChannelLabel, BankingPacketBatch)>,
+    freeze_time_by_slot: BTreeMap<Slot, SystemTime>,
+    hashes_by_slot: BTreeMap<Slot, (Hash, Hash)>,
+}
+
+impl BankingTraceEvents {
+    /// Loads events from the rolling trace files under `path`, oldest file first.
+    pub fn load_from_dir(path: &Path) -> Result<Self, SimulateError> {
+        let event_file_paths = (0..TRACE_FILE_ROTATE_COUNT)
+            .rev()
+            .map(|index| match index {
+                0 => path.join(BASENAME),
+                index => path.join(format!("{BASENAME}.{index}")),
+            })
+            .filter(|event_file_path| event_file_path.exists())
+            .collect::<Vec<_>>();
+        if event_file_paths.is_empty() {
+            return Err(SimulateError::NoEvents(path.to_path_buf()));
+        }
+
+        Self::load(&event_file_paths)
+    }
+
+    pub fn load(event_file_paths: &[PathBuf]) -> Result<Self, SimulateError> {
+        let mut events = Self::default();
+        for event_file_path in event_file_paths {
+            info!("loading banking trace events from {:?}", event_file_path);
+            events.read_event_file(event_file_path)?;
+        }
+        // events across files are almost always ordered already; stable-sort just in case of
+        // clock skews around rotation.
+        events.packet_batches.sort_by_key(|(time, ..)| *time);
+        info!(
+            "loaded {} packet batches and {} frozen slots",
+            events.packet_batches.len(),
+            events.hashes_by_slot.len()
+        );
+
+        Ok(events)
+    }
+
+    fn read_event_file(&mut self, event_file_path: &Path) -> Result<(), SimulateError> {
+        let mut reader = BufReader::new(File::open(event_file_path)?);
+        loop {
+            match bincode::deserialize_from::<_, TimedTracedEvent>(&mut reader) {
+                Ok(TimedTracedEvent(time, TracedEvent::PacketBatch(label, batch))) => {
+                    self.packet_batches.push((time, label, batch));
+                }
+                Ok(TimedTracedEvent(
+                    time,
+                    TracedEvent::BlockAndBankHash(slot, blockhash, bank_hash),
+                )) => {
+                    self.freeze_time_by_slot.insert(slot, time);
+                    self.hashes_by_slot.insert(slot, (blockhash, bank_hash));
+                }
+                Err(err) => match *err {
+                    // the last event could be partially written due to an unclean shutdown
+                    bincode::ErrorKind::Io(ref io_err)
+                        if io_err.kind() == io::ErrorKind::UnexpectedEof =>
+                    {
+                        break;
+                    }
+                    _ => return Err(err.into()),
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The recorded (blockhash, bank hash) pairs of frozen banks keyed by their slots.
+    pub fn hashes_by_slot(&self) -> &BTreeMap<Slot, (Hash, Hash)> {
+        &self.hashes_by_slot
+    }
+}
+
+/// Result of a single simulated block.
+#[derive(Debug)]
+pub struct SimulatedBlock {
+    pub slot: Slot,
+    pub blockhash: Hash,
+    pub bank_hash: Hash,
+    pub transaction_count: u64,
+    /// (blockhash, bank hash) recorded for the same slot by the traced node, if any
+    pub recorded_hashes: Option<(Hash, Hash)>,
+}
+
+impl SimulatedBlock {
+    pub fn is_bank_hash_matched(&self) -> Option<bool> {
+        self.recorded_hashes
+            .map(|(_blockhash, bank_hash)| bank_hash == self.bank_hash)
+    }
+}
+
+pub struct BankingSimulator {
+    banking_trace_events: BankingTraceEvents,
+    first_simulated_slot: Slot,
+}
+
+impl BankingSimulator {
+    pub fn new(banking_trace_events: BankingTraceEvents, first_simulated_slot: Slot) -> Self {
+        Self {
+            banking_trace_events,
+            first_simulated_slot,
+        }
+    }
+
+    /// Simulates the consecutive leader slots starting at `first_simulated_slot`, on top of the
+    /// working bank of `bank_forks`, which must be the parent of `first_simulated_slot`.
+    pub fn start(
+        self,
+        genesis_config: &GenesisConfig,
+        bank_forks: Arc<RwLock<BankForks>>,
+        blockstore: Arc<Blockstore>,
+    ) -> Result<Vec<SimulatedBlock>, SimulateError> {
+        let mut bank = bank_forks.read().unwrap().working_bank();
+        let parent_slot = bank.slot();
+        if self.first_simulated_slot <= parent_slot {
+            return Err(SimulateError::InvalidFirstSimulatedSlot(
+                self.first_simulated_slot,
+                parent_slot,
+            ));
+        }
+        let parent_freeze_time = *self
+            .banking_trace_events
+            .freeze_time_by_slot
+            .get(&parent_slot)
+            .ok_or(SimulateError::MissingParentFreeze(parent_slot))?;
+
+        let leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(&bank));
+        let simulated_leader = leader_schedule_cache
+            .slot_leader_at(self.first_simulated_slot, Some(&bank))
+            .ok_or(SimulateError::UnknownLeader(self.first_simulated_slot))?;
+        info!(
+            "simulating leader {} from slot {} on top of slot {}",
+            simulated_leader, self.first_simulated_slot, parent_slot
+        );
+
+        let exit = Arc::new(AtomicBool::default());
+        let (poh_recorder, entry_receiver, record_receiver) = PohRecorder::new(
+            bank.tick_height(),
+            bank.last_blockhash(),
+            bank.clone(),
+            None,
+            bank.ticks_per_slot(),
+            &simulated_leader,
+            &blockstore,
+            &leader_schedule_cache,
+            &genesis_config.poh_config,
+            exit.clone(),
+        );
+        let poh_recorder = Arc::new(RwLock::new(poh_recorder));
+        let poh_service = PohService::new(
+            poh_recorder.clone(),
+            &genesis_config.poh_config,
+            &exit,
+            bank.ticks_per_slot(),
+            DEFAULT_PINNED_CPU_CORE,
+            DEFAULT_HASHES_PER_BATCH,
+            record_receiver,
+        );
+        // simulated entries aren't broadcast nor stored anywhere; just drain them.
+        let entry_drainer = Self::spawn_entry_drainer(entry_receiver, exit.clone())?;
+
+        // The simulated node can't sign anything as the leader, so use a throwaway identity.
+        // Packets are always consumed because a working bank is kept installed to poh recorder
+        // for the whole simulation.
+        let keypair = Arc::new(Keypair::new());
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::new_localhost(&keypair.pubkey(), timestamp()),
+            keypair,
+            SocketAddrSpace::Unspecified,
+        ));
+        let banking_tracer = BankingTracer::new_disabled();
+        let (non_vote_sender, non_vote_receiver) = banking_tracer.create_channel_non_vote();
+        let (tpu_vote_sender, tpu_vote_receiver) = banking_tracer.create_channel_tpu_vote();
+        let (gossip_vote_sender, gossip_vote_receiver) =
+            banking_tracer.create_channel_gossip_vote();
+        let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let banking_stage = BankingStage::new(
+            &cluster_info,
+            &poh_recorder,
+            non_vote_receiver,
+            tpu_vote_receiver,
+            gossip_vote_receiver,
+            None,
+            replay_vote_sender,
+            None,
+            Arc::new(ConnectionCache::default()),
+            bank_forks.clone(),
+        );
+
+        let warmup_start_time = parent_freeze_time
+            .checked_sub(WARMUP_DURATION)
+            .unwrap_or(parent_freeze_time);
+        let BankingTraceEvents {
+            packet_batches,
+            hashes_by_slot,
+            ..
+        } = self.banking_trace_events;
+        let packet_batches = packet_batches
+            .into_iter()
+            .skip_while(|(time, ..)| *time < warmup_start_time)
+            .collect::<Vec<_>>();
+        let simulation_start = Instant::now();
+        let sender_exit = exit.clone();
+        let sender_thread: JoinHandle<()> = thread::Builder::new()
+            .name("solSimSender".into())
+            .spawn(move || {
+                let sent_batch_count = Self::send_packet_batches(
+                    packet_batches,
+                    warmup_start_time,
+                    simulation_start,
+                    &sender_exit,
+                    &non_vote_sender,
+                    &tpu_vote_sender,
+                    &gossip_vote_sender,
+                );
+                info!("sent {} recorded packet batches", sent_batch_count);
+            })?;
+
+        // let banking stage buffer packets which were received before the first leader slot
+        sleep(
+            parent_freeze_time
+                .duration_since(warmup_start_time)
+                .unwrap_or_default()
+                .saturating_sub(simulation_start.elapsed()),
+        );
+
+        let mut simulated_blocks = vec![];
+        let mut slot = self.first_simulated_slot;
+        while leader_schedule_cache.slot_leader_at(slot, Some(&bank)) == Some(simulated_leader) {
+            let parent = bank;
+            bank = Self::start_bank(&bank_forks, &poh_recorder, &parent, &simulated_leader, slot);
+            while poh_recorder.read().unwrap().bank().is_some() {
+                sleep(POLL_INTERVAL);
+            }
+            bank.freeze();
+            let simulated_block = SimulatedBlock {
+                slot,
+                blockhash: bank.last_blockhash(),
+                bank_hash: bank.hash(),
+                transaction_count: bank.transaction_count() - parent.transaction_count(),
+                recorded_hashes: hashes_by_slot.get(&slot).copied(),
+            };
+            info!("simulated block: {:?}", simulated_block);
+            simulated_blocks.push(simulated_block);
+            slot += 1;
+        }
+
+        exit.store(true, Ordering::Relaxed);
+        sender_thread.join().unwrap();
+        banking_stage.join().unwrap();
+        poh_service.join().unwrap();
+        entry_drainer.join().unwrap();
+
+        Ok(simulated_blocks)
+    }
+
+    /// Sends `packet_batches` to banking stage at their recorded offsets from `warmup_start_time`,
+    /// through the non-vote, tpu vote and gossip vote senders. Returns the number of sent batches.
+    fn send_packet_batches(
+        packet_batches: Vec<(SystemTime, ChannelLabel, BankingPacketBatch)>,
+        warmup_start_time: SystemTime,
+        simulation_start: Instant,
+        exit: &AtomicBool,
+        non_vote_sender: &BankingPacketSender,
+        tpu_vote_sender: &BankingPacketSender,
+        gossip_vote_sender: &BankingPacketSender,
+    ) -> usize {
+        let mut sent_batch_count = 0;
+        for (time, label, batch) in packet_batches {
+            let offset = time.duration_since(warmup_start_time).unwrap_or_default();
+            if !Self::sleep_until(simulation_start + offset, exit) {
+                break;
+            }
+            let sender = match label {
+                ChannelLabel::NonVote => non_vote_sender,
+                ChannelLabel::TpuVote => tpu_vote_sender,
+                ChannelLabel::GossipVote => gossip_vote_sender,
+                ChannelLabel::Dummy => continue,
+            };
+            if sender.send(batch).is_err() {
+                break;
+            }
+            sent_batch_count += 1;
+        }
+        sent_batch_count
+    }
+
+    /// Sleeps until `deadline` in slices of at most `POLL_INTERVAL`, so that `exit` is noticed even
+    /// during a long gap between recorded batches. Returns false if `exit` was set.
+    fn sleep_until(deadline: Instant, exit: &AtomicBool) -> bool {
+        loop {
+            if exit.load(Ordering::Relaxed) {
+                return false;
+            }
+            let wait = deadline.saturating_duration_since(Instant::now());
+            if wait.is_zero() {
+                return true;
+            }
+            sleep(wait.min(POLL_INTERVAL));
+        }
+    }
+
+    fn spawn_entry_drainer(
+        entry_receiver: Receiver<WorkingBankEntry>,
+        exit: Arc<AtomicBool>,
+    ) -> io::Result<JoinHandle<()>> {
+        thread::Builder::new()
+            .name("solSimEntryDrn".into())
+            .spawn(move || {
+                while !exit.load(Ordering::Relaxed) {
+                    match entry_receiver.recv_timeout(POLL_INTERVAL) {
+                        Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+            })
+    }
+
+    fn start_bank(
+        bank_forks: &RwLock<BankForks>,
+        poh_recorder: &RwLock<PohRecorder>,
+        parent: &Arc<Bank>,
+        leader: &Pubkey,
+        slot: Slot,
+    ) -> Arc<Bank> {
+        let bank = bank_forks
+            .write()
+            .unwrap()
+            .insert(Bank::new_from_parent(parent, leader, slot));
+        let mut poh_recorder = poh_recorder.write().unwrap();
+        poh_recorder.reset(parent.clone(), Some((slot, slot)));
+        poh_recorder.set_bank(&bank, false);
+        bank
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_trace::{for_test, receiving_loop_with_minimized_sender_overhead},
+        solana_perf::packet::PacketBatch,
+        std::str::FromStr,
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_load_banking_trace_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("banking-trace");
+        let exit = Arc::<AtomicBool>::default();
+        let (tracer, tracer_thread) =
+            BankingTracer::new(Some((&path, exit.clone(), u64::max_value()))).unwrap();
+        let (non_vote_sender, non_vote_receiver) = tracer.create_channel_non_vote();
+
+        let dummy_main_thread = thread::spawn(move || {
+            receiving_loop_with_minimized_sender_overhead::<_, SimulateError, 0>(
+                exit,
+                non_vote_receiver,
+                |_packet_batch| Ok(()),
+            )
+        });
+
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        let blockhash = Hash::from_str("B1ockhash1111111111111111111111111111111111").unwrap();
+        let bank_hash = Hash::from_str("BankHash11111111111111111111111111111111111").unwrap();
+        tracer.hash_event(4, &blockhash, &bank_hash);
+
+        drop((non_vote_sender, tracer));
+        dummy_main_thread.join().unwrap().unwrap();
+        tracer_thread.unwrap().join().unwrap().unwrap();
+
+        let events = BankingTraceEvents::load_from_dir(&path).unwrap();
+        assert_eq!(events.packet_batches.len(), 1);
+        assert_matches!(
+            events.packet_batches[0],
+            (_, ChannelLabel::NonVote, ref batch) if batch.0.iter().map(PacketBatch::len).sum::<usize>() == 4
+        );
+        assert_eq!(
+            events.hashes_by_slot(),
+            &BTreeMap::from([(4, (blockhash, bank_hash))])
+        );
+        assert!(events.freeze_time_by_slot.contains_key(&4));
+
+        for_test::drop_and_clean_temp_dir_unless_suppressed(temp_dir);
+    }
+
+    #[test]
+    fn test_send_packet_batches_exits_during_long_gap() {
+        let tracer = BankingTracer::new_disabled();
+        let (non_vote_sender, non_vote_receiver) = tracer.create_channel_non_vote();
+        let (tpu_vote_sender, _tpu_vote_receiver) = tracer.create_channel_tpu_vote();
+        let (gossip_vote_sender, _gossip_vote_receiver) = tracer.create_channel_gossip_vote();
+        let warmup_start_time = SystemTime::now();
+        let packet_batches = vec![
+            (
+                warmup_start_time,
+                ChannelLabel::NonVote,
+                for_test::sample_packet_batch(),
+            ),
+            // far behind the first batch, so the sender is still waiting for it on exit
+            (
+                warmup_start_time + Duration::from_secs(60 * 60),
+                ChannelLabel::NonVote,
+                for_test::sample_packet_batch(),
+            ),
+        ];
+
+        let exit = Arc::<AtomicBool>::default();
+        let (result_sender, result_receiver) = unbounded();
+        let sender_exit = exit.clone();
+        let sender_thread = thread::spawn(move || {
+            let sent_batch_count = BankingSimulator::send_packet_batches(
+                packet_batches,
+                warmup_start_time,
+                Instant::now(),
+                &sender_exit,
+                &non_vote_sender,
+                &tpu_vote_sender,
+                &gossip_vote_sender,
+            );
+            result_sender.send(sent_batch_count).unwrap();
+        });
+
+        non_vote_receiver
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap();
+        exit.store(true, Ordering::Relaxed);
+        assert_eq!(result_receiver.recv_timeout(Duration::from_secs(10)), Ok(1));
+        sender_thread.join().unwrap();
+        assert!(non_vote_receiver.is_empty());
+    }
+
+    #[test]
+    fn test_load_banking_trace_events_without_files() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_matches!(
+            BankingTraceEvents::load_from_dir(temp_dir.path()),
+            Err(SimulateError::NoEvents(_))
+        );
+    }
+}
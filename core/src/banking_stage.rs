@@ -39,7 +39,7 @@ use {
     solana_metrics::inc_new_counter_info,
     solana_perf::{data_budget::DataBudget, packet::PACKETS_PER_BATCH},
     solana_poh::poh_recorder::{BankStart, PohRecorder, PohRecorderError, TransactionRecorder},
-    solana_program_runtime::timings::ExecuteTimings,
+    solana_program_runtime::{instruction_tracer::InstructionTracing, timings::ExecuteTimings},
     solana_runtime::{
         bank::{Bank, LoadAndExecuteTransactionsOutput, TransactionCheckResult},
         bank_forks::BankForks,
@@ -888,6 +888,7 @@ impl BankingStage {
                 transaction_status_sender.is_some(),
                 transaction_status_sender.is_some(),
                 transaction_status_sender.is_some(),
+                InstructionTracing::Disabled,
                 &mut execute_and_commit_timings.execute_timings,
                 None, // account_overrides
                 log_messages_bytes_limit
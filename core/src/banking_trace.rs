@@ -46,8 +46,8 @@ pub enum TraceError {
     TooSmallDirByteLimit(DirByteLimit, DirByteLimit),
 }
 
-const BASENAME: &str = "events";
-const TRACE_FILE_ROTATE_COUNT: u64 = 14; // target 2 weeks retention under normal load
+pub(crate) const BASENAME: &str = "events";
+pub(crate) const TRACE_FILE_ROTATE_COUNT: u64 = 14; // target 2 weeks retention under normal load
 const TRACE_FILE_WRITE_INTERVAL_MS: u64 = 100;
 const BUF_WRITER_CAPACITY: usize = 10 * 1024 * 1024;
 pub const TRACE_FILE_DEFAULT_ROTATE_BYTE_THRESHOLD: u64 = 1024 * 1024 * 1024;
@@ -67,10 +67,10 @@ pub struct BankingTracer {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct TimedTracedEvent(std::time::SystemTime, TracedEvent);
+pub struct TimedTracedEvent(pub std::time::SystemTime, pub TracedEvent);
 
 #[derive(Serialize, Deserialize, Debug)]
-enum TracedEvent {
+pub enum TracedEvent {
     PacketBatch(ChannelLabel, BankingPacketBatch),
     BlockAndBankHash(Slot, Hash, Hash),
 }
@@ -4,6 +4,7 @@ use {
     solana_ledger::blockstore::Blockstore,
     solana_measure::measure::Measure,
     solana_runtime::bank::Bank,
+    solana_sdk::clock::Slot,
     std::{
         sync::{
             atomic::{AtomicBool, Ordering},
@@ -21,15 +22,20 @@ pub struct CacheBlockMetaService {
 }
 
 const CACHE_BLOCK_TIME_WARNING_MS: u64 = 150;
+const ACCOUNT_HISTORY_PURGE_INTERVAL_SLOTS: Slot = 1_000;
 
 impl CacheBlockMetaService {
     #[allow(clippy::new_ret_no_self)]
+    /// When `account_history_retention_slots` is set, the accounts written in each slot are
+    /// also recorded and purged once they fall that many slots behind the highest root
     pub fn new(
         cache_block_meta_receiver: CacheBlockMetaReceiver,
         blockstore: Arc<Blockstore>,
+        account_history_retention_slots: Option<Slot>,
         exit: &Arc<AtomicBool>,
     ) -> Self {
         let exit = exit.clone();
+        let mut last_account_history_purge_root = blockstore.max_root();
         let thread_hdl = Builder::new()
             .name("solCacheBlkTime".to_string())
             .spawn(move || loop {
@@ -51,6 +57,14 @@ impl CacheBlockMetaService {
                                 cache_block_meta_timer.as_ms()
                             );
                         }
+                        if let Some(retention_slots) = account_history_retention_slots {
+                            Self::record_account_history(&bank, &blockstore);
+                            Self::maybe_purge_account_history(
+                                &blockstore,
+                                retention_slots,
+                                &mut last_account_history_purge_root,
+                            );
+                        }
                     }
                     _ => {}
                 }
@@ -68,6 +82,43 @@ impl CacheBlockMetaService {
         }
     }
 
+    fn record_account_history(bank: &Bank, blockstore: &Blockstore) {
+        let accounts = bank.get_all_accounts_modified_since_parent();
+        if let Err(e) = blockstore.write_account_history(bank.slot(), &accounts) {
+            error!(
+                "write_account_history failed: slot {:?} {:?}",
+                bank.slot(),
+                e
+            );
+        }
+    }
+
+    fn maybe_purge_account_history(
+        blockstore: &Blockstore,
+        retention_slots: Slot,
+        last_purge_root: &mut Slot,
+    ) {
+        let max_root = blockstore.max_root();
+        if max_root.saturating_sub(*last_purge_root) < ACCOUNT_HISTORY_PURGE_INTERVAL_SLOTS {
+            return;
+        }
+        let last_max_root = std::mem::replace(last_purge_root, max_root);
+        let mut purge_timer = Measure::start("purge_account_history");
+        match blockstore
+            .purge_account_history(last_max_root, max_root.saturating_sub(retention_slots))
+        {
+            Ok(num_purged) => {
+                purge_timer.stop();
+                datapoint_info!(
+                    "cache_block_meta_service-purge_account_history",
+                    ("num_purged", num_purged, i64),
+                    ("purge_us", purge_timer.as_us(), i64),
+                );
+            }
+            Err(e) => error!("purge_account_history failed: root {:?} {:?}", max_root, e),
+        }
+    }
+
     pub fn join(self) -> thread::Result<()> {
         self.thread_hdl.join()
     }
@@ -10,6 +10,7 @@
 
 pub mod accounts_hash_verifier;
 pub mod ancestor_hashes_service;
+pub mod banking_simulation;
 pub mod banking_stage;
 pub mod banking_trace;
 pub mod broadcast_stage;
@@ -88,6 +88,7 @@ impl SnapshotPackagerService {
                         &snapshot_config.incremental_snapshot_archives_dir,
                         snapshot_config.maximum_full_snapshot_archives_to_retain,
                         snapshot_config.maximum_incremental_snapshot_archives_to_retain,
+                        snapshot_config.archive_compressed_storages,
                     )
                     .expect("failed to archive snapshot package");
 
@@ -344,6 +345,7 @@ mod tests {
             incremental_snapshot_archives_dir,
             snapshot_utils::DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
             snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            false,
         )
         .unwrap();
 
@@ -26,11 +26,14 @@ use {
         tpu::{Tpu, TpuSockets, DEFAULT_TPU_COALESCE_MS},
         tvu::{Tvu, TvuConfig, TvuSockets},
     },
-    crossbeam_channel::{bounded, unbounded, Receiver},
+    crossbeam_channel::{bounded, unbounded, Receiver, Sender},
     rand::{thread_rng, Rng},
     solana_client::connection_cache::ConnectionCache,
     solana_entry::poh::compute_hash_time_ns,
-    solana_geyser_plugin_manager::geyser_plugin_service::GeyserPluginService,
+    solana_geyser_plugin_manager::{
+        geyser_plugin_manager::GeyserPluginManagerRequest,
+        geyser_plugin_service::GeyserPluginService,
+    },
     solana_gossip::{
         cluster_info::{
             ClusterInfo, Node, DEFAULT_CONTACT_DEBUG_INTERVAL_MILLIS,
@@ -47,6 +50,7 @@ use {
         },
         blockstore_options::{BlockstoreOptions, BlockstoreRecoveryMode, LedgerColumnOptions},
         blockstore_processor::{self, TransactionStatusSender},
+        entry_notifier_service::{EntryNotifierSender, EntryNotifierService},
         leader_schedule::FixedSchedule,
         leader_schedule_cache::LeaderScheduleCache,
     },
@@ -74,7 +78,10 @@ use {
             AbsRequestHandlers, AbsRequestSender, AccountsBackgroundService, DroppedSlotsReceiver,
             PrunedBanksRequestHandler, SnapshotRequestHandler,
         },
-        accounts_db::{AccountShrinkThreshold, AccountsDbConfig},
+        accounts_db::{
+            AccountShrinkThreshold, AccountsDbConfig, PUBKEY_BINS_FOR_CALCULATING_HASHES,
+        },
+        accounts_hash::AccountsHashBins,
         accounts_index::AccountSecondaryIndexes,
         accounts_update_notifier_interface::AccountsUpdateNotifier,
         bank::Bank,
@@ -129,6 +136,7 @@ pub struct ValidatorConfig {
     pub account_shrink_paths: Option<Vec<PathBuf>>,
     pub rpc_config: JsonRpcConfig,
     pub geyser_plugin_config_files: Option<Vec<PathBuf>>,
+    pub geyser_plugin_always_enabled: bool,
     pub rpc_addrs: Option<(SocketAddr, SocketAddr)>, // (JsonRpc, JsonRpcPubSub)
     pub pubsub_config: PubSubConfig,
     pub snapshot_config: SnapshotConfig,
@@ -178,6 +186,9 @@ pub struct ValidatorConfig {
     pub runtime_config: RuntimeConfig,
     pub replay_slots_concurrently: bool,
     pub banking_trace_dir_byte_limit: banking_trace::DirByteLimit,
+    /// accounts hash bins summary of the snapshot on the RPC node it was fetched from, to compare
+    /// with the accounts of the loaded snapshot
+    pub rpc_peer_accounts_hash_bins: Option<AccountsHashBins>,
 }
 
 impl Default for ValidatorConfig {
@@ -193,6 +204,7 @@ impl Default for ValidatorConfig {
             account_shrink_paths: None,
             rpc_config: JsonRpcConfig::default(),
             geyser_plugin_config_files: None,
+            geyser_plugin_always_enabled: false,
             rpc_addrs: None,
             pubsub_config: PubSubConfig::default(),
             snapshot_config: SnapshotConfig::new_load_only(),
@@ -241,6 +253,7 @@ impl Default for ValidatorConfig {
             runtime_config: RuntimeConfig::default(),
             replay_slots_concurrently: false,
             banking_trace_dir_byte_limit: 0,
+            rpc_peer_accounts_hash_bins: None,
         }
     }
 }
@@ -358,6 +371,7 @@ pub struct Validator {
     transaction_status_service: Option<TransactionStatusService>,
     rewards_recorder_service: Option<RewardsRecorderService>,
     cache_block_meta_service: Option<CacheBlockMetaService>,
+    entry_notifier_service: Option<EntryNotifierService>,
     system_monitor_service: Option<SystemMonitorService>,
     sample_performance_service: Option<SamplePerformanceService>,
     poh_timing_report_service: PohTimingReportService,
@@ -378,6 +392,7 @@ pub struct Validator {
     ledger_metric_report_service: LedgerMetricReportService,
     accounts_background_service: AccountsBackgroundService,
     accounts_hash_verifier: AccountsHashVerifier,
+    snapshot_config: SnapshotConfig,
 }
 
 impl Validator {
@@ -414,11 +429,17 @@ impl Validator {
         let mut bank_notification_senders = Vec::new();
 
         let geyser_plugin_service =
-            if let Some(geyser_plugin_config_files) = &config.geyser_plugin_config_files {
+            if config.geyser_plugin_config_files.is_some() || config.geyser_plugin_always_enabled {
                 let (confirmed_bank_sender, confirmed_bank_receiver) = unbounded();
                 bank_notification_senders.push(confirmed_bank_sender);
-                let result =
-                    GeyserPluginService::new(confirmed_bank_receiver, geyser_plugin_config_files);
+                let result = GeyserPluginService::new_with_options(
+                    confirmed_bank_receiver,
+                    config.geyser_plugin_always_enabled,
+                    config
+                        .geyser_plugin_config_files
+                        .as_deref()
+                        .unwrap_or_default(),
+                );
                 match result {
                     Ok(geyser_plugin_service) => Some(geyser_plugin_service),
                     Err(err) => {
@@ -504,12 +525,24 @@ impl Validator {
             .as_ref()
             .and_then(|geyser_plugin_service| geyser_plugin_service.get_block_metadata_notifier());
 
+        let entry_notifier = geyser_plugin_service
+            .as_ref()
+            .and_then(|geyser_plugin_service| geyser_plugin_service.get_entry_notifier());
+
         info!(
-            "Geyser plugin: accounts_update_notifier: {} transaction_notifier: {}",
+            "Geyser plugin: accounts_update_notifier: {} transaction_notifier: {} \
+             entry_notifier: {}",
             accounts_update_notifier.is_some(),
-            transaction_notifier.is_some()
+            transaction_notifier.is_some(),
+            entry_notifier.is_some()
         );
 
+        let entry_notifier_service =
+            entry_notifier.map(|entry_notifier| EntryNotifierService::new(entry_notifier, &exit));
+        let entry_notification_sender = entry_notifier_service
+            .as_ref()
+            .map(|service| service.sender_cloned());
+
         let system_monitor_service = Some(SystemMonitorService::new(
             Arc::clone(&exit),
             SystemMonitorStatsReportConfig {
@@ -552,9 +585,15 @@ impl Validator {
             &start_progress,
             accounts_update_notifier,
             transaction_notifier,
+            entry_notification_sender.as_ref(),
             Some(poh_timing_point_sender.clone()),
         )?;
 
+        if let Some(rpc_peer_accounts_hash_bins) = &config.rpc_peer_accounts_hash_bins {
+            let root_bank = bank_forks.read().unwrap().root_bank();
+            compare_accounts_hash_bins(&root_bank, rpc_peer_accounts_hash_bins);
+        }
+
         node.info.wallclock = timestamp();
         node.info.shred_version = compute_shred_version(
             &genesis_config.hash(),
@@ -670,6 +709,7 @@ impl Validator {
             &blockstore_process_options,
             transaction_status_sender.as_ref(),
             cache_block_meta_sender.clone(),
+            entry_notification_sender.as_ref(),
             blockstore_root_scan,
             accounts_background_request_sender.clone(),
             config,
@@ -822,6 +862,7 @@ impl Validator {
                 connection_cache.clone(),
                 max_complete_transaction_status_slot,
                 prioritization_fee_cache.clone(),
+                Some(rpc_subscriptions.control().clone()),
             )?;
 
             (
@@ -990,6 +1031,7 @@ impl Validator {
             block_commitment_cache,
             config.turbine_disabled.clone(),
             transaction_status_sender.clone(),
+            entry_notification_sender,
             rewards_recorder_sender,
             cache_block_meta_sender,
             vote_tracker.clone(),
@@ -1074,6 +1116,7 @@ impl Validator {
             transaction_status_service,
             rewards_recorder_service,
             cache_block_meta_service,
+            entry_notifier_service,
             system_monitor_service,
             sample_performance_service,
             poh_timing_report_service,
@@ -1092,9 +1135,20 @@ impl Validator {
             ledger_metric_report_service,
             accounts_background_service,
             accounts_hash_verifier,
+            snapshot_config: config.snapshot_config.clone(),
         })
     }
 
+    /// The sender of requests to manage the Geyser plugins while the validator keeps running,
+    /// if the Geyser plugin service is enabled
+    pub fn geyser_plugin_manager_request_sender(
+        &self,
+    ) -> Option<Sender<GeyserPluginManagerRequest>> {
+        self.geyser_plugin_service
+            .as_ref()
+            .map(GeyserPluginService::get_plugin_manager_request_sender)
+    }
+
     // Used for notifying many nodes in parallel to exit
     pub fn exit(&mut self) {
         self.validator_exit.write().unwrap().exit();
@@ -1134,7 +1188,8 @@ impl Validator {
     }
 
     pub fn join(self) {
-        drop(self.bank_forks);
+        // the root bank to persist the accounts index with is only taken once replay has stopped
+        let bank_forks = self.bank_forks;
         drop(self.cluster_info);
 
         self.poh_service.join().expect("poh_service");
@@ -1219,6 +1274,12 @@ impl Validator {
             ip_echo_server.shutdown_background();
         }
 
+        if let Some(entry_notifier_service) = self.entry_notifier_service {
+            entry_notifier_service
+                .join()
+                .expect("entry_notifier_service");
+        }
+
         if let Some(geyser_plugin_service) = self.geyser_plugin_service {
             geyser_plugin_service.join().expect("geyser_plugin_service");
         }
@@ -1226,6 +1287,64 @@ impl Validator {
         self.poh_timing_report_service
             .join()
             .expect("poh_timing_report_service");
+
+        // all services have stopped, so neither the root nor the accounts index will change anymore
+        let root_bank = bank_forks.read().unwrap().root_bank();
+        drop(bank_forks);
+        if self.snapshot_config.should_generate_snapshots() {
+            match snapshot_utils::bank_to_full_snapshot_archive_with_accounts_index(
+                &root_bank,
+                &self.snapshot_config,
+            ) {
+                Ok(Some(full_snapshot_archive_info)) => info!(
+                    "Persisted the accounts index with full snapshot {}",
+                    full_snapshot_archive_info.path().display()
+                ),
+                Ok(None) => {}
+                Err(err) => warn!("Unable to persist the accounts index: {err}"),
+            }
+        }
+    }
+}
+
+/// Compare the accounts of the snapshot 'bank' was loaded from with the summary of the same
+/// snapshot on the RPC node it was fetched from, and report the bins that differ
+fn compare_accounts_hash_bins(bank: &Bank, rpc_peer_accounts_hash_bins: &AccountsHashBins) {
+    if rpc_peer_accounts_hash_bins.slot != bank.slot() {
+        warn!(
+            "Not comparing accounts hash bins: the RPC node summary is for slot {} but the \
+             snapshot was loaded at slot {}",
+            rpc_peer_accounts_hash_bins.slot,
+            bank.slot(),
+        );
+        return;
+    }
+    let num_bins = rpc_peer_accounts_hash_bins.bins.len();
+    if !num_bins.is_power_of_two() || num_bins > PUBKEY_BINS_FOR_CALCULATING_HASHES {
+        warn!("Not comparing accounts hash bins: the RPC node summary has {num_bins} bins");
+        return;
+    }
+
+    let mut measure = Measure::start("compare_accounts_hash_bins");
+    let accounts_hash_bins = bank.calculate_accounts_hash_bins(num_bins);
+    measure.stop();
+    let mismatched_bins = accounts_hash_bins
+        .mismatched_bins(rpc_peer_accounts_hash_bins)
+        .unwrap(); // unwrap here will never fail since the number of bins is the same
+    if mismatched_bins.is_empty() {
+        info!(
+            "Accounts at slot {} match the RPC node in all {num_bins} accounts hash bins, {measure}",
+            bank.slot(),
+        );
+    } else {
+        warn!(
+            "Accounts at slot {} differ from the RPC node in {} of {num_bins} accounts hash bins: \
+             {mismatched_bins:?}. Run `solana-ledger-tool accounts-hash-bins --halt-at-slot {} \
+             --bins {num_bins}` on both nodes, and compare the outputs to find the accounts",
+            bank.slot(),
+            mismatched_bins.len(),
+            bank.slot(),
+        );
     }
 }
 
@@ -1371,6 +1490,7 @@ fn load_blockstore(
     start_progress: &Arc<RwLock<ValidatorStartProgress>>,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     transaction_notifier: Option<TransactionNotifierLock>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     poh_timing_point_sender: Option<PohTimingSender>,
 ) -> Result<
     (
@@ -1458,6 +1578,9 @@ fn load_blockstore(
     let enable_rpc_transaction_history =
         config.rpc_addrs.is_some() && config.rpc_config.enable_rpc_transaction_history;
     let is_plugin_transaction_history_required = transaction_notifier.as_ref().is_some();
+    let account_history_retention_slots = (config.rpc_addrs.is_some()
+        && config.rpc_config.enable_rpc_account_history)
+        .then_some(config.rpc_config.account_history_retention_slots);
     let transaction_history_services =
         if enable_rpc_transaction_history || is_plugin_transaction_history_required {
             initialize_rpc_transaction_history_services(
@@ -1465,8 +1588,22 @@ fn load_blockstore(
                 exit,
                 enable_rpc_transaction_history,
                 config.rpc_config.enable_extended_tx_metadata_storage,
+                account_history_retention_slots,
                 transaction_notifier,
             )
+        } else if account_history_retention_slots.is_some() {
+            // Account history is recorded from the same frozen-bank feed as block metadata
+            let (cache_block_meta_sender, cache_block_meta_receiver) = unbounded();
+            TransactionHistoryServices {
+                cache_block_meta_sender: Some(cache_block_meta_sender),
+                cache_block_meta_service: Some(CacheBlockMetaService::new(
+                    cache_block_meta_receiver,
+                    blockstore.clone(),
+                    account_history_retention_slots,
+                    exit,
+                )),
+                ..TransactionHistoryServices::default()
+            }
         } else {
             TransactionHistoryServices::default()
         };
@@ -1482,6 +1619,7 @@ fn load_blockstore(
             transaction_history_services
                 .cache_block_meta_sender
                 .as_ref(),
+            entry_notification_sender,
             accounts_update_notifier,
             exit,
         );
@@ -1548,6 +1686,7 @@ pub struct ProcessBlockStore<'a> {
     process_options: &'a blockstore_processor::ProcessOptions,
     transaction_status_sender: Option<&'a TransactionStatusSender>,
     cache_block_meta_sender: Option<CacheBlockMetaSender>,
+    entry_notification_sender: Option<&'a EntryNotifierSender>,
     blockstore_root_scan: Option<BlockstoreRootScan>,
     accounts_background_request_sender: AbsRequestSender,
     config: &'a ValidatorConfig,
@@ -1567,6 +1706,7 @@ impl<'a> ProcessBlockStore<'a> {
         process_options: &'a blockstore_processor::ProcessOptions,
         transaction_status_sender: Option<&'a TransactionStatusSender>,
         cache_block_meta_sender: Option<CacheBlockMetaSender>,
+        entry_notification_sender: Option<&'a EntryNotifierSender>,
         blockstore_root_scan: BlockstoreRootScan,
         accounts_background_request_sender: AbsRequestSender,
         config: &'a ValidatorConfig,
@@ -1582,6 +1722,7 @@ impl<'a> ProcessBlockStore<'a> {
             process_options,
             transaction_status_sender,
             cache_block_meta_sender,
+            entry_notification_sender,
             blockstore_root_scan: Some(blockstore_root_scan),
             accounts_background_request_sender,
             config,
@@ -1619,6 +1760,7 @@ impl<'a> ProcessBlockStore<'a> {
                 self.process_options,
                 self.transaction_status_sender,
                 self.cache_block_meta_sender.as_ref(),
+                self.entry_notification_sender,
                 &self.accounts_background_request_sender,
             ) {
                 exit.store(true, Ordering::Relaxed);
@@ -1814,6 +1956,7 @@ fn initialize_rpc_transaction_history_services(
     exit: &Arc<AtomicBool>,
     enable_rpc_transaction_history: bool,
     enable_extended_tx_metadata_storage: bool,
+    account_history_retention_slots: Option<Slot>,
     transaction_notifier: Option<TransactionNotifierLock>,
 ) -> TransactionHistoryServices {
     let max_complete_transaction_status_slot = Arc::new(AtomicU64::new(blockstore.max_root()));
@@ -1844,6 +1987,7 @@ fn initialize_rpc_transaction_history_services(
     let cache_block_meta_service = Some(CacheBlockMetaService::new(
         cache_block_meta_receiver,
         blockstore,
+        account_history_retention_slots,
         exit,
     ));
     TransactionHistoryServices {
@@ -2055,6 +2199,7 @@ fn cleanup_accounts_paths(config: &ValidatorConfig) {
             move_and_async_delete_path(accounts_path);
         }
     }
+    // the cold storage paths are kept. Loading a snapshot replaces the storages on them.
 }
 
 pub fn is_snapshot_config_valid(
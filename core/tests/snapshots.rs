@@ -292,6 +292,7 @@ fn run_bank_forks_snapshot_n<F>(
         &snapshot_config.incremental_snapshot_archives_dir,
         snapshot_config.maximum_full_snapshot_archives_to_retain,
         snapshot_config.maximum_incremental_snapshot_archives_to_retain,
+        snapshot_config.archive_compressed_storages,
     )
     .unwrap();
 
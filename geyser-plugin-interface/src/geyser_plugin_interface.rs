@@ -71,6 +71,59 @@ pub struct ReplicaAccountInfoV2<'a> {
     pub txn_signature: Option<&'a Signature>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A range of account data bytes which differ from the previous version of the account
+pub struct ReplicaAccountDataRange<'a> {
+    /// The offset of the range in the account data
+    pub offset: usize,
+
+    /// The new bytes of the range
+    pub data: &'a [u8],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Information about an account being updated
+/// (extended with the changes against the previous version of the account)
+pub struct ReplicaAccountInfoV3<'a> {
+    /// The Pubkey for the account
+    pub pubkey: &'a [u8],
+
+    /// The lamports for the account
+    pub lamports: u64,
+
+    /// The Pubkey of the owner program account
+    pub owner: &'a [u8],
+
+    /// This account's data contains a loaded program (and is now read-only)
+    pub executable: bool,
+
+    /// The epoch at which this account will next owe rent
+    pub rent_epoch: u64,
+
+    /// The data held in this account.
+    pub data: &'a [u8],
+
+    /// A global monotonically increasing atomic number, which can be used
+    /// to tell the order of the account update. For example, when an
+    /// account is updated in the same slot multiple times, the update
+    /// with higher write_version should supersede the one with lower
+    /// write_version.
+    pub write_version: u64,
+
+    /// First signature of the transaction caused this account modification
+    pub txn_signature: Option<&'a Signature>,
+
+    /// The SHA-256 hash of the data of the previous version of the account,
+    /// which `data_diff` applies to. None when there is no previous version,
+    /// e.g. for a new account or an account restored from a snapshot.
+    pub previous_data_hash: Option<&'a [u8]>,
+
+    /// The ranges of `data` which differ from the previous version of the account.
+    /// Resizing the previous data to `data.len()` and writing the ranges over it
+    /// yields `data`. None when `previous_data_hash` is None.
+    pub data_diff: Option<&'a [ReplicaAccountDataRange<'a>]>,
+}
+
 /// A wrapper to future-proof ReplicaAccountInfo handling.
 /// If there were a change to the structure of ReplicaAccountInfo,
 /// there would be new enum entry for the newer version, forcing
@@ -78,6 +131,7 @@ pub struct ReplicaAccountInfoV2<'a> {
 pub enum ReplicaAccountInfoVersions<'a> {
     V0_0_1(&'a ReplicaAccountInfo<'a>),
     V0_0_2(&'a ReplicaAccountInfoV2<'a>),
+    V0_0_3(&'a ReplicaAccountInfoV3<'a>),
 }
 
 /// Information about a transaction
@@ -151,6 +205,25 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_2(&'a ReplicaBlockInfoV2<'a>),
 }
 
+/// Information about an Entry replayed in a block
+#[derive(Clone, Debug)]
+pub struct ReplicaEntryInfo<'a> {
+    /// The slot number of the block containing this Entry
+    pub slot: u64,
+    /// The Entry's index in the block
+    pub index: usize,
+    /// The number of hashes since the previous Entry
+    pub num_hashes: u64,
+    /// The Entry's SHA-256 PoH hash, generated from the previous Entry's hash
+    pub hash: &'a [u8],
+    /// The number of executed transactions in the Entry
+    pub executed_transaction_count: u64,
+}
+
+pub enum ReplicaEntryInfoVersions<'a> {
+    V0_0_1(&'a ReplicaEntryInfo<'a>),
+}
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 pub enum GeyserPluginError {
@@ -274,6 +347,12 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called when an entry is replayed.
+    #[allow(unused_variables)]
+    fn notify_entry(&mut self, entry: ReplicaEntryInfoVersions) -> Result<()> {
+        Ok(())
+    }
+
     /// Check if the plugin is interested in account data
     /// Default is true -- if the plugin is not interested in
     /// account data, please return false.
@@ -281,10 +360,25 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         true
     }
 
+    /// Check if the plugin is interested in account data diffs
+    /// Default is false -- if the plugin is interested in the
+    /// changes against the previous version of updated accounts,
+    /// return true to receive `ReplicaAccountInfoVersions::V0_0_3`.
+    fn account_data_diff_notifications_enabled(&self) -> bool {
+        false
+    }
+
     /// Check if the plugin is interested in transaction data
     /// Default is false -- if the plugin is not interested in
     /// transaction data, please return false.
     fn transaction_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// Check if the plugin is interested in entry data
+    /// Default is false -- if the plugin is interested in
+    /// entry data, return true.
+    fn entry_notifications_enabled(&self) -> bool {
+        false
+    }
 }
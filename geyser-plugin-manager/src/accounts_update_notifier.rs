@@ -3,7 +3,8 @@ use {
     crate::geyser_plugin_manager::GeyserPluginManager,
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::{
-        ReplicaAccountInfoV2, ReplicaAccountInfoVersions,
+        ReplicaAccountDataRange, ReplicaAccountInfoV2, ReplicaAccountInfoV3,
+        ReplicaAccountInfoVersions,
     },
     solana_measure::measure::Measure,
     solana_metrics::*,
@@ -14,11 +15,23 @@ use {
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount},
         clock::Slot,
+        hash::{hash, Hash},
         pubkey::Pubkey,
         signature::Signature,
     },
-    std::sync::{Arc, RwLock},
+    std::{
+        ops::Range,
+        sync::{Arc, RwLock},
+    },
 };
+
+/// Changed bytes separated by fewer unchanged bytes than this are sent as one range,
+/// as the offset of another range would cost about as much as the unchanged bytes
+const ACCOUNT_DATA_DIFF_MIN_GAP: usize = 16;
+
+/// Unchanged account data is skipped a chunk at a time before looking for changed bytes
+const ACCOUNT_DATA_DIFF_CHUNK_SIZE: usize = 64;
+
 #[derive(Debug)]
 pub(crate) struct AccountsUpdateNotifierImpl {
     plugin_manager: Arc<RwLock<GeyserPluginManager>>,
@@ -29,6 +42,7 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         &self,
         slot: Slot,
         account: &AccountSharedData,
+        previous_account: Option<&AccountSharedData>,
         txn_signature: &Option<&Signature>,
         pubkey: &Pubkey,
         write_version: u64,
@@ -36,10 +50,22 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         if let Some(account_info) =
             self.accountinfo_from_shared_account_data(account, txn_signature, pubkey, write_version)
         {
-            self.notify_plugins_of_account_update(account_info, slot, false);
+            self.notify_plugins_of_account_update(
+                account_info,
+                previous_account.map(|previous_account| previous_account.data()),
+                slot,
+                false,
+            );
         }
     }
 
+    fn previous_account_notifications_enabled(&self) -> bool {
+        self.plugin_manager
+            .read()
+            .unwrap()
+            .account_data_diff_notifications_enabled()
+    }
+
     fn notify_account_restore_from_snapshot(&self, slot: Slot, account: &StoredAccountMeta) {
         let mut measure_all = Measure::start("geyser-plugin-notify-account-restore-all");
         let mut measure_copy = Measure::start("geyser-plugin-copy-stored-account-info");
@@ -55,7 +81,7 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         );
 
         if let Some(account_info) = account {
-            self.notify_plugins_of_account_update(account_info, slot, true);
+            self.notify_plugins_of_account_update(account_info, None, slot, true);
         }
         measure_all.stop();
 
@@ -139,9 +165,12 @@ impl AccountsUpdateNotifierImpl {
         })
     }
 
+    /// The data of the account before the update is 'previous_data', if known, from which the
+    /// diff of the data is computed for the plugins that want it
     fn notify_plugins_of_account_update(
         &self,
         account: ReplicaAccountInfoV2,
+        previous_data: Option<&[u8]>,
         slot: Slot,
         is_startup: bool,
     ) {
@@ -151,13 +180,43 @@ impl AccountsUpdateNotifierImpl {
         if plugin_manager.plugins.is_empty() {
             return;
         }
+        // only computed once a plugin wanting data diffs selects the account
+        let mut data_diff = None;
         for plugin in plugin_manager.plugins.iter_mut() {
+            if !plugin
+                .filter
+                .wants_account(account.pubkey, account.owner, account.data)
+            {
+                inc_new_counter_debug!("geyser-plugin-update-account-filtered", 1);
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-update-account");
-            match plugin.update_account(
-                ReplicaAccountInfoVersions::V0_0_2(&account),
-                slot,
-                is_startup,
-            ) {
+            let account_v3;
+            let account_info = if plugin.account_data_diff_notifications_enabled() {
+                if data_diff.is_none() {
+                    data_diff = previous_data.map(|previous_data| {
+                        diff_account_data_with_hash(previous_data, account.data)
+                    });
+                }
+                let data_diff = data_diff.as_ref();
+                account_v3 = ReplicaAccountInfoV3 {
+                    pubkey: account.pubkey,
+                    lamports: account.lamports,
+                    owner: account.owner,
+                    executable: account.executable,
+                    rent_epoch: account.rent_epoch,
+                    data: account.data,
+                    write_version: account.write_version,
+                    txn_signature: account.txn_signature,
+                    previous_data_hash: data_diff
+                        .map(|(previous_data_hash, _)| previous_data_hash.as_ref()),
+                    data_diff: data_diff.map(|(_, ranges)| ranges.as_slice()),
+                };
+                ReplicaAccountInfoVersions::V0_0_3(&account_v3)
+            } else {
+                ReplicaAccountInfoVersions::V0_0_2(&account)
+            };
+            match plugin.update_account(account_info, slot, is_startup) {
                 Err(err) => {
                     error!(
                         "Failed to update account {} at slot {}, error: {} to plugin {}",
@@ -193,3 +252,145 @@ impl AccountsUpdateNotifierImpl {
         );
     }
 }
+
+/// The hash of `previous_data` and the ranges of `data` which differ from it
+fn diff_account_data_with_hash<'a>(
+    previous_data: &[u8],
+    data: &'a [u8],
+) -> (Hash, Vec<ReplicaAccountDataRange<'a>>) {
+    let mut measure = Measure::start("geyser-plugin-diff-account-data");
+    let data_diff = (hash(previous_data), diff_account_data(previous_data, data));
+    measure.stop();
+    inc_new_counter_debug!(
+        "geyser-plugin-diff-account-data-us",
+        measure.as_us() as usize,
+        100000,
+        100000
+    );
+    data_diff
+}
+
+/// Compute the ranges of `data` which differ from `previous_data`, including any bytes
+/// appended past the end of `previous_data`
+fn diff_account_data<'a>(previous_data: &[u8], data: &'a [u8]) -> Vec<ReplicaAccountDataRange<'a>> {
+    fn add_changed_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+        match ranges.last_mut() {
+            Some(last) if range.start < last.end + ACCOUNT_DATA_DIFF_MIN_GAP => {
+                last.end = range.end
+            }
+            _ => ranges.push(range),
+        }
+    }
+
+    let mut ranges = vec![];
+    let common_len = previous_data.len().min(data.len());
+    let chunks = previous_data[..common_len]
+        .chunks(ACCOUNT_DATA_DIFF_CHUNK_SIZE)
+        .zip(data[..common_len].chunks(ACCOUNT_DATA_DIFF_CHUNK_SIZE));
+    for (chunk_index, (previous_chunk, chunk)) in chunks.enumerate() {
+        if previous_chunk == chunk {
+            continue;
+        }
+        let chunk_offset = chunk_index * ACCOUNT_DATA_DIFF_CHUNK_SIZE;
+        for (i, (previous_byte, byte)) in previous_chunk.iter().zip(chunk).enumerate() {
+            if previous_byte != byte {
+                let offset = chunk_offset + i;
+                add_changed_range(&mut ranges, offset..offset + 1);
+            }
+        }
+    }
+    if data.len() > common_len {
+        add_changed_range(&mut ranges, common_len..data.len());
+    }
+
+    ranges
+        .into_iter()
+        .map(|range| ReplicaAccountDataRange {
+            offset: range.start,
+            data: &data[range],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply_account_data_diff(
+        previous_data: &[u8],
+        data_len: usize,
+        ranges: &[ReplicaAccountDataRange],
+    ) -> Vec<u8> {
+        let mut data = previous_data.to_vec();
+        data.resize(data_len, 0);
+        for range in ranges {
+            data[range.offset..range.offset + range.data.len()].copy_from_slice(range.data);
+        }
+        data
+    }
+
+    #[test]
+    fn test_diff_account_data_unchanged() {
+        let data = vec![7; 200];
+        assert!(diff_account_data(&data, &data).is_empty());
+        assert!(diff_account_data(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_diff_account_data_coalesces_close_changes() {
+        let previous_data = vec![0; 200];
+        let mut data = previous_data.clone();
+        data[10] = 1;
+        data[20] = 1;
+        data[150] = 1;
+
+        let ranges = diff_account_data(&previous_data, &data);
+        assert_eq!(
+            ranges,
+            vec![
+                ReplicaAccountDataRange {
+                    offset: 10,
+                    data: &data[10..21],
+                },
+                ReplicaAccountDataRange {
+                    offset: 150,
+                    data: &data[150..151],
+                },
+            ]
+        );
+        assert_eq!(
+            apply_account_data_diff(&previous_data, data.len(), &ranges),
+            data
+        );
+    }
+
+    #[test]
+    fn test_diff_account_data_resized() {
+        let previous_data = vec![0; 100];
+
+        // grown: the appended bytes are merged with a change right before them
+        let mut data = vec![0; 130];
+        data[95] = 1;
+        let ranges = diff_account_data(&previous_data, &data);
+        assert_eq!(
+            ranges,
+            vec![ReplicaAccountDataRange {
+                offset: 95,
+                data: &data[95..],
+            }]
+        );
+        assert_eq!(
+            apply_account_data_diff(&previous_data, data.len(), &ranges),
+            data
+        );
+
+        // shrunk: only the length changes
+        let data = vec![0; 60];
+        let ranges = diff_account_data(&previous_data, &data);
+        assert!(ranges.is_empty());
+        assert_eq!(
+            apply_account_data_diff(&previous_data, data.len(), &ranges),
+            data
+        );
+    }
+}
@@ -0,0 +1,358 @@
+/// Declarative filters evaluated by the plugin manager before calling into a plugin
+use {
+    serde::Deserialize,
+    solana_sdk::{pubkey::Pubkey, transaction::SanitizedTransaction},
+    std::{collections::HashSet, str::FromStr},
+    thiserror::Error,
+};
+
+/// The maximum number of bytes a single memcmp predicate can compare, which is the same limit
+/// as the one of RPC's memcmp filter.
+const MAX_MEMCMP_BYTES: usize = 128;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GeyserPluginFilterError {
+    #[error("Invalid pubkey: {0}")]
+    InvalidPubkey(String),
+
+    #[error("Invalid base58 memcmp bytes: {0}")]
+    InvalidMemcmpBytes(String),
+
+    #[error("Memcmp bytes are too long: {0} > {}", MAX_MEMCMP_BYTES)]
+    MemcmpBytesTooLong(usize),
+
+    #[error("Invalid data size range: min {0} > max {1}")]
+    InvalidDataSizeRange(u64, u64),
+}
+
+/// The `filter` section of a plugin config file.
+///
+/// ```json5
+/// filter: {
+///     accounts: {
+///         owners: ["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"],
+///         pubkeys: [],
+///         dataSize: { min: 165, max: 165 },
+///         memcmp: [{ offset: 32, bytes: "..." }],
+///     },
+///     transactions: {
+///         vote: false,
+///         mentions: ["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"],
+///     },
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GeyserPluginFilterConfig {
+    #[serde(default)]
+    pub accounts: Option<AccountsFilterConfig>,
+    #[serde(default)]
+    pub transactions: Option<TransactionsFilterConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccountsFilterConfig {
+    /// Accounts owned by any of these programs are selected
+    #[serde(default)]
+    pub owners: Vec<String>,
+    /// These accounts are selected regardless of their owners
+    #[serde(default)]
+    pub pubkeys: Vec<String>,
+    #[serde(default)]
+    pub data_size: Option<DataSizeRangeConfig>,
+    /// All of these predicates must match
+    #[serde(default)]
+    pub memcmp: Vec<MemcmpConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DataSizeRangeConfig {
+    #[serde(default)]
+    pub min: Option<u64>,
+    #[serde(default)]
+    pub max: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MemcmpConfig {
+    pub offset: usize,
+    /// base58 encoded bytes
+    pub bytes: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TransactionsFilterConfig {
+    /// `true` selects only vote transactions, `false` only non-vote transactions; both are
+    /// selected if unspecified
+    #[serde(default)]
+    pub vote: Option<bool>,
+    /// Transactions mentioning any of these accounts are selected
+    #[serde(default)]
+    pub mentions: Vec<String>,
+}
+
+/// A compiled [`GeyserPluginFilterConfig`]. The default filter selects everything.
+#[derive(Debug, Default)]
+pub struct GeyserPluginFilter {
+    accounts: Option<AccountsFilter>,
+    transactions: Option<TransactionsFilter>,
+}
+
+#[derive(Debug, Default)]
+struct AccountsFilter {
+    owners: HashSet<Pubkey>,
+    pubkeys: HashSet<Pubkey>,
+    min_data_size: Option<u64>,
+    max_data_size: Option<u64>,
+    memcmp: Vec<(usize, Vec<u8>)>,
+}
+
+#[derive(Debug, Default)]
+struct TransactionsFilter {
+    vote: Option<bool>,
+    mentions: HashSet<Pubkey>,
+}
+
+fn parse_pubkeys(pubkeys: &[String]) -> Result<HashSet<Pubkey>, GeyserPluginFilterError> {
+    pubkeys
+        .iter()
+        .map(|pubkey| {
+            Pubkey::from_str(pubkey)
+                .map_err(|_| GeyserPluginFilterError::InvalidPubkey(pubkey.clone()))
+        })
+        .collect()
+}
+
+impl GeyserPluginFilter {
+    pub fn new(config: &GeyserPluginFilterConfig) -> Result<Self, GeyserPluginFilterError> {
+        let accounts = config
+            .accounts
+            .as_ref()
+            .map(AccountsFilter::new)
+            .transpose()?;
+        let transactions = config
+            .transactions
+            .as_ref()
+            .map(TransactionsFilter::new)
+            .transpose()?;
+        Ok(Self {
+            accounts,
+            transactions,
+        })
+    }
+
+    pub fn wants_account(&self, pubkey: &[u8], owner: &[u8], data: &[u8]) -> bool {
+        self.accounts
+            .as_ref()
+            .map(|filter| filter.matches(pubkey, owner, data))
+            .unwrap_or(true)
+    }
+
+    pub fn wants_transaction(&self, is_vote: bool, transaction: &SanitizedTransaction) -> bool {
+        self.transactions
+            .as_ref()
+            .map(|filter| filter.matches(is_vote, transaction))
+            .unwrap_or(true)
+    }
+}
+
+impl AccountsFilter {
+    fn new(config: &AccountsFilterConfig) -> Result<Self, GeyserPluginFilterError> {
+        let (min_data_size, max_data_size) = match &config.data_size {
+            Some(DataSizeRangeConfig {
+                min: Some(min),
+                max: Some(max),
+            }) if min > max => {
+                return Err(GeyserPluginFilterError::InvalidDataSizeRange(*min, *max));
+            }
+            Some(DataSizeRangeConfig { min, max }) => (*min, *max),
+            None => (None, None),
+        };
+        let memcmp = config
+            .memcmp
+            .iter()
+            .map(|MemcmpConfig { offset, bytes }| {
+                let bytes = bs58::decode(bytes)
+                    .into_vec()
+                    .map_err(|_| GeyserPluginFilterError::InvalidMemcmpBytes(bytes.clone()))?;
+                if bytes.len() > MAX_MEMCMP_BYTES {
+                    return Err(GeyserPluginFilterError::MemcmpBytesTooLong(bytes.len()));
+                }
+                Ok((*offset, bytes))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            owners: parse_pubkeys(&config.owners)?,
+            pubkeys: parse_pubkeys(&config.pubkeys)?,
+            min_data_size,
+            max_data_size,
+            memcmp,
+        })
+    }
+
+    fn matches(&self, pubkey: &[u8], owner: &[u8], data: &[u8]) -> bool {
+        if !self.owners.is_empty() || !self.pubkeys.is_empty() {
+            let is_selected = |keys: &HashSet<Pubkey>, key: &[u8]| {
+                Pubkey::try_from(key)
+                    .map(|key| keys.contains(&key))
+                    .unwrap_or(false)
+            };
+            if !is_selected(&self.owners, owner) && !is_selected(&self.pubkeys, pubkey) {
+                return false;
+            }
+        }
+
+        let data_size = data.len() as u64;
+        if self
+            .min_data_size
+            .map(|min| data_size < min)
+            .unwrap_or(false)
+            || self
+                .max_data_size
+                .map(|max| data_size > max)
+                .unwrap_or(false)
+        {
+            return false;
+        }
+
+        self.memcmp.iter().all(|(offset, bytes)| {
+            offset
+                .checked_add(bytes.len())
+                .and_then(|end| data.get(*offset..end))
+                .map(|slice| slice == bytes.as_slice())
+                .unwrap_or(false)
+        })
+    }
+}
+
+impl TransactionsFilter {
+    fn new(config: &TransactionsFilterConfig) -> Result<Self, GeyserPluginFilterError> {
+        Ok(Self {
+            vote: config.vote,
+            mentions: parse_pubkeys(&config.mentions)?,
+        })
+    }
+
+    fn matches(&self, is_vote: bool, transaction: &SanitizedTransaction) -> bool {
+        if self.vote.map(|vote| vote != is_vote).unwrap_or(false) {
+            return false;
+        }
+
+        self.mentions.is_empty()
+            || transaction
+                .message()
+                .account_keys()
+                .iter()
+                .any(|key| self.mentions.contains(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            signature::{Keypair, Signer},
+            system_transaction,
+        },
+    };
+
+    fn new_filter(config: &str) -> Result<GeyserPluginFilter, GeyserPluginFilterError> {
+        let config: GeyserPluginFilterConfig = serde_json::from_str(config).unwrap();
+        GeyserPluginFilter::new(&config)
+    }
+
+    #[test]
+    fn test_default_filter_selects_everything() {
+        let filter = GeyserPluginFilter::default();
+        let pubkey = Pubkey::new_unique();
+        assert!(filter.wants_account(pubkey.as_ref(), pubkey.as_ref(), &[]));
+
+        let tx = SanitizedTransaction::from_transaction_for_tests(system_transaction::transfer(
+            &Keypair::new(),
+            &pubkey,
+            1,
+            Hash::default(),
+        ));
+        assert!(filter.wants_transaction(false, &tx));
+        assert!(filter.wants_transaction(true, &tx));
+    }
+
+    #[test]
+    fn test_accounts_filter() {
+        let owner = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let filter = new_filter(&format!(
+            r#"{{"accounts": {{
+                "owners": ["{owner}"],
+                "pubkeys": ["{pubkey}"],
+                "dataSize": {{"min": 2, "max": 4}},
+                "memcmp": [{{"offset": 1, "bytes": "{}"}}]
+            }}}}"#,
+            bs58::encode([7, 8]).into_string()
+        ))
+        .unwrap();
+
+        // selected by owner or pubkey
+        assert!(filter.wants_account(other.as_ref(), owner.as_ref(), &[0, 7, 8]));
+        assert!(filter.wants_account(pubkey.as_ref(), other.as_ref(), &[0, 7, 8]));
+        assert!(!filter.wants_account(other.as_ref(), other.as_ref(), &[0, 7, 8]));
+        // data size out of range
+        assert!(!filter.wants_account(pubkey.as_ref(), owner.as_ref(), &[0]));
+        assert!(!filter.wants_account(pubkey.as_ref(), owner.as_ref(), &[0, 7, 8, 0, 0]));
+        // memcmp mismatch or out of bounds
+        assert!(!filter.wants_account(pubkey.as_ref(), owner.as_ref(), &[0, 7, 9]));
+        assert!(!filter.wants_account(pubkey.as_ref(), owner.as_ref(), &[0, 7]));
+    }
+
+    #[test]
+    fn test_transactions_filter() {
+        let from = Keypair::new();
+        let to = Pubkey::new_unique();
+        let tx = SanitizedTransaction::from_transaction_for_tests(system_transaction::transfer(
+            &from,
+            &to,
+            1,
+            Hash::default(),
+        ));
+
+        let filter = new_filter(r#"{"transactions": {"vote": false}}"#).unwrap();
+        assert!(filter.wants_transaction(false, &tx));
+        assert!(!filter.wants_transaction(true, &tx));
+
+        let filter =
+            new_filter(&format!(r#"{{"transactions": {{"mentions": ["{to}"]}}}}"#)).unwrap();
+        assert!(filter.wants_transaction(false, &tx));
+        let filter = new_filter(&format!(
+            r#"{{"transactions": {{"mentions": ["{}"]}}}}"#,
+            Pubkey::new_unique()
+        ))
+        .unwrap();
+        assert!(!filter.wants_transaction(false, &tx));
+        assert!(filter.wants_account(from.pubkey().as_ref(), to.as_ref(), &[]));
+    }
+
+    #[test]
+    fn test_invalid_filter_config() {
+        assert_eq!(
+            new_filter(r#"{"accounts": {"owners": ["bogus"]}}"#).unwrap_err(),
+            GeyserPluginFilterError::InvalidPubkey("bogus".to_string())
+        );
+        assert_eq!(
+            new_filter(r#"{"accounts": {"dataSize": {"min": 3, "max": 2}}}"#).unwrap_err(),
+            GeyserPluginFilterError::InvalidDataSizeRange(3, 2)
+        );
+        assert_eq!(
+            new_filter(r#"{"accounts": {"memcmp": [{"offset": 0, "bytes": "0OIl"}]}}"#)
+                .unwrap_err(),
+            GeyserPluginFilterError::InvalidMemcmpBytes("0OIl".to_string())
+        );
+    }
+}
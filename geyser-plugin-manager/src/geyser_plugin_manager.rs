@@ -1,22 +1,99 @@
 /// Managing the Geyser plugins
 use {
+    crate::geyser_plugin_filter::GeyserPluginFilter,
+    crossbeam_channel::Sender,
     libloading::{Library, Symbol},
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
-    std::error::Error,
+    std::{
+        error::Error,
+        ops::{Deref, DerefMut},
+        sync::RwLock,
+    },
 };
 
+/// Requests to manage the loaded plugins while the validator keeps running, which are sent by
+/// the admin RPC service. Each request carries the sender for its result.
+#[derive(Debug)]
+pub enum GeyserPluginManagerRequest {
+    ListPlugins {
+        response_sender: Sender<Result<Vec<String>, String>>,
+    },
+    LoadPlugin {
+        config_file: String,
+        response_sender: Sender<Result<String, String>>,
+    },
+    UnloadPlugin {
+        name: String,
+        response_sender: Sender<Result<(), String>>,
+    },
+    ReloadPlugin {
+        name: String,
+        config_file: String,
+        response_sender: Sender<Result<(), String>>,
+    },
+}
+
+/// A loaded plugin, along with its filter and the library it was loaded from
+#[derive(Debug)]
+pub struct LoadedGeyserPlugin {
+    plugin: Box<dyn GeyserPlugin>,
+    /// Evaluated before calling into the plugin
+    pub filter: GeyserPluginFilter,
+    /// Declared after `plugin`, as the plugin must be dropped before its library
+    _lib: Library,
+}
+
+impl LoadedGeyserPlugin {
+    /// Fire the `on_load()` method of `plugin`, which is dropped along with `lib` if it fails
+    fn load(
+        mut plugin: Box<dyn GeyserPlugin>,
+        lib: Library,
+        config_file: &str,
+        filter: GeyserPluginFilter,
+    ) -> Result<Self, Box<dyn Error>> {
+        if let Err(err) = plugin.on_load(config_file) {
+            drop(plugin);
+            drop(lib);
+            return Err(err.into());
+        }
+        Ok(Self {
+            plugin,
+            filter,
+            _lib: lib,
+        })
+    }
+
+    /// Fire the `on_unload()` method of the plugin, then drop the plugin and its library
+    fn unload(mut self) {
+        info!("Unloading plugin for {:?}", self.name());
+        self.plugin.on_unload();
+    }
+}
+
+impl Deref for LoadedGeyserPlugin {
+    type Target = dyn GeyserPlugin;
+
+    fn deref(&self) -> &Self::Target {
+        self.plugin.as_ref()
+    }
+}
+
+impl DerefMut for LoadedGeyserPlugin {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.plugin.as_mut()
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct GeyserPluginManager {
-    pub plugins: Vec<Box<dyn GeyserPlugin>>,
-    libs: Vec<Library>,
+    pub plugins: Vec<LoadedGeyserPlugin>,
 }
 
 impl GeyserPluginManager {
     pub fn new() -> Self {
         GeyserPluginManager {
             plugins: Vec::default(),
-            libs: Vec::default(),
         }
     }
 
@@ -24,32 +101,161 @@ impl GeyserPluginManager {
     ///
     /// This function loads the dynamically linked library specified in the path. The library
     /// must do necessary initializations.
+    ///
+    /// Returns the name of the loaded plugin. Plugin names must be unique, so that a plugin can be
+    /// unloaded or reloaded by its name later.
+    ///
+    /// `plugin_manager` is not locked while the library is loaded and the plugin's `on_load()`
+    /// runs, so a slow plugin does not hold up the notifications to the other plugins.
     pub unsafe fn load_plugin(
-        &mut self,
+        plugin_manager: &RwLock<Self>,
+        libpath: &str,
+        config_file: &str,
+        filter: GeyserPluginFilter,
+    ) -> Result<String, Box<dyn Error>> {
+        let (plugin, lib) = Self::construct_plugin(libpath)?;
+        Self::add_plugin(plugin_manager, plugin, lib, config_file, filter)
+    }
+
+    /// # Safety
+    ///
+    /// This function loads the dynamically linked library specified in the path, like
+    /// `load_plugin()`.
+    ///
+    /// Replace the plugin named `name` with a new instance loaded from `libpath`. The new instance
+    /// is loaded before the current one is unloaded, so the current one keeps running if loading
+    /// the new one fails. Like in `load_plugin()`, `plugin_manager` is only locked to swap the
+    /// instances, not while either of them is loaded or unloaded.
+    pub unsafe fn reload_plugin(
+        plugin_manager: &RwLock<Self>,
+        name: &str,
         libpath: &str,
         config_file: &str,
+        filter: GeyserPluginFilter,
     ) -> Result<(), Box<dyn Error>> {
+        if !plugin_manager.read().unwrap().is_loaded(name) {
+            return Err(format!("No plugin named {name} is loaded").into());
+        }
+        let (plugin, lib) = Self::construct_plugin(libpath)?;
+        Self::replace_plugin(plugin_manager, name, plugin, lib, config_file, filter)
+    }
+
+    unsafe fn construct_plugin(
+        libpath: &str,
+    ) -> Result<(Box<dyn GeyserPlugin>, Library), Box<dyn Error>> {
         type PluginConstructor = unsafe fn() -> *mut dyn GeyserPlugin;
         let lib = Library::new(libpath)?;
         let constructor: Symbol<PluginConstructor> = lib.get(b"_create_plugin")?;
         let plugin_raw = constructor();
-        let mut plugin = Box::from_raw(plugin_raw);
-        plugin.on_load(config_file)?;
-        self.plugins.push(plugin);
-        self.libs.push(lib);
-        Ok(())
+        Ok((Box::from_raw(plugin_raw), lib))
     }
 
-    /// Unload all plugins and loaded plugin libraries, making sure to fire
-    /// their `on_plugin_unload()` methods so they can do any necessary cleanup.
-    pub fn unload(&mut self) {
-        for mut plugin in self.plugins.drain(..) {
-            info!("Unloading plugin for {:?}", plugin.name());
-            plugin.on_unload();
+    fn is_loaded(&self, name: &str) -> bool {
+        self.plugins.iter().any(|loaded| loaded.name() == name)
+    }
+
+    fn add_plugin(
+        plugin_manager: &RwLock<Self>,
+        plugin: Box<dyn GeyserPlugin>,
+        lib: Library,
+        config_file: &str,
+        filter: GeyserPluginFilter,
+    ) -> Result<String, Box<dyn Error>> {
+        let name = plugin.name().to_string();
+        if plugin_manager.read().unwrap().is_loaded(&name) {
+            // the constructed plugin must be dropped before its library
+            drop(plugin);
+            drop(lib);
+            return Err(format!("A plugin named {name} is already loaded").into());
+        }
+        let plugin = LoadedGeyserPlugin::load(plugin, lib, config_file, filter)?;
+
+        let mut locked_plugin_manager = plugin_manager.write().unwrap();
+        // a plugin of the same name may have been loaded in the meantime
+        if locked_plugin_manager.is_loaded(&name) {
+            drop(locked_plugin_manager);
+            plugin.unload();
+            return Err(format!("A plugin named {name} is already loaded").into());
         }
+        locked_plugin_manager.plugins.push(plugin);
+        Ok(name)
+    }
 
-        for lib in self.libs.drain(..) {
+    fn replace_plugin(
+        plugin_manager: &RwLock<Self>,
+        name: &str,
+        plugin: Box<dyn GeyserPlugin>,
+        lib: Library,
+        config_file: &str,
+        filter: GeyserPluginFilter,
+    ) -> Result<(), Box<dyn Error>> {
+        if !plugin_manager.read().unwrap().is_loaded(name) {
+            drop(plugin);
             drop(lib);
+            return Err(format!("No plugin named {name} is loaded").into());
+        }
+        if plugin.name() != name {
+            let new_name = plugin.name().to_string();
+            drop(plugin);
+            drop(lib);
+            return Err(format!("The new plugin is named {new_name} instead of {name}").into());
+        }
+        let plugin = LoadedGeyserPlugin::load(plugin, lib, config_file, filter)?;
+
+        let mut locked_plugin_manager = plugin_manager.write().unwrap();
+        // the plugin may have been unloaded in the meantime
+        let index = locked_plugin_manager
+            .plugins
+            .iter()
+            .position(|loaded| loaded.name() == name);
+        match index {
+            Some(index) => {
+                let old_plugin =
+                    std::mem::replace(&mut locked_plugin_manager.plugins[index], plugin);
+                drop(locked_plugin_manager);
+                old_plugin.unload();
+                Ok(())
+            }
+            None => {
+                drop(locked_plugin_manager);
+                plugin.unload();
+                Err(format!("No plugin named {name} is loaded").into())
+            }
+        }
+    }
+
+    /// The names of all the loaded plugins
+    pub fn list_plugins(&self) -> Vec<String> {
+        self.plugins
+            .iter()
+            .map(|plugin| plugin.name().to_string())
+            .collect()
+    }
+
+    /// Unload the plugin named `name` and its library, firing its `on_unload()` method first.
+    /// Returns false if no such plugin is loaded.
+    /// `plugin_manager` is only locked to remove the plugin, not while it is unloaded.
+    pub fn unload_plugin(plugin_manager: &RwLock<Self>, name: &str) -> bool {
+        let plugin = {
+            let mut plugin_manager = plugin_manager.write().unwrap();
+            match plugin_manager
+                .plugins
+                .iter()
+                .position(|plugin| plugin.name() == name)
+            {
+                Some(index) => plugin_manager.plugins.remove(index),
+                None => return false,
+            }
+        };
+        plugin.unload();
+        true
+    }
+
+    /// Unload all plugins and loaded plugin libraries, making sure to fire
+    /// their `on_plugin_unload()` methods so they can do any necessary cleanup.
+    pub fn unload(&mut self) {
+        for plugin in self.plugins.drain(..) {
+            plugin.unload();
         }
     }
 
@@ -72,4 +278,346 @@ impl GeyserPluginManager {
         }
         false
     }
+
+    /// Check if there is any plugin interested in account data diffs
+    pub fn account_data_diff_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.account_data_diff_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Check if there is any plugin interested in entry data
+    pub fn entry_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.entry_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::geyser_plugin_filter::{AccountsFilterConfig, GeyserPluginFilterConfig},
+        solana_geyser_plugin_interface::geyser_plugin_interface::{GeyserPluginError, Result},
+        solana_sdk::pubkey::Pubkey,
+        std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    /// A plugin that counts its loads and unloads, and fails to load with the config "fail"
+    #[derive(Debug)]
+    struct TestPlugin {
+        name: &'static str,
+        loaded: Arc<AtomicUsize>,
+        unloaded: Arc<AtomicUsize>,
+    }
+
+    impl TestPlugin {
+        fn new(name: &'static str) -> Self {
+            Self {
+                name,
+                loaded: Arc::default(),
+                unloaded: Arc::default(),
+            }
+        }
+    }
+
+    impl GeyserPlugin for TestPlugin {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn on_load(&mut self, config_file: &str) -> Result<()> {
+            if config_file == "fail" {
+                return Err(GeyserPluginError::ConfigFileReadError {
+                    msg: config_file.to_string(),
+                });
+            }
+            self.loaded.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn on_unload(&mut self) {
+            self.unloaded.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// A plugin that counts the loads and unloads during which the plugin manager is not locked
+    #[derive(Debug)]
+    struct LockCheckingPlugin {
+        plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+        unlocked_calls: Arc<AtomicUsize>,
+    }
+
+    impl LockCheckingPlugin {
+        fn count_if_unlocked(&self) {
+            if self.plugin_manager.try_write().is_ok() {
+                self.unlocked_calls.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    impl GeyserPlugin for LockCheckingPlugin {
+        fn name(&self) -> &'static str {
+            "lock_checking"
+        }
+
+        fn on_load(&mut self, _config_file: &str) -> Result<()> {
+            self.count_if_unlocked();
+            Ok(())
+        }
+
+        fn on_unload(&mut self) {
+            self.count_if_unlocked();
+        }
+    }
+
+    /// The test plugins live in the test binary itself, so they use its handle as their library
+    fn dummy_library() -> Library {
+        #[cfg(unix)]
+        let library = libloading::os::unix::Library::this();
+        #[cfg(windows)]
+        let library = libloading::os::windows::Library::this().unwrap();
+        Library::from(library)
+    }
+
+    #[test]
+    fn test_load_and_list_plugins() {
+        let plugin_manager = RwLock::new(GeyserPluginManager::new());
+        assert!(plugin_manager.read().unwrap().list_plugins().is_empty());
+
+        let plugin = TestPlugin::new("dummy");
+        let loaded = plugin.loaded.clone();
+        let name = GeyserPluginManager::add_plugin(
+            &plugin_manager,
+            Box::new(plugin),
+            dummy_library(),
+            "config",
+            GeyserPluginFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(name, "dummy");
+        assert_eq!(loaded.load(Ordering::Relaxed), 1);
+        GeyserPluginManager::add_plugin(
+            &plugin_manager,
+            Box::new(TestPlugin::new("another_dummy")),
+            dummy_library(),
+            "config",
+            GeyserPluginFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            plugin_manager.read().unwrap().list_plugins(),
+            vec!["dummy", "another_dummy"]
+        );
+        assert_eq!(plugin_manager.read().unwrap().plugins.len(), 2);
+
+        // plugin names are unique
+        assert!(GeyserPluginManager::add_plugin(
+            &plugin_manager,
+            Box::new(TestPlugin::new("dummy")),
+            dummy_library(),
+            "config",
+            GeyserPluginFilter::default(),
+        )
+        .is_err());
+        // a plugin failing to load is not added
+        assert!(GeyserPluginManager::add_plugin(
+            &plugin_manager,
+            Box::new(TestPlugin::new("failing_dummy")),
+            dummy_library(),
+            "fail",
+            GeyserPluginFilter::default(),
+        )
+        .is_err());
+        assert_eq!(
+            plugin_manager.read().unwrap().list_plugins(),
+            vec!["dummy", "another_dummy"]
+        );
+        assert_eq!(plugin_manager.read().unwrap().plugins.len(), 2);
+    }
+
+    #[test]
+    fn test_unload_plugin() {
+        let plugin_manager = RwLock::new(GeyserPluginManager::new());
+        let plugin = TestPlugin::new("dummy");
+        let unloaded = plugin.unloaded.clone();
+        GeyserPluginManager::add_plugin(
+            &plugin_manager,
+            Box::new(plugin),
+            dummy_library(),
+            "config",
+            GeyserPluginFilter::default(),
+        )
+        .unwrap();
+
+        assert!(!GeyserPluginManager::unload_plugin(
+            &plugin_manager,
+            "another_dummy"
+        ));
+        assert_eq!(unloaded.load(Ordering::Relaxed), 0);
+
+        // the filter of a plugin stays with it when another plugin is unloaded
+        let filter = GeyserPluginFilter::new(&GeyserPluginFilterConfig {
+            accounts: Some(AccountsFilterConfig {
+                owners: vec![Pubkey::new_unique().to_string()],
+                ..AccountsFilterConfig::default()
+            }),
+            transactions: None,
+        })
+        .unwrap();
+        GeyserPluginManager::add_plugin(
+            &plugin_manager,
+            Box::new(TestPlugin::new("another_dummy")),
+            dummy_library(),
+            "config",
+            filter,
+        )
+        .unwrap();
+        assert!(GeyserPluginManager::unload_plugin(&plugin_manager, "dummy"));
+        assert_eq!(unloaded.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            plugin_manager.read().unwrap().list_plugins(),
+            vec!["another_dummy"]
+        );
+        assert!(!plugin_manager.read().unwrap().plugins[0]
+            .filter
+            .wants_account(
+                Pubkey::new_unique().as_ref(),
+                Pubkey::new_unique().as_ref(),
+                &[],
+            ));
+        assert!(GeyserPluginManager::unload_plugin(
+            &plugin_manager,
+            "another_dummy"
+        ));
+        assert!(plugin_manager.read().unwrap().plugins.is_empty());
+        assert!(!GeyserPluginManager::unload_plugin(
+            &plugin_manager,
+            "dummy"
+        ));
+    }
+
+    #[test]
+    fn test_reload_plugin() {
+        let plugin_manager = RwLock::new(GeyserPluginManager::new());
+        let plugin = TestPlugin::new("dummy");
+        let old_unloaded = plugin.unloaded.clone();
+        GeyserPluginManager::add_plugin(
+            &plugin_manager,
+            Box::new(plugin),
+            dummy_library(),
+            "config",
+            GeyserPluginFilter::default(),
+        )
+        .unwrap();
+
+        // the current plugin keeps running when the new one fails to load
+        let plugin = TestPlugin::new("dummy");
+        let new_loaded = plugin.loaded.clone();
+        assert!(GeyserPluginManager::replace_plugin(
+            &plugin_manager,
+            "dummy",
+            Box::new(plugin),
+            dummy_library(),
+            "fail",
+            GeyserPluginFilter::default(),
+        )
+        .is_err());
+        assert_eq!(new_loaded.load(Ordering::Relaxed), 0);
+        assert_eq!(old_unloaded.load(Ordering::Relaxed), 0);
+        assert_eq!(plugin_manager.read().unwrap().list_plugins(), vec!["dummy"]);
+
+        // the new plugin must keep the name
+        assert!(GeyserPluginManager::replace_plugin(
+            &plugin_manager,
+            "dummy",
+            Box::new(TestPlugin::new("another_dummy")),
+            dummy_library(),
+            "config",
+            GeyserPluginFilter::default(),
+        )
+        .is_err());
+        assert_eq!(old_unloaded.load(Ordering::Relaxed), 0);
+
+        // only loaded plugins can be reloaded
+        assert!(GeyserPluginManager::replace_plugin(
+            &plugin_manager,
+            "another_dummy",
+            Box::new(TestPlugin::new("another_dummy")),
+            dummy_library(),
+            "config",
+            GeyserPluginFilter::default(),
+        )
+        .is_err());
+
+        let plugin = TestPlugin::new("dummy");
+        let new_loaded = plugin.loaded.clone();
+        let new_unloaded = plugin.unloaded.clone();
+        GeyserPluginManager::replace_plugin(
+            &plugin_manager,
+            "dummy",
+            Box::new(plugin),
+            dummy_library(),
+            "config",
+            GeyserPluginFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(new_loaded.load(Ordering::Relaxed), 1);
+        assert_eq!(old_unloaded.load(Ordering::Relaxed), 1);
+        assert_eq!(plugin_manager.read().unwrap().list_plugins(), vec!["dummy"]);
+        assert_eq!(plugin_manager.read().unwrap().plugins.len(), 1);
+
+        plugin_manager.write().unwrap().unload();
+        assert_eq!(new_unloaded.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_plugin_manager_unlocked_during_load_and_unload() {
+        let plugin_manager = Arc::new(RwLock::new(GeyserPluginManager::new()));
+        let unlocked_calls = Arc::<AtomicUsize>::default();
+        let new_plugin = || {
+            Box::new(LockCheckingPlugin {
+                plugin_manager: plugin_manager.clone(),
+                unlocked_calls: unlocked_calls.clone(),
+            })
+        };
+
+        GeyserPluginManager::add_plugin(
+            &plugin_manager,
+            new_plugin(),
+            dummy_library(),
+            "config",
+            GeyserPluginFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(unlocked_calls.load(Ordering::Relaxed), 1);
+
+        // both loading the new instance and unloading the old one
+        GeyserPluginManager::replace_plugin(
+            &plugin_manager,
+            "lock_checking",
+            new_plugin(),
+            dummy_library(),
+            "config",
+            GeyserPluginFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(unlocked_calls.load(Ordering::Relaxed), 3);
+
+        assert!(GeyserPluginManager::unload_plugin(
+            &plugin_manager,
+            "lock_checking"
+        ));
+        assert_eq!(unlocked_calls.load(Ordering::Relaxed), 4);
+    }
 }
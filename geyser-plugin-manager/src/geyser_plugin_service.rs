@@ -3,11 +3,16 @@ use {
         accounts_update_notifier::AccountsUpdateNotifierImpl,
         block_metadata_notifier::BlockMetadataNotifierImpl,
         block_metadata_notifier_interface::BlockMetadataNotifierLock,
-        geyser_plugin_manager::GeyserPluginManager, slot_status_notifier::SlotStatusNotifierImpl,
-        slot_status_observer::SlotStatusObserver, transaction_notifier::TransactionNotifierImpl,
+        entry_notifier::EntryNotifierImpl,
+        geyser_plugin_filter::{GeyserPluginFilter, GeyserPluginFilterConfig},
+        geyser_plugin_manager::{GeyserPluginManager, GeyserPluginManagerRequest},
+        slot_status_notifier::SlotStatusNotifierImpl,
+        slot_status_observer::SlotStatusObserver,
+        transaction_notifier::TransactionNotifierImpl,
     },
-    crossbeam_channel::Receiver,
+    crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender},
     log::*,
+    solana_ledger::entry_notifier_interface::EntryNotifierLock,
     solana_rpc::{
         optimistically_confirmed_bank_tracker::BankNotification,
         transaction_notifier_interface::TransactionNotifierLock,
@@ -17,8 +22,12 @@ use {
         fs::File,
         io::Read,
         path::{Path, PathBuf},
-        sync::{Arc, RwLock},
-        thread,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
     },
     thiserror::Error,
 };
@@ -42,15 +51,24 @@ pub enum GeyserPluginServiceError {
 
     #[error("Cannot load plugin shared library")]
     PluginLoadError(String),
+
+    #[error("The filter in the config file is invalid")]
+    InvalidFilter(String),
 }
 
+const PLUGIN_MANAGER_REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
+
 /// The service managing the Geyser plugin workflow.
 pub struct GeyserPluginService {
     slot_status_observer: Option<SlotStatusObserver>,
     plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+    plugin_manager_request_sender: Sender<GeyserPluginManagerRequest>,
+    plugin_manager_request_handler: JoinHandle<()>,
+    exit: Arc<AtomicBool>,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     transaction_notifier: Option<TransactionNotifierLock>,
     block_metadata_notifier: Option<BlockMetadataNotifierLock>,
+    entry_notifier: Option<EntryNotifierLock>,
 }
 
 impl GeyserPluginService {
@@ -64,27 +82,49 @@ impl GeyserPluginService {
     ///    (.so file) to be loaded. The shared library must implement the `GeyserPlugin`
     ///    trait. And the shared library shall export a `C` function `_create_plugin` which
     ///    shall create the implementation of `GeyserPlugin` and returns to the caller.
+    ///    The optional `filter` field declares which accounts and transactions are passed to
+    ///    the plugin. See [`GeyserPluginFilterConfig`] for its format.
     ///    The rest of the JSON fields' definition is up to to the concrete plugin implementation
     ///    It is usually used to configure the connection information for the external data store.
 
     pub fn new(
         confirmed_bank_receiver: Receiver<BankNotification>,
         geyser_plugin_config_files: &[PathBuf],
+    ) -> Result<Self, GeyserPluginServiceError> {
+        Self::new_with_options(confirmed_bank_receiver, false, geyser_plugin_config_files)
+    }
+
+    /// Creates and returns the GeyserPluginService, like [`GeyserPluginService::new`].
+    /// # Arguments
+    /// * `geyser_plugin_always_enabled` - Set up the account, transaction and entry notifiers even if
+    ///    none of the plugins loaded at startup is interested in them, so that plugins loaded
+    ///    later through [`GeyserPluginManagerRequest`]s receive the notifications as well.
+    pub fn new_with_options(
+        confirmed_bank_receiver: Receiver<BankNotification>,
+        geyser_plugin_always_enabled: bool,
+        geyser_plugin_config_files: &[PathBuf],
     ) -> Result<Self, GeyserPluginServiceError> {
         info!(
             "Starting GeyserPluginService from config files: {:?}",
             geyser_plugin_config_files
         );
-        let mut plugin_manager = GeyserPluginManager::new();
+        let plugin_manager = Arc::new(RwLock::new(GeyserPluginManager::new()));
 
         for geyser_plugin_config_file in geyser_plugin_config_files {
-            Self::load_plugin(&mut plugin_manager, geyser_plugin_config_file)?;
+            Self::load_plugin(&plugin_manager, geyser_plugin_config_file)?;
         }
-        let account_data_notifications_enabled =
-            plugin_manager.account_data_notifications_enabled();
-        let transaction_notifications_enabled = plugin_manager.transaction_notifications_enabled();
-
-        let plugin_manager = Arc::new(RwLock::new(plugin_manager));
+        let (
+            account_data_notifications_enabled,
+            transaction_notifications_enabled,
+            entry_notifications_enabled,
+        ) = {
+            let plugin_manager = plugin_manager.read().unwrap();
+            (
+                plugin_manager.account_data_notifications_enabled() || geyser_plugin_always_enabled,
+                plugin_manager.transaction_notifications_enabled() || geyser_plugin_always_enabled,
+                plugin_manager.entry_notifications_enabled() || geyser_plugin_always_enabled,
+            )
+        };
 
         let accounts_update_notifier: Option<AccountsUpdateNotifier> =
             if account_data_notifications_enabled {
@@ -103,10 +143,20 @@ impl GeyserPluginService {
                 None
             };
 
+        let entry_notifier: Option<EntryNotifierLock> = if entry_notifications_enabled {
+            let entry_notifier = EntryNotifierImpl::new(plugin_manager.clone());
+            Some(Arc::new(RwLock::new(entry_notifier)))
+        } else {
+            None
+        };
+
         let (slot_status_observer, block_metadata_notifier): (
             Option<SlotStatusObserver>,
             Option<BlockMetadataNotifierLock>,
-        ) = if account_data_notifications_enabled || transaction_notifications_enabled {
+        ) = if account_data_notifications_enabled
+            || transaction_notifications_enabled
+            || entry_notifications_enabled
+        {
             let slot_status_notifier = SlotStatusNotifierImpl::new(plugin_manager.clone());
             let slot_status_notifier = Arc::new(RwLock::new(slot_status_notifier));
             (
@@ -122,20 +172,127 @@ impl GeyserPluginService {
             (None, None)
         };
 
+        let exit = Arc::new(AtomicBool::new(false));
+        let (plugin_manager_request_sender, plugin_manager_request_receiver) = unbounded();
+        let plugin_manager_request_handler = Self::start_plugin_manager_request_handler(
+            plugin_manager.clone(),
+            plugin_manager_request_receiver,
+            exit.clone(),
+        );
+
         info!("Started GeyserPluginService");
         Ok(GeyserPluginService {
             slot_status_observer,
             plugin_manager,
+            plugin_manager_request_sender,
+            plugin_manager_request_handler,
+            exit,
             accounts_update_notifier,
             transaction_notifier,
             block_metadata_notifier,
+            entry_notifier,
         })
     }
 
+    fn start_plugin_manager_request_handler(
+        plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+        request_receiver: Receiver<GeyserPluginManagerRequest>,
+        exit: Arc<AtomicBool>,
+    ) -> JoinHandle<()> {
+        Builder::new()
+            .name("solGeyserPlgMgr".to_string())
+            .spawn(move || {
+                while !exit.load(Ordering::Relaxed) {
+                    match request_receiver.recv_timeout(PLUGIN_MANAGER_REQUEST_TIMEOUT) {
+                        Ok(request) => {
+                            Self::handle_plugin_manager_request(&plugin_manager, request)
+                        }
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+            })
+            .unwrap()
+    }
+
+    fn handle_plugin_manager_request(
+        plugin_manager: &RwLock<GeyserPluginManager>,
+        request: GeyserPluginManagerRequest,
+    ) {
+        // the requester may have gone away already; there's nothing to do about it
+        match request {
+            GeyserPluginManagerRequest::ListPlugins { response_sender } => {
+                let _ = response_sender.send(Ok(plugin_manager.read().unwrap().list_plugins()));
+            }
+            GeyserPluginManagerRequest::LoadPlugin {
+                config_file,
+                response_sender,
+            } => {
+                info!("Loading plugin from {config_file}");
+                let result = Self::load_plugin(plugin_manager, Path::new(&config_file))
+                    .map_err(|err| format!("{err:?}"));
+                let _ = response_sender.send(result);
+            }
+            GeyserPluginManagerRequest::UnloadPlugin {
+                name,
+                response_sender,
+            } => {
+                let result = if GeyserPluginManager::unload_plugin(plugin_manager, &name) {
+                    Ok(())
+                } else {
+                    Err(format!("No plugin named {name} is loaded"))
+                };
+                let _ = response_sender.send(result);
+            }
+            GeyserPluginManagerRequest::ReloadPlugin {
+                name,
+                config_file,
+                response_sender,
+            } => {
+                info!("Reloading plugin {name} from {config_file}");
+                let result = Self::reload_plugin(plugin_manager, &name, Path::new(&config_file))
+                    .map_err(|err| format!("{err:?}"));
+                let _ = response_sender.send(result);
+            }
+        }
+    }
+
     fn load_plugin(
-        plugin_manager: &mut GeyserPluginManager,
+        plugin_manager: &RwLock<GeyserPluginManager>,
+        geyser_plugin_config_file: &Path,
+    ) -> Result<String, GeyserPluginServiceError> {
+        let (libpath, config_file, filter) = Self::read_plugin_config(geyser_plugin_config_file)?;
+        unsafe {
+            GeyserPluginManager::load_plugin(plugin_manager, &libpath, &config_file, filter)
+                .map_err(|err| {
+                    let msg =
+                        format!("Failed to load the plugin library: {libpath:?}, error: {err:?}");
+                    GeyserPluginServiceError::PluginLoadError(msg)
+                })
+        }
+    }
+
+    fn reload_plugin(
+        plugin_manager: &RwLock<GeyserPluginManager>,
+        name: &str,
         geyser_plugin_config_file: &Path,
     ) -> Result<(), GeyserPluginServiceError> {
+        let (libpath, config_file, filter) = Self::read_plugin_config(geyser_plugin_config_file)?;
+        unsafe {
+            GeyserPluginManager::reload_plugin(plugin_manager, name, &libpath, &config_file, filter)
+                .map_err(|err| {
+                    let msg =
+                        format!("Failed to reload the plugin library: {libpath:?}, error: {err:?}");
+                    GeyserPluginServiceError::PluginLoadError(msg)
+                })
+        }
+    }
+
+    /// Returns the plugin library path, the config file path and the filter of the plugin
+    /// configured by `geyser_plugin_config_file`
+    fn read_plugin_config(
+        geyser_plugin_config_file: &Path,
+    ) -> Result<(String, String, GeyserPluginFilter), GeyserPluginServiceError> {
         let mut file = match File::open(geyser_plugin_config_file) {
             Ok(file) => file,
             Err(err) => {
@@ -174,19 +331,32 @@ impl GeyserPluginService {
             libpath = config_dir.join(libpath);
         }
 
+        let filter = match result.get("filter") {
+            None => GeyserPluginFilter::default(),
+            Some(filter) => {
+                let config: GeyserPluginFilterConfig = serde_json::from_value(filter.clone())
+                    .map_err(|err| {
+                        GeyserPluginServiceError::InvalidFilter(format!(
+                            "The filter in the config file {geyser_plugin_config_file:?} is malformed, error: {err:?}"
+                        ))
+                    })?;
+                GeyserPluginFilter::new(&config).map_err(|err| {
+                    GeyserPluginServiceError::InvalidFilter(format!(
+                        "The filter in the config file {geyser_plugin_config_file:?} is invalid, error: {err}"
+                    ))
+                })?
+            }
+        };
+
         let config_file = geyser_plugin_config_file
             .as_os_str()
             .to_str()
             .ok_or(GeyserPluginServiceError::InvalidPluginPath)?;
+        let libpath = libpath
+            .to_str()
+            .ok_or(GeyserPluginServiceError::InvalidPluginPath)?;
 
-        unsafe {
-            let result = plugin_manager.load_plugin(libpath.to_str().unwrap(), config_file);
-            if let Err(err) = result {
-                let msg = format!("Failed to load the plugin library: {libpath:?}, error: {err:?}");
-                return Err(GeyserPluginServiceError::PluginLoadError(msg));
-            }
-        }
-        Ok(())
+        Ok((libpath.to_string(), config_file.to_string(), filter))
     }
 
     pub fn get_accounts_update_notifier(&self) -> Option<AccountsUpdateNotifier> {
@@ -201,10 +371,22 @@ impl GeyserPluginService {
         self.block_metadata_notifier.clone()
     }
 
+    pub fn get_entry_notifier(&self) -> Option<EntryNotifierLock> {
+        self.entry_notifier.clone()
+    }
+
+    /// The sender of requests to list, load, unload or reload plugins while the service is
+    /// running
+    pub fn get_plugin_manager_request_sender(&self) -> Sender<GeyserPluginManagerRequest> {
+        self.plugin_manager_request_sender.clone()
+    }
+
     pub fn join(self) -> thread::Result<()> {
         if let Some(mut slot_status_observer) = self.slot_status_observer {
             slot_status_observer.join()?;
         }
+        self.exit.store(true, Ordering::Relaxed);
+        self.plugin_manager_request_handler.join()?;
         self.plugin_manager.write().unwrap().unload();
         Ok(())
     }
@@ -31,25 +31,34 @@ impl TransactionNotifier for TransactionNotifierImpl {
         transaction: &SanitizedTransaction,
     ) {
         let mut measure = Measure::start("geyser-plugin-notify_plugins_of_transaction_info");
-        let transaction_log_info = Self::build_replica_transaction_info(
-            index,
-            signature,
-            transaction_status_meta,
-            transaction,
-        );
-
         let mut plugin_manager = self.plugin_manager.write().unwrap();
 
         if plugin_manager.plugins.is_empty() {
             return;
         }
 
+        let is_vote = transaction.is_simple_vote_transaction();
+        // only built once a plugin's filter selects the transaction
+        let mut transaction_log_info = None;
         for plugin in plugin_manager.plugins.iter_mut() {
             if !plugin.transaction_notifications_enabled() {
                 continue;
             }
+            if !plugin.filter.wants_transaction(is_vote, transaction) {
+                inc_new_counter_debug!("geyser-plugin-notify-transaction-filtered", 1);
+                continue;
+            }
+            let transaction_log_info = transaction_log_info.get_or_insert_with(|| {
+                Self::build_replica_transaction_info(
+                    index,
+                    signature,
+                    is_vote,
+                    transaction_status_meta,
+                    transaction,
+                )
+            });
             match plugin.notify_transaction(
-                ReplicaTransactionInfoVersions::V0_0_2(&transaction_log_info),
+                ReplicaTransactionInfoVersions::V0_0_2(transaction_log_info),
                 slot,
             ) {
                 Err(err) => {
@@ -85,13 +94,14 @@ impl TransactionNotifierImpl {
     fn build_replica_transaction_info<'a>(
         index: usize,
         signature: &'a Signature,
+        is_vote: bool,
         transaction_status_meta: &'a TransactionStatusMeta,
         transaction: &'a SanitizedTransaction,
     ) -> ReplicaTransactionInfoV2<'a> {
         ReplicaTransactionInfoV2 {
             index,
             signature,
-            is_vote: transaction.is_simple_vote_transaction(),
+            is_vote,
             transaction,
             transaction_status_meta,
         }
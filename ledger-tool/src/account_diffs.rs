@@ -0,0 +1,288 @@
+//! Output of the accounts each transaction wrote, before and after executing it
+use {
+    crossbeam_channel::unbounded,
+    serde::Serialize,
+    serde_json::Value,
+    solana_account_decoder::{UiAccount, UiAccountData, UiAccountEncoding},
+    solana_cli_output::{OutputFormat, QuietDisplay, VerboseDisplay},
+    solana_ledger::blockstore_processor::{
+        TransactionStatusBatch, TransactionStatusMessage, TransactionStatusSender,
+    },
+    solana_runtime::bank::TransactionAccountDiff,
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        clock::Slot,
+        transaction::TransactionError,
+    },
+    std::{
+        collections::{BTreeSet, HashSet},
+        fmt::{self, Display, Formatter},
+        thread::{Builder, JoinHandle},
+    },
+};
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliAccountDiff {
+    pub pubkey: String,
+    pub pre: UiAccount,
+    pub post: UiAccount,
+    #[serde(skip_serializing)]
+    changed_data_bytes: usize,
+}
+
+impl CliAccountDiff {
+    fn new(account_diff: &TransactionAccountDiff) -> Self {
+        let encode = |account: &AccountSharedData| {
+            UiAccount::encode(
+                &account_diff.pubkey,
+                account,
+                UiAccountEncoding::JsonParsed,
+                None,
+                None,
+            )
+        };
+        let pre_data = account_diff.pre_account.data();
+        let post_data = account_diff.post_account.data();
+        let changed_data_bytes = pre_data
+            .iter()
+            .zip(post_data)
+            .filter(|(pre_byte, post_byte)| pre_byte != post_byte)
+            .count()
+            + pre_data.len().abs_diff(post_data.len());
+        Self {
+            pubkey: account_diff.pubkey.to_string(),
+            pre: encode(&account_diff.pre_account),
+            post: encode(&account_diff.post_account),
+            changed_data_bytes,
+        }
+    }
+
+    /// The changed fields of the account, with their values before and after. The data is
+    /// compared field by field when the owner program's account format is known, otherwise
+    /// only the number of changed bytes is reported.
+    fn changes(&self) -> Vec<(String, String, String)> {
+        let mut pre = serde_json::to_value(&self.pre).unwrap();
+        let mut post = serde_json::to_value(&self.post).unwrap();
+        let data_is_parsed = matches!(
+            (&self.pre.data, &self.post.data),
+            (UiAccountData::Json(_), UiAccountData::Json(_))
+        );
+        if !data_is_parsed {
+            pre.as_object_mut().unwrap().remove("data");
+            post.as_object_mut().unwrap().remove("data");
+        }
+
+        let mut changes = vec![];
+        diff_json_values(String::default(), &pre, &post, &mut changes);
+        if !data_is_parsed && self.changed_data_bytes > 0 {
+            changes.push((
+                "data".to_string(),
+                format!("{} bytes", self.pre.space.unwrap_or_default()),
+                format!(
+                    "{} bytes, {} changed",
+                    self.post.space.unwrap_or_default(),
+                    self.changed_data_bytes
+                ),
+            ));
+        }
+        changes
+    }
+}
+
+fn diff_json_values(
+    path: String,
+    pre: &Value,
+    post: &Value,
+    changes: &mut Vec<(String, String, String)>,
+) {
+    let child_path = |key: &dyn Display| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match (pre, post) {
+        (Value::Object(pre), Value::Object(post)) => {
+            let keys = pre.keys().chain(post.keys()).collect::<BTreeSet<_>>();
+            for key in keys {
+                diff_json_values(
+                    child_path(key),
+                    pre.get(key).unwrap_or(&Value::Null),
+                    post.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        (Value::Array(pre), Value::Array(post)) if pre.len() == post.len() => {
+            for (index, (pre, post)) in pre.iter().zip(post).enumerate() {
+                diff_json_values(child_path(&index), pre, post, changes);
+            }
+        }
+        (pre, post) if pre != post => changes.push((path, pre.to_string(), post.to_string())),
+        _ => {}
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTransactionAccountDiffs {
+    pub slot: Slot,
+    pub index: usize,
+    pub signature: String,
+    pub err: Option<TransactionError>,
+    pub accounts: Vec<CliAccountDiff>,
+}
+
+impl CliTransactionAccountDiffs {
+    pub fn from_batch(batch: &TransactionStatusBatch) -> Vec<Self> {
+        batch
+            .transactions
+            .iter()
+            .zip(&batch.execution_results)
+            .zip(&batch.transaction_indexes)
+            .filter_map(|((transaction, execution_details), index)| {
+                let execution_details = execution_details.as_ref()?;
+                let account_diffs = execution_details.account_diffs.as_ref()?;
+                Some(Self {
+                    slot: batch.bank.slot(),
+                    index: *index,
+                    signature: transaction.signature().to_string(),
+                    err: execution_details.status.clone().err(),
+                    accounts: account_diffs.iter().map(CliAccountDiff::new).collect(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl QuietDisplay for CliTransactionAccountDiffs {}
+impl VerboseDisplay for CliTransactionAccountDiffs {}
+
+impl Display for CliTransactionAccountDiffs {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Slot {} transaction {} {}: ",
+            self.slot, self.index, self.signature
+        )?;
+        match &self.err {
+            None => writeln!(f, "ok")?,
+            Some(err) => writeln!(f, "failed: {err}, only the fee is charged")?,
+        }
+        for account in &self.accounts {
+            let changes = account.changes();
+            if changes.is_empty() {
+                writeln!(f, "  {}: unchanged", account.pubkey)?;
+                continue;
+            }
+            writeln!(f, "  {}:", account.pubkey)?;
+            for (path, pre, post) in changes {
+                writeln!(f, "    {path}: {pre} -> {post}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Spawn a thread printing the account diffs of the transactions replayed in `slots`, or in
+/// every slot if None. The thread exits once the returned sender, to replay the blockstore
+/// with, is dropped.
+pub fn spawn_account_diffs_printer(
+    output_format: OutputFormat,
+    slots: Option<HashSet<Slot>>,
+) -> (TransactionStatusSender, JoinHandle<()>) {
+    let (sender, transaction_status_receiver) = unbounded();
+    let printer = Builder::new()
+        .name("solAcctDiffs".to_string())
+        .spawn(move || {
+            for message in transaction_status_receiver.iter() {
+                let TransactionStatusMessage::Batch(batch) = message else {
+                    continue;
+                };
+                if let Some(slots) = &slots {
+                    if !slots.contains(&batch.bank.slot()) {
+                        continue;
+                    }
+                }
+                for transaction_account_diffs in CliTransactionAccountDiffs::from_batch(&batch) {
+                    print!(
+                        "{}",
+                        output_format.formatted_string(&transaction_account_diffs)
+                    );
+                    if output_format != OutputFormat::Display {
+                        println!();
+                    }
+                }
+            }
+        })
+        .unwrap();
+    (TransactionStatusSender { sender }, printer)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{account::WritableAccount, pubkey::Pubkey},
+    };
+
+    #[test]
+    fn test_diff_json_values() {
+        let pre = serde_json::json!({
+            "lamports": 10,
+            "data": {"parsed": {"info": {"amount": "5", "owners": ["a", "b"]}}},
+            "removed": true,
+        });
+        let post = serde_json::json!({
+            "lamports": 7,
+            "data": {"parsed": {"info": {"amount": "5", "owners": ["a", "c"]}}},
+            "added": 1,
+        });
+        let mut changes = vec![];
+        diff_json_values(String::default(), &pre, &post, &mut changes);
+        assert_eq!(
+            changes,
+            vec![
+                ("added".to_string(), "null".to_string(), "1".to_string()),
+                (
+                    "data.parsed.info.owners.1".to_string(),
+                    "\"b\"".to_string(),
+                    "\"c\"".to_string()
+                ),
+                ("lamports".to_string(), "10".to_string(), "7".to_string()),
+                (
+                    "removed".to_string(),
+                    "true".to_string(),
+                    "null".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_account_diff_changes_binary_data() {
+        let owner = Pubkey::new_unique();
+        let pre_account = AccountSharedData::new(10, 4, &owner);
+        let mut post_account = AccountSharedData::new(12, 6, &owner);
+        post_account.data_as_mut_slice()[1] = 1;
+        let account_diff = CliAccountDiff::new(&TransactionAccountDiff {
+            pubkey: Pubkey::new_unique(),
+            pre_account,
+            post_account,
+        });
+        assert_eq!(
+            account_diff.changes(),
+            vec![
+                ("lamports".to_string(), "10".to_string(), "12".to_string()),
+                ("space".to_string(), "4".to_string(), "6".to_string()),
+                (
+                    "data".to_string(),
+                    "4 bytes".to_string(),
+                    "6 bytes, 3 changed".to_string()
+                ),
+            ]
+        );
+    }
+}
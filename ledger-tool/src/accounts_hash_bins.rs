@@ -0,0 +1,172 @@
+//! Comparing the accounts of two nodes or snapshots bin by bin, to find the accounts responsible
+//! for an accounts hash mismatch
+use {
+    serde::{Deserialize, Serialize},
+    solana_runtime::{
+        accounts_hash::{AccountHashEntry, AccountsHashBins},
+        bank::Bank,
+    },
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fs::File,
+        io::{BufReader, BufWriter},
+        path::Path,
+    },
+};
+
+/// Mismatched bins whose accounts are loaded when comparing, to keep the output manageable
+pub const MAX_MISMATCHED_BINS_WITH_ACCOUNTS: usize = 16;
+
+/// The accounts hash bins of a bank, along with the accounts of some of the bins
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsHashBinsOutput {
+    pub summary: AccountsHashBins,
+    /// accounts of the selected bins, by bin index
+    pub bin_accounts: BTreeMap<usize, Vec<AccountHashEntry>>,
+}
+
+impl AccountsHashBinsOutput {
+    pub fn new(summary: AccountsHashBins) -> Self {
+        Self {
+            summary,
+            bin_accounts: BTreeMap::default(),
+        }
+    }
+
+    /// load the accounts of 'bins' from 'bank'
+    pub fn add_bin_accounts(&mut self, bank: &Bank, bins: &BTreeSet<usize>) {
+        let num_bins = self.summary.bins.len();
+        for bin in bins {
+            self.bin_accounts
+                .entry(*bin)
+                .or_insert_with(|| bank.get_accounts_hash_bin_entries(num_bins, *bin));
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|err| format!("Unable to open {path:?}: {err}"))?;
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|err| format!("Unable to parse {path:?}: {err}"))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let file = File::create(path).map_err(|err| format!("Unable to create {path:?}: {err}"))?;
+        serde_json::to_writer(BufWriter::new(file), self)
+            .map_err(|err| format!("Unable to write {path:?}: {err}"))
+    }
+}
+
+/// Return the indexes of the bins of 'ours' that differ from 'theirs'
+pub fn mismatched_bins(
+    ours: &AccountsHashBins,
+    theirs: &AccountsHashBins,
+) -> Result<Vec<usize>, String> {
+    ours.mismatched_bins(theirs).ok_or_else(|| {
+        format!(
+            "Unable to compare {} bins to {} bins, use the same --bins value on both sides",
+            ours.bins.len(),
+            theirs.bins.len()
+        )
+    })
+}
+
+/// Print the bins in 'mismatched' and, where both sides have them, the accounts that differ
+pub fn print_mismatched_bins(
+    ours: &AccountsHashBinsOutput,
+    theirs: &AccountsHashBinsOutput,
+    mismatched: &[usize],
+) {
+    if ours.summary.slot != theirs.summary.slot {
+        println!(
+            "Warning: comparing slot {} to slot {}",
+            ours.summary.slot, theirs.summary.slot
+        );
+    }
+    println!(
+        "Root: {} (theirs: {}), {} accounts (theirs: {}), {} lamports (theirs: {})",
+        ours.summary.root,
+        theirs.summary.root,
+        ours.summary.num_accounts(),
+        theirs.summary.num_accounts(),
+        ours.summary.lamports(),
+        theirs.summary.lamports(),
+    );
+    println!(
+        "{} of {} bins differ",
+        mismatched.len(),
+        ours.summary.bins.len()
+    );
+    for bin in mismatched {
+        let our_bin = &ours.summary.bins[*bin];
+        let their_bin = &theirs.summary.bins[*bin];
+        println!(
+            "Bin {bin}: hash {} (theirs: {}), {} accounts (theirs: {}), {} lamports (theirs: {})",
+            our_bin.hash,
+            their_bin.hash,
+            our_bin.num_accounts,
+            their_bin.num_accounts,
+            our_bin.lamports,
+            their_bin.lamports,
+        );
+        let (our_accounts, their_accounts) =
+            match (ours.bin_accounts.get(bin), theirs.bin_accounts.get(bin)) {
+                (Some(our_accounts), Some(their_accounts)) => (our_accounts, their_accounts),
+                _ => {
+                    println!(
+                        "  Accounts are not available on both sides, rerun both with --bin {bin}"
+                    );
+                    continue;
+                }
+            };
+        for (our_account, their_account) in
+            AccountsHashBins::mismatched_accounts(our_accounts, their_accounts)
+        {
+            let pubkey = our_account.or(their_account).unwrap().pubkey;
+            let describe = |account: Option<&AccountHashEntry>| {
+                account
+                    .map(|account| format!("hash {} lamports {}", account.hash, account.lamports))
+                    .unwrap_or_else(|| "missing".to_string())
+            };
+            println!(
+                "  {pubkey}: {} (theirs: {})",
+                describe(our_account),
+                describe(their_account)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_runtime::accounts_hash::AccountsHashBin,
+        solana_sdk::{hash::Hash, pubkey::Pubkey},
+    };
+
+    #[test]
+    fn test_accounts_hash_bins_output_json() {
+        let accounts = vec![AccountHashEntry {
+            pubkey: Pubkey::new_unique(),
+            hash: Hash::new_unique(),
+            lamports: 42,
+        }];
+        let summary = AccountsHashBins::new(
+            7,
+            vec![AccountsHashBin::new(&accounts), AccountsHashBin::new(&[])],
+        );
+        let mut output = AccountsHashBinsOutput::new(summary.clone());
+        output.bin_accounts.insert(0, accounts);
+
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            serde_json::from_str::<AccountsHashBinsOutput>(&json).unwrap(),
+            output
+        );
+
+        assert_eq!(mismatched_bins(&summary, &summary), Ok(vec![]));
+        let other = AccountsHashBins::new(7, vec![AccountsHashBin::new(&[])]);
+        assert!(mismatched_bins(&summary, &other).is_err());
+    }
+}
@@ -0,0 +1,434 @@
+//! Finding the first slot whose bank hash diverges from a reference, and capturing what went
+//! into the bank hash of that slot
+use {
+    crossbeam_channel::{unbounded, Receiver},
+    serde::{Deserialize, Serialize},
+    solana_account_decoder::{UiAccount, UiAccountEncoding},
+    solana_ledger::{
+        blockstore::Blockstore,
+        blockstore_processor::{TransactionStatusMessage, TransactionStatusSender},
+    },
+    solana_runtime::bank::Bank,
+    solana_sdk::{clock::Slot, hash::Hash, transaction::TransactionError},
+    std::{
+        collections::{BTreeMap, HashMap},
+        fmt::{self, Display, Formatter},
+        fs::File,
+        io::{BufRead, BufReader},
+        path::Path,
+        str::FromStr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+    },
+};
+
+/// Known-good bank hashes, by slot
+pub type ReferenceBankHashes = BTreeMap<Slot, Hash>;
+
+/// Load reference bank hashes from a file with one `<slot> <bank hash>` pair per line.
+/// Empty lines and lines starting with `#` are ignored.
+pub fn load_reference_bank_hashes_from_file(path: &Path) -> Result<ReferenceBankHashes, String> {
+    let file = File::open(path).map_err(|err| format!("Unable to open {path:?}: {err}"))?;
+    parse_reference_bank_hashes(BufReader::new(file))
+}
+
+fn parse_reference_bank_hashes(reader: impl BufRead) -> Result<ReferenceBankHashes, String> {
+    let mut reference_bank_hashes = ReferenceBankHashes::new();
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|err| format!("Unable to read line {}: {err}", line_index + 1))?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(|c: char| c.is_whitespace() || c == ',');
+        let fields = (
+            fields.next(),
+            fields.find(|field| !field.is_empty()),
+            fields.find(|field| !field.is_empty()),
+        );
+        let (Some(slot), Some(bank_hash), None) = fields else {
+            return Err(format!(
+                "Line {}: expected `<slot> <bank hash>`, found `{line}`",
+                line_index + 1
+            ));
+        };
+        let slot = Slot::from_str(slot)
+            .map_err(|err| format!("Line {}: invalid slot `{slot}`: {err}", line_index + 1))?;
+        let bank_hash = Hash::from_str(bank_hash).map_err(|err| {
+            format!(
+                "Line {}: invalid bank hash `{bank_hash}`: {err}",
+                line_index + 1
+            )
+        })?;
+        reference_bank_hashes.insert(slot, bank_hash);
+    }
+    Ok(reference_bank_hashes)
+}
+
+/// Load the bank hashes recorded in the blockstore of another node
+pub fn load_reference_bank_hashes_from_blockstore(
+    blockstore: &Blockstore,
+) -> Result<ReferenceBankHashes, String> {
+    let slot_meta_iterator = blockstore
+        .slot_meta_iterator(0)
+        .map_err(|err| format!("Unable to iterate over the reference ledger: {err:?}"))?;
+    Ok(slot_meta_iterator
+        .filter_map(|(slot, _meta)| Some((slot, blockstore.get_bank_hash(slot)?)))
+        .collect())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BankHashTransaction {
+    pub index: usize,
+    pub signature: String,
+    /// None if the transaction was not executed, e.g. because its accounts failed to load
+    pub executed: Option<bool>,
+    pub err: Option<TransactionError>,
+    pub executed_units: Option<u64>,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    pub log_messages: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BankHashAccount {
+    pub pubkey: String,
+    pub hash: String,
+    pub account: UiAccount,
+}
+
+/// The inputs of the bank hash of a slot: its accounts delta and the transactions producing it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BankHashDetails {
+    pub slot: Slot,
+    pub bank_hash: String,
+    pub reference_bank_hash: Option<String>,
+    pub parent_bank_hash: String,
+    pub accounts_delta_hash: Option<String>,
+    pub signature_count: u64,
+    pub last_blockhash: String,
+    pub transactions: Vec<BankHashTransaction>,
+    pub accounts: Vec<BankHashAccount>,
+}
+
+impl BankHashDetails {
+    fn new(
+        bank: &Bank,
+        reference_bank_hash: Option<&Hash>,
+        transactions: Vec<BankHashTransaction>,
+    ) -> Self {
+        let accounts = bank
+            .get_all_accounts_modified_since_parent()
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        let mut accounts_delta_hashes = bank.get_accounts_delta_hashes();
+        accounts_delta_hashes.sort_unstable_by_key(|(pubkey, _hash)| *pubkey);
+        let accounts = accounts_delta_hashes
+            .into_iter()
+            .filter_map(|(pubkey, hash)| {
+                let account = accounts.get(&pubkey)?;
+                Some(BankHashAccount {
+                    pubkey: pubkey.to_string(),
+                    hash: hash.to_string(),
+                    account: UiAccount::encode(
+                        &pubkey,
+                        account,
+                        UiAccountEncoding::Base64,
+                        None,
+                        None,
+                    ),
+                })
+            })
+            .collect();
+
+        Self {
+            slot: bank.slot(),
+            bank_hash: bank.hash().to_string(),
+            reference_bank_hash: reference_bank_hash.map(Hash::to_string),
+            parent_bank_hash: bank.parent_hash().to_string(),
+            accounts_delta_hash: bank
+                .rc
+                .accounts
+                .accounts_db
+                .get_accounts_delta_hash(bank.slot())
+                .map(|accounts_delta_hash| accounts_delta_hash.0.to_string()),
+            signature_count: bank.signature_count(),
+            last_blockhash: bank.last_blockhash().to_string(),
+            transactions,
+            accounts,
+        }
+    }
+
+    /// Compare the accounts delta against the details of the same slot on another node,
+    /// returning the accounts whose hashes differ along with the hash on each side
+    pub fn diff_account_hashes<'a>(
+        &'a self,
+        reference: &'a BankHashDetails,
+    ) -> Vec<(&'a str, Option<&'a str>, Option<&'a str>)> {
+        let mut hashes = BTreeMap::<&str, (Option<&str>, Option<&str>)>::new();
+        for account in &self.accounts {
+            hashes.entry(&account.pubkey).or_default().0 = Some(&account.hash);
+        }
+        for account in &reference.accounts {
+            hashes.entry(&account.pubkey).or_default().1 = Some(&account.hash);
+        }
+        hashes
+            .into_iter()
+            .filter(|(_pubkey, (hash, reference_hash))| hash != reference_hash)
+            .map(|(pubkey, (hash, reference_hash))| (pubkey, hash, reference_hash))
+            .collect()
+    }
+}
+
+impl Display for BankHashDetails {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "Slot {}", self.slot)?;
+        writeln!(f, "  bank hash: {}", self.bank_hash)?;
+        if let Some(reference_bank_hash) = &self.reference_bank_hash {
+            writeln!(f, "  reference bank hash: {reference_bank_hash}")?;
+        }
+        writeln!(f, "  parent bank hash: {}", self.parent_bank_hash)?;
+        writeln!(
+            f,
+            "  accounts delta hash: {}",
+            self.accounts_delta_hash.as_deref().unwrap_or("unknown")
+        )?;
+        writeln!(f, "  signature count: {}", self.signature_count)?;
+        writeln!(f, "  last blockhash: {}", self.last_blockhash)?;
+
+        writeln!(f, "Transactions: {}", self.transactions.len())?;
+        for transaction in &self.transactions {
+            let status = match (transaction.executed, &transaction.err) {
+                (None, _) => "not executed".to_string(),
+                (Some(_), None) => "ok".to_string(),
+                (Some(_), Some(err)) => format!("failed: {err}"),
+            };
+            writeln!(
+                f,
+                "  {:>5} {}: {status}, {} compute units",
+                transaction.index,
+                transaction.signature,
+                transaction.executed_units.unwrap_or_default()
+            )?;
+            writeln!(
+                f,
+                "        balances: {:?} -> {:?}",
+                transaction.pre_balances, transaction.post_balances
+            )?;
+            for log_message in transaction.log_messages.iter().flatten() {
+                writeln!(f, "        {log_message}")?;
+            }
+        }
+
+        writeln!(f, "Accounts delta: {}", self.accounts.len())?;
+        for account in &self.accounts {
+            writeln!(
+                f,
+                "  {} hash: {} lamports: {} owner: {} executable: {} rent epoch: {} data len: {}",
+                account.pubkey,
+                account.hash,
+                account.account.lamports,
+                account.account.owner,
+                account.account.executable,
+                account.account.rent_epoch,
+                account.account.space.unwrap_or_default(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares each replayed bank against the reference bank hashes, capturing the details of
+/// the first one which diverges
+pub struct BankHashBisector {
+    reference_bank_hashes: ReferenceBankHashes,
+    /// A slot to capture the details of regardless of the reference, for use as a reference
+    /// on another node
+    capture_slot: Option<Slot>,
+    transaction_status_sender: TransactionStatusSender,
+    transaction_status_receiver: Receiver<TransactionStatusMessage>,
+    compared_slot_count: AtomicUsize,
+    divergence: Mutex<Option<BankHashDetails>>,
+}
+
+impl BankHashBisector {
+    pub fn new(reference_bank_hashes: ReferenceBankHashes, capture_slot: Option<Slot>) -> Self {
+        let (sender, transaction_status_receiver) = unbounded();
+        Self {
+            reference_bank_hashes,
+            capture_slot,
+            transaction_status_sender: TransactionStatusSender { sender },
+            transaction_status_receiver,
+            compared_slot_count: AtomicUsize::default(),
+            divergence: Mutex::default(),
+        }
+    }
+
+    /// The last slot worth replaying
+    pub fn halt_slot(&self) -> Option<Slot> {
+        self.reference_bank_hashes
+            .keys()
+            .next_back()
+            .copied()
+            .max(self.capture_slot)
+    }
+
+    /// Sender to replay the blockstore with, to capture the results of each transaction
+    pub fn transaction_status_sender(&self) -> &TransactionStatusSender {
+        &self.transaction_status_sender
+    }
+
+    pub fn compared_slot_count(&self) -> usize {
+        self.compared_slot_count.load(Ordering::Relaxed)
+    }
+
+    pub fn take_divergence(&self) -> Option<BankHashDetails> {
+        self.divergence.lock().unwrap().take()
+    }
+
+    /// Check a frozen bank, returning true if processing should halt at it
+    pub fn check_bank(&self, bank: &Bank) -> bool {
+        // The statuses of a slot are all sent before its bank is frozen, so anything left
+        // over belongs to earlier slots
+        let transactions = self.take_transactions(bank.slot());
+
+        let reference_bank_hash = self.reference_bank_hashes.get(&bank.slot());
+        if reference_bank_hash.is_some() {
+            self.compared_slot_count.fetch_add(1, Ordering::Relaxed);
+        }
+        let diverged = reference_bank_hash
+            .map(|reference_bank_hash| *reference_bank_hash != bank.hash())
+            .unwrap_or(false);
+        if diverged || self.capture_slot == Some(bank.slot()) {
+            *self.divergence.lock().unwrap() = Some(BankHashDetails::new(
+                bank,
+                reference_bank_hash,
+                transactions,
+            ));
+            return true;
+        }
+        false
+    }
+
+    fn take_transactions(&self, slot: Slot) -> Vec<BankHashTransaction> {
+        let mut transactions = vec![];
+        for message in self.transaction_status_receiver.try_iter() {
+            let TransactionStatusMessage::Batch(batch) = message else {
+                continue;
+            };
+            if batch.bank.slot() != slot {
+                continue;
+            }
+            for (i, transaction) in batch.transactions.iter().enumerate() {
+                let execution_details = batch.execution_results[i].as_ref();
+                transactions.push(BankHashTransaction {
+                    index: batch.transaction_indexes[i],
+                    signature: transaction.signature().to_string(),
+                    executed: execution_details.map(|_| true),
+                    err: execution_details.and_then(|details| details.status.clone().err()),
+                    executed_units: execution_details.map(|details| details.executed_units),
+                    pre_balances: batch.balances.pre_balances[i].clone(),
+                    post_balances: batch.balances.post_balances[i].clone(),
+                    log_messages: execution_details
+                        .and_then(|details| details.log_messages.clone()),
+                });
+            }
+        }
+        transactions.sort_unstable_by_key(|transaction| transaction.index);
+        transactions
+    }
+}
+
+/// Display the accounts whose hashes differ from the details of the same slot on another node
+pub fn format_account_hash_diffs(details: &BankHashDetails, reference: &BankHashDetails) -> String {
+    let diffs = details.diff_account_hashes(reference);
+    let mut output = format!("Accounts with differing hashes: {}\n", diffs.len());
+    for (pubkey, hash, reference_hash) in diffs {
+        output.push_str(&format!(
+            "  {pubkey} hash: {} reference hash: {}\n",
+            hash.unwrap_or("missing"),
+            reference_hash.unwrap_or("missing"),
+        ));
+    }
+    output
+}
+
+/// Load bank hash details written as JSON by another node, for `format_account_hash_diffs`
+pub fn load_bank_hash_details(path: &Path) -> Result<BankHashDetails, String> {
+    let file = File::open(path).map_err(|err| format!("Unable to open {path:?}: {err}"))?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| format!("Unable to parse {path:?}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::pubkey::Pubkey};
+
+    #[test]
+    fn test_parse_reference_bank_hashes() {
+        let hash1 = Hash::new_unique();
+        let hash2 = Hash::new_unique();
+        let input = format!("# slot bank-hash\n\n10 {hash1}\n 12,\t{hash2} \n");
+        let reference_bank_hashes = parse_reference_bank_hashes(input.as_bytes()).unwrap();
+        assert_eq!(
+            reference_bank_hashes,
+            ReferenceBankHashes::from([(10, hash1), (12, hash2)])
+        );
+
+        assert!(parse_reference_bank_hashes("10\n".as_bytes()).is_err());
+        assert!(parse_reference_bank_hashes(format!("x {hash1}\n").as_bytes()).is_err());
+        assert!(parse_reference_bank_hashes("10 not-a-hash\n".as_bytes()).is_err());
+        assert!(parse_reference_bank_hashes(format!("10 {hash1} 11\n").as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_diff_account_hashes() {
+        let account = |pubkey: &str, hash: &str| BankHashAccount {
+            pubkey: pubkey.to_string(),
+            hash: hash.to_string(),
+            account: UiAccount::encode(
+                &Pubkey::default(),
+                &solana_sdk::account::AccountSharedData::default(),
+                UiAccountEncoding::Base64,
+                None,
+                None,
+            ),
+        };
+        let details = |accounts| BankHashDetails {
+            slot: 1,
+            bank_hash: String::default(),
+            reference_bank_hash: None,
+            parent_bank_hash: String::default(),
+            accounts_delta_hash: None,
+            signature_count: 0,
+            last_blockhash: String::default(),
+            transactions: vec![],
+            accounts,
+        };
+
+        let details_a = details(vec![
+            account("a", "1"),
+            account("b", "2"),
+            account("c", "3"),
+        ]);
+        let details_b = details(vec![
+            account("a", "1"),
+            account("b", "4"),
+            account("d", "5"),
+        ]);
+        assert_eq!(
+            details_a.diff_account_hashes(&details_b),
+            vec![
+                ("b", Some("2"), Some("4")),
+                ("c", Some("3"), None),
+                ("d", None, Some("5")),
+            ]
+        );
+        assert!(details_a.diff_account_hashes(&details_a).is_empty());
+    }
+}
@@ -1,9 +1,11 @@
 #![allow(clippy::integer_arithmetic)]
 use {
-    crate::{bigtable::*, ledger_path::*, output::*},
+    crate::{
+        account_diffs::*, accounts_hash_bins::*, bigtable::*, bisect::*, ledger_path::*, output::*,
+    },
     chrono::{DateTime, Utc},
     clap::{
-        crate_description, crate_name, value_t, value_t_or_exit, values_t_or_exit, App,
+        crate_description, crate_name, value_t, value_t_or_exit, values_t, values_t_or_exit, App,
         AppSettings, Arg, ArgMatches, SubCommand,
     },
     crossbeam_channel::unbounded,
@@ -24,7 +26,10 @@ use {
         },
     },
     solana_cli_output::{CliAccount, CliAccountNewConfig, OutputFormat},
-    solana_core::system_monitor_service::{SystemMonitorService, SystemMonitorStatsReportConfig},
+    solana_core::{
+        banking_simulation::{BankingSimulator, BankingTraceEvents},
+        system_monitor_service::{SystemMonitorService, SystemMonitorStatsReportConfig},
+    },
     solana_entry::entry::Entry,
     solana_geyser_plugin_manager::geyser_plugin_service::GeyserPluginService,
     solana_ledger::{
@@ -36,7 +41,12 @@ use {
             AccessType, BlockstoreOptions, BlockstoreRecoveryMode, LedgerColumnOptions,
             ShredStorageType, BLOCKSTORE_DIRECTORY_ROCKS_FIFO,
         },
-        blockstore_processor::{self, BlockstoreProcessorError, ProcessOptions},
+        blockstore_processor::{
+            self, BlockstoreProcessorError, ProcessOptions, TransactionStatusSender,
+        },
+        entry_notifier_interface::EntryNotifierLock,
+        entry_notifier_service::EntryNotifierService,
+        leader_schedule_cache::LeaderScheduleCache,
         shred::Shred,
     },
     solana_measure::{measure, measure::Measure},
@@ -48,6 +58,7 @@ use {
         },
         accounts_db::{
             AccountsDb, AccountsDbConfig, CalcAccountsHashDataSource, FillerAccountsConfig,
+            PUBKEY_BINS_FOR_CALCULATING_HASHES,
         },
         accounts_index::{AccountsIndexConfig, IndexLimitMb, ScanConfig},
         accounts_update_notifier_interface::AccountsUpdateNotifier,
@@ -65,6 +76,7 @@ use {
             self, create_accounts_run_and_snapshot_dirs, move_and_async_delete_path, ArchiveFormat,
             SnapshotVersion, DEFAULT_ARCHIVE_COMPRESSION, SUPPORTED_ARCHIVE_COMPRESSION,
         },
+        transaction_fixture::TransactionFixture,
     },
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount, WritableAccount},
@@ -79,6 +91,7 @@ use {
         pubkey::Pubkey,
         rent::Rent,
         shred_version::compute_shred_version,
+        signature::Signature,
         stake::{self, state::StakeState},
         system_program,
         transaction::{MessageHash, SanitizedTransaction, SimpleAddressLoader},
@@ -104,7 +117,10 @@ use {
     },
 };
 
+mod account_diffs;
+mod accounts_hash_bins;
 mod bigtable;
+mod bisect;
 mod ledger_path;
 mod output;
 
@@ -835,6 +851,8 @@ fn analyze_storage(database: &Database) {
     analyze_column::<BlockHeight>(database, "BlockHeight");
     analyze_column::<ProgramCosts>(database, "ProgramCosts");
     analyze_column::<OptimisticSlots>(database, "OptimisticSlots");
+    analyze_column::<AccountHistory>(database, "AccountHistory");
+    analyze_column::<AccountHistorySlots>(database, "AccountHistorySlots");
 }
 
 /// Open blockstore with temporary primary access to allow necessary,
@@ -973,6 +991,10 @@ fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
         cf::OptimisticSlots::NAME => {
             Some(cf::OptimisticSlots::slot(cf::OptimisticSlots::index(key)))
         }
+        cf::AccountHistory::NAME => Some(cf::AccountHistory::slot(cf::AccountHistory::index(key))),
+        cf::AccountHistorySlots::NAME => Some(cf::AccountHistorySlots::slot(
+            cf::AccountHistorySlots::index(key),
+        )),
         &_ => None,
     }
 }
@@ -1074,6 +1096,7 @@ fn load_bank_forks(
     process_options: ProcessOptions,
     snapshot_archive_path: Option<PathBuf>,
     incremental_snapshot_archive_path: Option<PathBuf>,
+    transaction_status_sender: Option<&TransactionStatusSender>,
 ) -> Result<(Arc<RwLock<BankForks>>, Option<StartingSnapshotHashes>), BlockstoreProcessorError> {
     let bank_snapshots_dir = blockstore
         .ledger_path()
@@ -1180,6 +1203,7 @@ fn load_bank_forks(
     info!("done. {}", measure);
 
     let mut accounts_update_notifier = Option::<AccountsUpdateNotifier>::default();
+    let mut entry_notifier = Option::<EntryNotifierLock>::default();
     if arg_matches.is_present("geyser_plugin_config") {
         let geyser_config_files = values_t_or_exit!(arg_matches, "geyser_plugin_config", String)
             .into_iter()
@@ -1196,8 +1220,16 @@ fn load_bank_forks(
                 },
             );
         accounts_update_notifier = geyser_service.get_accounts_update_notifier();
+        entry_notifier = geyser_service.get_entry_notifier();
     }
 
+    let exit = Arc::new(AtomicBool::new(false));
+    let entry_notifier_service =
+        entry_notifier.map(|entry_notifier| EntryNotifierService::new(entry_notifier, &exit));
+    let entry_notification_sender = entry_notifier_service
+        .as_ref()
+        .map(|service| service.sender());
+
     let (bank_forks, leader_schedule_cache, starting_snapshot_hashes, ..) =
         bank_forks_utils::load_bank_forks(
             genesis_config,
@@ -1207,6 +1239,7 @@ fn load_bank_forks(
             snapshot_config.as_ref(),
             &process_options,
             None,
+            entry_notification_sender,
             accounts_update_notifier,
             &Arc::default(),
         );
@@ -1229,7 +1262,6 @@ fn load_bank_forks(
         snapshot_request_handler,
         pruned_banks_request_handler,
     };
-    let exit = Arc::new(AtomicBool::new(false));
     let accounts_background_service = AccountsBackgroundService::new(
         bank_forks.clone(),
         &exit,
@@ -1243,14 +1275,18 @@ fn load_bank_forks(
         &bank_forks,
         &leader_schedule_cache,
         &process_options,
+        transaction_status_sender,
         None,
-        None,
+        entry_notification_sender,
         &accounts_background_request_sender,
     )
     .map(|_| (bank_forks, starting_snapshot_hashes));
 
     exit.store(true, Ordering::Relaxed);
     accounts_background_service.join().unwrap();
+    if let Some(service) = entry_notifier_service {
+        service.join().unwrap();
+    }
 
     result
 }
@@ -1339,6 +1375,46 @@ fn minimize_bank_for_snapshot(
     SnapshotMinimizer::minimize(bank, snapshot_slot, ending_slot, transaction_account_set);
 }
 
+/// Replays the transactions of `slot` on a child of `parent_bank` up to the transaction with
+/// `signature`, then captures the state it executes against and the result of executing it
+fn capture_transaction_fixture(
+    blockstore: &Blockstore,
+    parent_bank: &Arc<Bank>,
+    slot: Slot,
+    signature: &Signature,
+) -> Result<TransactionFixture, String> {
+    let entries = blockstore
+        .get_slot_entries(slot, 0)
+        .map_err(|err| format!("Failed to load entries of slot {slot}: {err:?}"))?;
+    let leader = LeaderScheduleCache::new_from_bank(parent_bank)
+        .slot_leader_at(slot, Some(parent_bank))
+        .unwrap_or_default();
+    let bank = Bank::new_from_parent(parent_bank, &leader, slot);
+
+    for entry in entries {
+        if let Some(transaction) = entry
+            .transactions
+            .iter()
+            .find(|transaction| transaction.signatures.first() == Some(signature))
+        {
+            // transactions within an entry don't conflict, so the ones executed before it in
+            // the same entry can't change the accounts it loads
+            let mut fixture = TransactionFixture::capture(&bank, transaction.clone())
+                .map_err(|err| format!("Failed to capture transaction {signature}: {err}"))?;
+            let execution_result = fixture
+                .execute(&bank)
+                .map_err(|err| format!("Failed to execute transaction {signature}: {err}"))?;
+            fixture.expected_result = Some(execution_result.flattened_result());
+            return Ok(fixture);
+        }
+        if !entry.transactions.is_empty() {
+            bank.try_process_entry_transactions(entry.transactions)
+                .map_err(|err| format!("Failed to replay slot {slot}: {err}"))?;
+        }
+    }
+    Err(format!("Transaction {signature} not found in slot {slot}"))
+}
+
 fn assert_capitalization(bank: &Bank) {
     let debug_verify = true;
     assert!(bank.calculate_and_verify_capitalization(debug_verify));
@@ -1518,6 +1594,14 @@ fn main() {
         .takes_value(true)
         .default_value(SnapshotVersion::default().into())
         .help("Output snapshot version");
+    let transaction_account_diffs_arg = Arg::with_name("transaction_account_diffs")
+        .long("transaction-account-diffs")
+        .takes_value(false)
+        .help(
+            "Print the state of each account written by each replayed transaction, \
+             before and after executing it. Account data is decoded field by field \
+             for the programs known to the account decoder.",
+        );
     let debug_key_arg = Arg::with_name("debug_key")
         .long("debug-key")
         .validator(is_pubkey)
@@ -1699,6 +1783,18 @@ fn main() {
                     .help("Slots to print"),
             )
             .arg(&allow_dead_slots_arg)
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(&no_bpf_jit_arg)
+            .arg(
+                transaction_account_diffs_arg
+                    .clone()
+                    .help("Instead of printing the slots, replay the ledger up to them and \
+                           print the state of each account written by each of their \
+                           transactions, before and after executing it"),
+            )
         )
         .subcommand(
             SubCommand::with_name("dead-slots")
@@ -1816,6 +1912,149 @@ fn main() {
             .arg(&max_genesis_archive_unpacked_size_arg)
             .arg(&halt_at_slot_arg)
         )
+        .subcommand(
+            SubCommand::with_name("bisect-bank-hash")
+            .about("Replay the ledger and stop at the first slot whose bank hash differs from \
+                    a reference, printing the accounts delta and transaction results of that slot")
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&accounts_index_bins)
+            .arg(&accounts_index_limit)
+            .arg(&disable_disk_index)
+            .arg(&accountsdb_skip_shrink)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(&no_bpf_jit_arg)
+            .arg(&allow_dead_slots_arg)
+            .arg(
+                Arg::with_name("reference_bank_hashes")
+                    .long("reference-bank-hashes")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .conflicts_with("reference_ledger")
+                    .help("File with a known-good `<slot> <bank hash>` pair on each line"),
+            )
+            .arg(
+                Arg::with_name("reference_ledger")
+                    .long("reference-ledger")
+                    .value_name("DIR")
+                    .takes_value(true)
+                    .help("Ledger of a known-good node to read the reference bank hashes from"),
+            )
+            .arg(
+                Arg::with_name("capture_slot")
+                    .long("capture-slot")
+                    .value_name("SLOT")
+                    .validator(is_slot)
+                    .takes_value(true)
+                    .help("Stop at this slot even if its bank hash matches, to capture its \
+                           details on a known-good ledger for use with \
+                           --reference-bank-hash-details"),
+            )
+            .arg(
+                Arg::with_name("reference_bank_hash_details")
+                    .long("reference-bank-hash-details")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .help("Details of the divergent slot written with --output-file by a \
+                           known-good node, to list the accounts whose hashes differ"),
+            )
+            .arg(
+                Arg::with_name("output_file")
+                    .long("output-file")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .help("Also write the details of the divergent slot to FILE as JSON"),
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("accounts-hash-bins")
+            .about("Summarize the accounts of the working bank by pubkey bin, and compare the \
+                    summary to one from another node or snapshot to find the accounts responsible \
+                    for an accounts hash mismatch")
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&accounts_index_bins)
+            .arg(&accounts_index_limit)
+            .arg(&disable_disk_index)
+            .arg(&halt_at_slot_arg)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(
+                Arg::with_name("bins")
+                    .long("bins")
+                    .value_name("NUMBER")
+                    .takes_value(true)
+                    .default_value("4096")
+                    .validator(is_pow2)
+                    .help("Number of pubkey bins to split the accounts into, a power of two up \
+                           to 65536"),
+            )
+            .arg(
+                Arg::with_name("bin")
+                    .long("bin")
+                    .value_name("BIN")
+                    .takes_value(true)
+                    .multiple(true)
+                    .validator(is_parsable::<usize>)
+                    .help("Also include the accounts of this bin in the output"),
+            )
+            .arg(
+                Arg::with_name("compare")
+                    .long("compare")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .help("Summary written with --output-file by another node or from another \
+                           snapshot to compare to. The accounts of up to 16 mismatched bins are \
+                           included in the output"),
+            )
+            .arg(
+                Arg::with_name("output_file")
+                    .long("output-file")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .help("Write the summary and the accounts of the selected bins to FILE as JSON"),
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("extract-transaction-fixture")
+            .about("Replay the ledger up to a transaction and save the accounts, sysvars and \
+                    features it executes against to a file, from which it can be executed again \
+                    in a bank created with `TransactionFixture::create_bank`")
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&accounts_index_bins)
+            .arg(&accounts_index_limit)
+            .arg(&disable_disk_index)
+            .arg(&accountsdb_skip_shrink)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(&no_bpf_jit_arg)
+            .arg(
+                Arg::with_name("signature")
+                    .index(1)
+                    .value_name("SIGNATURE")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Signature of the transaction to extract"),
+            )
+            .arg(
+                Arg::with_name("slot")
+                    .long("slot")
+                    .value_name("SLOT")
+                    .validator(is_slot)
+                    .takes_value(true)
+                    .help("Slot of the transaction, required if the ledger has no \
+                           transaction status index"),
+            )
+            .arg(
+                Arg::with_name("output_file")
+                    .long("output-file")
+                    .value_name("FILE")
+                    .takes_value(true)
+                    .help("Write the fixture to FILE [default: <SIGNATURE>.fixture]"),
+            )
+        )
         .subcommand(
             SubCommand::with_name("bounds")
             .about(
@@ -1863,6 +2102,7 @@ fn main() {
             .arg(&max_genesis_archive_unpacked_size_arg)
             .arg(&debug_key_arg)
             .arg(&geyser_plugin_args)
+            .arg(&transaction_account_diffs_arg)
             .arg(
                 Arg::with_name("skip_poh_verify")
                     .long("skip-poh-verify")
@@ -2308,6 +2548,37 @@ fn main() {
                            If no file name is specified, it will print the metadata of all ledger files.")
             )
         )
+        .subcommand(
+            SubCommand::with_name("simulate-block-production")
+            .about("Replay the packet batches recorded by the banking trace through the banking \
+                    stage on top of the parent bank of the given slot, simulating block production")
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&accounts_index_bins)
+            .arg(&accounts_index_limit)
+            .arg(&disable_disk_index)
+            .arg(&accountsdb_skip_shrink)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(&no_bpf_jit_arg)
+            .arg(
+                Arg::with_name("banking_trace_events_dir")
+                    .long("banking-trace-events-dir")
+                    .value_name("DIR")
+                    .takes_value(true)
+                    .help("Use DIR for the banking trace events location \
+                           [default: [ledger]/banking_trace]"),
+            )
+            .arg(
+                Arg::with_name("first_simulated_slot")
+                    .long("first-simulated-slot")
+                    .value_name("SLOT")
+                    .validator(is_slot)
+                    .takes_value(true)
+                    .required(true)
+                    .help("The first slot of the leader window to simulate"),
+            )
+        )
         .get_matches();
 
     info!("{} {}", crate_name!(), solana_version::version!());
@@ -2476,6 +2747,7 @@ fn main() {
                     process_options,
                     snapshot_archive_path,
                     incremental_snapshot_archive_path,
+                    None,
                 ) {
                     Ok((bank_forks, ..)) => {
                         println!(
@@ -2569,6 +2841,7 @@ fn main() {
                     process_options,
                     snapshot_archive_path,
                     incremental_snapshot_archive_path,
+                    None,
                 ) {
                     Ok((bank_forks, ..)) => {
                         println!("{}", &bank_forks.read().unwrap().working_bank().hash());
@@ -2579,6 +2852,216 @@ fn main() {
                     }
                 }
             }
+            ("accounts-hash-bins", Some(arg_matches)) => {
+                let num_bins = value_t_or_exit!(arg_matches, "bins", usize);
+                if num_bins > PUBKEY_BINS_FOR_CALCULATING_HASHES {
+                    eprintln!(
+                        "--bins {num_bins} is more than the {PUBKEY_BINS_FOR_CALCULATING_HASHES} \
+                         bins of the accounts hash calculation"
+                    );
+                    exit(1);
+                }
+                let mut bins_with_accounts = values_t!(arg_matches, "bin", usize)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect::<BTreeSet<_>>();
+                if let Some(bin) = bins_with_accounts.iter().find(|bin| **bin >= num_bins) {
+                    eprintln!("Bin {bin} does not exist with --bins {num_bins}");
+                    exit(1);
+                }
+                let theirs = value_t!(arg_matches, "compare", PathBuf).ok().map(|path| {
+                    AccountsHashBinsOutput::load(&path).unwrap_or_else(|err| {
+                        eprintln!("Failed to load accounts hash bins to compare to: {err}");
+                        exit(1);
+                    })
+                });
+                let output_file = value_t!(arg_matches, "output_file", PathBuf).ok();
+
+                let mut accounts_db_config = get_accounts_db_config(&ledger_path, arg_matches);
+                // a snapshot whose accounts hash does not verify has to load to find out why
+                accounts_db_config.skip_initial_hash_calc = true;
+                let process_options = ProcessOptions {
+                    new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                    halt_at_slot: value_t!(arg_matches, "halt_at_slot", Slot).ok(),
+                    poh_verify: false,
+                    accounts_db_config: Some(accounts_db_config),
+                    ..ProcessOptions::default()
+                };
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let blockstore = open_blockstore(
+                    &ledger_path,
+                    AccessType::Secondary,
+                    wal_recovery_mode,
+                    &shred_storage_type,
+                    force_update_to_open,
+                );
+                let (bank_forks, ..) = load_bank_forks(
+                    arg_matches,
+                    &genesis_config,
+                    &blockstore,
+                    process_options,
+                    snapshot_archive_path,
+                    incremental_snapshot_archive_path,
+                    None,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("Failed to load ledger: {err:?}");
+                    exit(1);
+                });
+
+                let bank = bank_forks.read().unwrap().working_bank();
+                // need to flush the write cache in order to scan the storages, and need to root
+                // `bank` before flushing the cache
+                bank.rc.accounts.accounts_db.add_root(bank.slot());
+                bank.force_flush_accounts_cache();
+                let mut ours =
+                    AccountsHashBinsOutput::new(bank.calculate_accounts_hash_bins(num_bins));
+                let mismatched = theirs.as_ref().map(|theirs| {
+                    mismatched_bins(&ours.summary, &theirs.summary).unwrap_or_else(|err| {
+                        eprintln!("{err}");
+                        exit(1);
+                    })
+                });
+                if let Some(mismatched) = &mismatched {
+                    bins_with_accounts
+                        .extend(mismatched.iter().take(MAX_MISMATCHED_BINS_WITH_ACCOUNTS));
+                }
+                ours.add_bin_accounts(&bank, &bins_with_accounts);
+
+                println!(
+                    "Slot {}: root {}, {} accounts, {} lamports in {num_bins} bins",
+                    ours.summary.slot,
+                    ours.summary.root,
+                    ours.summary.num_accounts(),
+                    ours.summary.lamports(),
+                );
+                if let (Some(theirs), Some(mismatched)) = (&theirs, &mismatched) {
+                    print_mismatched_bins(&ours, theirs, mismatched);
+                }
+                if let Some(output_file) = output_file {
+                    ours.write(&output_file).unwrap_or_else(|err| {
+                        eprintln!("{err}");
+                        exit(1);
+                    });
+                }
+            }
+            ("bisect-bank-hash", Some(arg_matches)) => {
+                let reference_bank_hashes = if let Some(reference_bank_hashes) =
+                    value_t!(arg_matches, "reference_bank_hashes", PathBuf).ok()
+                {
+                    load_reference_bank_hashes_from_file(&reference_bank_hashes)
+                } else if let Some(reference_ledger) =
+                    value_t!(arg_matches, "reference_ledger", PathBuf).ok()
+                {
+                    let reference_blockstore = open_blockstore(
+                        &reference_ledger,
+                        AccessType::Secondary,
+                        wal_recovery_mode.clone(),
+                        &shred_storage_type,
+                        force_update_to_open,
+                    );
+                    load_reference_bank_hashes_from_blockstore(&reference_blockstore)
+                } else {
+                    Ok(ReferenceBankHashes::default())
+                }
+                .unwrap_or_else(|err| {
+                    eprintln!("Failed to load reference bank hashes: {err}");
+                    exit(1);
+                });
+                let capture_slot = value_t!(arg_matches, "capture_slot", Slot).ok();
+                let reference_bank_hash_details =
+                    value_t!(arg_matches, "reference_bank_hash_details", PathBuf)
+                        .ok()
+                        .map(|path| {
+                            load_bank_hash_details(&path).unwrap_or_else(|err| {
+                                eprintln!("Failed to load reference bank hash details: {err}");
+                                exit(1);
+                            })
+                        });
+                let output_file = value_t!(arg_matches, "output_file", PathBuf).ok();
+
+                let bisector = Arc::new(BankHashBisector::new(reference_bank_hashes, capture_slot));
+                let Some(halt_slot) = bisector.halt_slot() else {
+                    eprintln!(
+                        "No reference bank hashes found, specify --reference-bank-hashes, \
+                         --reference-ledger or --capture-slot"
+                    );
+                    exit(1);
+                };
+                let process_options = ProcessOptions {
+                    new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                    halt_at_slot: Some(halt_slot),
+                    slot_callback: Some({
+                        let bisector = bisector.clone();
+                        Arc::new(move |bank: &Bank| bisector.check_bank(bank))
+                    }),
+                    poh_verify: false,
+                    accounts_db_config: Some(get_accounts_db_config(&ledger_path, arg_matches)),
+                    accounts_db_skip_shrink: arg_matches.is_present("accounts_db_skip_shrink"),
+                    allow_dead_slots: arg_matches.is_present("allow_dead_slots"),
+                    runtime_config: RuntimeConfig {
+                        bpf_jit: !arg_matches.is_present("no_bpf_jit"),
+                        ..RuntimeConfig::default()
+                    },
+                    ..ProcessOptions::default()
+                };
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let blockstore = open_blockstore(
+                    &ledger_path,
+                    AccessType::Secondary,
+                    wal_recovery_mode,
+                    &shred_storage_type,
+                    force_update_to_open,
+                );
+                if let Err(err) = load_bank_forks(
+                    arg_matches,
+                    &genesis_config,
+                    &blockstore,
+                    process_options,
+                    snapshot_archive_path,
+                    incremental_snapshot_archive_path,
+                    Some(bisector.transaction_status_sender()),
+                ) {
+                    eprintln!("Failed to load ledger: {err:?}");
+                    exit(1);
+                }
+
+                match bisector.take_divergence() {
+                    None => println!(
+                        "No divergent slot found, {} replayed slots matched the reference bank hashes",
+                        bisector.compared_slot_count()
+                    ),
+                    Some(details) => {
+                        if details.reference_bank_hash.is_some() {
+                            println!("First divergent slot: {}", details.slot);
+                        }
+                        print!("{details}");
+                        if let Some(reference_bank_hash_details) = &reference_bank_hash_details {
+                            if reference_bank_hash_details.slot != details.slot {
+                                eprintln!(
+                                    "Reference bank hash details are for slot {}, not slot {}",
+                                    reference_bank_hash_details.slot, details.slot
+                                );
+                            } else {
+                                print!(
+                                    "{}",
+                                    format_account_hash_diffs(&details, reference_bank_hash_details)
+                                );
+                            }
+                        }
+                        if let Some(output_file) = output_file {
+                            let file = File::create(&output_file).unwrap_or_else(|err| {
+                                eprintln!("Unable to create {output_file:?}: {err}");
+                                exit(1);
+                            });
+                            serde_json::to_writer_pretty(file, &details).unwrap_or_else(|err| {
+                                eprintln!("Unable to write {output_file:?}: {err}");
+                                exit(1);
+                            });
+                        }
+                    }
+                }
+            }
             ("slot", Some(arg_matches)) => {
                 let slots = values_t_or_exit!(arg_matches, "slots", Slot);
                 let allow_dead_slots = arg_matches.is_present("allow_dead_slots");
@@ -2589,17 +3072,52 @@ fn main() {
                     &shred_storage_type,
                     force_update_to_open,
                 );
-                for slot in slots {
-                    println!("Slot {slot}");
-                    if let Err(err) = output_slot(
-                        &blockstore,
-                        slot,
+                if arg_matches.is_present("transaction_account_diffs") {
+                    let process_options = ProcessOptions {
+                        new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                        halt_at_slot: slots.iter().max().copied(),
+                        poh_verify: false,
                         allow_dead_slots,
-                        &LedgerOutputMethod::Print,
-                        verbose_level,
-                        &mut HashMap::new(),
+                        runtime_config: RuntimeConfig {
+                            bpf_jit: !arg_matches.is_present("no_bpf_jit"),
+                            record_transaction_account_diffs: true,
+                            ..RuntimeConfig::default()
+                        },
+                        ..ProcessOptions::default()
+                    };
+                    let (transaction_status_sender, account_diffs_printer) =
+                        spawn_account_diffs_printer(
+                            OutputFormat::from_matches(arg_matches, "output_format", false),
+                            Some(slots.into_iter().collect()),
+                        );
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    if let Err(err) = load_bank_forks(
+                        arg_matches,
+                        &genesis_config,
+                        &blockstore,
+                        process_options,
+                        snapshot_archive_path,
+                        incremental_snapshot_archive_path,
+                        Some(&transaction_status_sender),
                     ) {
-                        eprintln!("{err}");
+                        eprintln!("Failed to load ledger: {err:?}");
+                        exit(1);
+                    }
+                    drop(transaction_status_sender);
+                    account_diffs_printer.join().unwrap();
+                } else {
+                    for slot in slots {
+                        println!("Slot {slot}");
+                        if let Err(err) = output_slot(
+                            &blockstore,
+                            slot,
+                            allow_dead_slots,
+                            &LedgerOutputMethod::Print,
+                            verbose_level,
+                            &mut HashMap::new(),
+                        ) {
+                            eprintln!("{err}");
+                        }
                     }
                 }
             }
@@ -2766,6 +3284,7 @@ fn main() {
 
                 let debug_keys = pubkeys_of(arg_matches, "debug_key")
                     .map(|pubkeys| Arc::new(pubkeys.into_iter().collect::<HashSet<_>>()));
+                let transaction_account_diffs = arg_matches.is_present("transaction_account_diffs");
 
                 let process_options = ProcessOptions {
                     new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
@@ -2790,6 +3309,7 @@ fn main() {
                     accounts_db_skip_shrink: arg_matches.is_present("accounts_db_skip_shrink"),
                     runtime_config: RuntimeConfig {
                         bpf_jit: !arg_matches.is_present("no_bpf_jit"),
+                        record_transaction_account_diffs: transaction_account_diffs,
                         ..RuntimeConfig::default()
                     },
                     ..ProcessOptions::default()
@@ -2805,6 +3325,17 @@ fn main() {
                     &shred_storage_type,
                     force_update_to_open,
                 );
+                let (transaction_status_sender, account_diffs_printer) =
+                    if transaction_account_diffs {
+                        let (transaction_status_sender, account_diffs_printer) =
+                            spawn_account_diffs_printer(
+                                OutputFormat::from_matches(arg_matches, "output_format", false),
+                                None,
+                            );
+                        (Some(transaction_status_sender), Some(account_diffs_printer))
+                    } else {
+                        (None, None)
+                    };
                 let (bank_forks, ..) = load_bank_forks(
                     arg_matches,
                     &genesis_config,
@@ -2812,11 +3343,16 @@ fn main() {
                     process_options,
                     snapshot_archive_path,
                     incremental_snapshot_archive_path,
+                    transaction_status_sender.as_ref(),
                 )
                 .unwrap_or_else(|err| {
                     eprintln!("Ledger verification failed: {err:?}");
                     exit(1);
                 });
+                drop(transaction_status_sender);
+                if let Some(account_diffs_printer) = account_diffs_printer {
+                    account_diffs_printer.join().unwrap();
+                }
                 if print_accounts_stats {
                     let working_bank = bank_forks.read().unwrap().working_bank();
                     working_bank.print_accounts_stats();
@@ -2856,6 +3392,7 @@ fn main() {
                     process_options,
                     snapshot_archive_path,
                     incremental_snapshot_archive_path,
+                    None,
                 ) {
                     Ok((bank_forks, ..)) => {
                         let dot = graph_forks(&bank_forks.read().unwrap(), &graph_config);
@@ -3028,6 +3565,7 @@ fn main() {
                     },
                     snapshot_archive_path,
                     incremental_snapshot_archive_path,
+                    None,
                 ) {
                     Ok((bank_forks, starting_snapshot_hashes)) => {
                         let mut bank = bank_forks
@@ -3365,6 +3903,7 @@ fn main() {
                     process_options,
                     snapshot_archive_path,
                     incremental_snapshot_archive_path,
+                    None,
                 )
                 .unwrap_or_else(|err| {
                     eprintln!("Failed to load ledger: {err:?}");
@@ -3454,6 +3993,7 @@ fn main() {
                     process_options,
                     snapshot_archive_path,
                     incremental_snapshot_archive_path,
+                    None,
                 ) {
                     Ok((bank_forks, ..)) => {
                         let bank_forks = bank_forks.read().unwrap();
@@ -4174,6 +4714,100 @@ fn main() {
                     println!("No missing roots found in range {end_root} to {start_root}");
                 }
             }
+            ("extract-transaction-fixture", Some(arg_matches)) => {
+                let signature = value_t_or_exit!(arg_matches, "signature", Signature);
+                let output_file = value_t!(arg_matches, "output_file", PathBuf)
+                    .unwrap_or_else(|_| PathBuf::from(format!("{signature}.fixture")));
+                let blockstore = open_blockstore(
+                    &ledger_path,
+                    AccessType::Secondary,
+                    wal_recovery_mode,
+                    &shred_storage_type,
+                    force_update_to_open,
+                );
+                let slot = value_t!(arg_matches, "slot", Slot).ok().or_else(|| {
+                    blockstore
+                        .get_rooted_transaction_status(signature)
+                        .ok()
+                        .flatten()
+                        .map(|(slot, _status_meta)| slot)
+                });
+                let Some(slot) = slot else {
+                    eprintln!(
+                        "Transaction {signature} not found in the transaction status index, \
+                         specify its --slot"
+                    );
+                    exit(1);
+                };
+                let Some(parent_slot) = blockstore
+                    .meta(slot)
+                    .ok()
+                    .flatten()
+                    .and_then(|meta| meta.parent_slot)
+                else {
+                    eprintln!("Parent of slot {slot} not found in the ledger");
+                    exit(1);
+                };
+
+                let process_options = ProcessOptions {
+                    new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                    halt_at_slot: Some(parent_slot),
+                    poh_verify: false,
+                    accounts_db_config: Some(get_accounts_db_config(&ledger_path, arg_matches)),
+                    accounts_db_skip_shrink: arg_matches.is_present("accounts_db_skip_shrink"),
+                    runtime_config: RuntimeConfig {
+                        bpf_jit: !arg_matches.is_present("no_bpf_jit"),
+                        ..RuntimeConfig::default()
+                    },
+                    ..ProcessOptions::default()
+                };
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let (bank_forks, ..) = load_bank_forks(
+                    arg_matches,
+                    &genesis_config,
+                    &blockstore,
+                    process_options,
+                    snapshot_archive_path,
+                    incremental_snapshot_archive_path,
+                    None,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("Failed to load ledger: {err:?}");
+                    exit(1);
+                });
+                let parent_bank =
+                    bank_forks
+                        .read()
+                        .unwrap()
+                        .get(parent_slot)
+                        .unwrap_or_else(|| {
+                            eprintln!(
+                        "Bank for parent slot {parent_slot} not available, the snapshot may be \
+                         too recent, try --no-snapshot"
+                    );
+                            exit(1);
+                        });
+
+                let fixture =
+                    capture_transaction_fixture(&blockstore, &parent_bank, slot, &signature)
+                        .unwrap_or_else(|err| {
+                            eprintln!("{err}");
+                            exit(1);
+                        });
+                fixture.save(&output_file).unwrap_or_else(|err| {
+                    eprintln!("Unable to write {output_file:?}: {err}");
+                    exit(1);
+                });
+                println!(
+                    "Saved transaction {signature} of slot {slot} with {} accounts to {output_file:?}",
+                    fixture.accounts.len()
+                );
+                match &fixture.expected_result {
+                    Some(Ok(())) => println!("  Result: ok"),
+                    Some(Err(err)) => println!("  Result: {err}"),
+                    None => {}
+                }
+            }
             ("bounds", Some(arg_matches)) => {
                 let blockstore = open_blockstore(
                     &ledger_path,
@@ -4295,6 +4929,91 @@ fn main() {
                     eprintln!("{err}");
                 }
             }
+            ("simulate-block-production", Some(arg_matches)) => {
+                let first_simulated_slot =
+                    value_t_or_exit!(arg_matches, "first_simulated_slot", Slot);
+                let blockstore = open_blockstore(
+                    &ledger_path,
+                    AccessType::Secondary,
+                    wal_recovery_mode,
+                    &shred_storage_type,
+                    force_update_to_open,
+                );
+                let banking_trace_events_dir =
+                    value_t!(arg_matches, "banking_trace_events_dir", PathBuf)
+                        .unwrap_or_else(|_| blockstore.banking_trace_path());
+                let banking_trace_events = BankingTraceEvents::load_from_dir(
+                    &banking_trace_events_dir,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("Failed to load banking trace events: {err:?}");
+                    exit(1);
+                });
+
+                let parent_slot = blockstore
+                    .meta(first_simulated_slot)
+                    .ok()
+                    .flatten()
+                    .and_then(|meta| meta.parent_slot)
+                    .unwrap_or_else(|| first_simulated_slot.saturating_sub(1));
+                let process_options = ProcessOptions {
+                    new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                    halt_at_slot: Some(parent_slot),
+                    poh_verify: false,
+                    accounts_db_config: Some(get_accounts_db_config(&ledger_path, arg_matches)),
+                    accounts_db_skip_shrink: arg_matches.is_present("accounts_db_skip_shrink"),
+                    runtime_config: RuntimeConfig {
+                        bpf_jit: !arg_matches.is_present("no_bpf_jit"),
+                        ..RuntimeConfig::default()
+                    },
+                    ..ProcessOptions::default()
+                };
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let (bank_forks, ..) = load_bank_forks(
+                    arg_matches,
+                    &genesis_config,
+                    &blockstore,
+                    process_options,
+                    snapshot_archive_path,
+                    incremental_snapshot_archive_path,
+                    None,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("Failed to load ledger: {err:?}");
+                    exit(1);
+                });
+
+                let simulator = BankingSimulator::new(banking_trace_events, first_simulated_slot);
+                match simulator.start(&genesis_config, bank_forks, Arc::new(blockstore)) {
+                    Ok(simulated_blocks) => {
+                        for block in simulated_blocks {
+                            let recorded = match block.recorded_hashes {
+                                Some((blockhash, bank_hash)) => format!(
+                                    "recorded blockhash: {blockhash} bank hash: {bank_hash} ({})",
+                                    if block.is_bank_hash_matched() == Some(true) {
+                                        "matched"
+                                    } else {
+                                        "mismatched"
+                                    }
+                                ),
+                                None => "not recorded".to_string(),
+                            };
+                            println!(
+                                "Slot {}: {} transactions, blockhash: {} bank hash: {}, {}",
+                                block.slot,
+                                block.transaction_count,
+                                block.blockhash,
+                                block.bank_hash,
+                                recorded,
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        eprintln!("Failed to simulate block production: {err:?}");
+                        exit(1);
+                    }
+                }
+            }
             ("", _) => {
                 eprintln!("{}", matches.usage());
                 exit(1);
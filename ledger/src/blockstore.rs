@@ -41,6 +41,7 @@ use {
     solana_rayon_threadlimit::get_max_thread_count,
     solana_runtime::hardened_unpack::{unpack_genesis_archive, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE},
     solana_sdk::{
+        account::{Account, AccountSharedData},
         clock::{Slot, UnixTimestamp, DEFAULT_TICKS_PER_SECOND, MS_PER_TICK},
         genesis_config::{GenesisConfig, DEFAULT_GENESIS_ARCHIVE, DEFAULT_GENESIS_FILE},
         hash::Hash,
@@ -173,6 +174,8 @@ pub struct Blockstore {
     program_costs_cf: LedgerColumn<cf::ProgramCosts>,
     bank_hash_cf: LedgerColumn<cf::BankHash>,
     optimistic_slots_cf: LedgerColumn<cf::OptimisticSlots>,
+    account_history_cf: LedgerColumn<cf::AccountHistory>,
+    account_history_slots_cf: LedgerColumn<cf::AccountHistorySlots>,
     last_root: RwLock<Slot>,
     insert_shreds_lock: Mutex<()>,
     new_shreds_signals: Mutex<Vec<Sender<bool>>>,
@@ -281,6 +284,8 @@ impl Blockstore {
         let program_costs_cf = db.column();
         let bank_hash_cf = db.column();
         let optimistic_slots_cf = db.column();
+        let account_history_cf = db.column();
+        let account_history_slots_cf = db.column();
 
         let db = Arc::new(db);
 
@@ -333,6 +338,8 @@ impl Blockstore {
             program_costs_cf,
             bank_hash_cf,
             optimistic_slots_cf,
+            account_history_cf,
+            account_history_slots_cf,
             new_shreds_signals: Mutex::default(),
             completed_slots_senders: Mutex::default(),
             shred_timing_point_sender: None,
@@ -691,6 +698,8 @@ impl Blockstore {
         self.program_costs_cf.submit_rocksdb_cf_metrics();
         self.bank_hash_cf.submit_rocksdb_cf_metrics();
         self.optimistic_slots_cf.submit_rocksdb_cf_metrics();
+        self.account_history_cf.submit_rocksdb_cf_metrics();
+        self.account_history_slots_cf.submit_rocksdb_cf_metrics();
     }
 
     fn try_shred_recovery(
@@ -2773,6 +2782,112 @@ impl Blockstore {
         self.program_costs_cf.delete(*key)
     }
 
+    /// Records the state of each account written in `slot`. Entries are written for every
+    /// frozen slot; those of unrooted slots are dropped by `purge_account_history()`.
+    pub fn write_account_history(
+        &self,
+        slot: Slot,
+        accounts: &[(Pubkey, AccountSharedData)],
+    ) -> Result<()> {
+        let mut write_batch = self.db.batch()?;
+        for (pubkey, account) in accounts {
+            write_batch
+                .put::<cf::AccountHistory>((*pubkey, slot), &Account::from(account.clone()))?;
+            write_batch.put::<cf::AccountHistorySlots>((slot, *pubkey), &true)?;
+        }
+        self.db.write(write_batch)
+    }
+
+    /// Returns the most recent rooted state of `pubkey` recorded at or before `slot`, along with
+    /// the slot that wrote it
+    pub fn get_rooted_account_history(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+    ) -> Result<Option<(Slot, Account)>> {
+        // The roots at or below `lowest_cleanup_slot` may have been cleaned up, but
+        // `purge_account_history()` drops the entries of unrooted slots as soon as they fall
+        // behind the highest root, long before the ledger is cleaned up that far
+        let lowest_cleanup_slot = self.lowest_cleanup_slot();
+        let iterator = self.account_history_cf.iter(IteratorMode::From(
+            (*pubkey, slot),
+            IteratorDirection::Reverse,
+        ))?;
+        for ((key_pubkey, key_slot), data) in iterator {
+            if key_pubkey != *pubkey {
+                break;
+            }
+            if key_slot <= lowest_cleanup_slot || self.is_root(key_slot) {
+                return Ok(Some((key_slot, deserialize(&data)?)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Purges the account history entries that are no longer needed to serve slots at or above
+    /// `oldest_slot`: those of the unrooted slots in `(last_max_root, max_root]`, and those
+    /// superseded by a rooted entry below `oldest_slot`. Only the slots between the bounds of
+    /// the previous purge and these ones are visited. Returns the number of purged entries.
+    pub fn purge_account_history(&self, last_max_root: Slot, oldest_slot: Slot) -> Result<usize> {
+        let max_root = self.max_root();
+        let lowest_cleanup_slot = self.lowest_cleanup_slot();
+        let mut purged = HashSet::new();
+
+        // Entries of slots that didn't become roots are dead
+        let newly_rooted = self.account_history_slots_cf.iter(IteratorMode::From(
+            (last_max_root.saturating_add(1), Pubkey::default()),
+            IteratorDirection::Forward,
+        ))?;
+        for ((slot, pubkey), _) in newly_rooted {
+            if slot > max_root {
+                break;
+            }
+            if !self.is_root(slot) {
+                purged.insert((pubkey, slot));
+            }
+        }
+
+        // Below `oldest_slot`, only the newest rooted entry of each account is needed. The slot
+        // index only lists the entries that haven't been through this yet.
+        let expired = self
+            .account_history_slots_cf
+            .iter(IteratorMode::Start)?
+            .take_while(|((slot, _), _)| *slot < oldest_slot);
+        for ((slot, pubkey), _) in expired {
+            if slot > lowest_cleanup_slot && !self.is_root(slot) {
+                // A dead entry written before the last restart
+                purged.insert((pubkey, slot));
+                continue;
+            }
+            let superseded = self
+                .account_history_cf
+                .iter(IteratorMode::From(
+                    (pubkey, slot.saturating_sub(1)),
+                    IteratorDirection::Reverse,
+                ))?
+                .map(|(index, _)| index)
+                .take_while(|(key_pubkey, key_slot)| *key_pubkey == pubkey && *key_slot < slot);
+            purged.extend(superseded);
+        }
+
+        let mut write_batch = self.db.batch()?;
+        for (pubkey, slot) in &purged {
+            write_batch.delete::<cf::AccountHistory>((*pubkey, *slot))?;
+            if *slot >= oldest_slot {
+                write_batch.delete::<cf::AccountHistorySlots>((*slot, *pubkey))?;
+            }
+        }
+        if oldest_slot > 0 {
+            self.db.delete_range_cf::<cf::AccountHistorySlots>(
+                &mut write_batch,
+                0,
+                oldest_slot - 1,
+            )?;
+        }
+        self.db.write(write_batch)?;
+        Ok(purged.len())
+    }
+
     /// Returns the entry vector for the slot starting with `shred_start_index`
     pub fn get_slot_entries(&self, slot: Slot, shred_start_index: u64) -> Result<Vec<Entry>> {
         self.get_slot_entries_with_shred_info(slot, shred_start_index, false)
@@ -9783,4 +9898,90 @@ pub mod tests {
             assert_eq!(read_cost, *cost_table.get(&read_key).unwrap());
         }
     }
+
+    #[test]
+    fn test_account_history() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let pubkey = Pubkey::new_unique();
+        let other_pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let account = |lamports| AccountSharedData::new(lamports, 0, &owner);
+
+        // Slots 2 and 5 are rooted; slot 3 is on a dead fork and slot 6 is not yet rooted
+        for (slot, lamports) in [(2, 20), (3, 30), (5, 50), (6, 60)] {
+            blockstore
+                .write_account_history(slot, &[(pubkey, account(lamports))])
+                .unwrap();
+        }
+        blockstore
+            .write_account_history(4, &[(other_pubkey, account(1))])
+            .unwrap();
+        blockstore.set_roots([0, 2, 4, 5].iter()).unwrap();
+
+        let lamports_at = |slot| {
+            blockstore
+                .get_rooted_account_history(&pubkey, slot)
+                .unwrap()
+                .map(|(slot, account)| (slot, account.lamports))
+        };
+        assert_eq!(lamports_at(1), None);
+        assert_eq!(lamports_at(2), Some((2, 20)));
+        assert_eq!(lamports_at(3), Some((2, 20)));
+        assert_eq!(lamports_at(4), Some((2, 20)));
+        assert_eq!(lamports_at(5), Some((5, 50)));
+        assert_eq!(lamports_at(6), Some((5, 50)));
+        assert_eq!(lamports_at(u64::MAX), Some((5, 50)));
+        assert_eq!(
+            blockstore
+                .get_rooted_account_history(&other_pubkey, 5)
+                .unwrap()
+                .map(|(slot, _)| slot),
+            Some(4)
+        );
+
+        // Dead-fork entry at slot 3 and the superseded entry at slot 2 are purged; slot 6 stays
+        // until it is rooted or abandoned
+        assert_eq!(blockstore.purge_account_history(0, 6).unwrap(), 2);
+        let entries = |blockstore: &Blockstore| {
+            let mut entries = blockstore
+                .account_history_cf
+                .iter(IteratorMode::Start)
+                .unwrap()
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>();
+            entries.sort_unstable();
+            entries
+        };
+        let mut expected = vec![(pubkey, 5), (pubkey, 6), (other_pubkey, 4)];
+        expected.sort_unstable();
+        assert_eq!(entries(&blockstore), expected);
+        assert_eq!(lamports_at(5), Some((5, 50)));
+        // Only the slots since the last purge are visited again
+        assert_eq!(
+            blockstore
+                .account_history_slots_cf
+                .iter(IteratorMode::Start)
+                .unwrap()
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>(),
+            vec![(6, pubkey)]
+        );
+        assert_eq!(blockstore.purge_account_history(5, 6).unwrap(), 0);
+
+        // Slot 7 is abandoned when slot 8 is rooted, and its entry is purged while the roots are
+        // still around to tell. Readers then rely on this once the ledger is cleaned up past it.
+        for (slot, lamports) in [(7, 70), (8, 80)] {
+            blockstore
+                .write_account_history(slot, &[(pubkey, account(lamports))])
+                .unwrap();
+        }
+        blockstore.set_roots([8].iter()).unwrap();
+        assert_eq!(blockstore.purge_account_history(5, 6).unwrap(), 2);
+        assert_eq!(lamports_at(7), Some((5, 50)));
+        *blockstore.lowest_cleanup_slot.write().unwrap() = 8;
+        assert_eq!(lamports_at(7), Some((5, 50)));
+        assert_eq!(lamports_at(8), Some((8, 80)));
+    }
 }
@@ -27,6 +27,7 @@ use {
     serde::{de::DeserializeOwned, Serialize},
     solana_runtime::hardened_unpack::UnpackError,
     solana_sdk::{
+        account::Account,
         clock::{Slot, UnixTimestamp},
         pubkey::Pubkey,
         signature::Signature,
@@ -94,6 +95,10 @@ const BLOCK_HEIGHT_CF: &str = "block_height";
 const PROGRAM_COSTS_CF: &str = "program_costs";
 /// Column family for optimistic slots
 const OPTIMISTIC_SLOTS_CF: &str = "optimistic_slots";
+/// Column family for Account History
+const ACCOUNT_HISTORY_CF: &str = "account_history";
+/// Column family for the slot index of Account History
+const ACCOUNT_HISTORY_SLOTS_CF: &str = "account_history_slots";
 
 #[derive(Error, Debug)]
 pub enum BlockstoreError {
@@ -314,6 +319,20 @@ pub mod columns {
     /// * value type: [`blockstore_meta::OptimisticSlotMetaVersioned`]
     pub struct OptimisticSlots;
 
+    #[derive(Debug)]
+    /// The account history column
+    ///
+    /// * index type: `(`[`Pubkey`]`, `[`Slot`]`)`
+    /// * value type: [`solana_sdk::account::Account`]
+    pub struct AccountHistory;
+
+    #[derive(Debug)]
+    /// The slot index of the account history column, listing the entries written in each slot
+    ///
+    /// * index type: `(`[`Slot`]`, `[`Pubkey`]`)`
+    /// * value type: `bool`
+    pub struct AccountHistorySlots;
+
     // When adding a new column ...
     // - Add struct below and implement `Column` and `ColumnName` traits
     // - Add descriptor in Rocks::cf_descriptors() and name in Rocks::columns()
@@ -440,6 +459,8 @@ impl Rocks {
             new_cf_descriptor::<BlockHeight>(options, oldest_slot),
             new_cf_descriptor::<ProgramCosts>(options, oldest_slot),
             new_cf_descriptor::<OptimisticSlots>(options, oldest_slot),
+            new_cf_descriptor::<AccountHistory>(options, oldest_slot),
+            new_cf_descriptor::<AccountHistorySlots>(options, oldest_slot),
         ]
     }
 
@@ -467,6 +488,8 @@ impl Rocks {
             BlockHeight::NAME,
             ProgramCosts::NAME,
             OptimisticSlots::NAME,
+            AccountHistory::NAME,
+            AccountHistorySlots::NAME,
         ]
     }
 
@@ -872,6 +895,76 @@ impl Column for columns::ProgramCosts {
     }
 }
 
+impl Column for columns::AccountHistory {
+    type Index = (Pubkey, Slot);
+
+    fn key((pubkey, slot): (Pubkey, Slot)) -> Vec<u8> {
+        let mut key = vec![0; 32 + 8]; // size_of Pubkey + size_of Slot
+        key[0..32].clone_from_slice(&pubkey.as_ref()[0..32]);
+        BigEndian::write_u64(&mut key[32..40], slot);
+        key
+    }
+
+    fn index(key: &[u8]) -> (Pubkey, Slot) {
+        let pubkey = Pubkey::try_from(&key[0..32]).unwrap();
+        let slot = BigEndian::read_u64(&key[32..40]);
+        (pubkey, slot)
+    }
+
+    fn primary_index(_index: Self::Index) -> u64 {
+        unimplemented!()
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.1
+    }
+
+    fn as_index(slot: u64) -> Self::Index {
+        (Pubkey::default(), slot)
+    }
+}
+impl ColumnName for columns::AccountHistory {
+    const NAME: &'static str = ACCOUNT_HISTORY_CF;
+}
+impl TypedColumn for columns::AccountHistory {
+    type Type = Account;
+}
+
+impl Column for columns::AccountHistorySlots {
+    type Index = (Slot, Pubkey);
+
+    fn key((slot, pubkey): (Slot, Pubkey)) -> Vec<u8> {
+        let mut key = vec![0; 8 + 32]; // size_of Slot + size_of Pubkey
+        BigEndian::write_u64(&mut key[0..8], slot);
+        key[8..40].clone_from_slice(&pubkey.as_ref()[0..32]);
+        key
+    }
+
+    fn index(key: &[u8]) -> (Slot, Pubkey) {
+        let slot = BigEndian::read_u64(&key[0..8]);
+        let pubkey = Pubkey::try_from(&key[8..40]).unwrap();
+        (slot, pubkey)
+    }
+
+    fn primary_index(index: Self::Index) -> u64 {
+        index.0
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.0
+    }
+
+    fn as_index(slot: u64) -> Self::Index {
+        (slot, Pubkey::default())
+    }
+}
+impl ColumnName for columns::AccountHistorySlots {
+    const NAME: &'static str = ACCOUNT_HISTORY_SLOTS_CF;
+}
+impl TypedColumn for columns::AccountHistorySlots {
+    type Type = bool;
+}
+
 impl Column for columns::ShredCode {
     type Index = (u64, u64);
 
@@ -1850,6 +1943,10 @@ fn should_exclude_from_compaction(cf_name: &str) -> bool {
         columns::TransactionStatusIndex::NAME,
         columns::ProgramCosts::NAME,
         columns::TransactionMemos::NAME,
+        // Retention is managed by `Blockstore::purge_account_history()`, which keeps the newest
+        // entry of each account regardless of its slot
+        columns::AccountHistory::NAME,
+        columns::AccountHistorySlots::NAME,
     ]
     .into_iter()
     .collect();
@@ -1938,7 +2035,7 @@ pub mod tests {
 
     #[test]
     fn test_should_exclude_from_compaction() {
-        // currently there are three CFs excluded from compaction:
+        // currently there are five CFs excluded from compaction:
         assert!(should_exclude_from_compaction(
             columns::TransactionStatusIndex::NAME
         ));
@@ -1946,6 +2043,12 @@ pub mod tests {
         assert!(should_exclude_from_compaction(
             columns::TransactionMemos::NAME
         ));
+        assert!(should_exclude_from_compaction(
+            columns::AccountHistory::NAME
+        ));
+        assert!(should_exclude_from_compaction(
+            columns::AccountHistorySlots::NAME
+        ));
         assert!(!should_exclude_from_compaction("something else"));
     }
 }
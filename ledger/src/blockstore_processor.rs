@@ -1,7 +1,11 @@
 use {
     crate::{
-        block_error::BlockError, blockstore::Blockstore, blockstore_db::BlockstoreError,
-        blockstore_meta::SlotMeta, leader_schedule_cache::LeaderScheduleCache,
+        block_error::BlockError,
+        blockstore::Blockstore,
+        blockstore_db::BlockstoreError,
+        blockstore_meta::SlotMeta,
+        entry_notifier_service::{EntryNotification, EntryNotifierSender},
+        leader_schedule_cache::LeaderScheduleCache,
         token_balances::collect_token_balances,
     },
     chrono_humanize::{Accuracy, HumanTime, Tense},
@@ -592,12 +596,17 @@ pub enum BlockstoreProcessorError {
 /// Callback for accessing bank state while processing the blockstore
 pub type ProcessCallback = Arc<dyn Fn(&Bank) + Sync + Send>;
 
+/// Callback for inspecting each bank once it is frozen while processing the blockstore.
+/// Returning true halts processing at that bank, as if it were at `halt_at_slot`.
+pub type ProcessSlotCallback = Arc<dyn Fn(&Bank) -> bool + Sync + Send>;
+
 #[derive(Default, Clone)]
 pub struct ProcessOptions {
     pub poh_verify: bool,
     pub full_leader_cache: bool,
     pub halt_at_slot: Option<Slot>,
     pub entry_callback: Option<ProcessCallback>,
+    pub slot_callback: Option<ProcessSlotCallback>,
     pub new_hard_forks: Option<Vec<Slot>>,
     pub debug_keys: Option<Arc<HashSet<Pubkey>>>,
     pub account_indexes: AccountSecondaryIndexes,
@@ -662,6 +671,7 @@ pub fn test_process_blockstore(
         opts,
         None,
         None,
+        None,
         exit,
     );
 
@@ -672,6 +682,7 @@ pub fn test_process_blockstore(
         opts,
         None,
         None,
+        None,
         &abs_request_sender,
     )
     .unwrap();
@@ -688,6 +699,7 @@ pub(crate) fn process_blockstore_for_bank_0(
     account_paths: Vec<PathBuf>,
     opts: &ProcessOptions,
     cache_block_meta_sender: Option<&CacheBlockMetaSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     exit: &Arc<AtomicBool>,
 ) -> Arc<RwLock<BankForks>> {
@@ -714,6 +726,7 @@ pub(crate) fn process_blockstore_for_bank_0(
         opts,
         &VerifyRecyclers::default(),
         cache_block_meta_sender,
+        entry_notification_sender,
     );
     bank_forks
 }
@@ -727,6 +740,7 @@ pub fn process_blockstore_from_root(
     opts: &ProcessOptions,
     transaction_status_sender: Option<&TransactionStatusSender>,
     cache_block_meta_sender: Option<&CacheBlockMetaSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     accounts_background_request_sender: &AbsRequestSender,
 ) -> result::Result<(), BlockstoreProcessorError> {
     // Starting slot must be a root, and thus has no parents
@@ -774,6 +788,7 @@ pub fn process_blockstore_from_root(
             opts,
             transaction_status_sender,
             cache_block_meta_sender,
+            entry_notification_sender,
             &mut timing,
             accounts_background_request_sender,
         )?;
@@ -877,6 +892,7 @@ fn confirm_full_slot(
     recyclers: &VerifyRecyclers,
     progress: &mut ConfirmationProgress,
     transaction_status_sender: Option<&TransactionStatusSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     replay_vote_sender: Option<&ReplayVoteSender>,
     timing: &mut ExecuteTimings,
 ) -> result::Result<(), BlockstoreProcessorError> {
@@ -891,6 +907,7 @@ fn confirm_full_slot(
         progress,
         skip_verification,
         transaction_status_sender,
+        entry_notification_sender,
         replay_vote_sender,
         opts.entry_callback.as_ref(),
         recyclers,
@@ -1018,6 +1035,7 @@ pub fn confirm_slot(
     progress: &mut ConfirmationProgress,
     skip_verification: bool,
     transaction_status_sender: Option<&TransactionStatusSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     replay_vote_sender: Option<&ReplayVoteSender>,
     entry_callback: Option<&ProcessCallback>,
     recyclers: &VerifyRecyclers,
@@ -1048,6 +1066,7 @@ pub fn confirm_slot(
         progress,
         skip_verification,
         transaction_status_sender,
+        entry_notification_sender,
         replay_vote_sender,
         entry_callback,
         recyclers,
@@ -1064,6 +1083,7 @@ fn confirm_slot_entries(
     progress: &mut ConfirmationProgress,
     skip_verification: bool,
     transaction_status_sender: Option<&TransactionStatusSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     replay_vote_sender: Option<&ReplayVoteSender>,
     entry_callback: Option<&ProcessCallback>,
     recyclers: &VerifyRecyclers,
@@ -1112,6 +1132,22 @@ fn confirm_slot_entries(
         })?;
     }
 
+    if let Some(entry_notification_sender) = entry_notification_sender {
+        for (index, entry) in entries.iter().enumerate() {
+            let entry_index = progress.num_entries.saturating_add(index);
+            if let Err(err) = entry_notification_sender.send(EntryNotification {
+                slot,
+                index: entry_index,
+                entry: entry.into(),
+            }) {
+                warn!(
+                    "Slot {}, entry {} entry_notification_sender send failed: {:?}",
+                    slot, entry_index, err
+                );
+            }
+        }
+    }
+
     let last_entry_hash = entries.last().map(|e| e.hash);
     let verifier = if !skip_verification {
         datapoint_debug!("verify-batch-size", ("size", num_entries as i64, i64));
@@ -1222,6 +1258,7 @@ fn process_bank_0(
     opts: &ProcessOptions,
     recyclers: &VerifyRecyclers,
     cache_block_meta_sender: Option<&CacheBlockMetaSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
 ) {
     assert_eq!(bank0.slot(), 0);
     let mut progress = ConfirmationProgress::new(bank0.last_blockhash());
@@ -1232,6 +1269,7 @@ fn process_bank_0(
         recyclers,
         &mut progress,
         None,
+        entry_notification_sender,
         None,
         &mut ExecuteTimings::default(),
     )
@@ -1310,6 +1348,7 @@ fn load_frozen_forks(
     opts: &ProcessOptions,
     transaction_status_sender: Option<&TransactionStatusSender>,
     cache_block_meta_sender: Option<&CacheBlockMetaSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     timing: &mut ExecuteTimings,
     accounts_background_request_sender: &AbsRequestSender,
 ) -> result::Result<u64, BlockstoreProcessorError> {
@@ -1381,6 +1420,7 @@ fn load_frozen_forks(
                 &mut progress,
                 transaction_status_sender,
                 cache_block_meta_sender,
+                entry_notification_sender,
                 None,
                 timing,
             )
@@ -1487,10 +1527,16 @@ fn load_frozen_forks(
                 slot,
             );
 
-            let done_processing = opts
-                .halt_at_slot
-                .map(|halt_at_slot| slot >= halt_at_slot)
+            let halted_by_callback = opts
+                .slot_callback
+                .as_ref()
+                .map(|slot_callback| slot_callback(&bank))
                 .unwrap_or(false);
+            let done_processing = halted_by_callback
+                || opts
+                    .halt_at_slot
+                    .map(|halt_at_slot| slot >= halt_at_slot)
+                    .unwrap_or(false);
             if done_processing {
                 if opts.run_final_accounts_hash_calc {
                     run_final_hash_calc(&bank, on_halt_store_hash_raw_data_for_debug);
@@ -1592,6 +1638,7 @@ fn process_single_slot(
     progress: &mut ConfirmationProgress,
     transaction_status_sender: Option<&TransactionStatusSender>,
     cache_block_meta_sender: Option<&CacheBlockMetaSender>,
+    entry_notification_sender: Option<&EntryNotifierSender>,
     replay_vote_sender: Option<&ReplayVoteSender>,
     timing: &mut ExecuteTimings,
 ) -> result::Result<(), BlockstoreProcessorError> {
@@ -1604,6 +1651,7 @@ fn process_single_slot(
         recyclers,
         progress,
         transaction_status_sender,
+        entry_notification_sender,
         replay_vote_sender,
         timing,
     )
@@ -2702,6 +2750,47 @@ pub mod tests {
         assert_eq!(*callback_counter.write().unwrap(), 2);
     }
 
+    #[test]
+    fn test_process_ledger_options_slot_callback() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(123);
+
+        // Create a chain of slots 0 - 3
+        let forks = tr(0) / (tr(1) / (tr(2) / tr(3)));
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        blockstore.add_tree(
+            forks,
+            false,
+            true,
+            genesis_config.ticks_per_slot,
+            genesis_config.hash(),
+        );
+
+        let processed_slots: Arc<RwLock<Vec<Slot>>> = Arc::default();
+        let slot_callback = {
+            let processed_slots = processed_slots.clone();
+            Arc::new(move |bank: &Bank| {
+                assert!(bank.is_frozen());
+                processed_slots.write().unwrap().push(bank.slot());
+                bank.slot() == 2
+            })
+        };
+
+        let opts = ProcessOptions {
+            slot_callback: Some(slot_callback),
+            accounts_db_test_hash_calculation: true,
+            ..ProcessOptions::default()
+        };
+        let (bank_forks, ..) =
+            test_process_blockstore(&genesis_config, &blockstore, &opts, &Arc::default());
+
+        // Processing halts at slot 2, so slot 3 is never replayed
+        assert_eq!(*processed_slots.read().unwrap(), vec![1, 2]);
+        let bank_forks = bank_forks.read().unwrap();
+        assert!(bank_forks.get(2).is_some());
+        assert!(bank_forks.get(3).is_none());
+    }
+
     #[test]
     fn test_process_entries_tick() {
         let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(1000);
@@ -3406,7 +3495,7 @@ pub mod tests {
             ..ProcessOptions::default()
         };
         let recyclers = VerifyRecyclers::default();
-        process_bank_0(&bank0, &blockstore, &opts, &recyclers, None);
+        process_bank_0(&bank0, &blockstore, &opts, &recyclers, None, None);
         let bank1 = bank_forks.insert(Bank::new_from_parent(&bank0, &Pubkey::default(), 1));
         confirm_full_slot(
             &blockstore,
@@ -3416,6 +3505,7 @@ pub mod tests {
             &mut ConfirmationProgress::new(bank0.last_blockhash()),
             None,
             None,
+            None,
             &mut ExecuteTimings::default(),
         )
         .unwrap();
@@ -3436,6 +3526,7 @@ pub mod tests {
             &opts,
             None,
             None,
+            None,
             &AbsRequestSender::default(),
         )
         .unwrap();
@@ -4059,6 +4150,7 @@ pub mod tests {
             None,
             None,
             None,
+            None,
             &VerifyRecyclers::default(),
             None,
             &PrioritizationFeeCache::new(0u64),
@@ -4177,6 +4269,8 @@ pub mod tests {
         let transaction_status_sender = TransactionStatusSender {
             sender: transaction_status_sender,
         };
+        let (entry_notification_sender, entry_notification_receiver) =
+            crossbeam_channel::unbounded();
 
         let blockhash = bank.last_blockhash();
         let tx1 = system_transaction::transfer(
@@ -4201,6 +4295,7 @@ pub mod tests {
             &mut progress,
             false,
             Some(&transaction_status_sender),
+            Some(&entry_notification_sender),
             None,
             None,
             &VerifyRecyclers::default(),
@@ -4209,6 +4304,11 @@ pub mod tests {
         )
         .unwrap();
         assert_eq!(progress.num_txs, 2);
+        let entry_notification = entry_notification_receiver.recv().unwrap();
+        assert_eq!(entry_notification.slot, bank.slot());
+        assert_eq!(entry_notification.index, 0);
+        assert_eq!(entry_notification.entry.hash, new_hash);
+        assert_eq!(entry_notification.entry.num_transactions, 2);
         let batch = transaction_status_receiver.recv().unwrap();
         if let TransactionStatusMessage::Batch(batch) = batch {
             assert_eq!(batch.transactions.len(), 2);
@@ -4247,6 +4347,7 @@ pub mod tests {
             &mut progress,
             false,
             Some(&transaction_status_sender),
+            Some(&entry_notification_sender),
             None,
             None,
             &VerifyRecyclers::default(),
@@ -4255,6 +4356,9 @@ pub mod tests {
         )
         .unwrap();
         assert_eq!(progress.num_txs, 5);
+        let entry_notification = entry_notification_receiver.recv().unwrap();
+        assert_eq!(entry_notification.index, 1);
+        assert_eq!(entry_notification.entry.num_transactions, 3);
         let batch = transaction_status_receiver.recv().unwrap();
         if let TransactionStatusMessage::Batch(batch) = batch {
             assert_eq!(batch.transactions.len(), 3);
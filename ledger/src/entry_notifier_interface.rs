@@ -0,0 +1,12 @@
+use {
+    solana_entry::entry::EntrySummary,
+    solana_sdk::clock::Slot,
+    std::sync::{Arc, RwLock},
+};
+
+/// Interface for notifying the entries replayed from the blockstore
+pub trait EntryNotifier {
+    fn notify_entry(&self, slot: Slot, index: usize, entry: &EntrySummary);
+}
+
+pub type EntryNotifierLock = Arc<RwLock<dyn EntryNotifier + Sync + Send>>;
@@ -6,7 +6,12 @@ use {
     solana_measure::measure::Measure,
     solana_metrics::datapoint_debug,
     solana_runtime::{bank::Bank, transaction_batch::TransactionBatch},
-    solana_sdk::{account::ReadableAccount, pubkey::Pubkey},
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        pubkey::Pubkey,
+        transaction::SanitizedTransaction,
+        transaction_context::TransactionAccount,
+    },
     solana_transaction_status::{
         token_balances::TransactionTokenBalances, TransactionTokenBalance,
     },
@@ -44,41 +49,78 @@ pub fn collect_token_balances(
     let mut collect_time = Measure::start("collect_token_balances");
 
     for transaction in batch.sanitized_transactions() {
-        let account_keys = transaction.message().account_keys();
-        let has_token_program = account_keys.iter().any(is_known_spl_token_id);
+        balances.push(collect_transaction_token_balances(
+            transaction,
+            |_index, account_id, mint_decimals| {
+                collect_token_balance_from_account(bank, account_id, mint_decimals)
+            },
+            mint_decimals,
+        ));
+    }
+    collect_time.stop();
+    datapoint_debug!(
+        "collect_token_balances",
+        ("collect_time_us", collect_time.as_us(), i64),
+    );
+    balances
+}
 
-        let mut transaction_balances: Vec<TransactionTokenBalance> = vec![];
-        if has_token_program {
-            for (index, account_id) in account_keys.iter().enumerate() {
-                if transaction.message().is_invoked(index) || is_known_spl_token_id(account_id) {
-                    continue;
-                }
+/// Collect the token balances of `transaction` from `accounts`, the states of its accounts in
+/// the order of its account keys, e.g. before or after simulating it. Mint decimals are read
+/// from `bank`.
+pub fn collect_token_balances_from_accounts(
+    bank: &Bank,
+    transaction: &SanitizedTransaction,
+    accounts: &[TransactionAccount],
+    mint_decimals: &mut HashMap<Pubkey, u8>,
+) -> Vec<TransactionTokenBalance> {
+    collect_transaction_token_balances(
+        transaction,
+        |index, _account_id, mint_decimals| {
+            let (_, account) = accounts.get(index)?;
+            token_balance_from_account(bank, account, mint_decimals)
+        },
+        mint_decimals,
+    )
+}
+
+fn collect_transaction_token_balances(
+    transaction: &SanitizedTransaction,
+    mut collect_token_balance: impl FnMut(
+        usize,
+        &Pubkey,
+        &mut HashMap<Pubkey, u8>,
+    ) -> Option<TokenBalanceData>,
+    mint_decimals: &mut HashMap<Pubkey, u8>,
+) -> Vec<TransactionTokenBalance> {
+    let account_keys = transaction.message().account_keys();
+    let has_token_program = account_keys.iter().any(is_known_spl_token_id);
+
+    let mut transaction_balances: Vec<TransactionTokenBalance> = vec![];
+    if has_token_program {
+        for (index, account_id) in account_keys.iter().enumerate() {
+            if transaction.message().is_invoked(index) || is_known_spl_token_id(account_id) {
+                continue;
+            }
 
-                if let Some(TokenBalanceData {
+            if let Some(TokenBalanceData {
+                mint,
+                ui_token_amount,
+                owner,
+                program_id,
+            }) = collect_token_balance(index, account_id, mint_decimals)
+            {
+                transaction_balances.push(TransactionTokenBalance {
+                    account_index: index as u8,
                     mint,
                     ui_token_amount,
                     owner,
                     program_id,
-                }) = collect_token_balance_from_account(bank, account_id, mint_decimals)
-                {
-                    transaction_balances.push(TransactionTokenBalance {
-                        account_index: index as u8,
-                        mint,
-                        ui_token_amount,
-                        owner,
-                        program_id,
-                    });
-                }
+                });
             }
         }
-        balances.push(transaction_balances);
     }
-    collect_time.stop();
-    datapoint_debug!(
-        "collect_token_balances",
-        ("collect_time_us", collect_time.as_us(), i64),
-    );
-    balances
+    transaction_balances
 }
 
 #[derive(Debug, PartialEq)]
@@ -95,7 +137,14 @@ fn collect_token_balance_from_account(
     mint_decimals: &mut HashMap<Pubkey, u8>,
 ) -> Option<TokenBalanceData> {
     let account = bank.get_account(account_id)?;
+    token_balance_from_account(bank, &account, mint_decimals)
+}
 
+fn token_balance_from_account(
+    bank: &Bank,
+    account: &AccountSharedData,
+    mint_decimals: &mut HashMap<Pubkey, u8>,
+) -> Option<TokenBalanceData> {
     if !is_known_spl_token_id(account.owner()) {
         return None;
     }
@@ -122,7 +171,13 @@ mod test {
     use {
         super::*,
         solana_account_decoder::parse_token::{pubkey_from_spl_token, spl_token_pubkey},
-        solana_sdk::{account::Account, genesis_config::create_genesis_config},
+        solana_sdk::{
+            account::Account,
+            genesis_config::create_genesis_config,
+            instruction::{AccountMeta, Instruction},
+            signature::Signer,
+            transaction::Transaction,
+        },
         spl_token_2022::{
             extension::{
                 immutable_owner::ImmutableOwner, memo_transfer::MemoTransfer,
@@ -474,4 +529,97 @@ mod test {
             None
         );
     }
+
+    #[test]
+    fn test_collect_token_balances_from_accounts() {
+        let (mut genesis_config, mint_keypair) = create_genesis_config(500);
+
+        let mint_data = Mint {
+            mint_authority: COption::None,
+            supply: 4242,
+            decimals: 2,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut data = [0; Mint::LEN];
+        Mint::pack(mint_data, &mut data).unwrap();
+        let mint_pubkey = Pubkey::new_unique();
+        genesis_config.accounts.insert(
+            mint_pubkey,
+            Account {
+                lamports: 100,
+                data: data.to_vec(),
+                owner: pubkey_from_spl_token(&spl_token::id()),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        let bank = Bank::new_for_tests(&genesis_config);
+
+        // The token account only exists in the given account states, not in the bank
+        let token_owner = Pubkey::new_unique();
+        let token_data = TokenAccount {
+            mint: spl_token_pubkey(&mint_pubkey),
+            owner: spl_token_pubkey(&token_owner),
+            amount: 42,
+            delegate: COption::None,
+            state: spl_token_2022::state::AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        let mut data = [0; TokenAccount::LEN];
+        TokenAccount::pack(token_data, &mut data).unwrap();
+        let token_account_pubkey = Pubkey::new_unique();
+        let token_account = AccountSharedData::from(Account {
+            lamports: 100,
+            data: data.to_vec(),
+            owner: pubkey_from_spl_token(&spl_token::id()),
+            executable: false,
+            rent_epoch: 0,
+        });
+
+        let program_id = pubkey_from_spl_token(&spl_token::id());
+        let transaction =
+            SanitizedTransaction::from_transaction_for_tests(Transaction::new_with_payer(
+                &[Instruction::new_with_bytes(
+                    program_id,
+                    &[],
+                    vec![AccountMeta::new(token_account_pubkey, false)],
+                )],
+                Some(&mint_keypair.pubkey()),
+            ));
+        let accounts = vec![
+            (mint_keypair.pubkey(), AccountSharedData::default()),
+            (token_account_pubkey, token_account),
+            (program_id, AccountSharedData::default()),
+        ];
+        let mut mint_decimals = HashMap::new();
+
+        assert_eq!(
+            collect_token_balances_from_accounts(
+                &bank,
+                &transaction,
+                &accounts,
+                &mut mint_decimals
+            ),
+            vec![TransactionTokenBalance {
+                account_index: 1,
+                mint: mint_pubkey.to_string(),
+                ui_token_amount: UiTokenAmount {
+                    ui_amount: Some(0.42),
+                    decimals: 2,
+                    amount: "42".to_string(),
+                    ui_amount_string: "0.42".to_string(),
+                },
+                owner: token_owner.to_string(),
+                program_id: spl_token::id().to_string(),
+            }]
+        );
+        // Nothing is collected for accounts the transaction doesn't have states for
+        assert_eq!(
+            collect_token_balances_from_accounts(&bank, &transaction, &[], &mut mint_decimals),
+            vec![]
+        );
+    }
 }
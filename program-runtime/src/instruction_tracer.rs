@@ -0,0 +1,287 @@
+//! Opt-in tracing of the instructions a transaction executes, for profiling programs. The
+//! tracer records the tree of instructions and cross-program invocations, the compute units
+//! each of them consumed, the syscalls they made and, for SBF programs, a sample of the
+//! program counter.
+
+use {
+    serde::{Deserialize, Serialize},
+    solana_sdk::{instruction::InstructionError, pubkey::Pubkey},
+    std::{
+        cell::RefCell,
+        collections::BTreeMap,
+        io::{self, Write},
+        rc::Rc,
+    },
+};
+
+/// Number of SBF instructions executed between two samples of the program counter
+pub const DEFAULT_PC_SAMPLE_INTERVAL: u64 = 100;
+
+/// Whether and how to trace the instructions a transaction executes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionTracing {
+    #[default]
+    Disabled,
+    /// Trace the instructions, their compute units and syscalls. Programs keep running with
+    /// the cached, JIT compiled executors.
+    Enabled,
+    /// Also sample the SBF program counter every given number of SBF instructions. This runs
+    /// programs in the interpreter, with executors that are compiled for the transaction and
+    /// not cached.
+    SampleProgramCounter(u64),
+}
+
+impl InstructionTracing {
+    /// A new tracer for a transaction, or None if disabled
+    pub fn new_tracer(&self) -> Option<Rc<RefCell<InstructionTracer>>> {
+        match self {
+            Self::Disabled => None,
+            Self::Enabled => Some(InstructionTracer::new_ref(None)),
+            Self::SampleProgramCounter(pc_sample_interval) => {
+                Some(InstructionTracer::new_ref(Some(*pc_sample_interval)))
+            }
+        }
+    }
+}
+
+/// An instruction, either top-level or invoked by another program, in an `InstructionTrace`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct InstructionFrame {
+    pub program_id: Pubkey,
+    /// 1 for top-level instructions
+    pub stack_height: usize,
+    /// Index of the invoking instruction in `InstructionTrace::frames`
+    pub parent: Option<usize>,
+    /// Compute units consumed by the instruction, including the instructions it invoked
+    pub compute_units_consumed: u64,
+    /// Number of calls of each syscall, by name
+    pub syscall_counts: BTreeMap<String, u64>,
+    /// Number of samples at each SBF program counter, i.e. instruction index in the text
+    /// section. Empty for builtin programs.
+    pub pc_samples: BTreeMap<u64, u64>,
+    pub error: Option<InstructionError>,
+}
+
+/// The instructions a transaction executed, in the order they were invoked
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct InstructionTrace {
+    pub frames: Vec<InstructionFrame>,
+}
+
+impl InstructionTrace {
+    /// Compute units consumed by the instruction at `index` itself, excluding the instructions
+    /// it invoked
+    pub fn self_compute_units(&self, index: usize) -> u64 {
+        let Some(frame) = self.frames.get(index) else {
+            return 0;
+        };
+        self.frames
+            .iter()
+            .filter(|child| child.parent == Some(index))
+            .fold(frame.compute_units_consumed, |units, child| {
+                units.saturating_sub(child.compute_units_consumed)
+            })
+    }
+
+    /// Program ids from the top-level instruction down to the instruction at `index`, joined
+    /// by `;`
+    fn stack(&self, index: usize) -> String {
+        let mut program_ids = vec![];
+        let mut next = Some(index);
+        while let Some(frame) = next.and_then(|index| self.frames.get(index)) {
+            program_ids.push(frame.program_id.to_string());
+            next = frame.parent;
+        }
+        program_ids.reverse();
+        program_ids.join(";")
+    }
+
+    /// Write the trace in the folded stacks format flamegraph tools read, one line per stack
+    /// with the compute units consumed in it. The compute units of an SBF program are
+    /// attributed to its sampled program counters, in proportion to their number of samples.
+    pub fn write_folded_stacks<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for (index, frame) in self.frames.iter().enumerate() {
+            let stack = self.stack(index);
+            let mut units = self.self_compute_units(index);
+            let total_samples = frame.pc_samples.values().sum::<u64>();
+            if total_samples > 0 {
+                let frame_units = units;
+                for (pc, samples) in &frame.pc_samples {
+                    let pc_units = u128::from(frame_units)
+                        .saturating_mul(u128::from(*samples))
+                        .checked_div(u128::from(total_samples))
+                        .and_then(|pc_units| u64::try_from(pc_units).ok())
+                        .unwrap_or_default();
+                    if pc_units > 0 {
+                        writeln!(writer, "{stack};pc:{pc} {pc_units}")?;
+                    }
+                    units = units.saturating_sub(pc_units);
+                }
+            }
+            if units > 0 {
+                writeln!(writer, "{stack} {units}")?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn folded_stacks(&self) -> String {
+        let mut folded_stacks = vec![];
+        self.write_folded_stacks(&mut folded_stacks).unwrap();
+        String::from_utf8(folded_stacks).unwrap()
+    }
+}
+
+/// Records an `InstructionTrace` while `InvokeContext` processes instructions
+#[derive(Debug, Default)]
+pub struct InstructionTracer {
+    trace: InstructionTrace,
+    /// Indexes of the instructions being processed, with the remaining compute units when
+    /// they started
+    stack: Vec<(usize, u64)>,
+    pc_sample_interval: Option<u64>,
+    sbf_instruction_count: u64,
+}
+
+impl InstructionTracer {
+    /// Create a tracer sampling the SBF program counter every `pc_sample_interval` SBF
+    /// instructions, or not sampling it if None
+    pub fn new(pc_sample_interval: Option<u64>) -> Self {
+        Self {
+            pc_sample_interval: pc_sample_interval.filter(|interval| *interval > 0),
+            ..Self::default()
+        }
+    }
+
+    pub fn new_ref(pc_sample_interval: Option<u64>) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self::new(pc_sample_interval)))
+    }
+
+    /// Sampling the program counter requires executing SBF programs in the interpreter with
+    /// instruction tracing enabled
+    pub fn samples_pc(&self) -> bool {
+        self.pc_sample_interval.is_some()
+    }
+
+    pub fn begin_instruction(&mut self, program_id: Pubkey, remaining_units: u64) {
+        let index = self.trace.frames.len();
+        self.trace.frames.push(InstructionFrame {
+            program_id,
+            stack_height: self.stack.len().saturating_add(1),
+            parent: self.stack.last().map(|(parent, _)| *parent),
+            ..InstructionFrame::default()
+        });
+        self.stack.push((index, remaining_units));
+    }
+
+    pub fn end_instruction(&mut self, remaining_units: u64, result: &Result<(), InstructionError>) {
+        if let Some((index, start_remaining_units)) = self.stack.pop() {
+            if let Some(frame) = self.trace.frames.get_mut(index) {
+                frame.compute_units_consumed =
+                    start_remaining_units.saturating_sub(remaining_units);
+                frame.error = result.as_ref().err().cloned();
+            }
+        }
+    }
+
+    fn current_frame(&mut self) -> Option<&mut InstructionFrame> {
+        let (index, _) = self.stack.last()?;
+        self.trace.frames.get_mut(*index)
+    }
+
+    pub fn record_syscall(&mut self, name: &str) {
+        if let Some(frame) = self.current_frame() {
+            let count = frame.syscall_counts.entry(name.to_string()).or_default();
+            *count = count.saturating_add(1);
+        }
+    }
+
+    /// Called for every SBF instruction executed, with its program counter
+    pub fn record_sbf_instruction(&mut self, pc: u64) {
+        let Some(pc_sample_interval) = self.pc_sample_interval else {
+            return;
+        };
+        let is_sample = self.sbf_instruction_count.checked_rem(pc_sample_interval) == Some(0);
+        self.sbf_instruction_count = self.sbf_instruction_count.saturating_add(1);
+        if is_sample {
+            if let Some(frame) = self.current_frame() {
+                let samples = frame.pc_samples.entry(pc).or_default();
+                *samples = samples.saturating_add(1);
+            }
+        }
+    }
+
+    pub fn into_trace(self) -> InstructionTrace {
+        self.trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instruction_tracer() {
+        let program_a = Pubkey::new_unique();
+        let program_b = Pubkey::new_unique();
+        let mut tracer = InstructionTracer::new(Some(2));
+        tracer.begin_instruction(program_a, 1_000);
+        tracer.record_syscall("SyscallLog");
+        for pc in 0..4 {
+            tracer.record_sbf_instruction(pc);
+        }
+        tracer.record_syscall("SyscallInvokeSignedRust");
+        tracer.begin_instruction(program_b, 900);
+        tracer.record_syscall("SyscallLog");
+        tracer.record_syscall("SyscallLog");
+        tracer.end_instruction(600, &Err(InstructionError::Custom(1)));
+        tracer.end_instruction(500, &Ok(()));
+        tracer.begin_instruction(program_b, 500);
+        tracer.end_instruction(450, &Ok(()));
+
+        let trace = tracer.into_trace();
+        assert_eq!(
+            trace.frames,
+            vec![
+                InstructionFrame {
+                    program_id: program_a,
+                    stack_height: 1,
+                    parent: None,
+                    compute_units_consumed: 500,
+                    syscall_counts: BTreeMap::from([
+                        ("SyscallInvokeSignedRust".to_string(), 1),
+                        ("SyscallLog".to_string(), 1),
+                    ]),
+                    pc_samples: BTreeMap::from([(0, 1), (2, 1)]),
+                    error: None,
+                },
+                InstructionFrame {
+                    program_id: program_b,
+                    stack_height: 2,
+                    parent: Some(0),
+                    compute_units_consumed: 300,
+                    syscall_counts: BTreeMap::from([("SyscallLog".to_string(), 2)]),
+                    pc_samples: BTreeMap::default(),
+                    error: Some(InstructionError::Custom(1)),
+                },
+                InstructionFrame {
+                    program_id: program_b,
+                    stack_height: 1,
+                    parent: None,
+                    compute_units_consumed: 50,
+                    ..InstructionFrame::default()
+                },
+            ]
+        );
+        assert_eq!(trace.self_compute_units(0), 200);
+        assert_eq!(
+            trace.folded_stacks(),
+            format!(
+                "{program_a};pc:0 100\n\
+                 {program_a};pc:2 100\n\
+                 {program_a};{program_b} 300\n\
+                 {program_b} 50\n"
+            )
+        );
+    }
+}
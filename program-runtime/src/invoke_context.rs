@@ -4,6 +4,7 @@ use {
         compute_budget::ComputeBudget,
         executor_cache::TransactionExecutorCache,
         ic_logger_msg, ic_msg,
+        instruction_tracer::InstructionTracer,
         log_collector::LogCollector,
         pre_account::PreAccount,
         stable_log,
@@ -61,6 +62,13 @@ impl std::fmt::Debug for BuiltinProgram {
 
 impl<'a> ContextObject for InvokeContext<'a> {
     fn trace(&mut self, state: [u64; 12]) {
+        if let Some(instruction_tracer) = &self.instruction_tracer {
+            // the last element of the state is the program counter
+            instruction_tracer
+                .borrow_mut()
+                .record_sbf_instruction(state[11]);
+            return;
+        }
         self.trace_log_stack
             .last_mut()
             .expect("Inconsistent trace log stack")
@@ -109,6 +117,7 @@ pub struct InvokeContext<'a> {
     pub sysvar_cache: Cow<'a, SysvarCache>,
     pub trace_log_stack: Vec<Vec<[u64; 12]>>,
     log_collector: Option<Rc<RefCell<LogCollector>>>,
+    instruction_tracer: Option<Rc<RefCell<InstructionTracer>>>,
     compute_budget: ComputeBudget,
     current_compute_budget: ComputeBudget,
     compute_meter: RefCell<u64>,
@@ -144,6 +153,7 @@ impl<'a> InvokeContext<'a> {
             sysvar_cache,
             trace_log_stack: vec![Vec::new()],
             log_collector,
+            instruction_tracer: None,
             current_compute_budget: compute_budget,
             compute_budget,
             compute_meter: RefCell::new(compute_budget.compute_unit_limit),
@@ -742,6 +752,12 @@ impl<'a> InvokeContext<'a> {
                     .set_return_data(program_id, Vec::new())?;
 
                 let pre_remaining_units = self.get_remaining();
+                let instruction_tracer = self.get_instruction_tracer();
+                if let Some(instruction_tracer) = &instruction_tracer {
+                    instruction_tracer
+                        .borrow_mut()
+                        .begin_instruction(program_id, pre_remaining_units);
+                }
                 let result = if builtin_id == program_id {
                     let logger = self.get_log_collector();
                     stable_log::program_invoke(&logger, &program_id, self.get_stack_height());
@@ -758,6 +774,11 @@ impl<'a> InvokeContext<'a> {
                 };
                 let post_remaining_units = self.get_remaining();
                 *compute_units_consumed = pre_remaining_units.saturating_sub(post_remaining_units);
+                if let Some(instruction_tracer) = &instruction_tracer {
+                    instruction_tracer
+                        .borrow_mut()
+                        .end_instruction(post_remaining_units, &result);
+                }
 
                 process_executable_chain_time.stop();
                 saturating_add_assign!(
@@ -779,6 +800,26 @@ impl<'a> InvokeContext<'a> {
         self.log_collector.clone()
     }
 
+    /// Trace the instructions processed from now on with `instruction_tracer`
+    pub fn set_instruction_tracer(
+        &mut self,
+        instruction_tracer: Option<Rc<RefCell<InstructionTracer>>>,
+    ) {
+        self.instruction_tracer = instruction_tracer;
+    }
+
+    /// Get this invocation's InstructionTracer
+    pub fn get_instruction_tracer(&self) -> Option<Rc<RefCell<InstructionTracer>>> {
+        self.instruction_tracer.clone()
+    }
+
+    /// Record a call of the syscall `name` by the current instruction, if tracing
+    pub fn trace_syscall(&self, name: &str) {
+        if let Some(instruction_tracer) = &self.instruction_tracer {
+            instruction_tracer.borrow_mut().record_syscall(name);
+        }
+    }
+
     /// Consume compute units
     pub fn consume_checked(&self, amount: u64) -> Result<(), InstructionError> {
         let mut compute_meter = self.compute_meter.borrow_mut();
@@ -1319,7 +1360,9 @@ mod tests {
         // Compute unit consumption tests
         let compute_units_to_consume = 10;
         let expected_results = vec![Ok(()), Err(InstructionError::GenericError)];
-        for expected_result in expected_results {
+        let instruction_tracer = InstructionTracer::new_ref(None);
+        invoke_context.set_instruction_tracer(Some(instruction_tracer.clone()));
+        for expected_result in expected_results.clone() {
             invoke_context
                 .transaction_context
                 .get_next_instruction_context()
@@ -1356,6 +1399,20 @@ mod tests {
 
             invoke_context.pop().unwrap();
         }
+
+        // Instruction tracing tests
+        invoke_context.set_instruction_tracer(None);
+        let instruction_trace = Rc::try_unwrap(instruction_tracer)
+            .unwrap()
+            .into_inner()
+            .into_trace();
+        assert_eq!(instruction_trace.frames.len(), expected_results.len());
+        for (frame, expected_result) in instruction_trace.frames.iter().zip(expected_results) {
+            assert_eq!(frame.program_id, callee_program_id);
+            assert_eq!(frame.parent, None);
+            assert_eq!(frame.compute_units_consumed, compute_units_to_consume);
+            assert_eq!(frame.error, expected_result.err());
+        }
     }
 
     #[test]
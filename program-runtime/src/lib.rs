@@ -13,6 +13,7 @@ pub mod accounts_data_meter;
 pub mod compute_budget;
 pub mod executor;
 pub mod executor_cache;
+pub mod instruction_tracer;
 pub mod invoke_context;
 pub mod log_collector;
 pub mod pre_account;
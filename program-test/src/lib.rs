@@ -7,6 +7,8 @@ use {
     async_trait::async_trait,
     chrono_humanize::{Accuracy, HumanTime, Tense},
     log::*,
+    serde_derive::Deserialize,
+    solana_account_decoder::UiAccount,
     solana_banks_client::start_client,
     solana_banks_server::banks_server::start_local_server,
     solana_bpf_loader_program::serialization::serialize_parameters,
@@ -16,16 +18,17 @@ use {
     },
     solana_runtime::{
         accounts_background_service::{AbsRequestSender, SnapshotRequestType},
-        bank::Bank,
+        bank::{Bank, TransactionSimulationResult},
         bank_forks::BankForks,
         builtins::Builtin,
         commitment::BlockCommitmentCache,
         epoch_accounts_hash::EpochAccountsHash,
         genesis_utils::{create_genesis_config_with_leader_ex, GenesisConfigInfo},
         runtime_config::RuntimeConfig,
+        snapshot_utils,
     },
     solana_sdk::{
-        account::{Account, AccountSharedData},
+        account::{Account, AccountSharedData, ReadableAccount},
         account_info::AccountInfo,
         clock::Slot,
         entrypoint::{deserialize, ProgramResult, SUCCESS},
@@ -41,16 +44,21 @@ use {
         rent::Rent,
         signature::{Keypair, Signer},
         sysvar::{Sysvar, SysvarId},
+        transaction::{
+            self, MessageHash, SanitizedTransaction, TransactionError, VersionedTransaction,
+        },
     },
     solana_vote_program::vote_state::{self, VoteState, VoteStateVersions},
     std::{
         cell::RefCell,
         collections::{HashMap, HashSet},
         convert::TryFrom,
+        ffi::OsStr,
         fs::File,
-        io::{self, Read},
+        io::{self, BufReader, Read},
         mem::transmute,
         path::{Path, PathBuf},
+        str::FromStr,
         sync::{
             atomic::{AtomicBool, Ordering},
             Arc, RwLock,
@@ -64,7 +72,10 @@ use {
 pub use {
     solana_banks_client::{BanksClient, BanksClientError},
     solana_banks_interface::BanksTransactionResultWithMetadata,
-    solana_program_runtime::invoke_context::InvokeContext,
+    solana_program_runtime::{
+        instruction_tracer::{InstructionTrace, InstructionTracing, DEFAULT_PC_SAMPLE_INTERVAL},
+        invoke_context::InvokeContext,
+    },
     solana_sdk::transaction_context::IndexOfAccount,
 };
 
@@ -436,6 +447,54 @@ pub fn read_file<P: AsRef<Path>>(path: P) -> Vec<u8> {
     file_data
 }
 
+/// Selects which accounts to add to the test environment when adding accounts in bulk, from a
+/// snapshot archive or a directory of account files
+#[derive(Clone, Debug, Default)]
+pub struct AccountsFilter {
+    /// Add the accounts owned by these programs
+    pub owners: HashSet<Pubkey>,
+    /// Add these accounts
+    pub pubkeys: HashSet<Pubkey>,
+}
+
+impl AccountsFilter {
+    /// Select all accounts
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Select the accounts owned by `owners`
+    pub fn owners(owners: impl IntoIterator<Item = Pubkey>) -> Self {
+        Self {
+            owners: owners.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Select the accounts with addresses in `pubkeys`
+    pub fn pubkeys(pubkeys: impl IntoIterator<Item = Pubkey>) -> Self {
+        Self {
+            pubkeys: pubkeys.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether the account is selected, either by its owner or its address. All accounts are
+    /// selected if neither owners nor addresses are specified.
+    pub fn matches(&self, address: &Pubkey, owner: &Pubkey) -> bool {
+        (self.owners.is_empty() && self.pubkeys.is_empty())
+            || self.owners.contains(owner)
+            || self.pubkeys.contains(address)
+    }
+}
+
+/// An account in the format `solana account --output json` writes
+#[derive(Deserialize)]
+struct JsonAccount {
+    pubkey: String,
+    account: UiAccount,
+}
+
 pub struct ProgramTest {
     accounts: Vec<(Pubkey, AccountSharedData)>,
     builtins: Vec<Builtin>,
@@ -576,6 +635,65 @@ impl ProgramTest {
         );
     }
 
+    /// Add the latest version of the accounts `filter` selects in a full snapshot archive, and
+    /// optionally an incremental snapshot archive on top of it, to the test environment.
+    ///
+    /// The archives are searched for like program shared objects, and must keep the file names
+    /// they were created with.
+    pub fn add_accounts_from_snapshot_archives(
+        &mut self,
+        full_snapshot_archive: &str,
+        incremental_snapshot_archive: Option<&str>,
+        filter: &AccountsFilter,
+    ) {
+        let find_archive = |filename: &str| {
+            find_file(filename).unwrap_or_else(|| panic!("Unable to locate {filename}"))
+        };
+        let accounts = snapshot_utils::accounts_from_snapshot_archives(
+            find_archive(full_snapshot_archive),
+            incremental_snapshot_archive.map(find_archive),
+            |stored_account| filter.matches(stored_account.pubkey(), stored_account.owner()),
+        )
+        .unwrap_or_else(|err| panic!("Failed to load accounts from snapshot archives: {err}"));
+        info!(
+            "Adding {} accounts from snapshot archive {}",
+            accounts.len(),
+            full_snapshot_archive
+        );
+        self.accounts.extend(accounts);
+    }
+
+    /// Add the accounts `filter` selects in the `.json` files of `dirname` to the test
+    /// environment. The files are in the format `solana account --output json` writes.
+    pub fn add_accounts_from_directory(&mut self, dirname: &str, filter: &AccountsFilter) {
+        let dir = find_file(dirname).unwrap_or_else(|| panic!("Unable to locate {dirname}"));
+        let mut paths = std::fs::read_dir(&dir)
+            .unwrap_or_else(|err| panic!("Failed to read \"{}\": {}", dir.display(), err))
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension() == Some(OsStr::new("json")))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        for path in paths {
+            let file = File::open(&path)
+                .unwrap_or_else(|err| panic!("Failed to open \"{}\": {}", path.display(), err));
+            let json_account: JsonAccount = serde_json::from_reader(BufReader::new(file))
+                .unwrap_or_else(|err| {
+                    panic!("Unable to deserialize \"{}\": {}", path.display(), err)
+                });
+            let address = Pubkey::from_str(&json_account.pubkey)
+                .unwrap_or_else(|err| panic!("Invalid pubkey in \"{}\": {}", path.display(), err));
+            let account = json_account
+                .account
+                .decode::<AccountSharedData>()
+                .unwrap_or_else(|| panic!("Unable to decode account in \"{}\"", path.display()));
+            if filter.matches(&address, account.owner()) {
+                self.accounts.push((address, account));
+            }
+        }
+    }
+
     /// Add a SBF program to the test environment.
     ///
     /// `program_name` will also be used to locate the SBF shared object in the current or fixtures
@@ -1098,6 +1216,40 @@ impl ProgramTestContext {
         bank.set_sysvar_for_tests(sysvar);
     }
 
+    /// Execute `transaction` against the working bank without committing it, and return the
+    /// trace of the instructions it executed: the compute units each instruction and
+    /// cross-program invocation consumed, their syscalls and sampled program counters. Use
+    /// `InstructionTrace::write_folded_stacks` to render the trace as a flamegraph.
+    ///
+    /// Returns the transaction error if the transaction could not be executed
+    pub fn trace_transaction(
+        &self,
+        transaction: impl Into<VersionedTransaction>,
+    ) -> transaction::Result<InstructionTrace> {
+        let bank_forks = self.bank_forks.read().unwrap();
+        let bank = bank_forks.working_bank();
+        let transaction = SanitizedTransaction::try_create(
+            transaction.into(),
+            MessageHash::Compute,
+            Some(false), // is_simple_vote_tx
+            bank.as_ref(),
+            true, // require_static_program_ids
+        )?;
+        let TransactionSimulationResult {
+            result,
+            instruction_trace,
+            ..
+        } = bank.simulate_transaction_unchecked(
+            transaction,
+            false, // enable_cpi_recording
+            InstructionTracing::SampleProgramCounter(DEFAULT_PC_SAMPLE_INTERVAL),
+        );
+        match instruction_trace {
+            Some(instruction_trace) => Ok(instruction_trace),
+            None => Err(result.err().unwrap_or(TransactionError::SanitizeFailure)),
+        }
+    }
+
     /// Force the working bank ahead to a new slot
     pub fn warp_to_slot(&mut self, warp_slot: Slot) -> Result<(), ProgramTestError> {
         let mut bank_forks = self.bank_forks.write().unwrap();
@@ -0,0 +1,133 @@
+use {
+    solana_program_test::{AccountsFilter, ProgramTest},
+    solana_runtime::{
+        bank::Bank,
+        genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        snapshot_archive_info::SnapshotArchiveInfoGetter,
+        snapshot_utils::{
+            self, ArchiveFormat, DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        },
+    },
+    solana_sdk::{
+        account::{Account, AccountSharedData},
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    std::fs,
+};
+
+#[tokio::test]
+async fn add_accounts_from_directory() {
+    let accounts_dir = tempfile::TempDir::new().unwrap();
+    let owner = Pubkey::new_unique();
+    let other_owner = Pubkey::new_unique();
+    let selected_account = Pubkey::new_unique();
+    let accounts = [
+        (Pubkey::new_unique(), owner, vec![1, 2, 3]),
+        (Pubkey::new_unique(), other_owner, vec![4, 5]),
+        (selected_account, other_owner, vec![6]),
+    ];
+    for (index, (address, owner, data)) in accounts.iter().enumerate() {
+        let json_account = serde_json::json!({
+            "pubkey": address.to_string(),
+            "account": {
+                "lamports": 1_000_000,
+                "data": [base64::encode(data), "base64"],
+                "owner": owner.to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": data.len(),
+            },
+        });
+        fs::write(
+            accounts_dir.path().join(format!("account{index}.json")),
+            json_account.to_string(),
+        )
+        .unwrap();
+    }
+
+    let mut program_test = ProgramTest::default();
+    let mut filter = AccountsFilter::owners([owner]);
+    filter.pubkeys.insert(selected_account);
+    program_test.add_accounts_from_directory(accounts_dir.path().to_str().unwrap(), &filter);
+    let mut context = program_test.start_with_context().await;
+
+    for (address, account_owner, data) in accounts {
+        let account = context.banks_client.get_account(address).await.unwrap();
+        if account_owner == owner || address == selected_account {
+            let account = account.unwrap();
+            assert_eq!(account.owner, account_owner);
+            assert_eq!(account.data, data);
+        } else {
+            assert_eq!(account, None);
+        }
+    }
+}
+
+#[tokio::test]
+async fn add_accounts_from_snapshot_archives() {
+    let GenesisConfigInfo {
+        genesis_config,
+        mint_keypair,
+        ..
+    } = create_genesis_config(1_000_000_000);
+    let bank = Bank::new_for_tests(&genesis_config);
+    let owner = Pubkey::new_unique();
+    let owned_account = Pubkey::new_unique();
+    bank.store_account(
+        &owned_account,
+        &AccountSharedData::from(Account {
+            lamports: 1_000_000,
+            data: vec![7; 10],
+            owner,
+            ..Account::default()
+        }),
+    );
+    let recipient = Keypair::new();
+    bank.transfer(1_000_000, &mint_keypair, &recipient.pubkey())
+        .unwrap();
+    while !bank.is_complete() {
+        bank.register_tick(&Hash::new_unique());
+    }
+
+    let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+    let snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+    let snapshot_archive_info = snapshot_utils::bank_to_full_snapshot_archive(
+        &bank_snapshots_dir,
+        &bank,
+        None,
+        snapshot_archives_dir.path(),
+        snapshot_archives_dir.path(),
+        ArchiveFormat::Tar,
+        DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+    )
+    .unwrap();
+
+    let mut program_test = ProgramTest::default();
+    program_test.add_accounts_from_snapshot_archives(
+        snapshot_archive_info.path().to_str().unwrap(),
+        None,
+        &AccountsFilter::owners([owner]),
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let account = context
+        .banks_client
+        .get_account(owned_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, owner);
+    assert_eq!(account.data, vec![7; 10]);
+    assert_eq!(
+        context
+            .banks_client
+            .get_account(recipient.pubkey())
+            .await
+            .unwrap(),
+        None
+    );
+}
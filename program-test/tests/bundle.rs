@@ -0,0 +1,56 @@
+use {
+    solana_program_test::ProgramTest,
+    solana_sdk::{
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_transaction,
+        transaction::TransactionError,
+    },
+};
+
+#[tokio::test]
+async fn simulate_bundle() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let rent_exempt_amount = Rent::default().minimum_balance(0);
+    let alice = Keypair::new();
+    let bob = Pubkey::new_unique();
+    let fund_alice = system_transaction::transfer(
+        &context.payer,
+        &alice.pubkey(),
+        3 * rent_exempt_amount,
+        context.last_blockhash,
+    );
+    let alice_to_bob =
+        system_transaction::transfer(&alice, &bob, rent_exempt_amount, context.last_blockhash);
+
+    // Alice has to be funded by an earlier transaction of the bundle
+    let result = context
+        .banks_client
+        .simulate_transaction(alice_to_bob.clone())
+        .await
+        .unwrap();
+    assert_eq!(result.result, Some(Err(TransactionError::AccountNotFound)));
+
+    let results = context
+        .banks_client
+        .simulate_bundle(vec![fund_alice, alice_to_bob])
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    for result in results {
+        assert_eq!(result.result, Some(Ok(())));
+        assert!(result.simulation_details.is_some());
+    }
+
+    // Nothing was committed
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(alice.pubkey())
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(context.banks_client.get_balance(bob).await.unwrap(), 0);
+}
@@ -151,6 +151,55 @@ async fn cpi() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn trace_cpi() {
+    let invoker_program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "invoker",
+        invoker_program_id,
+        processor!(invoker_process_instruction),
+    );
+    let invoked_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "invoked",
+        invoked_program_id,
+        processor!(invoked_process_instruction),
+    );
+
+    let context = program_test.start_with_context().await;
+    let instructions = vec![Instruction::new_with_bincode(
+        invoker_program_id,
+        &[0],
+        vec![AccountMeta::new_readonly(invoked_program_id, false)],
+    )];
+
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let trace = context.trace_transaction(transaction).unwrap();
+    let frames = trace
+        .frames
+        .iter()
+        .map(|frame| (frame.program_id, frame.stack_height, frame.parent))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        frames,
+        vec![
+            (invoker_program_id, 1, None),
+            (invoked_program_id, 2, Some(0)),
+        ]
+    );
+    assert!(trace.frames.iter().all(|frame| frame.error.is_none()));
+    assert!(trace
+        .folded_stacks()
+        .lines()
+        .all(|line| line.starts_with(&invoker_program_id.to_string())));
+}
+
 #[tokio::test]
 async fn cpi_dupes() {
     let invoker_program_id = Pubkey::new_unique();
@@ -126,6 +126,7 @@ fn create_executor_from_bytes(
     programdata: &[u8],
     use_jit: bool,
     reject_deployment_of_broken_elfs: bool,
+    debugging_features: bool,
 ) -> Result<Arc<BpfExecutor>, InstructionError> {
     let mut register_syscalls_time = Measure::start("register_syscalls_time");
     let disable_deploy_of_alloc_free_syscall = reject_deployment_of_broken_elfs
@@ -135,7 +136,7 @@ fn create_executor_from_bytes(
         compute_budget,
         reject_deployment_of_broken_elfs,
         disable_deploy_of_alloc_free_syscall,
-        false,
+        debugging_features,
     )
     .map_err(|e| {
         ic_logger_msg!(log_collector, "Failed to register syscalls: {}", e);
@@ -186,6 +187,7 @@ pub fn create_executor_from_account(
     program: &BorrowedAccount,
     programdata: &BorrowedAccount,
     use_jit: bool,
+    debugging_features: bool,
 ) -> Result<(Arc<dyn Executor>, Option<CreateMetrics>), InstructionError> {
     if !check_loader_id(program.get_owner()) {
         ic_logger_msg!(
@@ -247,6 +249,7 @@ pub fn create_executor_from_account(
             .ok_or(InstructionError::AccountDataTooSmall)?,
         use_jit,
         false, /* reject_deployment_of_broken_elfs */
+        debugging_features,
     )?;
     if let Some(mut tx_executor_cache) = tx_executor_cache {
         tx_executor_cache.set(*program.get_key(), executor.clone(), false);
@@ -413,15 +416,22 @@ fn process_instruction_common(
                 first_instruction_account,
             )?)
         };
+        // Sampling the program counter requires an executor with instruction tracing, which
+        // runs in the interpreter and must not be cached for other invocations
+        let samples_pc = invoke_context
+            .get_instruction_tracer()
+            .map(|instruction_tracer| instruction_tracer.borrow().samples_pc())
+            .unwrap_or(false);
         let mut get_or_create_executor_time = Measure::start("get_or_create_executor_time");
         let (executor, create_executor_metrics) = create_executor_from_account(
             &invoke_context.feature_set,
             invoke_context.get_compute_budget(),
             log_collector,
-            Some(invoke_context.tx_executor_cache.borrow_mut()),
+            (!samples_pc).then(|| invoke_context.tx_executor_cache.borrow_mut()),
             &program,
             programdata.as_ref().unwrap_or(&program),
-            use_jit,
+            use_jit && !samples_pc,
+            samples_pc,
         )?;
         drop(program);
         drop(programdata);
@@ -653,6 +663,7 @@ fn process_loader_upgradeable_instruction(
                     .ok_or(InstructionError::AccountDataTooSmall)?,
                 use_jit,
                 true,
+                false,
             )?;
             drop(buffer);
             create_executor_metrics.program_id = new_program_id.to_string();
@@ -846,6 +857,7 @@ fn process_loader_upgradeable_instruction(
                     .ok_or(InstructionError::AccountDataTooSmall)?,
                 use_jit,
                 true,
+                false,
             )?;
             drop(buffer);
             create_executor_metrics.program_id = new_program_id.to_string();
@@ -1366,6 +1378,7 @@ fn process_loader_instruction(
                 program.get_data(),
                 use_jit,
                 true,
+                false,
             )?;
             create_executor_metrics.program_id = program.get_key().to_string();
             create_executor_metrics.submit_datapoint(&mut invoke_context.timings);
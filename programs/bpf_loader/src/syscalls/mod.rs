@@ -459,6 +459,7 @@ macro_rules! declare_syscall {
                 memory_mapping: &mut MemoryMapping,
                 result: &mut ProgramResult,
             ) {
+                invoke_context.trace_syscall(stringify!($name));
                 let converted_result: ProgramResult = Self::inner_call(
                     invoke_context, arg_a, arg_b, arg_c, arg_d, arg_e, memory_mapping,
                 ).into();
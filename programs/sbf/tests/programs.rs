@@ -58,7 +58,9 @@ use {
         serialization::{deserialize_parameters, serialize_parameters},
         syscalls::create_loader,
     },
-    solana_program_runtime::invoke_context::with_mock_invoke_context,
+    solana_program_runtime::{
+        instruction_tracer::InstructionTracing, invoke_context::with_mock_invoke_context,
+    },
     solana_rbpf::{elf::Executable, verifier::RequisiteVerifier, vm::VerifiedExecutable},
     solana_runtime::{
         bank::Bank,
@@ -816,7 +818,7 @@ fn test_return_data_and_log_data_syscall() {
         let transaction = Transaction::new(&[&mint_keypair], message, blockhash);
         let sanitized_tx = SanitizedTransaction::from_transaction_for_tests(transaction);
 
-        let result = bank.simulate_transaction(sanitized_tx);
+        let result = bank.simulate_transaction(sanitized_tx, false, InstructionTracing::Disabled);
 
         assert!(result.result.is_ok());
 
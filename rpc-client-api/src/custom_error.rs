@@ -24,6 +24,9 @@ pub const JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_LEN_MISMATCH: i64 = -32013
 pub const JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET: i64 = -32014;
 pub const JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION: i64 = -32015;
 pub const JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED: i64 = -32016;
+pub const JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE: i64 = -32017;
+pub const JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_SLOT_NOT_AVAILABLE: i64 = -32018;
+pub const JSON_RPC_SERVER_ERROR_RATE_LIMITED: i64 = -32019;
 
 #[derive(Error, Debug)]
 pub enum RpcCustomError {
@@ -65,6 +68,16 @@ pub enum RpcCustomError {
     UnsupportedTransactionVersion(u8),
     #[error("MinContextSlotNotReached")]
     MinContextSlotNotReached { context_slot: Slot },
+    #[error("AccountHistoryNotAvailable")]
+    AccountHistoryNotAvailable,
+    #[error("AccountHistorySlotNotAvailable")]
+    AccountHistorySlotNotAvailable {
+        slot: Slot,
+        first_available_slot: Slot,
+        last_available_slot: Slot,
+    },
+    #[error("RateLimited")]
+    RateLimited { class: String, retry_after_ms: u64 },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -79,6 +92,12 @@ pub struct MinContextSlotNotReachedErrorData {
     pub context_slot: Slot,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitedErrorData {
+    pub retry_after_ms: u64,
+}
+
 impl From<EncodeError> for RpcCustomError {
     fn from(err: EncodeError) -> Self {
         match err {
@@ -206,6 +225,35 @@ impl From<RpcCustomError> for Error {
                     context_slot,
                 })),
             },
+            RpcCustomError::AccountHistoryNotAvailable => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE),
+                message: "Account history is not available from this node".to_string(),
+                data: None,
+            },
+            RpcCustomError::AccountHistorySlotNotAvailable {
+                slot,
+                first_available_slot,
+                last_available_slot,
+            } => Self {
+                code: ErrorCode::ServerError(
+                    JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_SLOT_NOT_AVAILABLE,
+                ),
+                message: format!(
+                    "Account history not available for slot {slot}. \
+                    Available slots: {first_available_slot}-{last_available_slot}"
+                ),
+                data: None,
+            },
+            RpcCustomError::RateLimited {
+                class,
+                retry_after_ms,
+            } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_RATE_LIMITED),
+                message: format!(
+                    "Too many {class} requests from this client; retry after {retry_after_ms}ms"
+                ),
+                data: Some(serde_json::json!(RateLimitedErrorData { retry_after_ms })),
+            },
         }
     }
 }
@@ -1,8 +1,15 @@
 #![allow(deprecated)]
 use {
     crate::version_req::VersionReq,
-    solana_sdk::account::{AccountSharedData, ReadableAccount},
-    spl_token_2022::{generic_token_account::GenericTokenAccount, state::Account},
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        rent::Rent,
+    },
+    spl_token_2022::{
+        extension::{BaseStateWithExtensions, StateWithExtensions},
+        generic_token_account::GenericTokenAccount,
+        state::{Account, Mint},
+    },
     std::borrow::Cow,
     thiserror::Error,
 };
@@ -10,6 +17,7 @@ use {
 const MAX_DATA_SIZE: usize = 128;
 const MAX_DATA_BASE58_SIZE: usize = 175;
 const MAX_DATA_BASE64_SIZE: usize = 172;
+pub const MAX_MEMCMP_ANY_OF_PATTERNS: usize = 8;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -17,73 +25,91 @@ pub enum RpcFilterType {
     DataSize(u64),
     Memcmp(Memcmp),
     TokenAccountState,
+    /// Account data length within the range
+    DataSizeRange(RpcFilterRange),
+    /// Account lamports within the range
+    LamportsRange(RpcFilterRange),
+    /// Matches if any of the patterns matches
+    MemcmpAnyOf(Vec<Memcmp>),
+    /// Matches if the pattern doesn't match
+    NotMemcmp(Memcmp),
+    /// Account holds at least the rent-exempt minimum balance for its data length
+    RentExempt,
+    /// Token-2022 account or mint carrying the extension, identified by its `ExtensionType`
+    /// discriminant
+    TokenExtension(u16),
+}
+
+/// Inclusive range; an unset bound is unbounded
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFilterRange {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
+impl RpcFilterRange {
+    pub fn contains(&self, value: u64) -> bool {
+        self.min.map_or(true, |min| value >= min) && self.max.map_or(true, |max| value <= max)
+    }
 }
 
 impl RpcFilterType {
     pub fn verify(&self) -> Result<(), RpcFilterError> {
         match self {
             RpcFilterType::DataSize(_) => Ok(()),
-            RpcFilterType::Memcmp(compare) => {
-                let encoding = compare.encoding.as_ref().unwrap_or(&MemcmpEncoding::Binary);
-                match encoding {
-                    MemcmpEncoding::Binary => {
-                        use MemcmpEncodedBytes::*;
-                        match &compare.bytes {
-                            // DEPRECATED
-                            Binary(bytes) => {
-                                if bytes.len() > MAX_DATA_BASE58_SIZE {
-                                    return Err(RpcFilterError::Base58DataTooLarge);
-                                }
-                                let bytes = bs58::decode(&bytes)
-                                    .into_vec()
-                                    .map_err(RpcFilterError::DecodeError)?;
-                                if bytes.len() > MAX_DATA_SIZE {
-                                    Err(RpcFilterError::Base58DataTooLarge)
-                                } else {
-                                    Ok(())
-                                }
-                            }
-                            Base58(bytes) => {
-                                if bytes.len() > MAX_DATA_BASE58_SIZE {
-                                    return Err(RpcFilterError::DataTooLarge);
-                                }
-                                let bytes = bs58::decode(&bytes).into_vec()?;
-                                if bytes.len() > MAX_DATA_SIZE {
-                                    Err(RpcFilterError::DataTooLarge)
-                                } else {
-                                    Ok(())
-                                }
-                            }
-                            Base64(bytes) => {
-                                if bytes.len() > MAX_DATA_BASE64_SIZE {
-                                    return Err(RpcFilterError::DataTooLarge);
-                                }
-                                let bytes = base64::decode(bytes)?;
-                                if bytes.len() > MAX_DATA_SIZE {
-                                    Err(RpcFilterError::DataTooLarge)
-                                } else {
-                                    Ok(())
-                                }
-                            }
-                            Bytes(bytes) => {
-                                if bytes.len() > MAX_DATA_SIZE {
-                                    return Err(RpcFilterError::DataTooLarge);
-                                }
-                                Ok(())
-                            }
-                        }
-                    }
+            RpcFilterType::Memcmp(compare) | RpcFilterType::NotMemcmp(compare) => compare.verify(),
+            RpcFilterType::TokenAccountState => Ok(()),
+            RpcFilterType::DataSizeRange(range) | RpcFilterType::LamportsRange(range) => {
+                match (range.min, range.max) {
+                    (Some(min), Some(max)) if min > max => Err(RpcFilterError::InvalidRange),
+                    _ => Ok(()),
                 }
             }
-            RpcFilterType::TokenAccountState => Ok(()),
+            RpcFilterType::MemcmpAnyOf(compares) => {
+                if compares.is_empty() || compares.len() > MAX_MEMCMP_ANY_OF_PATTERNS {
+                    return Err(RpcFilterError::InvalidMemcmpAnyOfLength);
+                }
+                compares.iter().try_for_each(Memcmp::verify)
+            }
+            RpcFilterType::RentExempt => Ok(()),
+            RpcFilterType::TokenExtension(_) => Ok(()),
         }
     }
 
+    /// Whether the account passes the filter, evaluating `RentExempt` with the default rent;
+    /// prefer `allows_with_rent` when the cluster rent is known
     pub fn allows(&self, account: &AccountSharedData) -> bool {
+        self.allows_with_rent(account, &Rent::default())
+    }
+
+    pub fn allows_with_rent(&self, account: &AccountSharedData, rent: &Rent) -> bool {
         match self {
             RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
             RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
             RpcFilterType::TokenAccountState => Account::valid_account_data(account.data()),
+            RpcFilterType::DataSizeRange(range) => range.contains(account.data().len() as u64),
+            RpcFilterType::LamportsRange(range) => range.contains(account.lamports()),
+            RpcFilterType::MemcmpAnyOf(compares) => compares
+                .iter()
+                .any(|compare| compare.bytes_match(account.data())),
+            RpcFilterType::NotMemcmp(compare) => !compare.bytes_match(account.data()),
+            RpcFilterType::RentExempt => rent.is_exempt(account.lamports(), account.data().len()),
+            RpcFilterType::TokenExtension(extension) => {
+                let data = account.data();
+                let extension_types =
+                    if let Ok(account) = StateWithExtensions::<Account>::unpack(data) {
+                        account.get_extension_types()
+                    } else if let Ok(mint) = StateWithExtensions::<Mint>::unpack(data) {
+                        mint.get_extension_types()
+                    } else {
+                        return false;
+                    };
+                extension_types
+                    .unwrap_or_default()
+                    .into_iter()
+                    .any(|extension_type| extension_type as u16 == *extension)
+            }
         }
     }
 }
@@ -108,6 +134,10 @@ pub enum RpcFilterError {
     Base58DecodeError(#[from] bs58::decode::Error),
     #[error("base64 decode error")]
     Base64DecodeError(#[from] base64::DecodeError),
+    #[error("range minimum should not exceed its maximum")]
+    InvalidRange,
+    #[error("memcmpAnyOf should hold between 1 and 8 patterns")]
+    InvalidMemcmpAnyOfLength,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -178,6 +208,59 @@ impl Memcmp {
         }
     }
 
+    fn verify(&self) -> Result<(), RpcFilterError> {
+        let encoding = self.encoding.as_ref().unwrap_or(&MemcmpEncoding::Binary);
+        match encoding {
+            MemcmpEncoding::Binary => {
+                use MemcmpEncodedBytes::*;
+                match &self.bytes {
+                    // DEPRECATED
+                    Binary(bytes) => {
+                        if bytes.len() > MAX_DATA_BASE58_SIZE {
+                            return Err(RpcFilterError::Base58DataTooLarge);
+                        }
+                        let bytes = bs58::decode(&bytes)
+                            .into_vec()
+                            .map_err(RpcFilterError::DecodeError)?;
+                        if bytes.len() > MAX_DATA_SIZE {
+                            Err(RpcFilterError::Base58DataTooLarge)
+                        } else {
+                            Ok(())
+                        }
+                    }
+                    Base58(bytes) => {
+                        if bytes.len() > MAX_DATA_BASE58_SIZE {
+                            return Err(RpcFilterError::DataTooLarge);
+                        }
+                        let bytes = bs58::decode(&bytes).into_vec()?;
+                        if bytes.len() > MAX_DATA_SIZE {
+                            Err(RpcFilterError::DataTooLarge)
+                        } else {
+                            Ok(())
+                        }
+                    }
+                    Base64(bytes) => {
+                        if bytes.len() > MAX_DATA_BASE64_SIZE {
+                            return Err(RpcFilterError::DataTooLarge);
+                        }
+                        let bytes = base64::decode(bytes)?;
+                        if bytes.len() > MAX_DATA_SIZE {
+                            Err(RpcFilterError::DataTooLarge)
+                        } else {
+                            Ok(())
+                        }
+                    }
+                    Bytes(bytes) => {
+                        if bytes.len() > MAX_DATA_SIZE {
+                            return Err(RpcFilterError::DataTooLarge);
+                        }
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+
     pub fn bytes(&self) -> Option<Cow<Vec<u8>>> {
         use MemcmpEncodedBytes::*;
         match &self.bytes {
@@ -427,4 +510,176 @@ mod tests {
             Err(RpcFilterError::DataTooLarge)
         );
     }
+
+    #[test]
+    fn test_verify_filters() {
+        let range = |min, max| RpcFilterRange { min, max };
+        assert_eq!(
+            RpcFilterType::DataSizeRange(range(Some(1), Some(1))).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::LamportsRange(range(None, Some(0))).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::LamportsRange(range(Some(2), Some(1))).verify(),
+            Err(RpcFilterError::InvalidRange)
+        );
+
+        let memcmp = Memcmp::new_raw_bytes(0, vec![1]);
+        assert_eq!(
+            RpcFilterType::MemcmpAnyOf(vec![memcmp.clone(); MAX_MEMCMP_ANY_OF_PATTERNS]).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::MemcmpAnyOf(vec![]).verify(),
+            Err(RpcFilterError::InvalidMemcmpAnyOfLength)
+        );
+        assert_eq!(
+            RpcFilterType::MemcmpAnyOf(vec![memcmp; MAX_MEMCMP_ANY_OF_PATTERNS + 1]).verify(),
+            Err(RpcFilterError::InvalidMemcmpAnyOfLength)
+        );
+        let too_large = Memcmp::new_raw_bytes(0, vec![0; MAX_DATA_SIZE + 1]);
+        assert_eq!(
+            RpcFilterType::MemcmpAnyOf(vec![too_large.clone()]).verify(),
+            Err(RpcFilterError::DataTooLarge)
+        );
+        assert_eq!(
+            RpcFilterType::NotMemcmp(too_large).verify(),
+            Err(RpcFilterError::DataTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_filter_serde() {
+        let filter: RpcFilterType =
+            serde_json::from_str(r#"{"dataSizeRange":{"min":10,"max":20}}"#).unwrap();
+        assert_eq!(
+            filter,
+            RpcFilterType::DataSizeRange(RpcFilterRange {
+                min: Some(10),
+                max: Some(20)
+            })
+        );
+        let filter: RpcFilterType =
+            serde_json::from_str(r#"{"lamportsRange":{"min":10}}"#).unwrap();
+        assert_eq!(
+            filter,
+            RpcFilterType::LamportsRange(RpcFilterRange {
+                min: Some(10),
+                max: None
+            })
+        );
+        let filter: RpcFilterType = serde_json::from_str(
+            r#"{"memcmpAnyOf":[{"offset":0,"bytes":"2"},{"offset":1,"bytes":"3"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            filter,
+            RpcFilterType::MemcmpAnyOf(vec![
+                Memcmp::new_base58_encoded(0, &[1]),
+                Memcmp::new_base58_encoded(1, &[2]),
+            ])
+        );
+        let filter: RpcFilterType =
+            serde_json::from_str(r#"{"notMemcmp":{"offset":0,"bytes":"2"}}"#).unwrap();
+        assert_eq!(
+            filter,
+            RpcFilterType::NotMemcmp(Memcmp::new_base58_encoded(0, &[1]))
+        );
+        let filter: RpcFilterType = serde_json::from_str(r#""rentExempt""#).unwrap();
+        assert_eq!(filter, RpcFilterType::RentExempt);
+        let filter: RpcFilterType = serde_json::from_str(r#"{"tokenExtension":7}"#).unwrap();
+        assert_eq!(filter, RpcFilterType::TokenExtension(7));
+    }
+
+    #[test]
+    fn test_allows() {
+        let rent = Rent::default();
+        let owner = solana_sdk::pubkey::Pubkey::new_unique();
+        let mut account = AccountSharedData::new(100, 5, &owner);
+        account.set_data(vec![1, 2, 3, 4, 5]);
+
+        let range = |min, max| RpcFilterRange { min, max };
+        assert!(RpcFilterType::DataSizeRange(range(Some(5), Some(5))).allows(&account));
+        assert!(RpcFilterType::DataSizeRange(range(None, Some(10))).allows(&account));
+        assert!(!RpcFilterType::DataSizeRange(range(Some(6), None)).allows(&account));
+        assert!(RpcFilterType::LamportsRange(range(Some(100), None)).allows(&account));
+        assert!(!RpcFilterType::LamportsRange(range(None, Some(99))).allows(&account));
+
+        let any_of = RpcFilterType::MemcmpAnyOf(vec![
+            Memcmp::new_raw_bytes(0, vec![9]),
+            Memcmp::new_raw_bytes(1, vec![2, 3]),
+        ]);
+        assert!(any_of.allows(&account));
+        let any_of = RpcFilterType::MemcmpAnyOf(vec![
+            Memcmp::new_raw_bytes(0, vec![9]),
+            Memcmp::new_raw_bytes(4, vec![5, 6]),
+        ]);
+        assert!(!any_of.allows(&account));
+
+        assert!(RpcFilterType::NotMemcmp(Memcmp::new_raw_bytes(0, vec![9])).allows(&account));
+        assert!(!RpcFilterType::NotMemcmp(Memcmp::new_raw_bytes(0, vec![1])).allows(&account));
+
+        assert!(!RpcFilterType::RentExempt.allows_with_rent(&account, &rent));
+        account.set_lamports(rent.minimum_balance(5));
+        assert!(RpcFilterType::RentExempt.allows_with_rent(&account, &rent));
+        let free_rent = Rent {
+            lamports_per_byte_year: 0,
+            ..Rent::default()
+        };
+        account.set_lamports(0);
+        assert!(RpcFilterType::RentExempt.allows_with_rent(&account, &free_rent));
+    }
+
+    #[test]
+    fn test_token_extension_filter() {
+        use {
+            solana_sdk::program_pack::Pack,
+            spl_token_2022::{
+                extension::{
+                    immutable_owner::ImmutableOwner, ExtensionType, StateWithExtensionsMut,
+                },
+                state::AccountState,
+            },
+        };
+
+        let owner = solana_sdk::pubkey::Pubkey::new_unique();
+        let immutable_owner = RpcFilterType::TokenExtension(ExtensionType::ImmutableOwner as u16);
+        let memo_transfer = RpcFilterType::TokenExtension(ExtensionType::MemoTransfer as u16);
+
+        let account_size =
+            ExtensionType::get_account_len::<Account>(&[ExtensionType::ImmutableOwner]);
+        let mut data = vec![0; account_size];
+        let mut state = StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data).unwrap();
+        state.base = Account {
+            state: AccountState::Initialized,
+            ..Account::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        state.init_extension::<ImmutableOwner>(true).unwrap();
+        let mut account = AccountSharedData::new(0, account_size, &owner);
+        account.set_data(data);
+        assert!(immutable_owner.allows(&account));
+        assert!(!memo_transfer.allows(&account));
+
+        // Legacy-sized account carries no extensions
+        let mut data = vec![0; Account::get_packed_len()];
+        Account::pack(
+            Account {
+                state: AccountState::Initialized,
+                ..Account::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+        account.set_data(data);
+        assert!(!immutable_owner.allows(&account));
+
+        // Non-token data
+        account.set_data(vec![1, 2, 3]);
+        assert!(!immutable_owner.allows(&account));
+    }
 }
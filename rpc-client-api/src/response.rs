@@ -7,13 +7,20 @@ use {
         fee_calculator::{FeeCalculator, FeeRateGovernor},
         hash::Hash,
         inflation::Inflation,
+        instruction::InstructionError,
         transaction::{Result, TransactionError},
     },
     solana_transaction_status::{
-        ConfirmedTransactionStatusWithSignature, TransactionConfirmationStatus, UiConfirmedBlock,
-        UiTransactionReturnData,
+        ConfirmedTransactionStatusWithSignature, EncodedTransactionWithStatusMeta,
+        TransactionConfirmationStatus, UiConfirmedBlock, UiInnerInstructions, UiLoadedAddresses,
+        UiTransactionReturnData, UiTransactionTokenBalance,
+    },
+    std::{
+        collections::{BTreeMap, HashMap},
+        fmt,
+        net::SocketAddr,
+        str::FromStr,
     },
-    std::{collections::HashMap, fmt, net::SocketAddr, str::FromStr},
     thiserror::Error,
 };
 
@@ -194,6 +201,14 @@ pub struct RpcKeyedAccount {
     pub account: UiAccount,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsPage {
+    pub accounts: Vec<RpcKeyedAccount>,
+    /// Opaque cursor of the next page, unset once the last page is returned
+    pub cursor: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SlotInfo {
     pub slot: Slot,
@@ -413,7 +428,7 @@ pub struct RpcSignatureConfirmation {
     pub status: Result<()>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcSimulateTransactionResult {
     pub err: Option<TransactionError>,
@@ -421,6 +436,56 @@ pub struct RpcSimulateTransactionResult {
     pub accounts: Option<Vec<Option<UiAccount>>>,
     pub units_consumed: Option<u64>,
     pub return_data: Option<UiTransactionReturnData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_instructions: Option<Vec<UiInnerInstructions>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_balances: Option<Vec<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_balances: Option<Vec<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_token_balances: Option<Vec<UiTransactionTokenBalance>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_token_balances: Option<Vec<UiTransactionTokenBalance>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loaded_addresses: Option<UiLoadedAddresses>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_deltas: Option<Vec<RpcAccountDelta>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction_trace: Option<RpcInstructionTrace>,
+}
+
+/// The change of an account over a simulated transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountDelta {
+    pub pubkey: String,
+    pub lamports_delta: i64,
+    pub data_len_delta: i64,
+}
+
+/// The instructions a simulated transaction executed, when `traceInstructions` is requested
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInstructionTrace {
+    pub frames: Vec<RpcInstructionFrame>,
+    /// The compute units consumed per stack of program invocations, and per sampled program
+    /// counter of SBF programs, in the folded stacks format flamegraph tools read
+    pub folded_stacks: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInstructionFrame {
+    pub program_id: String,
+    pub stack_height: usize,
+    /// Index of the invoking instruction in `frames`
+    pub parent: Option<usize>,
+    /// Including the instructions it invoked
+    pub compute_units_consumed: u64,
+    /// Excluding the instructions it invoked
+    pub self_compute_units: u64,
+    pub syscall_counts: BTreeMap<String, u64>,
+    pub err: Option<InstructionError>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -519,6 +584,16 @@ pub struct RpcBlockUpdate {
     pub err: Option<RpcBlockUpdateError>,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionUpdate {
+    pub slot: Slot,
+    /// Unset if the block containing the transaction could not be read
+    pub signature: Option<String>,
+    pub transaction: Option<EncodedTransactionWithStatusMeta>,
+    pub err: Option<RpcBlockUpdateError>,
+}
+
 impl From<ConfirmedTransactionStatusWithSignature> for RpcConfirmedTransactionStatusWithSignature {
     fn from(value: ConfirmedTransactionStatusWithSignature) -> Self {
         let ConfirmedTransactionStatusWithSignature {
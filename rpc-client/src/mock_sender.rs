@@ -13,9 +13,9 @@ use {
             Response, RpcAccountBalance, RpcBlockProduction, RpcBlockProductionRange, RpcBlockhash,
             RpcConfirmedTransactionStatusWithSignature, RpcContactInfo, RpcFees, RpcIdentity,
             RpcInflationGovernor, RpcInflationRate, RpcInflationReward, RpcKeyedAccount,
-            RpcPerfSample, RpcPrioritizationFee, RpcResponseContext, RpcSimulateTransactionResult,
-            RpcSnapshotSlotInfo, RpcStakeActivation, RpcSupply, RpcVersionInfo, RpcVoteAccountInfo,
-            RpcVoteAccountStatus, StakeActivationState,
+            RpcPerfSample, RpcPrioritizationFee, RpcProgramAccountsPage, RpcResponseContext,
+            RpcSimulateTransactionResult, RpcSnapshotSlotInfo, RpcStakeActivation, RpcSupply,
+            RpcVersionInfo, RpcVoteAccountInfo, RpcVoteAccountStatus, StakeActivationState,
         },
     },
     solana_sdk::{
@@ -349,6 +349,14 @@ impl RpcSender for MockSender {
                     accounts: None,
                     units_consumed: None,
                     return_data: None,
+                    inner_instructions: None,
+                    pre_balances: None,
+                    post_balances: None,
+                    pre_token_balances: None,
+                    post_token_balances: None,
+                    loaded_addresses: None,
+                    account_deltas: None,
+                    instruction_trace: None,
                 },
             })?,
             "getMinimumBalanceForRentExemption" => json![20],
@@ -479,6 +487,13 @@ impl RpcSender for MockSender {
                     }
                 ])?
             },
+            "getProgramAccountsPaginated" => serde_json::to_value(Response {
+                context: RpcResponseContext { slot: 1, api_version: None },
+                value: RpcProgramAccountsPage {
+                    accounts: vec![],
+                    cursor: None,
+                },
+            })?,
             _ => Value::Null,
         };
         Ok(val)
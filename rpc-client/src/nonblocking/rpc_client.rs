@@ -3975,6 +3975,38 @@ impl RpcClient {
             })?
     }
 
+    /// Returns the state of an account as of a past rooted slot.
+    ///
+    /// The value is `None` if no write to the account was recorded at or
+    /// before `slot`, or if the account had been closed by then. Only nodes
+    /// running with `--enable-rpc-account-history` serve this method.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the [`getAccountInfoAtSlot`] RPC method.
+    ///
+    /// [`getAccountInfoAtSlot`]: https://docs.solana.com/developing/clients/jsonrpc-api#getaccountinfoatslot
+    pub async fn get_account_at_slot_with_config(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        config: RpcAccountInfoAtSlotConfig,
+    ) -> RpcResult<Option<Account>> {
+        let Response {
+            context,
+            value: rpc_account,
+        } = self
+            .send::<Response<Option<UiAccount>>>(
+                RpcRequest::GetAccountInfoAtSlot,
+                json!([pubkey.to_string(), slot, config]),
+            )
+            .await?;
+        Ok(Response {
+            context,
+            value: rpc_account.and_then(|rpc_account| rpc_account.decode()),
+        })
+    }
+
     /// Get the max slot seen from retransmit stage.
     ///
     /// # RPC Reference
@@ -4474,6 +4506,77 @@ impl RpcClient {
         parse_keyed_accounts(accounts, RpcRequest::GetProgramAccounts)
     }
 
+    /// Returns a page of the accounts owned by the provided program pubkey, in ascending pubkey
+    /// order.
+    ///
+    /// The next page is requested by passing the returned `cursor` back in the config; it is
+    /// `None` once the last page is returned.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the [`getProgramAccountsPaginated`] RPC method.
+    ///
+    /// [`getProgramAccountsPaginated`]: https://docs.solana.com/developing/clients/jsonrpc-api#getprogramaccountspaginated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{
+    /// #     client_error::Error,
+    /// #     config::RpcProgramAccountsPaginatedConfig,
+    /// # };
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// #     signature::Signer,
+    /// #     signer::keypair::Keypair,
+    /// # };
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// #     let alice = Keypair::new();
+    /// let mut accounts = vec![];
+    /// let mut cursor = None;
+    /// loop {
+    ///     let config = RpcProgramAccountsPaginatedConfig {
+    ///         cursor,
+    ///         limit: Some(100),
+    ///         ..RpcProgramAccountsPaginatedConfig::default()
+    ///     };
+    ///     let page = rpc_client
+    ///         .get_program_accounts_paginated_with_config(&alice.pubkey(), config)
+    ///         .await?
+    ///         .value;
+    ///     accounts.extend(page.accounts);
+    ///     cursor = page.cursor;
+    ///     if cursor.is_none() {
+    ///         break;
+    ///     }
+    /// }
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_program_accounts_paginated_with_config(
+        &self,
+        pubkey: &Pubkey,
+        mut config: RpcProgramAccountsPaginatedConfig,
+    ) -> RpcResult<RpcProgramAccountsPage> {
+        let commitment = config
+            .account_config
+            .commitment
+            .unwrap_or_else(|| self.commitment());
+        let commitment = self.maybe_map_commitment(commitment).await?;
+        config.account_config.commitment = Some(commitment);
+        if let Some(filters) = config.filters {
+            config.filters = Some(self.maybe_map_filters(filters).await?);
+        }
+
+        self.send(
+            RpcRequest::GetProgramAccountsPaginated,
+            json!([pubkey.to_string(), config]),
+        )
+        .await
+    }
+
     /// Returns the stake minimum delegation, in lamports.
     ///
     /// # RPC Reference
@@ -4963,6 +5066,35 @@ impl RpcClient {
         .await
     }
 
+    pub async fn get_token_accounts_by_owner_with_config(
+        &self,
+        owner: &Pubkey,
+        token_account_filter: TokenAccountsFilter,
+        mut config: RpcTokenAccountsByOwnerConfig,
+    ) -> RpcResult<Vec<RpcKeyedAccount>> {
+        let token_account_filter = match token_account_filter {
+            TokenAccountsFilter::Mint(mint) => RpcTokenAccountsFilter::Mint(mint.to_string()),
+            TokenAccountsFilter::ProgramId(program_id) => {
+                RpcTokenAccountsFilter::ProgramId(program_id.to_string())
+            }
+        };
+        let commitment = config
+            .account_config
+            .commitment
+            .unwrap_or_else(|| self.commitment());
+        let commitment = self.maybe_map_commitment(commitment).await?;
+        config.account_config.commitment = Some(commitment);
+        if let Some(filters) = config.filters {
+            config.filters = Some(self.maybe_map_filters(filters).await?);
+        }
+
+        self.send(
+            RpcRequest::GetTokenAccountsByOwner,
+            json!([owner.to_string(), token_account_filter, config]),
+        )
+        .await
+    }
+
     pub async fn get_token_largest_accounts(
         &self,
         mint: &Pubkey,
@@ -3255,6 +3255,28 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_account_with_config(pubkey, config))
     }
 
+    /// Returns the state of an account as of a past rooted slot.
+    ///
+    /// The value is `None` if no write to the account was recorded at or
+    /// before `slot`, or if the account had been closed by then. Only nodes
+    /// running with `--enable-rpc-account-history` serve this method.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the [`getAccountInfoAtSlot`] RPC method.
+    ///
+    /// [`getAccountInfoAtSlot`]: https://docs.solana.com/developing/clients/jsonrpc-api#getaccountinfoatslot
+    pub fn get_account_at_slot_with_config(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        config: RpcAccountInfoAtSlotConfig,
+    ) -> RpcResult<Option<Account>> {
+        self.invoke(
+            (self.rpc_client.as_ref()).get_account_at_slot_with_config(pubkey, slot, config),
+        )
+    }
+
     /// Get the max slot seen from retransmit stage.
     ///
     /// # RPC Reference
@@ -3634,6 +3656,61 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_program_accounts_with_config(pubkey, config))
     }
 
+    /// Returns a page of the accounts owned by the provided program pubkey, in ascending pubkey
+    /// order.
+    ///
+    /// The next page is requested by passing the returned `cursor` back in the config; it is
+    /// `None` once the last page is returned.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the [`getProgramAccountsPaginated`] RPC method.
+    ///
+    /// [`getProgramAccountsPaginated`]: https://docs.solana.com/developing/clients/jsonrpc-api#getprogramaccountspaginated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{
+    /// #     client_error::Error,
+    /// #     config::RpcProgramAccountsPaginatedConfig,
+    /// # };
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// #     signature::Signer,
+    /// #     signer::keypair::Keypair,
+    /// # };
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// # let alice = Keypair::new();
+    /// let mut accounts = vec![];
+    /// let mut cursor = None;
+    /// loop {
+    ///     let config = RpcProgramAccountsPaginatedConfig {
+    ///         cursor,
+    ///         limit: Some(100),
+    ///         ..RpcProgramAccountsPaginatedConfig::default()
+    ///     };
+    ///     let page = rpc_client
+    ///         .get_program_accounts_paginated_with_config(&alice.pubkey(), config)?
+    ///         .value;
+    ///     accounts.extend(page.accounts);
+    ///     cursor = page.cursor;
+    ///     if cursor.is_none() {
+    ///         break;
+    ///     }
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get_program_accounts_paginated_with_config(
+        &self,
+        pubkey: &Pubkey,
+        config: RpcProgramAccountsPaginatedConfig,
+    ) -> RpcResult<RpcProgramAccountsPage> {
+        self.invoke(
+            (self.rpc_client.as_ref()).get_program_accounts_paginated_with_config(pubkey, config),
+        )
+    }
+
     /// Returns the stake minimum delegation, in lamports.
     ///
     /// # RPC Reference
@@ -3871,6 +3948,21 @@ impl RpcClient {
         )
     }
 
+    pub fn get_token_accounts_by_owner_with_config(
+        &self,
+        owner: &Pubkey,
+        token_account_filter: TokenAccountsFilter,
+        config: RpcTokenAccountsByOwnerConfig,
+    ) -> RpcResult<Vec<RpcKeyedAccount>> {
+        self.invoke(
+            (self.rpc_client.as_ref()).get_token_accounts_by_owner_with_config(
+                owner,
+                token_account_filter,
+                config,
+            ),
+        )
+    }
+
     pub fn get_token_largest_accounts(
         &self,
         mint: &Pubkey,
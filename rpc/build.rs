@@ -0,0 +1,26 @@
+fn main() -> Result<(), std::io::Error> {
+    const PROTOC_ENVAR: &str = "PROTOC";
+    if std::env::var(PROTOC_ENVAR).is_err() {
+        #[cfg(not(windows))]
+        std::env::set_var(PROTOC_ENVAR, protobuf_src::protoc());
+    }
+
+    let proto_base_path = std::path::PathBuf::from("proto");
+    let storage_proto_base_path = std::path::PathBuf::from("../storage-proto/proto");
+    let proto = proto_base_path.join("rpc.proto");
+    println!("cargo:rerun-if-changed={}", proto.display());
+
+    // Blocks and transactions reuse the ledger storage types instead of generating copies
+    tonic_build::configure()
+        .build_client(true)
+        .build_server(true)
+        .extern_path(
+            ".solana.storage.ConfirmedBlock",
+            "::solana_storage_proto::convert::generated",
+        )
+        .extern_path(
+            ".solana.storage.TransactionByAddr",
+            "::solana_storage_proto::convert::tx_by_addr",
+        )
+        .compile(&[proto], &[proto_base_path, storage_proto_base_path])
+}
@@ -3,7 +3,8 @@
 use {
     crate::{
         max_slots::MaxSlots, optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
-        parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_health::*,
+        parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_grpc_service::RpcGrpcConfig,
+        rpc_health::*, rpc_rate_limiter::RpcRateLimitConfig,
     },
     bincode::{config::Options, serialize},
     crossbeam_channel::{unbounded, Receiver, Sender},
@@ -25,9 +26,11 @@ use {
         blockstore_meta::{PerfSample, PerfSampleV1, PerfSampleV2},
         get_tmp_ledger_path,
         leader_schedule_cache::LeaderScheduleCache,
+        token_balances::collect_token_balances_from_accounts,
     },
     solana_metrics::inc_new_counter_info,
     solana_perf::packet::PACKET_DATA_SIZE,
+    solana_program_runtime::instruction_tracer::{InstructionTrace, InstructionTracing},
     solana_rpc_client_api::{
         config::*,
         custom_error::RpcCustomError,
@@ -36,16 +39,20 @@ use {
         request::{
             TokenAccountsFilter, DELINQUENT_VALIDATOR_SLOT_DISTANCE,
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
-            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
+            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE,
+            MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
             MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
+            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, MAX_SIMULATE_BUNDLE_TRANSACTIONS,
+            MAX_SIMULATION_ACCOUNT_OVERRIDES_DATA_LEN, NUM_LARGEST_ACCOUNTS,
         },
         response::{Response as RpcResponse, *},
     },
     solana_runtime::{
+        account_overrides::AccountOverrides,
         accounts::AccountAddressFilter,
+        accounts_hash::AccountsHashBins,
         accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey, ScanConfig},
-        bank::{Bank, TransactionSimulationResult},
+        bank::{Bank, InnerInstructionsList, TransactionSimulationResult},
         bank_forks::BankForks,
         commitment::{BlockCommitmentArray, BlockCommitmentCache, CommitmentSlots},
         inline_spl_token::{SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET},
@@ -56,7 +63,7 @@ use {
         snapshot_utils,
     },
     solana_sdk::{
-        account::{AccountSharedData, ReadableAccount},
+        account::{self, AccountSharedData, ReadableAccount},
         account_utils::StateMut,
         clock::{Slot, UnixTimestamp, MAX_RECENT_BLOCKHASHES},
         commitment_config::{CommitmentConfig, CommitmentLevel},
@@ -66,13 +73,13 @@ use {
         feature_set,
         fee_calculator::FeeCalculator,
         hash::Hash,
-        message::SanitizedMessage,
+        message::{AccountKeys, SanitizedMessage},
         pubkey::{Pubkey, PUBKEY_BYTES},
         signature::{Keypair, Signature, Signer},
         stake::state::{StakeActivationStatus, StakeState},
         stake_history::StakeHistory,
         system_instruction,
-        sysvar::stake_history,
+        sysvar::{self, stake_history},
         transaction::{
             self, AddressLoader, MessageHash, SanitizedTransaction, TransactionError,
             VersionedTransaction, MAX_TX_ACCOUNT_LOCKS,
@@ -87,9 +94,10 @@ use {
     solana_streamer::socket::SocketAddrSpace,
     solana_transaction_status::{
         BlockEncodingOptions, ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
-        ConfirmedTransactionWithStatusMeta, EncodedConfirmedTransactionWithStatusMeta, Reward,
-        RewardType, TransactionBinaryEncoding, TransactionConfirmationStatus, TransactionStatus,
-        UiConfirmedBlock, UiTransactionEncoding,
+        ConfirmedTransactionWithStatusMeta, EncodedConfirmedTransactionWithStatusMeta,
+        InnerInstruction, InnerInstructions, Reward, RewardType, TransactionBinaryEncoding,
+        TransactionConfirmationStatus, TransactionStatus, UiConfirmedBlock, UiInnerInstructions,
+        UiLoadedAddresses, UiTransactionEncoding,
     },
     solana_vote_program::vote_state::{VoteState, MAX_LOCKOUT_HISTORY},
     spl_token_2022::{
@@ -116,6 +124,7 @@ type RpcCustomResult<T> = std::result::Result<T, RpcCustomError>;
 
 pub const MAX_REQUEST_BODY_SIZE: usize = 50 * (1 << 10); // 50kB
 pub const PERFORMANCE_SAMPLES_LIMIT: usize = 720;
+pub const DEFAULT_ACCOUNT_HISTORY_RETENTION_SLOTS: u64 = 432_000; // one epoch
 
 fn new_response<T>(bank: &Bank, value: T) -> RpcResponse<T> {
     RpcResponse {
@@ -138,6 +147,8 @@ fn is_finalized(
 pub struct JsonRpcConfig {
     pub enable_rpc_transaction_history: bool,
     pub enable_extended_tx_metadata_storage: bool,
+    pub enable_rpc_account_history: bool,
+    pub account_history_retention_slots: u64,
     pub faucet_addr: Option<SocketAddr>,
     pub health_check_slot_distance: u64,
     pub rpc_bigtable_config: Option<RpcBigtableConfig>,
@@ -148,7 +159,14 @@ pub struct JsonRpcConfig {
     pub full_api: bool,
     pub obsolete_v1_7_api: bool,
     pub rpc_scan_and_fix_roots: bool,
+    /// Allow simulations to trace the instructions they execute
+    pub enable_simulation_instruction_tracing: bool,
+    /// Allow simulations to override program accounts and features, which bypasses the executor
+    /// cache
+    pub enable_simulation_program_overrides: bool,
     pub max_request_body_size: Option<usize>,
+    pub grpc_config: Option<RpcGrpcConfig>,
+    pub rate_limit_config: Option<RpcRateLimitConfig>,
 }
 
 impl JsonRpcConfig {
@@ -201,11 +219,14 @@ pub struct JsonRpcRequestProcessor {
     leader_schedule_cache: Arc<LeaderScheduleCache>,
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    // Identifies the client of the current request; set per request by the rpc service, and
+    // None when rate limiting is disabled or the client couldn't be identified
+    pub(crate) rate_limit_key: Option<Arc<str>>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
 impl JsonRpcRequestProcessor {
-    fn get_bank_with_config(&self, config: RpcContextConfig) -> Result<Arc<Bank>> {
+    pub(crate) fn get_bank_with_config(&self, config: RpcContextConfig) -> Result<Arc<Bank>> {
         let RpcContextConfig {
             commitment,
             min_context_slot,
@@ -328,6 +349,7 @@ impl JsonRpcRequestProcessor {
                 leader_schedule_cache,
                 max_complete_transaction_status_slot,
                 prioritization_fee_cache,
+                rate_limit_key: None,
             },
             receiver,
         )
@@ -396,6 +418,7 @@ impl JsonRpcRequestProcessor {
             leader_schedule_cache: Arc::new(LeaderScheduleCache::new_from_bank(bank)),
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
+            rate_limit_key: None,
         }
     }
 
@@ -457,7 +480,7 @@ impl JsonRpcRequestProcessor {
         &self,
         program_id: &Pubkey,
         config: Option<RpcAccountInfoConfig>,
-        mut filters: Vec<RpcFilterType>,
+        filters: Vec<RpcFilterType>,
         with_context: bool,
     ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>> {
         let RpcAccountInfoConfig {
@@ -471,35 +494,139 @@ impl JsonRpcRequestProcessor {
             min_context_slot,
         })?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        let keyed_accounts = self.get_keyed_program_accounts(&bank, program_id, filters)?;
+        let accounts = encode_program_accounts(
+            &bank,
+            program_id,
+            keyed_accounts,
+            encoding,
+            data_slice_config,
+        )?;
+        Ok(match with_context {
+            true => OptionalContext::Context(new_response(&bank, accounts)),
+            false => OptionalContext::NoContext(accounts),
+        })
+    }
+
+    /// Returns the accounts owned by `program_id` in `bank` that match all `filters`
+    pub(crate) fn get_keyed_program_accounts(
+        &self,
+        bank: &Arc<Bank>,
+        program_id: &Pubkey,
+        mut filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, AccountSharedData)>> {
         optimize_filters(&mut filters);
-        let keyed_accounts = {
-            if let Some(owner) = get_spl_token_owner_filter(program_id, &filters) {
-                self.get_filtered_spl_token_accounts_by_owner(&bank, program_id, &owner, filters)?
-            } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
-                self.get_filtered_spl_token_accounts_by_mint(&bank, program_id, &mint, filters)?
-            } else {
-                self.get_filtered_program_accounts(&bank, program_id, filters)?
+        let keyed_accounts = if let Some(owner) = get_spl_token_owner_filter(program_id, &filters) {
+            self.get_filtered_spl_token_accounts_by_owner(bank, program_id, &owner, filters)?
+        } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
+            self.get_filtered_spl_token_accounts_by_mint(bank, program_id, &mint, filters)?
+        } else if let Some(index_key) =
+            get_data_offset_index_key(program_id, &filters, &self.config.account_indexes)
+        {
+            self.get_filtered_data_offset_accounts(bank, program_id, &index_key, filters)?
+        } else {
+            self.get_filtered_program_accounts(bank, program_id, filters)?
+        };
+        Ok(keyed_accounts)
+    }
+
+    pub fn get_program_accounts_paginated(
+        &self,
+        program_id: &Pubkey,
+        config: Option<RpcAccountInfoConfig>,
+        mut filters: Vec<RpcFilterType>,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> Result<RpcResponse<RpcProgramAccountsPage>> {
+        let RpcAccountInfoConfig {
+            encoding,
+            data_slice: data_slice_config,
+            commitment,
+            min_context_slot,
+        } = config.unwrap_or_default();
+        let limit = limit.unwrap_or(MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT);
+        if limit == 0 || limit > MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT {
+            return Err(Error::invalid_params(format!(
+                "Invalid limit; max {MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT}"
+            )));
+        }
+        let cursor = cursor
+            .as_deref()
+            .map(decode_program_accounts_cursor)
+            .transpose()?;
+        // Later pages are read from the bank of the first page, so that all pages describe the
+        // same state. That bank is gone once its slot is pruned from bank forks.
+        let bank = match cursor {
+            Some((slot, _)) => {
+                let bank = self.bank_forks.read().unwrap().get(slot).ok_or_else(|| {
+                    Error::invalid_params(format!(
+                        "Slot {slot} of the cursor is no longer available; request the first \
+                         page again"
+                    ))
+                })?;
+                if min_context_slot.map_or(false, |min_context_slot| slot < min_context_slot) {
+                    return Err(
+                        RpcCustomError::MinContextSlotNotReached { context_slot: slot }.into(),
+                    );
+                }
+                bank
             }
+            None => self.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?,
         };
-        let accounts = if is_known_spl_token_id(program_id)
-            && encoding == UiAccountEncoding::JsonParsed
+        // Pages are read from the program id index in order, which a full scan can't do
+        if !self
+            .config
+            .account_indexes
+            .contains(&AccountIndex::ProgramId)
+            || !self.config.account_indexes.include_key(program_id)
         {
-            get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
-        } else {
+            return Err(RpcCustomError::KeyExcludedFromSecondaryIndex {
+                index_key: program_id.to_string(),
+            }
+            .into());
+        }
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        optimize_filters(&mut filters);
+        let rent = &bank.rent_collector().rent;
+        let keyed_accounts = bank
+            .get_filtered_indexed_accounts_page(
+                &IndexKey::ProgramId(*program_id),
+                |account| {
+                    // See `get_filtered_program_accounts` for why the owner is checked again
+                    account.owner() == program_id
+                        && filters
+                            .iter()
+                            .all(|filter_type| filter_type.allows_with_rent(account, rent))
+                },
+                cursor.as_ref().map(|(_slot, pubkey)| pubkey),
+                limit,
+                &ScanConfig::default(),
+                bank.byte_limit_for_scans(),
+            )
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })?;
+        let cursor = if keyed_accounts.len() == limit {
             keyed_accounts
-                .into_iter()
-                .map(|(pubkey, account)| {
-                    Ok(RpcKeyedAccount {
-                        pubkey: pubkey.to_string(),
-                        account: encode_account(&account, &pubkey, encoding, data_slice_config)?,
-                    })
-                })
-                .collect::<Result<Vec<_>>>()?
+                .last()
+                .map(|(pubkey, _account)| encode_program_accounts_cursor(bank.slot(), pubkey))
+        } else {
+            None
         };
-        Ok(match with_context {
-            true => OptionalContext::Context(new_response(&bank, accounts)),
-            false => OptionalContext::NoContext(accounts),
-        })
+        let accounts = encode_program_accounts(
+            &bank,
+            program_id,
+            keyed_accounts,
+            encoding,
+            data_slice_config,
+        )?;
+        Ok(new_response(
+            &bank,
+            RpcProgramAccountsPage { accounts, cursor },
+        ))
     }
 
     pub async fn get_inflation_reward(
@@ -735,6 +862,14 @@ impl JsonRpcRequestProcessor {
         Ok(bank.block_height())
     }
 
+    fn get_accounts_hash_bins(&self, slot: Slot) -> Option<AccountsHashBins> {
+        self.bank_forks
+            .read()
+            .unwrap()
+            .root_bank()
+            .get_snapshot_accounts_hash_bins(slot)
+    }
+
     fn get_max_retransmit_slot(&self) -> Slot {
         self.max_slots.retransmit.load(Ordering::Relaxed)
     }
@@ -1064,17 +1199,34 @@ impl JsonRpcRequestProcessor {
         slot: Slot,
         config: Option<RpcEncodingConfigWrapper<RpcBlockConfig>>,
     ) -> Result<Option<UiConfirmedBlock>> {
+        let config = config
+            .map(|config| config.convert_to_current())
+            .unwrap_or_default();
+        let encoding = config.encoding.unwrap_or(UiTransactionEncoding::Json);
+        let encoding_options = BlockEncodingOptions {
+            transaction_details: config.transaction_details.unwrap_or_default(),
+            show_rewards: config.rewards.unwrap_or(true),
+            max_supported_transaction_version: config.max_supported_transaction_version,
+        };
+        let commitment = config.commitment.unwrap_or_default();
+        self.get_confirmed_block(slot, commitment)
+            .await?
+            .map(|confirmed_block| {
+                Ok(confirmed_block
+                    .encode_with_options(encoding, encoding_options)
+                    .map_err(RpcCustomError::from)?)
+            })
+            .transpose()
+    }
+
+    /// Returns the block at `slot` from the blockstore, or from BigTable if it has been
+    /// cleaned up locally
+    pub(crate) async fn get_confirmed_block(
+        &self,
+        slot: Slot,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<ConfirmedBlock>> {
         if self.config.enable_rpc_transaction_history {
-            let config = config
-                .map(|config| config.convert_to_current())
-                .unwrap_or_default();
-            let encoding = config.encoding.unwrap_or(UiTransactionEncoding::Json);
-            let encoding_options = BlockEncodingOptions {
-                transaction_details: config.transaction_details.unwrap_or_default(),
-                show_rewards: config.rewards.unwrap_or(true),
-                max_supported_transaction_version: config.max_supported_transaction_version,
-            };
-            let commitment = config.commitment.unwrap_or_default();
             check_is_at_least_confirmed(commitment)?;
 
             // Block is old enough to be finalized
@@ -1088,40 +1240,33 @@ impl JsonRpcRequestProcessor {
                 self.check_status_is_complete(slot)?;
                 let result = self.blockstore.get_rooted_block(slot, true);
                 self.check_blockstore_root(&result, slot)?;
-                let encode_block = |confirmed_block: ConfirmedBlock| -> Result<UiConfirmedBlock> {
-                    let mut encoded_block = confirmed_block
-                        .encode_with_options(encoding, encoding_options)
-                        .map_err(RpcCustomError::from)?;
+                let with_genesis_time = |mut confirmed_block: ConfirmedBlock| -> ConfirmedBlock {
                     if slot == 0 {
-                        encoded_block.block_time = Some(self.genesis_creation_time());
-                        encoded_block.block_height = Some(0);
+                        confirmed_block.block_time = Some(self.genesis_creation_time());
+                        confirmed_block.block_height = Some(0);
                     }
-                    Ok(encoded_block)
+                    confirmed_block
                 };
                 if result.is_err() {
                     if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
                         let bigtable_result =
                             bigtable_ledger_storage.get_confirmed_block(slot).await;
                         self.check_bigtable_result(&bigtable_result)?;
-                        return bigtable_result.ok().map(encode_block).transpose();
+                        return Ok(bigtable_result.ok().map(with_genesis_time));
                     }
                 }
                 self.check_slot_cleaned_up(&result, slot)?;
-                return result
-                    .ok()
-                    .map(ConfirmedBlock::from)
-                    .map(encode_block)
-                    .transpose();
+                return Ok(result.ok().map(ConfirmedBlock::from).map(with_genesis_time));
             } else if commitment.is_confirmed() {
                 // Check if block is confirmed
                 let confirmed_bank = self.bank(Some(CommitmentConfig::confirmed()));
                 if confirmed_bank.status_cache_ancestors().contains(&slot) {
                     self.check_status_is_complete(slot)?;
                     let result = self.blockstore.get_complete_block(slot, true);
-                    return result
+                    return Ok(result
                         .ok()
                         .map(ConfirmedBlock::from)
-                        .map(|mut confirmed_block| -> Result<UiConfirmedBlock> {
+                        .map(|mut confirmed_block| {
                             if confirmed_block.block_time.is_none()
                                 || confirmed_block.block_height.is_none()
                             {
@@ -1136,12 +1281,8 @@ impl JsonRpcRequestProcessor {
                                     }
                                 }
                             }
-
-                            Ok(confirmed_block
-                                .encode_with_options(encoding, encoding_options)
-                                .map_err(RpcCustomError::from)?)
-                        })
-                        .transpose();
+                            confirmed_block
+                        }));
                 }
             }
         } else {
@@ -1471,6 +1612,23 @@ impl JsonRpcRequestProcessor {
         let encoding = config.encoding.unwrap_or(UiTransactionEncoding::Json);
         let max_supported_transaction_version = config.max_supported_transaction_version;
         let commitment = config.commitment.unwrap_or_default();
+        self.get_confirmed_transaction(signature, commitment)
+            .await?
+            .map(|confirmed_tx_with_meta| {
+                Ok(confirmed_tx_with_meta
+                    .encode(encoding, max_supported_transaction_version)
+                    .map_err(RpcCustomError::from)?)
+            })
+            .transpose()
+    }
+
+    /// Returns the transaction with `signature` from the blockstore, or from BigTable if it
+    /// is not found locally
+    pub(crate) async fn get_confirmed_transaction(
+        &self,
+        signature: Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
         check_is_at_least_confirmed(commitment)?;
 
         if self.config.enable_rpc_transaction_history {
@@ -1483,11 +1641,6 @@ impl JsonRpcRequestProcessor {
                 self.blockstore.get_rooted_transaction(signature)
             };
 
-            let encode_transaction =
-                |confirmed_tx_with_meta: ConfirmedTransactionWithStatusMeta| -> Result<EncodedConfirmedTransactionWithStatusMeta> {
-                    Ok(confirmed_tx_with_meta.encode(encoding, max_supported_transaction_version).map_err(RpcCustomError::from)?)
-                };
-
             match confirmed_transaction.unwrap_or(None) {
                 Some(mut confirmed_transaction) => {
                     if commitment.is_confirmed()
@@ -1501,7 +1654,7 @@ impl JsonRpcRequestProcessor {
                                 .get(confirmed_transaction.slot)
                                 .map(|bank| bank.clock().unix_timestamp);
                         }
-                        return Ok(Some(encode_transaction(confirmed_transaction)?));
+                        return Ok(Some(confirmed_transaction));
                     }
 
                     if confirmed_transaction.slot
@@ -1511,17 +1664,15 @@ impl JsonRpcRequestProcessor {
                             .unwrap()
                             .highest_confirmed_root()
                     {
-                        return Ok(Some(encode_transaction(confirmed_transaction)?));
+                        return Ok(Some(confirmed_transaction));
                     }
                 }
                 None => {
                     if let Some(bigtable_ledger_storage) = &self.bigtable_ledger_storage {
-                        return bigtable_ledger_storage
+                        return Ok(bigtable_ledger_storage
                             .get_confirmed_transaction(&signature)
                             .await
-                            .unwrap_or(None)
-                            .map(encode_transaction)
-                            .transpose();
+                            .unwrap_or(None));
                     }
                 }
             }
@@ -1700,6 +1851,58 @@ impl JsonRpcRequestProcessor {
         slot
     }
 
+    pub fn get_account_info_at_slot(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        config: Option<RpcAccountInfoAtSlotConfig>,
+    ) -> Result<RpcResponse<Option<UiAccount>>> {
+        if !self.config.enable_rpc_account_history {
+            return Err(RpcCustomError::AccountHistoryNotAvailable.into());
+        }
+        let RpcAccountInfoAtSlotConfig {
+            encoding,
+            data_slice,
+        } = config.unwrap_or_default();
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+
+        let last_available_slot = self.blockstore.max_root();
+        let first_available_slot =
+            last_available_slot.saturating_sub(self.config.account_history_retention_slots);
+        if slot < first_available_slot || slot > last_available_slot {
+            return Err(RpcCustomError::AccountHistorySlotNotAvailable {
+                slot,
+                first_available_slot,
+                last_available_slot,
+            }
+            .into());
+        }
+
+        let account = self
+            .blockstore
+            .get_rooted_account_history(pubkey, slot)
+            .map_err(|_| Error::internal_error())?
+            .map(|(_slot, account)| AccountSharedData::from(account))
+            // Zero-lamport entries record the account being closed
+            .filter(|account| account.lamports() > 0)
+            .map(|account| {
+                if is_known_spl_token_id(account.owner())
+                    && encoding == UiAccountEncoding::JsonParsed
+                {
+                    // Mint decimals are immutable, so the current mint can be used for parsing
+                    let bank = self.bank(Some(CommitmentConfig::finalized()));
+                    Ok(get_parsed_token_account(bank, pubkey, account))
+                } else {
+                    encode_account(&account, pubkey, encoding, data_slice)
+                }
+            })
+            .transpose()?;
+        Ok(RpcResponse {
+            context: RpcResponseContext::new(slot),
+            value: account,
+        })
+    }
+
     pub fn get_stake_activation(
         &self,
         pubkey: &Pubkey,
@@ -1875,6 +2078,7 @@ impl JsonRpcRequestProcessor {
         owner: &Pubkey,
         token_account_filter: TokenAccountsFilter,
         config: Option<RpcAccountInfoConfig>,
+        mut filters: Vec<RpcFilterType>,
     ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>> {
         let RpcAccountInfoConfig {
             encoding,
@@ -1889,7 +2093,6 @@ impl JsonRpcRequestProcessor {
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
         let (token_program_id, mint) = get_token_program_id_and_mint(&bank, token_account_filter)?;
 
-        let mut filters = vec![];
         if let Some(mint) = mint {
             // Optional filter on Mint address
             filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
@@ -1980,10 +2183,11 @@ impl JsonRpcRequestProcessor {
         mut filters: Vec<RpcFilterType>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         optimize_filters(&mut filters);
+        let rent = &bank.rent_collector().rent;
         let filter_closure = |account: &AccountSharedData| {
             filters
                 .iter()
-                .all(|filter_type| filter_type.allows(account))
+                .all(|filter_type| filter_type.allows_with_rent(account, rent))
         };
         if self
             .config
@@ -2022,6 +2226,33 @@ impl JsonRpcRequestProcessor {
         }
     }
 
+    /// Get the accounts of `program_id` found in one of its data offset indexes
+    fn get_filtered_data_offset_accounts(
+        &self,
+        bank: &Arc<Bank>,
+        program_id: &Pubkey,
+        index_key: &IndexKey,
+        filters: Vec<RpcFilterType>,
+    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        let rent = &bank.rent_collector().rent;
+        bank.get_filtered_indexed_accounts(
+            index_key,
+            |account| {
+                // The index is not updated when the indexed bytes change, so the filters are
+                // checked again, as for the other secondary indexes
+                account.owner() == program_id
+                    && filters
+                        .iter()
+                        .all(|filter_type| filter_type.allows_with_rent(account, rent))
+            },
+            &ScanConfig::default(),
+            bank.byte_limit_for_scans(),
+        )
+        .map_err(|e| RpcCustomError::ScanError {
+            message: e.to_string(),
+        })
+    }
+
     /// Get an iterator of spl-token accounts by owner address
     fn get_filtered_spl_token_accounts_by_owner(
         &self,
@@ -2053,6 +2284,8 @@ impl JsonRpcRequestProcessor {
                     index_key: owner_key.to_string(),
                 });
             }
+            optimize_filters(&mut filters);
+            let rent = &bank.rent_collector().rent;
             Ok(bank
                 .get_filtered_indexed_accounts(
                     &IndexKey::SplTokenOwner(*owner_key),
@@ -2060,7 +2293,7 @@ impl JsonRpcRequestProcessor {
                         account.owner() == program_id
                             && filters
                                 .iter()
-                                .all(|filter_type| filter_type.allows(account))
+                                .all(|filter_type| filter_type.allows_with_rent(account, rent))
                     },
                     &ScanConfig::default(),
                     bank.byte_limit_for_scans(),
@@ -2103,6 +2336,8 @@ impl JsonRpcRequestProcessor {
                     index_key: mint_key.to_string(),
                 });
             }
+            optimize_filters(&mut filters);
+            let rent = &bank.rent_collector().rent;
             Ok(bank
                 .get_filtered_indexed_accounts(
                     &IndexKey::SplTokenMint(*mint_key),
@@ -2110,7 +2345,7 @@ impl JsonRpcRequestProcessor {
                         account.owner() == program_id
                             && filters
                                 .iter()
-                                .all(|filter_type| filter_type.allows(account))
+                                .all(|filter_type| filter_type.allows_with_rent(account, rent))
                     },
                     &ScanConfig::default(),
                     bank.byte_limit_for_scans(),
@@ -2172,14 +2407,23 @@ impl JsonRpcRequestProcessor {
 }
 
 fn optimize_filters(filters: &mut [RpcFilterType]) {
-    filters.iter_mut().for_each(|filter_type| {
-        if let RpcFilterType::Memcmp(compare) = filter_type {
-            if let Err(err) = compare.convert_to_raw_bytes() {
-                // All filters should have been previously verified
-                warn!("Invalid filter: bytes could not be decoded, {err}");
-            }
+    let convert_to_raw_bytes = |compare: &mut Memcmp| {
+        if let Err(err) = compare.convert_to_raw_bytes() {
+            // All filters should have been previously verified
+            warn!("Invalid filter: bytes could not be decoded, {err}");
         }
-    })
+    };
+    filters
+        .iter_mut()
+        .for_each(|filter_type| match filter_type {
+            RpcFilterType::Memcmp(compare) | RpcFilterType::NotMemcmp(compare) => {
+                convert_to_raw_bytes(compare)
+            }
+            RpcFilterType::MemcmpAnyOf(compares) => {
+                compares.iter_mut().for_each(convert_to_raw_bytes)
+            }
+            _ => {}
+        })
 }
 
 fn verify_transaction(
@@ -2198,7 +2442,7 @@ fn verify_transaction(
     Ok(())
 }
 
-fn verify_filter(input: &RpcFilterType) -> Result<()> {
+pub(crate) fn verify_filter(input: &RpcFilterType) -> Result<()> {
     input
         .verify()
         .map_err(|e| Error::invalid_params(format!("Invalid param: {e:?}")))
@@ -2310,10 +2554,352 @@ fn encode_account<T: ReadableAccount>(
     }
 }
 
+fn rpc_instruction_trace(instruction_trace: &InstructionTrace) -> RpcInstructionTrace {
+    RpcInstructionTrace {
+        frames: instruction_trace
+            .frames
+            .iter()
+            .enumerate()
+            .map(|(index, frame)| RpcInstructionFrame {
+                program_id: frame.program_id.to_string(),
+                stack_height: frame.stack_height,
+                parent: frame.parent,
+                compute_units_consumed: frame.compute_units_consumed,
+                self_compute_units: instruction_trace.self_compute_units(index),
+                syscall_counts: frame.syscall_counts.clone(),
+                err: frame.error.clone(),
+            })
+            .collect(),
+        folded_stacks: instruction_trace.folded_stacks(),
+    }
+}
+
+fn encode_program_accounts(
+    bank: &Arc<Bank>,
+    program_id: &Pubkey,
+    keyed_accounts: Vec<(Pubkey, AccountSharedData)>,
+    encoding: UiAccountEncoding,
+    data_slice_config: Option<UiDataSliceConfig>,
+) -> Result<Vec<RpcKeyedAccount>> {
+    if is_known_spl_token_id(program_id) && encoding == UiAccountEncoding::JsonParsed {
+        Ok(get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect())
+    } else {
+        keyed_accounts
+            .into_iter()
+            .map(|(pubkey, account)| {
+                Ok(RpcKeyedAccount {
+                    pubkey: pubkey.to_string(),
+                    account: encode_account(&account, &pubkey, encoding, data_slice_config)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Cursors of `getProgramAccountsPaginated` are the base-58 encoding of the slot of the page
+/// followed by the last pubkey it returned
+fn encode_program_accounts_cursor(slot: Slot, pubkey: &Pubkey) -> String {
+    let mut bytes = slot.to_le_bytes().to_vec();
+    bytes.extend_from_slice(pubkey.as_ref());
+    bs58::encode(bytes).into_string()
+}
+
+fn decode_program_accounts_cursor(cursor: &str) -> Result<(Slot, Pubkey)> {
+    let invalid_cursor = || Error::invalid_params(format!("Invalid cursor: {cursor}"));
+    let bytes = bs58::decode(cursor)
+        .into_vec()
+        .map_err(|_| invalid_cursor())?;
+    if bytes.len() != std::mem::size_of::<Slot>() + PUBKEY_BYTES {
+        return Err(invalid_cursor());
+    }
+    let (slot, pubkey) = bytes.split_at(std::mem::size_of::<Slot>());
+    let slot = Slot::from_le_bytes(slot.try_into().map_err(|_| invalid_cursor())?);
+    let pubkey = Pubkey::try_from(pubkey).map_err(|_| invalid_cursor())?;
+    Ok((slot, pubkey))
+}
+
+fn verify_simulation_accounts_encoding(
+    config_accounts: &RpcSimulateTransactionAccountsConfig,
+) -> Result<UiAccountEncoding> {
+    let accounts_encoding = config_accounts
+        .encoding
+        .unwrap_or(UiAccountEncoding::Base64);
+    if accounts_encoding == UiAccountEncoding::Binary
+        || accounts_encoding == UiAccountEncoding::Base58
+    {
+        return Err(Error::invalid_params("base58 encoding not supported"));
+    }
+    Ok(accounts_encoding)
+}
+
+/// The instruction tracing of a simulation, which the node must allow when requested
+fn simulation_instruction_tracing(
+    config: &JsonRpcConfig,
+    trace_instructions: bool,
+) -> Result<InstructionTracing> {
+    if !trace_instructions {
+        return Ok(InstructionTracing::Disabled);
+    }
+    if !config.enable_simulation_instruction_tracing {
+        return Err(Error::invalid_params(
+            "traceInstructions is not enabled on this node",
+        ));
+    }
+    Ok(InstructionTracing::Enabled)
+}
+
+/// Builds the overrides of a simulation from the caller's config, or returns `None` if nothing is
+/// overridden. Sysvar overrides are applied after, and so win over, account overrides.
+///
+/// Program and feature overrides make the simulation compile its programs from scratch, so the
+/// node must allow them.
+fn simulation_account_overrides(
+    config: &JsonRpcConfig,
+    bank: &Bank,
+    account_overrides: Option<HashMap<String, UiAccount>>,
+    sysvar_overrides: Option<RpcSysvarOverrides>,
+    feature_overrides: Option<RpcFeatureOverrides>,
+) -> Result<Option<AccountOverrides>> {
+    if account_overrides.is_none() && sysvar_overrides.is_none() && feature_overrides.is_none() {
+        return Ok(None);
+    }
+    let mut overrides = AccountOverrides::default();
+
+    if let Some(account_overrides) = account_overrides {
+        if account_overrides.len() > MAX_MULTIPLE_ACCOUNTS {
+            return Err(Error::invalid_params(format!(
+                "Too many account overrides provided; max {MAX_MULTIPLE_ACCOUNTS}"
+            )));
+        }
+        let mut data_len = 0usize;
+        for (address, ui_account) in account_overrides {
+            let pubkey = verify_pubkey(&address)?;
+            let account = ui_account.decode::<AccountSharedData>().ok_or_else(|| {
+                Error::invalid_params(format!("Invalid account override for {address}"))
+            })?;
+            data_len = data_len.saturating_add(account.data().len());
+            if data_len > MAX_SIMULATION_ACCOUNT_OVERRIDES_DATA_LEN {
+                return Err(Error::invalid_params(format!(
+                    "Account overrides are too large; max {} bytes of data",
+                    MAX_SIMULATION_ACCOUNT_OVERRIDES_DATA_LEN
+                )));
+            }
+            overrides.set_account(&pubkey, Some(account));
+        }
+        if overrides.has_program_overrides() && !config.enable_simulation_program_overrides {
+            return Err(Error::invalid_params(
+                "Program account overrides are not enabled on this node",
+            ));
+        }
+    }
+
+    if let Some(RpcSysvarOverrides { clock }) = sysvar_overrides {
+        if let Some(RpcClockOverride {
+            slot,
+            epoch_start_timestamp,
+            epoch,
+            leader_schedule_epoch,
+            unix_timestamp,
+        }) = clock
+        {
+            let mut clock = bank.clock();
+            clock.slot = slot.unwrap_or(clock.slot);
+            clock.epoch_start_timestamp =
+                epoch_start_timestamp.unwrap_or(clock.epoch_start_timestamp);
+            clock.epoch = epoch.unwrap_or(clock.epoch);
+            clock.leader_schedule_epoch =
+                leader_schedule_epoch.unwrap_or(clock.leader_schedule_epoch);
+            clock.unix_timestamp = unix_timestamp.unwrap_or(clock.unix_timestamp);
+            let mut clock_account = bank
+                .get_account(&sysvar::clock::id())
+                .ok_or_else(Error::internal_error)?;
+            account::to_account(&clock, &mut clock_account).ok_or_else(Error::internal_error)?;
+            overrides.set_account(&sysvar::clock::id(), Some(clock_account));
+        }
+    }
+
+    if let Some(RpcFeatureOverrides {
+        activate,
+        deactivate,
+    }) = feature_overrides
+    {
+        if !config.enable_simulation_program_overrides {
+            return Err(Error::invalid_params(
+                "featureOverrides is not enabled on this node",
+            ));
+        }
+        let verify_feature_id = |input: &String| {
+            let feature_id = verify_pubkey(input)?;
+            if !feature_set::FEATURE_NAMES.contains_key(&feature_id) {
+                return Err(Error::invalid_params(format!("Unknown feature: {input}")));
+            }
+            Ok(feature_id)
+        };
+        let mut feature_set = feature_set::FeatureSet::clone(&bank.feature_set);
+        for feature_id in activate.iter().map(verify_feature_id) {
+            feature_set.activate(&feature_id?, bank.slot());
+        }
+        for feature_id in deactivate.iter().map(verify_feature_id) {
+            feature_set.deactivate(&feature_id?);
+        }
+        overrides.set_feature_set(Some(Arc::new(feature_set)));
+    }
+
+    Ok(Some(overrides))
+}
+
+fn rpc_simulate_transaction_result(
+    bank: &Bank,
+    transaction: &SanitizedTransaction,
+    simulation_result: TransactionSimulationResult,
+    accounts: Option<Vec<Option<UiAccount>>>,
+) -> RpcSimulateTransactionResult {
+    let TransactionSimulationResult {
+        result,
+        logs,
+        pre_simulation_accounts,
+        post_simulation_accounts,
+        units_consumed,
+        return_data,
+        inner_instructions,
+        instruction_trace,
+        written_accounts: _,
+    } = simulation_result;
+
+    let account_keys = transaction.message().account_keys();
+    let inner_instructions = inner_instructions
+        .map(|inner_instructions| ui_inner_instructions(inner_instructions, &account_keys));
+    let mut mint_decimals = HashMap::new();
+    let pre_balances = pre_simulation_accounts
+        .iter()
+        .map(|(_, account)| account.lamports())
+        .collect();
+    let pre_token_balances = collect_token_balances_from_accounts(
+        bank,
+        transaction,
+        &pre_simulation_accounts,
+        &mut mint_decimals,
+    );
+    // Like the accounts, the post-simulation state is only returned if the transaction succeeded
+    let (post_balances, post_token_balances, account_deltas) = if result.is_ok() {
+        let post_balances = post_simulation_accounts
+            .iter()
+            .map(|(_, account)| account.lamports())
+            .collect();
+        let post_token_balances = collect_token_balances_from_accounts(
+            bank,
+            transaction,
+            &post_simulation_accounts,
+            &mut mint_decimals,
+        );
+        let account_deltas =
+            rpc_account_deltas(&pre_simulation_accounts, &post_simulation_accounts);
+        (
+            Some(post_balances),
+            Some(post_token_balances),
+            Some(account_deltas),
+        )
+    } else {
+        (None, None, None)
+    };
+
+    RpcSimulateTransactionResult {
+        err: result.err(),
+        logs: Some(logs),
+        accounts,
+        units_consumed: Some(units_consumed),
+        return_data: return_data.map(|return_data| return_data.into()),
+        inner_instructions,
+        pre_balances: Some(pre_balances),
+        post_balances,
+        pre_token_balances: Some(pre_token_balances.into_iter().map(Into::into).collect()),
+        post_token_balances: post_token_balances
+            .map(|post_token_balances| post_token_balances.into_iter().map(Into::into).collect()),
+        loaded_addresses: Some(UiLoadedAddresses::from(&transaction.get_loaded_addresses())),
+        account_deltas,
+        instruction_trace: instruction_trace.as_ref().map(rpc_instruction_trace),
+    }
+}
+
+fn ui_inner_instructions(
+    inner_instructions: InnerInstructionsList,
+    account_keys: &AccountKeys,
+) -> Vec<UiInnerInstructions> {
+    inner_instructions
+        .into_iter()
+        .enumerate()
+        .filter(|(_, instructions)| !instructions.is_empty())
+        .map(|(index, instructions)| {
+            UiInnerInstructions::parse(
+                InnerInstructions {
+                    index: index as u8,
+                    instructions: instructions
+                        .into_iter()
+                        .map(|info| InnerInstruction {
+                            instruction: info.instruction,
+                            stack_height: Some(u32::from(info.stack_height)),
+                        })
+                        .collect(),
+                },
+                account_keys,
+            )
+        })
+        .collect()
+}
+
+/// The change of each of a transaction's accounts between `pre_accounts` and `post_accounts`
+fn rpc_account_deltas(
+    pre_accounts: &[(Pubkey, AccountSharedData)],
+    post_accounts: &[(Pubkey, AccountSharedData)],
+) -> Vec<RpcAccountDelta> {
+    pre_accounts
+        .iter()
+        .zip(post_accounts)
+        .map(
+            |((pubkey, pre_account), (_, post_account))| RpcAccountDelta {
+                pubkey: pubkey.to_string(),
+                lamports_delta: (post_account.lamports() as i64)
+                    .saturating_sub(pre_account.lamports() as i64),
+                data_len_delta: (post_account.data().len() as i64)
+                    .saturating_sub(pre_account.data().len() as i64),
+            },
+        )
+        .collect()
+}
+
 /// Analyze custom filters to determine if the result will be a subset of spl-token accounts by
 /// owner.
 /// NOTE: `optimize_filters()` should almost always be called before using this method because of
 /// the strict match on `MemcmpEncodedBytes::Bytes`.
+/// Returns the key to look up in a configured data offset index of `program_id`, if a memcmp
+/// filter covers the indexed bytes
+fn get_data_offset_index_key(
+    program_id: &Pubkey,
+    filters: &[RpcFilterType],
+    account_indexes: &AccountSecondaryIndexes,
+) -> Option<IndexKey> {
+    account_indexes
+        .data_offset_indexes()
+        .filter(|index| index.program_id == *program_id)
+        .find_map(|index| {
+            filters
+                .iter()
+                .find_map(|filter| match filter {
+                    #[allow(deprecated)]
+                    RpcFilterType::Memcmp(Memcmp {
+                        offset,
+                        bytes: MemcmpEncodedBytes::Bytes(bytes),
+                        ..
+                    }) if *offset == index.offset && bytes.len() >= index.length => {
+                        index.key_from_bytes(&bytes[..index.length])
+                    }
+                    _ => None,
+                })
+                .filter(|index_key| account_indexes.include_key(index_key))
+                .map(|index_key| IndexKey::DataOffset(*index, index_key))
+        })
+}
+
 fn get_spl_token_owner_filter(program_id: &Pubkey, filters: &[RpcFilterType]) -> Option<Pubkey> {
     if !is_known_spl_token_id(program_id) {
         return None;
@@ -2523,6 +3109,13 @@ pub mod rpc_minimal {
         #[rpc(meta, name = "getHighestSnapshotSlot")]
         fn get_highest_snapshot_slot(&self, meta: Self::Metadata) -> Result<RpcSnapshotSlotInfo>;
 
+        #[rpc(meta, name = "getAccountsHashBins")]
+        fn get_accounts_hash_bins(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+        ) -> Result<Option<AccountsHashBins>>;
+
         #[rpc(meta, name = "getTransactionCount")]
         fn get_transaction_count(
             &self,
@@ -2650,6 +3243,15 @@ pub mod rpc_minimal {
             })
         }
 
+        fn get_accounts_hash_bins(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+        ) -> Result<Option<AccountsHashBins>> {
+            debug!("get_accounts_hash_bins rpc request received: {:?}", slot);
+            Ok(meta.get_accounts_hash_bins(slot))
+        }
+
         fn get_transaction_count(
             &self,
             meta: Self::Metadata,
@@ -3101,6 +3703,14 @@ pub mod rpc_accounts_scan {
             config: Option<RpcProgramAccountsConfig>,
         ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>>;
 
+        #[rpc(meta, name = "getProgramAccountsPaginated")]
+        fn get_program_accounts_paginated(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            config: Option<RpcProgramAccountsPaginatedConfig>,
+        ) -> Result<RpcResponse<RpcProgramAccountsPage>>;
+
         #[rpc(meta, name = "getLargestAccounts")]
         fn get_largest_accounts(
             &self,
@@ -3133,7 +3743,7 @@ pub mod rpc_accounts_scan {
             meta: Self::Metadata,
             owner_str: String,
             token_account_filter: RpcTokenAccountsFilter,
-            config: Option<RpcAccountInfoConfig>,
+            config: Option<RpcTokenAccountsByOwnerConfig>,
         ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>>;
 
         #[rpc(meta, name = "getTokenAccountsByDelegate")]
@@ -3181,7 +3791,42 @@ pub mod rpc_accounts_scan {
             meta.get_program_accounts(&program_id, config, filters, with_context)
         }
 
-        fn get_largest_accounts(
+        fn get_program_accounts_paginated(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            config: Option<RpcProgramAccountsPaginatedConfig>,
+        ) -> Result<RpcResponse<RpcProgramAccountsPage>> {
+            debug!(
+                "get_program_accounts_paginated rpc request received: {:?}",
+                program_id_str
+            );
+            let program_id = verify_pubkey(&program_id_str)?;
+            let RpcProgramAccountsPaginatedConfig {
+                filters,
+                account_config,
+                cursor,
+                limit,
+            } = config.unwrap_or_default();
+            let filters = filters.unwrap_or_default();
+            if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+                return Err(Error::invalid_params(format!(
+                    "Too many filters provided; max {MAX_GET_PROGRAM_ACCOUNT_FILTERS}"
+                )));
+            }
+            for filter in &filters {
+                verify_filter(filter)?;
+            }
+            meta.get_program_accounts_paginated(
+                &program_id,
+                Some(account_config),
+                filters,
+                cursor,
+                limit,
+            )
+        }
+
+        fn get_largest_accounts(
             &self,
             meta: Self::Metadata,
             config: Option<RpcLargestAccountsConfig>,
@@ -3218,7 +3863,7 @@ pub mod rpc_accounts_scan {
             meta: Self::Metadata,
             owner_str: String,
             token_account_filter: RpcTokenAccountsFilter,
-            config: Option<RpcAccountInfoConfig>,
+            config: Option<RpcTokenAccountsByOwnerConfig>,
         ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>> {
             debug!(
                 "get_token_accounts_by_owner rpc request received: {:?}",
@@ -3226,7 +3871,23 @@ pub mod rpc_accounts_scan {
             );
             let owner = verify_pubkey(&owner_str)?;
             let token_account_filter = verify_token_account_filter(token_account_filter)?;
-            meta.get_token_accounts_by_owner(&owner, token_account_filter, config)
+            let (config, filters) = if let Some(config) = config {
+                (
+                    Some(config.account_config),
+                    config.filters.unwrap_or_default(),
+                )
+            } else {
+                (None, vec![])
+            };
+            if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+                return Err(Error::invalid_params(format!(
+                    "Too many filters provided; max {MAX_GET_PROGRAM_ACCOUNT_FILTERS}"
+                )));
+            }
+            for filter in &filters {
+                verify_filter(filter)?;
+            }
+            meta.get_token_accounts_by_owner(&owner, token_account_filter, config, filters)
         }
 
         fn get_token_accounts_by_delegate(
@@ -3315,6 +3976,14 @@ pub mod rpc_full {
             config: Option<RpcSimulateTransactionConfig>,
         ) -> Result<RpcResponse<RpcSimulateTransactionResult>>;
 
+        #[rpc(meta, name = "simulateBundle")]
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateTransactionResult>>>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -3370,6 +4039,15 @@ pub mod rpc_full {
         #[rpc(meta, name = "getFirstAvailableBlock")]
         fn get_first_available_block(&self, meta: Self::Metadata) -> BoxFuture<Result<Slot>>;
 
+        #[rpc(meta, name = "getAccountInfoAtSlot")]
+        fn get_account_info_at_slot(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            slot: Slot,
+            config: Option<RpcAccountInfoAtSlotConfig>,
+        ) -> Result<RpcResponse<Option<UiAccount>>>;
+
         #[rpc(meta, name = "getLatestBlockhash")]
         fn get_latest_blockhash(
             &self,
@@ -3651,11 +4329,18 @@ pub mod rpc_full {
                 if let TransactionSimulationResult {
                     result: Err(err),
                     logs,
+                    pre_simulation_accounts: _,
                     post_simulation_accounts: _,
                     units_consumed,
                     return_data,
-                } = preflight_bank.simulate_transaction(transaction)
-                {
+                    inner_instructions: _,
+                    instruction_trace: _,
+                    written_accounts: _,
+                } = preflight_bank.simulate_transaction(
+                    transaction,
+                    false,
+                    InstructionTracing::Disabled,
+                ) {
                     match err {
                         TransactionError::BlockhashNotFound => {
                             inc_new_counter_info!("rpc-send-tx_err-blockhash-not-found", 1);
@@ -3672,6 +4357,14 @@ pub mod rpc_full {
                             accounts: None,
                             units_consumed: Some(units_consumed),
                             return_data: return_data.map(|return_data| return_data.into()),
+                            inner_instructions: None,
+                            pre_balances: None,
+                            post_balances: None,
+                            pre_token_balances: None,
+                            post_token_balances: None,
+                            loaded_addresses: None,
+                            account_deltas: None,
+                            instruction_trace: None,
                         },
                     }
                     .into());
@@ -3702,6 +4395,11 @@ pub mod rpc_full {
                 encoding,
                 accounts: config_accounts,
                 min_context_slot,
+                inner_instructions,
+                trace_instructions,
+                account_overrides,
+                sysvar_overrides,
+                feature_overrides,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -3732,25 +4430,31 @@ pub mod rpc_full {
                 verify_transaction(&transaction, &bank.feature_set)?;
             }
             let number_of_accounts = transaction.message().account_keys().len();
+            let instruction_tracing =
+                simulation_instruction_tracing(&meta.config, trace_instructions)?;
 
-            let TransactionSimulationResult {
-                result,
-                logs,
-                post_simulation_accounts,
-                units_consumed,
-                return_data,
-            } = bank.simulate_transaction(transaction);
+            let simulation_result = match simulation_account_overrides(
+                &meta.config,
+                bank,
+                account_overrides,
+                sysvar_overrides,
+                feature_overrides,
+            )? {
+                Some(account_overrides) => bank.simulate_transaction_with_overrides(
+                    transaction.clone(),
+                    inner_instructions,
+                    instruction_tracing,
+                    account_overrides,
+                ),
+                None => bank.simulate_transaction(
+                    transaction.clone(),
+                    inner_instructions,
+                    instruction_tracing,
+                ),
+            };
 
             let accounts = if let Some(config_accounts) = config_accounts {
-                let accounts_encoding = config_accounts
-                    .encoding
-                    .unwrap_or(UiAccountEncoding::Base64);
-
-                if accounts_encoding == UiAccountEncoding::Binary
-                    || accounts_encoding == UiAccountEncoding::Base58
-                {
-                    return Err(Error::invalid_params("base58 encoding not supported"));
-                }
+                let accounts_encoding = verify_simulation_accounts_encoding(&config_accounts)?;
 
                 if config_accounts.addresses.len() > number_of_accounts {
                     return Err(Error::invalid_params(format!(
@@ -3758,7 +4462,7 @@ pub mod rpc_full {
                     )));
                 }
 
-                if result.is_err() {
+                if simulation_result.result.is_err() {
                     Some(vec![None; config_accounts.addresses.len()])
                 } else {
                     Some(
@@ -3767,7 +4471,8 @@ pub mod rpc_full {
                             .iter()
                             .map(|address_str| {
                                 let address = verify_pubkey(address_str)?;
-                                post_simulation_accounts
+                                simulation_result
+                                    .post_simulation_accounts
                                     .iter()
                                     .find(|(key, _account)| key == &address)
                                     .map(|(pubkey, account)| {
@@ -3784,16 +4489,136 @@ pub mod rpc_full {
 
             Ok(new_response(
                 bank,
-                RpcSimulateTransactionResult {
-                    err: result.err(),
-                    logs: Some(logs),
-                    accounts,
-                    units_consumed: Some(units_consumed),
-                    return_data: return_data.map(|return_data| return_data.into()),
-                },
+                rpc_simulate_transaction_result(bank, &transaction, simulation_result, accounts),
             ))
         }
 
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateTransactionResult>>> {
+            debug!("simulate_bundle rpc request received");
+            let RpcSimulateBundleConfig {
+                sig_verify,
+                replace_recent_blockhash,
+                commitment,
+                encoding,
+                accounts: config_accounts,
+                min_context_slot,
+                inner_instructions,
+                trace_instructions,
+            } = config.unwrap_or_default();
+            let instruction_tracing =
+                simulation_instruction_tracing(&meta.config, trace_instructions)?;
+            if data.len() > MAX_SIMULATE_BUNDLE_TRANSACTIONS {
+                return Err(Error::invalid_params(format!(
+                    "Too many transactions provided; max {MAX_SIMULATE_BUNDLE_TRANSACTIONS}"
+                )));
+            }
+            if replace_recent_blockhash && sig_verify {
+                return Err(Error::invalid_params(
+                    "sigVerify may not be used with replaceRecentBlockhash",
+                ));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                ))
+            })?;
+            let config_accounts = config_accounts
+                .map(|config_accounts| {
+                    let accounts_encoding = verify_simulation_accounts_encoding(&config_accounts)?;
+                    if config_accounts.addresses.len() > MAX_MULTIPLE_ACCOUNTS {
+                        return Err(Error::invalid_params(format!(
+                            "Too many accounts provided; max {MAX_MULTIPLE_ACCOUNTS}"
+                        )));
+                    }
+                    let addresses = config_accounts
+                        .addresses
+                        .iter()
+                        .map(|address_str| verify_pubkey(address_str))
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok((addresses, accounts_encoding))
+                })
+                .transpose()?;
+
+            let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?;
+            let transactions = data
+                .into_iter()
+                .map(|data| {
+                    let (_, mut unsanitized_tx) =
+                        decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+                    if replace_recent_blockhash {
+                        unsanitized_tx
+                            .message
+                            .set_recent_blockhash(bank.last_blockhash());
+                    }
+                    let transaction = sanitize_transaction(unsanitized_tx, bank)?;
+                    if sig_verify {
+                        verify_transaction(&transaction, &bank.feature_set)?;
+                    }
+                    Ok(transaction)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let simulation_results = bank.simulate_bundle(
+                transactions.clone(),
+                inner_instructions,
+                instruction_tracing,
+            );
+
+            // The accounts written so far by the bundle, which the bank doesn't see
+            let mut written_accounts = HashMap::new();
+            let results = transactions
+                .iter()
+                .zip(simulation_results)
+                .map(|(transaction, simulation_result)| {
+                    written_accounts.extend(simulation_result.written_accounts.iter().cloned());
+                    let accounts = config_accounts
+                        .as_ref()
+                        .map(|(addresses, accounts_encoding)| {
+                            if simulation_result.result.is_err() {
+                                return Ok(vec![None; addresses.len()]);
+                            }
+                            addresses
+                                .iter()
+                                .map(|address| {
+                                    written_accounts
+                                        .get(address)
+                                        .cloned()
+                                        .or_else(|| bank.get_account(address))
+                                        .filter(|account| account.lamports() > 0)
+                                        .map(|account| {
+                                            encode_account(
+                                                &account,
+                                                address,
+                                                *accounts_encoding,
+                                                None,
+                                            )
+                                        })
+                                        .transpose()
+                                })
+                                .collect::<Result<Vec<_>>>()
+                        })
+                        .transpose()?;
+                    Ok(rpc_simulate_transaction_result(
+                        bank,
+                        transaction,
+                        simulation_result,
+                        accounts,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(new_response(bank, results))
+        }
+
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot> {
             debug!("minimum_ledger_slot rpc request received");
             meta.minimum_ledger_slot()
@@ -3906,6 +4731,21 @@ pub mod rpc_full {
             Box::pin(async move { Ok(meta.get_first_available_block().await) })
         }
 
+        fn get_account_info_at_slot(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            slot: Slot,
+            config: Option<RpcAccountInfoAtSlotConfig>,
+        ) -> Result<RpcResponse<Option<UiAccount>>> {
+            debug!(
+                "get_account_info_at_slot rpc request received: {:?} {:?}",
+                pubkey_str, slot
+            );
+            let pubkey = verify_pubkey(&pubkey_str)?;
+            meta.get_account_info_at_slot(&pubkey, slot, config)
+        }
+
         fn get_inflation_reward(
             &self,
             meta: Self::Metadata,
@@ -4628,19 +5468,23 @@ pub mod tests {
         },
         solana_rpc_client_api::{
             custom_error::{
+                JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_SLOT_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_KEY_EXCLUDED_FROM_SECONDARY_INDEX,
                 JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION,
             },
             filter::{Memcmp, MemcmpEncodedBytes},
         },
         solana_runtime::{
-            accounts_background_service::AbsRequestSender, bank::BankTestConfig,
-            commitment::BlockCommitment, inline_spl_token,
+            accounts_background_service::AbsRequestSender, accounts_hash::AccountsHashBin,
+            bank::BankTestConfig, commitment::BlockCommitment, inline_spl_token,
             non_circulating_supply::non_circulating_accounts,
         },
         solana_sdk::{
             account::{Account, WritableAccount},
+            bpf_loader_upgradeable,
             clock::MAX_RECENT_BLOCKHASHES,
             compute_budget::ComputeBudgetInstruction,
             fee_calculator::{FeeRateGovernor, DEFAULT_BURN_PERCENT},
@@ -5543,6 +6387,86 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_rpc_get_account_info_at_slot() {
+        let rpc = RpcHandler::start();
+        let pubkey = Pubkey::new_unique();
+        let address = pubkey.to_string();
+
+        let request = create_test_request("getAccountInfoAtSlot", Some(json!([address, 0])));
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE);
+
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            enable_rpc_account_history: true,
+            account_history_retention_slots: 10,
+            ..JsonRpcConfig::default()
+        });
+        let owner = Pubkey::new_unique();
+        for (slot, data) in [(2, vec![2]), (4, vec![4, 4]), (5, vec![5])] {
+            rpc.blockstore
+                .write_account_history(
+                    slot,
+                    &[(
+                        pubkey,
+                        AccountSharedData::from(Account {
+                            lamports: 42,
+                            data,
+                            owner,
+                            ..Account::default()
+                        }),
+                    )],
+                )
+                .unwrap();
+        }
+        rpc.blockstore.set_roots([1, 2, 3, 4, 20].iter()).unwrap();
+
+        let request = create_test_request(
+            "getAccountInfoAtSlot",
+            Some(json!([address, 12, {"encoding": "base64"}])),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result["context"]["slot"], 12);
+        assert_eq!(result["value"]["lamports"], 42);
+        assert_eq!(result["value"]["owner"], owner.to_string());
+        assert_eq!(
+            result["value"]["data"],
+            json!([base64::encode([4, 4]), "base64"])
+        );
+
+        let request = create_test_request(
+            "getAccountInfoAtSlot",
+            Some(
+                json!([address, 12, {"encoding": "base64", "dataSlice": {"length": 1, "offset": 1}}]),
+            ),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result["value"]["data"],
+            json!([base64::encode([4]), "base64"])
+        );
+
+        let request = create_test_request(
+            "getAccountInfoAtSlot",
+            Some(json!([Pubkey::new_unique().to_string(), 12])),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result["value"], Value::Null);
+
+        for slot in [9, 21] {
+            let request = create_test_request("getAccountInfoAtSlot", Some(json!([address, slot])));
+            let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(
+                code,
+                JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_SLOT_NOT_AVAILABLE
+            );
+            assert_eq!(
+                message,
+                format!("Account history not available for slot {slot}. Available slots: 10-20")
+            );
+        }
+    }
+
     #[test]
     fn test_rpc_get_multiple_accounts() {
         let rpc = RpcHandler::start();
@@ -5811,6 +6735,99 @@ pub mod tests {
         assert_eq!(result.len(), 0);
     }
 
+    #[test]
+    fn test_rpc_get_program_accounts_paginated() {
+        let program_id = Pubkey::new_unique();
+
+        // The program id index is required
+        let rpc = RpcHandler::start();
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([program_id.to_string()])),
+        );
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(
+            code,
+            JSON_RPC_SERVER_ERROR_KEY_EXCLUDED_FROM_SECONDARY_INDEX
+        );
+
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            account_indexes: AccountSecondaryIndexes {
+                keys: None,
+                indexes: HashSet::from([AccountIndex::ProgramId]),
+            },
+            ..JsonRpcConfig::default()
+        });
+        let bank = rpc.working_bank();
+
+        let mut pubkeys = (0..5).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        pubkeys.sort_unstable();
+        for pubkey in &pubkeys {
+            bank.store_account(pubkey, &AccountSharedData::new(42, 0, &program_id));
+        }
+
+        let mut cursor = None;
+        let mut pages = vec![];
+        let mut context_slots = HashSet::new();
+        loop {
+            let request = create_test_request(
+                "getProgramAccountsPaginated",
+                Some(json!([
+                    program_id.to_string(),
+                    {"limit": 2, "cursor": cursor},
+                ])),
+            );
+            let result: RpcResponse<RpcProgramAccountsPage> =
+                parse_success_result(rpc.handle_request_sync(request));
+            context_slots.insert(result.context.slot);
+            pages.push(
+                result
+                    .value
+                    .accounts
+                    .into_iter()
+                    .map(|keyed_account| keyed_account.pubkey)
+                    .collect::<Vec<_>>(),
+            );
+            cursor = result.value.cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        let expected_pages = pubkeys
+            .chunks(2)
+            .map(|chunk| chunk.iter().map(|pubkey| pubkey.to_string()).collect())
+            .collect::<Vec<Vec<_>>>();
+        assert_eq!(pages, expected_pages);
+        // every page is read from the bank of the first one
+        assert_eq!(context_slots.len(), 1);
+
+        // the bank of the cursor must still be available
+        let cursor = encode_program_accounts_cursor(bank.slot() + 1000, &pubkeys[0]);
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([program_id.to_string(), { "cursor": cursor }])),
+        );
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([program_id.to_string(), {"cursor": "notacursor"}])),
+        );
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert_eq!(message, "Invalid cursor: notacursor");
+
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([program_id.to_string(), {
+                "limit": MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT + 1
+            },])),
+        );
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+    }
+
     #[test]
     fn test_rpc_simulate_transaction() {
         let rpc = RpcHandler::start();
@@ -5834,6 +6851,45 @@ pub mod tests {
         tx.message.recent_blockhash = Hash::default();
         let tx_invalid_recent_blockhash = bs58::encode(serialize(&tx).unwrap()).into_string();
 
+        let mint_pubkey = rpc.mint_keypair.pubkey();
+        let mint_balance = bank.get_balance(&mint_pubkey);
+        let system_program_balance = bank.get_balance(&system_program::id());
+        let fee = bank.get_lamports_per_signature();
+        let pre_simulation_details = json!({
+            "preBalances": [mint_balance, 0, system_program_balance],
+            "preTokenBalances": [],
+            "loadedAddresses": {"writable": [], "readonly": []},
+        });
+        let mut simulation_details = json!({
+            "postBalances": [
+                mint_balance - fee - rent_exempt_amount,
+                rent_exempt_amount,
+                system_program_balance,
+            ],
+            "postTokenBalances": [],
+            "accountDeltas": [
+                {
+                    "pubkey": mint_pubkey.to_string(),
+                    "lamportsDelta": -((fee + rent_exempt_amount) as i64),
+                    "dataLenDelta": 0,
+                },
+                {
+                    "pubkey": bob_pubkey.to_string(),
+                    "lamportsDelta": rent_exempt_amount,
+                    "dataLenDelta": 0,
+                },
+                {
+                    "pubkey": system_program::id().to_string(),
+                    "lamportsDelta": 0,
+                    "dataLenDelta": 0,
+                },
+            ],
+        });
+        simulation_details
+            .as_object_mut()
+            .unwrap()
+            .extend(pre_simulation_details.as_object().unwrap().clone());
+
         // Simulation bank must be frozen
         bank.freeze();
 
@@ -5849,7 +6905,8 @@ pub mod tests {
                      "accounts": {{
                        "encoding": "jsonParsed",
                        "addresses": ["{}", "{}"]
-                     }}
+                     }},
+                     "innerInstructions": true
                    }}
                  ]
             }}"#,
@@ -5858,7 +6915,7 @@ pub mod tests {
             bob_pubkey,
         );
         let res = io.handle_request_sync(&req, meta.clone());
-        let expected = json!({
+        let mut expected = json!({
             "jsonrpc": "2.0",
             "result": {
                 "context": {"slot": 0, "apiVersion": RpcApiVersion::default()},
@@ -5875,6 +6932,7 @@ pub mod tests {
                         }
                     ],
                     "err":null,
+                    "innerInstructions": [],
                     "logs":[
                         "Program 11111111111111111111111111111111 invoke [1]",
                         "Program 11111111111111111111111111111111 success"
@@ -5885,6 +6943,10 @@ pub mod tests {
             },
             "id": 1,
         });
+        expected["result"]["value"]
+            .as_object_mut()
+            .unwrap()
+            .extend(simulation_details.as_object().unwrap().clone());
         let expected: Response =
             serde_json::from_value(expected).expect("expected response deserialization");
         let result: Response = serde_json::from_str(&res.expect("actual response"))
@@ -5952,7 +7014,7 @@ pub mod tests {
             r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{tx_serialized_encoded}", {{"sigVerify": false}}]}}"#,
         );
         let res = io.handle_request_sync(&req, meta.clone());
-        let expected = json!({
+        let mut expected = json!({
             "jsonrpc": "2.0",
             "result": {
                 "context": {"slot": 0, "apiVersion": RpcApiVersion::default()},
@@ -5969,6 +7031,10 @@ pub mod tests {
             },
             "id": 1,
         });
+        expected["result"]["value"]
+            .as_object_mut()
+            .unwrap()
+            .extend(simulation_details.as_object().unwrap().clone());
         let expected: Response =
             serde_json::from_value(expected).expect("expected response deserialization");
         let result: Response = serde_json::from_str(&res.expect("actual response"))
@@ -5980,7 +7046,7 @@ pub mod tests {
             r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{tx_serialized_encoded}"]}}"#,
         );
         let res = io.handle_request_sync(&req, meta.clone());
-        let expected = json!({
+        let mut expected = json!({
             "jsonrpc": "2.0",
             "result": {
                 "context": {"slot": 0, "apiVersion": RpcApiVersion::default()},
@@ -5997,6 +7063,10 @@ pub mod tests {
             },
             "id": 1,
         });
+        expected["result"]["value"]
+            .as_object_mut()
+            .unwrap()
+            .extend(simulation_details.as_object().unwrap().clone());
         let expected: Response =
             serde_json::from_value(expected).expect("expected response deserialization");
         let result: Response = serde_json::from_str(&res.expect("actual response"))
@@ -6032,7 +7102,7 @@ pub mod tests {
             r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{tx_invalid_recent_blockhash}", {{"replaceRecentBlockhash": false}}]}}"#,
         );
         let res = io.handle_request_sync(&req, meta.clone());
-        let expected = json!({
+        let mut expected = json!({
             "jsonrpc":"2.0",
             "result": {
                 "context": {"slot": 0, "apiVersion": RpcApiVersion::default()},
@@ -6046,6 +7116,10 @@ pub mod tests {
             },
             "id":1
         });
+        expected["result"]["value"]
+            .as_object_mut()
+            .unwrap()
+            .extend(pre_simulation_details.as_object().unwrap().clone());
 
         let expected: Response =
             serde_json::from_value(expected).expect("expected response deserialization");
@@ -6058,7 +7132,7 @@ pub mod tests {
             r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{tx_invalid_recent_blockhash}", {{"replaceRecentBlockhash": true}}]}}"#,
         );
         let res = io.handle_request_sync(&req, meta.clone());
-        let expected = json!({
+        let mut expected = json!({
             "jsonrpc": "2.0",
             "result": {
                 "context": {"slot": 0, "apiVersion": RpcApiVersion::default()},
@@ -6075,6 +7149,10 @@ pub mod tests {
             },
             "id": 1,
         });
+        expected["result"]["value"]
+            .as_object_mut()
+            .unwrap()
+            .extend(simulation_details.as_object().unwrap().clone());
 
         let expected: Response =
             serde_json::from_value(expected).expect("expected response deserialization");
@@ -6083,6 +7161,272 @@ pub mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_rpc_simulate_bundle() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let alice = Keypair::new();
+        let bob_pubkey = Pubkey::new_unique();
+        let fund_alice = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &alice.pubkey(),
+            3 * rent_exempt_amount,
+            recent_blockhash,
+        );
+        let alice_to_bob =
+            system_transaction::transfer(&alice, &bob_pubkey, rent_exempt_amount, recent_blockhash);
+        let transactions = [fund_alice, alice_to_bob]
+            .iter()
+            .map(|tx| base64::encode(serialize(tx).unwrap()))
+            .collect::<Vec<_>>();
+
+        // Simulation bank must be frozen
+        bank.freeze();
+
+        let request = create_test_request(
+            "simulateBundle",
+            Some(json!([
+                transactions,
+                {
+                    "encoding": "base64",
+                    "accounts": {
+                        "addresses": [alice.pubkey().to_string(), bob_pubkey.to_string()],
+                    },
+                },
+            ])),
+        );
+        let result: RpcResponse<Vec<RpcSimulateTransactionResult>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let balances = result
+            .value
+            .iter()
+            .map(|result| {
+                assert_eq!(result.err, None);
+                result
+                    .accounts
+                    .as_ref()
+                    .unwrap()
+                    .iter()
+                    .map(|account| account.as_ref().map(|account| account.lamports))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let fee = bank.get_lamports_per_signature();
+        assert_eq!(
+            balances,
+            vec![
+                vec![Some(3 * rent_exempt_amount), None],
+                vec![Some(2 * rent_exempt_amount - fee), Some(rent_exempt_amount)],
+            ]
+        );
+
+        // Nothing was committed
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+
+        let request = create_test_request(
+            "simulateBundle",
+            Some(json!([
+                vec![transactions[0].clone(); MAX_SIMULATE_BUNDLE_TRANSACTIONS + 1],
+                {"encoding": "base64"},
+            ])),
+        );
+        let response = rpc.handle_request_sync(request);
+        let expected = json!({
+            "jsonrpc": "2.0",
+            "error": {
+                "code": ErrorCode::InvalidParams.code(),
+                "message": format!(
+                    "Too many transactions provided; max {MAX_SIMULATE_BUNDLE_TRANSACTIONS}"
+                ),
+            },
+            "id": 1,
+        });
+        let expected: Response = serde_json::from_value(expected).unwrap();
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_with_overrides() {
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            enable_simulation_program_overrides: true,
+            ..JsonRpcConfig::default()
+        });
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let alice = Keypair::new();
+        let bob_pubkey = Pubkey::new_unique();
+        let alice_to_bob =
+            system_transaction::transfer(&alice, &bob_pubkey, rent_exempt_amount, recent_blockhash);
+        let tx_serialized_encoded = base64::encode(serialize(&alice_to_bob).unwrap());
+        let account_overrides = HashMap::from([(
+            alice.pubkey().to_string(),
+            UiAccount::encode(
+                &alice.pubkey(),
+                &AccountSharedData::new(3 * rent_exempt_amount, 0, &system_program::id()),
+                UiAccountEncoding::Base64,
+                None,
+                None,
+            ),
+        )]);
+
+        // Simulation bank must be frozen
+        bank.freeze();
+
+        let request = create_test_request(
+            "simulateTransaction",
+            Some(json!([
+                tx_serialized_encoded,
+                {
+                    "encoding": "base64",
+                    "accounts": {
+                        "addresses": [alice.pubkey().to_string(), bob_pubkey.to_string()],
+                    },
+                    "accountOverrides": account_overrides,
+                    "sysvarOverrides": {
+                        "clock": {"unixTimestamp": 1_700_000_000},
+                    },
+                    "featureOverrides": {
+                        "deactivate": [
+                            feature_set::cap_accounts_data_allocations_per_transaction::id()
+                                .to_string(),
+                        ],
+                    },
+                },
+            ])),
+        );
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.err, None);
+        let balances = result
+            .value
+            .accounts
+            .unwrap()
+            .iter()
+            .map(|account| account.as_ref().map(|account| account.lamports))
+            .collect::<Vec<_>>();
+        let fee = bank.get_lamports_per_signature();
+        assert_eq!(
+            balances,
+            vec![Some(2 * rent_exempt_amount - fee), Some(rent_exempt_amount)]
+        );
+
+        // Nothing was committed
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+
+        let request = create_test_request(
+            "simulateTransaction",
+            Some(json!([
+                tx_serialized_encoded,
+                {
+                    "encoding": "base64",
+                    "featureOverrides": {"activate": [Pubkey::new_unique().to_string()]},
+                },
+            ])),
+        );
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert!(message.starts_with("Unknown feature"));
+
+        let request_with_overrides = |mut config: Value| {
+            config["encoding"] = json!("base64");
+            create_test_request(
+                "simulateTransaction",
+                Some(json!([tx_serialized_encoded, config])),
+            )
+        };
+        let program_account_override = |data_len: usize| {
+            let program_id = Pubkey::new_unique();
+            let account_overrides = HashMap::from([(
+                program_id.to_string(),
+                UiAccount::encode(
+                    &program_id,
+                    &AccountSharedData::new(
+                        rent_exempt_amount,
+                        data_len,
+                        &bpf_loader_upgradeable::id(),
+                    ),
+                    UiAccountEncoding::Base64,
+                    None,
+                    None,
+                ),
+            )]);
+            json!({ "accountOverrides": account_overrides })
+        };
+
+        // Program and feature overrides are off by default
+        let rpc = RpcHandler::start();
+        let request = request_with_overrides(json!({"featureOverrides": {"activate": []}}));
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert_eq!(message, "featureOverrides is not enabled on this node");
+        let request = request_with_overrides(program_account_override(0));
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert_eq!(
+            message,
+            "Program account overrides are not enabled on this node"
+        );
+
+        // The data of the overrides is capped
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            enable_simulation_program_overrides: true,
+            ..JsonRpcConfig::default()
+        });
+        let request = request_with_overrides(program_account_override(
+            MAX_SIMULATION_ACCOUNT_OVERRIDES_DATA_LEN + 1,
+        ));
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert!(message.starts_with("Account overrides are too large"));
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_instruction_tracing() {
+        let request_with_tracing = |rpc: &RpcHandler| {
+            let bank = rpc.working_bank();
+            let recent_blockhash = bank.confirmed_last_blockhash();
+            let tx = system_transaction::transfer(
+                &rpc.mint_keypair,
+                &Pubkey::new_unique(),
+                bank.get_minimum_balance_for_rent_exemption(0),
+                recent_blockhash,
+            );
+            bank.freeze();
+            create_test_request(
+                "simulateTransaction",
+                Some(json!([
+                    base64::encode(serialize(&tx).unwrap()),
+                    {"encoding": "base64", "traceInstructions": true},
+                ])),
+            )
+        };
+
+        // tracing is off by default
+        let rpc = RpcHandler::start();
+        let request = request_with_tracing(&rpc);
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert_eq!(message, "traceInstructions is not enabled on this node");
+
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            enable_simulation_instruction_tracing: true,
+            ..JsonRpcConfig::default()
+        });
+        let request = request_with_tracing(&rpc);
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.err, None);
+        let instruction_trace = result.value.instruction_trace.unwrap();
+        assert_eq!(instruction_trace.frames.len(), 1);
+        assert_eq!(
+            instruction_trace.frames[0].program_id,
+            system_program::id().to_string()
+        );
+    }
+
     #[test]
     #[should_panic(expected = "simulation bank must be frozen")]
     fn test_rpc_simulate_transaction_panic_on_unfrozen_bank() {
@@ -6616,6 +7960,30 @@ pub mod tests {
         assert_eq!(result, 43);
     }
 
+    #[test]
+    fn test_rpc_get_accounts_hash_bins() {
+        let rpc = RpcHandler::start();
+        let slot = 42;
+        let request = create_test_request("getAccountsHashBins", Some(json!([slot])));
+        let result: Option<AccountsHashBins> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result, None);
+
+        let accounts_hash_bins = AccountsHashBins::new(slot, vec![AccountsHashBin::new(&[])]);
+        rpc.bank_forks
+            .read()
+            .unwrap()
+            .root_bank()
+            .rc
+            .accounts
+            .accounts_db
+            .add_snapshot_accounts_hash_bins(accounts_hash_bins.clone(), slot);
+        let request = create_test_request("getAccountsHashBins", Some(json!([slot])));
+        let result: Option<AccountsHashBins> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result, Some(accounts_hash_bins));
+    }
+
     #[test]
     fn test_rpc_get_version() {
         let rpc = RpcHandler::start();
@@ -7663,6 +9031,83 @@ pub mod tests {
                 serde_json::from_value(result["result"]["value"].clone()).unwrap();
             assert_eq!(accounts.len(), 2);
 
+            // Test getTokenAccountsByOwner with additional filters
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByOwner",
+                    "params":["{owner}", {{"programId": "{program_id}"}}, {{
+                        "encoding":"base64",
+                        "filters":[{{"notMemcmp":{{"offset":0,"bytes":"{mint}"}}}}]
+                    }}]
+                }}"#,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let accounts: Vec<RpcKeyedAccount> =
+                serde_json::from_value(result["result"]["value"].clone()).unwrap();
+            assert_eq!(accounts.len(), 1);
+            assert_eq!(
+                accounts[0].pubkey,
+                token_with_different_mint_pubkey.to_string()
+            );
+
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByOwner",
+                    "params":["{owner}", {{"mint": "{mint}"}}, {{
+                        "encoding":"base64",
+                        "filters":[{{"lamportsRange":{{"max":110}}}}]
+                    }}]
+                }}"#,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let accounts: Vec<RpcKeyedAccount> =
+                serde_json::from_value(result["result"]["value"].clone()).unwrap();
+            assert!(accounts.is_empty());
+
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByOwner",
+                    "params":["{owner}", {{"programId": "{program_id}"}}, {{
+                        "encoding":"base64",
+                        "filters":[{{"tokenExtension":{}}}]
+                    }}]
+                }}"#,
+                ExtensionType::ImmutableOwner as u16,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let accounts: Vec<RpcKeyedAccount> =
+                serde_json::from_value(result["result"]["value"].clone()).unwrap();
+            if program_id == inline_spl_token_2022::id() {
+                assert_eq!(accounts.len(), 2);
+            } else {
+                assert!(accounts.is_empty());
+            }
+
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByOwner",
+                    "params":["{owner}", {{"mint": "{mint}"}}, {{"filters":[{{"memcmpAnyOf":[]}}]}}]
+                }}"#,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            assert!(result.get("error").is_some());
+
             // Test non-existent Mint/program id
             let req = format!(
                 r#"{{
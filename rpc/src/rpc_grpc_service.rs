@@ -0,0 +1,766 @@
+//! The `rpc_grpc_service` module implements a gRPC transport for the account, block and
+//! transaction read methods of the JSON RPC API, and for slot and account subscriptions.
+
+use {
+    crate::{
+        rpc::{verify_filter, JsonRpcRequestProcessor},
+        rpc_rate_limiter::{RpcMethodClass, RpcRateLimiter},
+        rpc_subscription_tracker::{
+            AccountSubscriptionParams, ProgramSubscriptionParams, SubscriptionControl,
+            SubscriptionParams,
+        },
+        rpc_subscriptions::RpcNotificationValue,
+    },
+    generated::{
+        solana_rpc_server::{SolanaRpc, SolanaRpcServer},
+        Account, AccountNotification, CommitmentLevel, ConfirmationStatus, DataSlice, Filter,
+        GetAccountInfoRequest, GetAccountInfoResponse, GetBlockRequest, GetBlockResponse,
+        GetMultipleAccountsRequest, GetMultipleAccountsResponse, GetProgramAccountsRequest,
+        GetProgramAccountsResponse, GetSignaturesForAddressRequest,
+        GetSignaturesForAddressResponse, GetTransactionRequest, GetTransactionResponse,
+        MaybeAccount, SignatureInfo, SlotNotification, SubscribeAccountRequest,
+        SubscribeProgramRequest, SubscribeSlotsRequest,
+    },
+    jsonrpc_core::ErrorCode,
+    solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig},
+    solana_rpc_client_api::{
+        config::RpcContextConfig,
+        filter::{Memcmp, RpcFilterType},
+        request::{
+            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
+        },
+        response::SlotInfo,
+    },
+    solana_runtime::bank::Bank,
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        commitment_config::CommitmentConfig,
+        exit::Exit,
+        pubkey::Pubkey,
+        signature::Signature,
+    },
+    solana_storage_proto::convert::{generated as storage_generated, tx_by_addr},
+    solana_transaction_status::TransactionConfirmationStatus,
+    std::{
+        future::Future,
+        net::SocketAddr,
+        sync::{Arc, RwLock, Weak},
+        thread::{self, Builder, JoinHandle},
+    },
+    stream_cancel::Tripwire,
+    tokio::{runtime::Handle, select, sync::mpsc},
+    tokio_stream::wrappers::ReceiverStream,
+    tonic::{transport::Server, Request, Response, Status},
+};
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+pub mod generated {
+    tonic::include_proto!("solana.rpc");
+}
+
+pub const DEFAULT_GRPC_WORKER_THREADS: usize = 4;
+
+// Notifications buffered per stream before a slow client is disconnected
+const NOTIFICATION_CHANNEL_CAPACITY: usize = 1_000;
+
+#[derive(Debug, Clone)]
+pub struct RpcGrpcConfig {
+    pub grpc_addr: SocketAddr,
+    pub worker_threads: usize,
+}
+
+pub struct RpcGrpcService {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl RpcGrpcService {
+    pub fn new(
+        config: RpcGrpcConfig,
+        request_processor: JsonRpcRequestProcessor,
+        max_multiple_accounts: usize,
+        rate_limiter: Option<Arc<RpcRateLimiter>>,
+        subscription_control: Option<SubscriptionControl>,
+        validator_exit: &Arc<RwLock<Exit>>,
+    ) -> Self {
+        info!("rpc grpc bound to {:?}", config.grpc_addr);
+
+        let (trigger, tripwire) = Tripwire::new();
+        validator_exit
+            .write()
+            .unwrap()
+            .register_exit(Box::new(move || trigger.cancel()));
+
+        let thread_hdl = Builder::new()
+            .name("solRpcGrpc".to_string())
+            .spawn(move || {
+                let runtime = tokio::runtime::Builder::new_multi_thread()
+                    .worker_threads(1.max(config.worker_threads))
+                    .thread_name("solRpcGrpcEl")
+                    .enable_all()
+                    .build()
+                    .expect("runtime creation failed");
+                let service = SolanaRpcServer::new(RpcGrpcImpl {
+                    request_processor,
+                    max_multiple_accounts,
+                    rate_limiter,
+                    subscription_control,
+                });
+                let server = Server::builder().add_service(service).serve_with_shutdown(
+                    config.grpc_addr,
+                    async move {
+                        let _ = tripwire.await;
+                    },
+                );
+                if let Err(err) = runtime.block_on(server) {
+                    error!("rpc grpc service failed: {}", err);
+                }
+            })
+            .unwrap();
+
+        Self { thread_hdl }
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+type NotificationStream<T> = ReceiverStream<Result<T, Status>>;
+
+struct RpcGrpcImpl {
+    request_processor: JsonRpcRequestProcessor,
+    max_multiple_accounts: usize,
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
+    subscription_control: Option<SubscriptionControl>,
+}
+
+impl RpcGrpcImpl {
+    /// Takes a token from the client's bucket for the JSON RPC method equivalent to `method`
+    fn check_rate_limit<T>(&self, request: &Request<T>, method: &str) -> Result<(), Status> {
+        let rate_limiter = match &self.rate_limiter {
+            Some(rate_limiter) => rate_limiter,
+            None => return Ok(()),
+        };
+        let client_key = rate_limiter
+            .grpc_client_key(request.metadata(), request.remote_addr())
+            .ok_or_else(|| Status::invalid_argument("Unable to identify the client"))?;
+        rate_limiter
+            .check(&client_key, method)
+            .map_err(|retry_after| {
+                Status::resource_exhausted(format!(
+                    "Too many {} requests from this client; retry after {}ms",
+                    RpcMethodClass::from_method(method).as_str(),
+                    retry_after.as_millis()
+                ))
+            })
+    }
+
+    fn bank(
+        request_processor: &JsonRpcRequestProcessor,
+        commitment: CommitmentConfig,
+        min_context_slot: u64,
+    ) -> Result<Arc<Bank>, Status> {
+        request_processor
+            .get_bank_with_config(RpcContextConfig {
+                commitment: Some(commitment),
+                min_context_slot: (min_context_slot > 0).then_some(min_context_slot),
+            })
+            .map_err(status_from_error)
+    }
+
+    /// Runs `f` on the blocking pool with a clone of the request processor. Bank scans and
+    /// account loads block, and would otherwise stall the few workers serving every stream.
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T, Status>
+    where
+        T: Send + 'static,
+        F: FnOnce(JsonRpcRequestProcessor) -> Result<T, Status> + Send + 'static,
+    {
+        let request_processor = self.request_processor.clone();
+        tokio::task::spawn_blocking(move || f(request_processor))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+    }
+
+    /// Runs the future returned by `f` on the blocking pool, like `run_blocking`. The async
+    /// methods of the request processor read the blockstore without awaiting, and only await the
+    /// BigTable fallback.
+    async fn run_blocking_async<T, F, Fut>(&self, f: F) -> Result<T, Status>
+    where
+        T: Send + 'static,
+        F: FnOnce(JsonRpcRequestProcessor) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        let runtime = Handle::current();
+        self.run_blocking(move |request_processor| runtime.block_on(f(request_processor)))
+            .await
+    }
+
+    /// Streams the notifications of a pubsub subscription, converted from their typed value by
+    /// `notification`, until the client goes away or falls too far behind
+    fn subscribe<T, F>(
+        &self,
+        params: SubscriptionParams,
+        notification: F,
+    ) -> Result<Response<NotificationStream<T>>, Status>
+    where
+        T: Send + 'static,
+        F: Fn(&RpcNotificationValue) -> Option<T> + Send + 'static,
+    {
+        let subscription_control = self
+            .subscription_control
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("Subscriptions are not available"))?;
+        // Receive broadcasts before subscribing so the first notification can't be missed
+        let mut broadcast_receiver = subscription_control.broadcast_receiver();
+        let token = subscription_control
+            .subscribe(params)
+            .map_err(|err| Status::resource_exhausted(err.to_string()))?;
+
+        let (sender, receiver) = mpsc::channel(NOTIFICATION_CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            loop {
+                let received = select! {
+                    _ = sender.closed() => break,
+                    received = broadcast_receiver.recv() => received,
+                };
+                let item = match received {
+                    Ok(received) if received.subscription_id != token.id() => continue,
+                    Ok(received) => match received.value.as_ref().map(Weak::upgrade) {
+                        Some(Some(value)) => match notification(&value) {
+                            Some(item) => Ok(item),
+                            None => {
+                                warn!("rpc grpc failed to convert notification: {:?}", value);
+                                continue;
+                            }
+                        },
+                        Some(None) => Err(Status::data_loss("Client has lagged behind")),
+                        None => {
+                            warn!("rpc grpc received a notification without a typed value");
+                            continue;
+                        }
+                    },
+                    Err(err) => Err(Status::data_loss(format!(
+                        "Client has lagged behind: {err}"
+                    ))),
+                };
+                let is_err = item.is_err();
+                if sender.try_send(item).is_err() || is_err {
+                    break;
+                }
+            }
+            drop(token);
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+#[tonic::async_trait]
+impl SolanaRpc for RpcGrpcImpl {
+    async fn get_account_info(
+        &self,
+        request: Request<GetAccountInfoRequest>,
+    ) -> Result<Response<GetAccountInfoResponse>, Status> {
+        self.check_rate_limit(&request, "getAccountInfo")?;
+        let GetAccountInfoRequest {
+            pubkey,
+            commitment,
+            min_context_slot,
+            data_slice,
+        } = request.into_inner();
+        let pubkey = pubkey_param(&pubkey)?;
+        let commitment = commitment_param(commitment)?;
+        self.run_blocking(move |request_processor| {
+            let bank = Self::bank(&request_processor, commitment, min_context_slot)?;
+            let account = bank
+                .get_account(&pubkey)
+                .map(|account| account_message(&pubkey, &account, data_slice.as_ref()));
+            Ok(Response::new(GetAccountInfoResponse {
+                slot: bank.slot(),
+                account,
+            }))
+        })
+        .await
+    }
+
+    async fn get_multiple_accounts(
+        &self,
+        request: Request<GetMultipleAccountsRequest>,
+    ) -> Result<Response<GetMultipleAccountsResponse>, Status> {
+        self.check_rate_limit(&request, "getMultipleAccounts")?;
+        let GetMultipleAccountsRequest {
+            pubkeys,
+            commitment,
+            min_context_slot,
+            data_slice,
+        } = request.into_inner();
+        if pubkeys.len() > self.max_multiple_accounts {
+            return Err(Status::invalid_argument(format!(
+                "Too many inputs provided; max {}",
+                self.max_multiple_accounts
+            )));
+        }
+        let pubkeys = pubkeys
+            .iter()
+            .map(|pubkey| pubkey_param(pubkey))
+            .collect::<Result<Vec<_>, _>>()?;
+        let commitment = commitment_param(commitment)?;
+        self.run_blocking(move |request_processor| {
+            let bank = Self::bank(&request_processor, commitment, min_context_slot)?;
+            let accounts = pubkeys
+                .iter()
+                .map(|pubkey| MaybeAccount {
+                    account: bank
+                        .get_account(pubkey)
+                        .map(|account| account_message(pubkey, &account, data_slice.as_ref())),
+                })
+                .collect();
+            Ok(Response::new(GetMultipleAccountsResponse {
+                slot: bank.slot(),
+                accounts,
+            }))
+        })
+        .await
+    }
+
+    async fn get_program_accounts(
+        &self,
+        request: Request<GetProgramAccountsRequest>,
+    ) -> Result<Response<GetProgramAccountsResponse>, Status> {
+        self.check_rate_limit(&request, "getProgramAccounts")?;
+        let GetProgramAccountsRequest {
+            program_id,
+            commitment,
+            min_context_slot,
+            data_slice,
+            filters,
+        } = request.into_inner();
+        let program_id = pubkey_param(&program_id)?;
+        let filters = filters_param(filters)?;
+        let commitment = commitment_param(commitment)?;
+        self.run_blocking(move |request_processor| {
+            let bank = Self::bank(&request_processor, commitment, min_context_slot)?;
+            let accounts = request_processor
+                .get_keyed_program_accounts(&bank, &program_id, filters)
+                .map_err(status_from_error)?
+                .iter()
+                .map(|(pubkey, account)| account_message(pubkey, account, data_slice.as_ref()))
+                .collect();
+            Ok(Response::new(GetProgramAccountsResponse {
+                slot: bank.slot(),
+                accounts,
+            }))
+        })
+        .await
+    }
+
+    async fn get_block(
+        &self,
+        request: Request<GetBlockRequest>,
+    ) -> Result<Response<GetBlockResponse>, Status> {
+        self.check_rate_limit(&request, "getBlock")?;
+        let GetBlockRequest { slot, commitment } = request.into_inner();
+        let commitment = commitment_param(commitment)?;
+        let block = self
+            .run_blocking_async(move |request_processor| async move {
+                request_processor
+                    .get_confirmed_block(slot, commitment)
+                    .await
+                    .map_err(status_from_error)
+            })
+            .await?
+            .ok_or_else(|| Status::not_found(format!("Block {slot} not found")))?;
+        Ok(Response::new(GetBlockResponse {
+            block: Some(block.into()),
+        }))
+    }
+
+    async fn get_transaction(
+        &self,
+        request: Request<GetTransactionRequest>,
+    ) -> Result<Response<GetTransactionResponse>, Status> {
+        self.check_rate_limit(&request, "getTransaction")?;
+        let GetTransactionRequest {
+            signature,
+            commitment,
+        } = request.into_inner();
+        let signature = signature_param(&signature)?;
+        let commitment = commitment_param(commitment)?;
+        let transaction = self
+            .run_blocking_async(move |request_processor| async move {
+                request_processor
+                    .get_confirmed_transaction(signature, commitment)
+                    .await
+                    .map_err(status_from_error)
+            })
+            .await?
+            .ok_or_else(|| Status::not_found(format!("Transaction {signature} not found")))?;
+        Ok(Response::new(GetTransactionResponse {
+            slot: transaction.slot,
+            transaction: Some(transaction.tx_with_meta.into()),
+            block_time: transaction
+                .block_time
+                .map(|timestamp| storage_generated::UnixTimestamp { timestamp }),
+        }))
+    }
+
+    async fn get_signatures_for_address(
+        &self,
+        request: Request<GetSignaturesForAddressRequest>,
+    ) -> Result<Response<GetSignaturesForAddressResponse>, Status> {
+        self.check_rate_limit(&request, "getSignaturesForAddress")?;
+        let GetSignaturesForAddressRequest {
+            address,
+            before,
+            until,
+            limit,
+            commitment,
+            min_context_slot,
+        } = request.into_inner();
+        let address = pubkey_param(&address)?;
+        let before = optional_signature_param(&before)?;
+        let until = optional_signature_param(&until)?;
+        let limit = match limit as usize {
+            0 => MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
+            limit if limit > MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT => {
+                return Err(Status::invalid_argument(format!(
+                    "Invalid limit; max {MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT}"
+                )));
+            }
+            limit => limit,
+        };
+        let config = RpcContextConfig {
+            commitment: Some(commitment_param(commitment)?),
+            min_context_slot: (min_context_slot > 0).then_some(min_context_slot),
+        };
+        let signatures = self
+            .run_blocking_async(move |request_processor| async move {
+                request_processor
+                    .get_signatures_for_address(address, before, until, limit, config)
+                    .await
+                    .map_err(status_from_error)
+            })
+            .await?
+            .into_iter()
+            .map(|info| {
+                let signature = info
+                    .signature
+                    .parse::<Signature>()
+                    .map_err(|err| Status::internal(err.to_string()))?;
+                Ok(SignatureInfo {
+                    signature: signature.as_ref().to_vec(),
+                    slot: info.slot,
+                    err: info.err.map(tx_by_addr::TransactionError::from),
+                    memo: info.memo.map(|memo| tx_by_addr::Memo { memo }),
+                    block_time: info
+                        .block_time
+                        .map(|timestamp| storage_generated::UnixTimestamp { timestamp }),
+                    confirmation_status: info
+                        .confirmation_status
+                        .map(|status| confirmation_status_message(status) as i32)
+                        .unwrap_or_default(),
+                })
+            })
+            .collect::<Result<Vec<_>, Status>>()?;
+        Ok(Response::new(GetSignaturesForAddressResponse {
+            signatures,
+        }))
+    }
+
+    type SubscribeSlotsStream = NotificationStream<SlotNotification>;
+
+    async fn subscribe_slots(
+        &self,
+        request: Request<SubscribeSlotsRequest>,
+    ) -> Result<Response<Self::SubscribeSlotsStream>, Status> {
+        self.check_rate_limit(&request, "slotSubscribe")?;
+        self.subscribe(SubscriptionParams::Slot, |value| match value {
+            RpcNotificationValue::Slot(SlotInfo { slot, parent, root }) => Some(SlotNotification {
+                slot: *slot,
+                parent: *parent,
+                root: *root,
+            }),
+            _ => None,
+        })
+    }
+
+    type SubscribeAccountStream = NotificationStream<AccountNotification>;
+
+    async fn subscribe_account(
+        &self,
+        request: Request<SubscribeAccountRequest>,
+    ) -> Result<Response<Self::SubscribeAccountStream>, Status> {
+        self.check_rate_limit(&request, "accountSubscribe")?;
+        let SubscribeAccountRequest {
+            pubkey,
+            commitment,
+            data_slice,
+        } = request.into_inner();
+        let pubkey = pubkey_param(&pubkey)?;
+        let params = SubscriptionParams::Account(AccountSubscriptionParams {
+            pubkey,
+            encoding: UiAccountEncoding::Base64,
+            data_slice: data_slice.as_ref().map(data_slice_config),
+            commitment: commitment_param(commitment)?,
+        });
+        self.subscribe(params, move |value| {
+            account_notification(value, data_slice.as_ref())
+        })
+    }
+
+    type SubscribeProgramStream = NotificationStream<AccountNotification>;
+
+    async fn subscribe_program(
+        &self,
+        request: Request<SubscribeProgramRequest>,
+    ) -> Result<Response<Self::SubscribeProgramStream>, Status> {
+        self.check_rate_limit(&request, "programSubscribe")?;
+        let SubscribeProgramRequest {
+            program_id,
+            commitment,
+            data_slice,
+            filters,
+        } = request.into_inner();
+        let params = SubscriptionParams::Program(ProgramSubscriptionParams {
+            pubkey: pubkey_param(&program_id)?,
+            filters: filters_param(filters)?,
+            encoding: UiAccountEncoding::Base64,
+            data_slice: data_slice.as_ref().map(data_slice_config),
+            commitment: commitment_param(commitment)?,
+            with_context: false,
+        });
+        self.subscribe(params, move |value| {
+            account_notification(value, data_slice.as_ref())
+        })
+    }
+}
+
+fn account_notification(
+    value: &RpcNotificationValue,
+    data_slice: Option<&DataSlice>,
+) -> Option<AccountNotification> {
+    match value {
+        RpcNotificationValue::Account {
+            slot,
+            pubkey,
+            account,
+        } => Some(AccountNotification {
+            slot: *slot,
+            account: Some(account_message(pubkey, account, data_slice)),
+        }),
+        _ => None,
+    }
+}
+
+fn status_from_error(err: jsonrpc_core::Error) -> Status {
+    match err.code {
+        ErrorCode::InvalidParams | ErrorCode::InvalidRequest => {
+            Status::invalid_argument(err.message)
+        }
+        ErrorCode::ServerError(_) => Status::failed_precondition(err.message),
+        _ => Status::internal(err.message),
+    }
+}
+
+fn commitment_param(commitment: i32) -> Result<CommitmentConfig, Status> {
+    match CommitmentLevel::from_i32(commitment) {
+        Some(CommitmentLevel::Finalized) => Ok(CommitmentConfig::finalized()),
+        Some(CommitmentLevel::Confirmed) => Ok(CommitmentConfig::confirmed()),
+        Some(CommitmentLevel::Processed) => Ok(CommitmentConfig::processed()),
+        None => Err(Status::invalid_argument(format!(
+            "Invalid commitment: {commitment}"
+        ))),
+    }
+}
+
+fn pubkey_param(pubkey: &[u8]) -> Result<Pubkey, Status> {
+    Pubkey::try_from(pubkey)
+        .map_err(|_| Status::invalid_argument("Invalid pubkey: expected 32 bytes"))
+}
+
+fn signature_param(signature: &[u8]) -> Result<Signature, Status> {
+    if signature.len() != std::mem::size_of::<Signature>() {
+        return Err(Status::invalid_argument(
+            "Invalid signature: expected 64 bytes",
+        ));
+    }
+    Ok(Signature::new(signature))
+}
+
+fn optional_signature_param(signature: &[u8]) -> Result<Option<Signature>, Status> {
+    if signature.is_empty() {
+        Ok(None)
+    } else {
+        signature_param(signature).map(Some)
+    }
+}
+
+fn filters_param(filters: Vec<Filter>) -> Result<Vec<RpcFilterType>, Status> {
+    if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+        return Err(Status::invalid_argument(format!(
+            "Too many filters provided; max {MAX_GET_PROGRAM_ACCOUNT_FILTERS}"
+        )));
+    }
+    filters
+        .into_iter()
+        .map(|filter| {
+            let filter = match filter.filter {
+                Some(generated::filter::Filter::DataSize(size)) => RpcFilterType::DataSize(size),
+                Some(generated::filter::Filter::Memcmp(memcmp)) => RpcFilterType::Memcmp(
+                    Memcmp::new_raw_bytes(memcmp.offset as usize, memcmp.bytes),
+                ),
+                Some(generated::filter::Filter::TokenAccountState(_)) => {
+                    RpcFilterType::TokenAccountState
+                }
+                None => return Err(Status::invalid_argument("Empty filter provided")),
+            };
+            verify_filter(&filter).map_err(status_from_error)?;
+            Ok(filter)
+        })
+        .collect()
+}
+
+fn data_slice_config(data_slice: &DataSlice) -> UiDataSliceConfig {
+    UiDataSliceConfig {
+        offset: data_slice.offset as usize,
+        length: data_slice.length as usize,
+    }
+}
+
+fn slice_data<'a>(data: &'a [u8], data_slice: Option<&DataSlice>) -> &'a [u8] {
+    match data_slice {
+        Some(data_slice) => {
+            let offset = (data_slice.offset as usize).min(data.len());
+            let end = offset
+                .saturating_add(data_slice.length as usize)
+                .min(data.len());
+            &data[offset..end]
+        }
+        None => data,
+    }
+}
+
+fn account_message(
+    pubkey: &Pubkey,
+    account: &AccountSharedData,
+    data_slice: Option<&DataSlice>,
+) -> Account {
+    Account {
+        pubkey: pubkey.to_bytes().to_vec(),
+        lamports: account.lamports(),
+        owner: account.owner().to_bytes().to_vec(),
+        executable: account.executable(),
+        rent_epoch: account.rent_epoch(),
+        data: slice_data(account.data(), data_slice).to_vec(),
+    }
+}
+
+fn confirmation_status_message(status: TransactionConfirmationStatus) -> ConfirmationStatus {
+    match status {
+        TransactionConfirmationStatus::Processed => ConfirmationStatus::Processed,
+        TransactionConfirmationStatus::Confirmed => ConfirmationStatus::Confirmed,
+        TransactionConfirmationStatus::Finalized => ConfirmationStatus::Finalized,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::account::WritableAccount};
+
+    #[test]
+    fn test_slice_data() {
+        let data = [1, 2, 3, 4, 5];
+        assert_eq!(slice_data(&data, None), &data);
+        let slice = |offset, length| DataSlice { offset, length };
+        assert_eq!(slice_data(&data, Some(&slice(1, 2))), &[2, 3]);
+        assert_eq!(slice_data(&data, Some(&slice(3, 10))), &[4, 5]);
+        assert_eq!(slice_data(&data, Some(&slice(10, 2))), &[] as &[u8]);
+        assert_eq!(slice_data(&data, Some(&slice(2, u64::MAX))), &[3, 4, 5]);
+    }
+
+    #[test]
+    fn test_filters_param() {
+        let filters = filters_param(vec![
+            Filter {
+                filter: Some(generated::filter::Filter::DataSize(165)),
+            },
+            Filter {
+                filter: Some(generated::filter::Filter::Memcmp(generated::Memcmp {
+                    offset: 4,
+                    bytes: vec![1, 2, 3],
+                })),
+            },
+            Filter {
+                filter: Some(generated::filter::Filter::TokenAccountState(
+                    generated::TokenAccountState {},
+                )),
+            },
+        ])
+        .unwrap();
+        assert_eq!(filters[0], RpcFilterType::DataSize(165));
+        assert_eq!(
+            filters[1],
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(4, vec![1, 2, 3]))
+        );
+        assert_eq!(filters[2], RpcFilterType::TokenAccountState);
+
+        let status = filters_param(vec![Filter { filter: None }]).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let status = filters_param(vec![
+            Filter {
+                filter: Some(generated::filter::Filter::DataSize(1)),
+            };
+            MAX_GET_PROGRAM_ACCOUNT_FILTERS + 1
+        ])
+        .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_account_notification() {
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut account = AccountSharedData::new(42, 4, &owner);
+        account.set_data(vec![1, 2, 3, 4]);
+        let value = RpcNotificationValue::Account {
+            slot: 7,
+            pubkey,
+            account,
+        };
+        let notification = account_notification(
+            &value,
+            Some(&DataSlice {
+                offset: 1,
+                length: 2,
+            }),
+        )
+        .unwrap();
+        assert_eq!(notification.slot, 7);
+        let account = notification.account.unwrap();
+        assert_eq!(account.pubkey, pubkey.to_bytes().to_vec());
+        assert_eq!(account.owner, owner.to_bytes().to_vec());
+        assert_eq!(account.lamports, 42);
+        assert_eq!(account.data, vec![2, 3]);
+
+        let value = RpcNotificationValue::Slot(SlotInfo {
+            slot: 1,
+            parent: 0,
+            root: 0,
+        });
+        assert_eq!(account_notification(&value, None), None);
+    }
+
+    #[test]
+    fn test_commitment_param() {
+        assert_eq!(
+            commitment_param(CommitmentLevel::Finalized as i32).unwrap(),
+            CommitmentConfig::finalized()
+        );
+        assert_eq!(
+            commitment_param(CommitmentLevel::Processed as i32).unwrap(),
+            CommitmentConfig::processed()
+        );
+        assert!(commitment_param(42).is_err());
+    }
+}
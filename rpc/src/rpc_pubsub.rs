@@ -7,9 +7,10 @@ use {
         rpc_pubsub_service::PubSubConfig,
         rpc_subscription_tracker::{
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
-            LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
-            SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionParams,
-            SubscriptionToken,
+            Error as SubscriptionError, LogsSubscriptionKind, LogsSubscriptionParams,
+            ProgramSubscriptionParams, SignatureSubscriptionParams, SubscriptionControl,
+            SubscriptionId, SubscriptionParams, SubscriptionToken, TransactionSubscriptionFilter,
+            TransactionSubscriptionParams,
         },
     },
     dashmap::DashMap,
@@ -19,20 +20,28 @@ use {
     solana_account_decoder::{UiAccount, UiAccountEncoding},
     solana_rpc_client_api::{
         config::{
-            RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
-            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcAccountInfoConfig, RpcAccountSubscribeConfig, RpcBlockSubscribeConfig,
+            RpcBlockSubscribeFilter, RpcProgramSubscribeConfig, RpcSignatureSubscribeConfig,
+            RpcSlotsUpdatesSubscribeConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+            RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
         },
         response::{
             Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
-            RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
+            RpcSignatureResult, RpcTransactionUpdate, RpcVersionInfo, RpcVote, SlotInfo,
+            SlotUpdate,
         },
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
     solana_transaction_status::UiTransactionEncoding,
-    std::{str::FromStr, sync::Arc},
+    std::{
+        collections::VecDeque,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    },
 };
 
+pub const MAX_TRANSACTION_SUBSCRIBE_FILTER_ACCOUNTS: usize = 256;
+
 // We have to keep both of the following traits to not break backwards compatibility.
 // `RpcSolPubSubInternal` is actually used by the current PubSub API implementation.
 // `RpcSolPubSub` and the corresponding `gen_client` module are preserved
@@ -61,7 +70,7 @@ pub trait RpcSolPubSub {
         meta: Self::Metadata,
         subscriber: Subscriber<RpcResponse<UiAccount>>,
         pubkey_str: String,
-        config: Option<RpcAccountInfoConfig>,
+        config: Option<RpcAccountSubscribeConfig>,
     );
 
     // Unsubscribe from account notification subscription.
@@ -88,7 +97,7 @@ pub trait RpcSolPubSub {
         meta: Self::Metadata,
         subscriber: Subscriber<RpcResponse<RpcKeyedAccount>>,
         pubkey_str: String,
-        config: Option<RpcProgramAccountsConfig>,
+        config: Option<RpcProgramSubscribeConfig>,
     );
 
     // Unsubscribe from account notification subscription.
@@ -178,6 +187,7 @@ pub trait RpcSolPubSub {
         &self,
         meta: Self::Metadata,
         subscriber: Subscriber<Arc<SlotUpdate>>,
+        config: Option<RpcSlotsUpdatesSubscribeConfig>,
     );
 
     // Unsubscribe from slots updates notification subscription.
@@ -214,6 +224,32 @@ pub trait RpcSolPubSub {
         id: PubSubSubscriptionId,
     ) -> Result<bool>;
 
+    // Subscribe to transactions matching the filter
+    #[pubsub(
+        subscription = "transactionNotification",
+        subscribe,
+        name = "transactionSubscribe"
+    )]
+    fn transaction_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcTransactionUpdate>>,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    );
+
+    // Unsubscribe from transaction notification subscription.
+    #[pubsub(
+        subscription = "transactionNotification",
+        unsubscribe,
+        name = "transactionUnsubscribe"
+    )]
+    fn transaction_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: PubSubSubscriptionId,
+    ) -> Result<bool>;
+
     // Get notification when vote is encountered
     #[pubsub(subscription = "voteNotification", subscribe, name = "voteSubscribe")]
     fn vote_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<RpcVote>);
@@ -261,7 +297,7 @@ mod internal {
         fn account_subscribe(
             &self,
             pubkey_str: String,
-            config: Option<RpcAccountInfoConfig>,
+            config: Option<RpcAccountSubscribeConfig>,
         ) -> Result<SubscriptionId>;
 
         // Unsubscribe from account notification subscription.
@@ -274,7 +310,7 @@ mod internal {
         fn program_subscribe(
             &self,
             pubkey_str: String,
-            config: Option<RpcProgramAccountsConfig>,
+            config: Option<RpcProgramSubscribeConfig>,
         ) -> Result<SubscriptionId>;
 
         // Unsubscribe from account notification subscription.
@@ -316,7 +352,10 @@ mod internal {
 
         // Get series of updates for all slots
         #[rpc(name = "slotsUpdatesSubscribe")]
-        fn slots_updates_subscribe(&self) -> Result<SubscriptionId>;
+        fn slots_updates_subscribe(
+            &self,
+            config: Option<RpcSlotsUpdatesSubscribeConfig>,
+        ) -> Result<SubscriptionId>;
 
         // Unsubscribe from slots updates notification subscription.
         #[rpc(name = "slotsUpdatesUnsubscribe")]
@@ -334,6 +373,18 @@ mod internal {
         #[rpc(name = "blockUnsubscribe")]
         fn block_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
 
+        // Subscribe to transactions matching the filter
+        #[rpc(name = "transactionSubscribe")]
+        fn transaction_subscribe(
+            &self,
+            filter: RpcTransactionSubscribeFilter,
+            config: Option<RpcTransactionSubscribeConfig>,
+        ) -> Result<SubscriptionId>;
+
+        // Unsubscribe from transaction notification subscription.
+        #[rpc(name = "transactionUnsubscribe")]
+        fn transaction_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
+
         // Get notification when vote is encountered
         #[rpc(name = "voteSubscribe")]
         fn vote_subscribe(&self) -> Result<SubscriptionId>;
@@ -360,6 +411,7 @@ pub struct RpcSolPubSubImpl {
     config: PubSubConfig,
     subscription_control: SubscriptionControl,
     current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
+    replay_queue: Arc<Mutex<VecDeque<Arc<String>>>>,
 }
 
 impl RpcSolPubSubImpl {
@@ -367,11 +419,13 @@ impl RpcSolPubSubImpl {
         config: PubSubConfig,
         subscription_control: SubscriptionControl,
         current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
+        replay_queue: Arc<Mutex<VecDeque<Arc<String>>>>,
     ) -> Self {
         Self {
             config,
             subscription_control,
             current_subscriptions,
+            replay_queue,
         }
     }
 
@@ -379,14 +433,36 @@ impl RpcSolPubSubImpl {
         let token = self
             .subscription_control
             .subscribe(params)
-            .map_err(|_| Error {
-                code: ErrorCode::InternalError,
-                message: "Internal Error: Subscription refused. Node subscription limit reached"
-                    .into(),
+            .map_err(subscription_error)?;
+        let id = token.id();
+        self.current_subscriptions.insert(id, token);
+        Ok(id)
+    }
+
+    // Replayed notifications are queued for the connection to send right after the response
+    fn subscribe_from_slot(
+        &self,
+        params: SubscriptionParams,
+        from_slot: Option<Slot>,
+    ) -> Result<SubscriptionId> {
+        let from_slot = match from_slot {
+            Some(from_slot) => from_slot,
+            None => return self.subscribe(params),
+        };
+        if !self.subscription_control.is_replay_enabled() {
+            return Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid Request: fromSlot is not enabled on this node".into(),
                 data: None,
-            })?;
+            });
+        }
+        let (token, notifications) = self
+            .subscription_control
+            .subscribe_from_slot(params, from_slot)
+            .map_err(subscription_error)?;
         let id = token.id();
         self.current_subscriptions.insert(id, token);
+        self.replay_queue.lock().unwrap().extend(notifications);
         Ok(id)
     }
 
@@ -411,6 +487,21 @@ impl RpcSolPubSubImpl {
     }
 }
 
+fn subscription_error(err: SubscriptionError) -> Error {
+    match err {
+        SubscriptionError::TooManySubscriptions => Error {
+            code: ErrorCode::InternalError,
+            message: "Internal Error: Subscription refused. Node subscription limit reached".into(),
+            data: None,
+        },
+        err => Error {
+            code: ErrorCode::InvalidParams,
+            message: format!("Invalid Request: {err}"),
+            data: None,
+        },
+    }
+}
+
 fn param<T: FromStr>(param_str: &str, thing: &str) -> Result<T> {
     param_str.parse::<T>().map_err(|_e| Error {
         code: ErrorCode::InvalidParams,
@@ -419,17 +510,52 @@ fn param<T: FromStr>(param_str: &str, thing: &str) -> Result<T> {
     })
 }
 
+fn transaction_filter(
+    filter: RpcTransactionSubscribeFilter,
+) -> Result<TransactionSubscriptionFilter> {
+    let pubkeys = |keys: Option<Vec<String>>, thing: &str| -> Result<Vec<Pubkey>> {
+        let keys = keys.unwrap_or_default();
+        if keys.len() > MAX_TRANSACTION_SUBSCRIBE_FILTER_ACCOUNTS {
+            return Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: format!(
+                    "Invalid Request: Too many {thing} addresses, max {MAX_TRANSACTION_SUBSCRIBE_FILTER_ACCOUNTS}"
+                ),
+                data: None,
+            });
+        }
+        let mut pubkeys = keys
+            .iter()
+            .map(|key| param::<Pubkey>(key, thing))
+            .collect::<Result<Vec<_>>>()?;
+        pubkeys.sort_unstable();
+        pubkeys.dedup();
+        Ok(pubkeys)
+    };
+    Ok(TransactionSubscriptionFilter {
+        vote: filter.vote,
+        failed: filter.failed,
+        account_include: pubkeys(filter.account_include, "accountInclude")?,
+        account_exclude: pubkeys(filter.account_exclude, "accountExclude")?,
+        account_required: pubkeys(filter.account_required, "accountRequired")?,
+    })
+}
+
 impl RpcSolPubSubInternal for RpcSolPubSubImpl {
     fn account_subscribe(
         &self,
         pubkey_str: String,
-        config: Option<RpcAccountInfoConfig>,
+        config: Option<RpcAccountSubscribeConfig>,
     ) -> Result<SubscriptionId> {
-        let RpcAccountInfoConfig {
-            encoding,
-            data_slice,
-            commitment,
-            min_context_slot: _, // ignored
+        let RpcAccountSubscribeConfig {
+            account_config:
+                RpcAccountInfoConfig {
+                    encoding,
+                    data_slice,
+                    commitment,
+                    min_context_slot: _, // ignored
+                },
+            from_slot,
         } = config.unwrap_or_default();
         let params = AccountSubscriptionParams {
             pubkey: param::<Pubkey>(&pubkey_str, "pubkey")?,
@@ -437,7 +563,7 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
             data_slice,
             encoding: encoding.unwrap_or(UiAccountEncoding::Binary),
         };
-        self.subscribe(SubscriptionParams::Account(params))
+        self.subscribe_from_slot(SubscriptionParams::Account(params), from_slot)
     }
 
     fn account_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
@@ -447,12 +573,23 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
     fn program_subscribe(
         &self,
         pubkey_str: String,
-        config: Option<RpcProgramAccountsConfig>,
+        config: Option<RpcProgramSubscribeConfig>,
     ) -> Result<SubscriptionId> {
-        let config = config.unwrap_or_default();
+        let RpcProgramSubscribeConfig {
+            program_config: config,
+            from_slot,
+        } = config.unwrap_or_default();
+        let filters = config.filters.unwrap_or_default();
+        for filter in &filters {
+            filter.verify().map_err(|e| Error {
+                code: ErrorCode::InvalidParams,
+                message: format!("Invalid Request: Invalid filter provided, {e}"),
+                data: None,
+            })?;
+        }
         let params = ProgramSubscriptionParams {
             pubkey: param::<Pubkey>(&pubkey_str, "pubkey")?,
-            filters: config.filters.unwrap_or_default(),
+            filters,
             encoding: config
                 .account_config
                 .encoding
@@ -461,7 +598,7 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
             commitment: config.account_config.commitment.unwrap_or_default(),
             with_context: config.with_context.unwrap_or_default(),
         };
-        self.subscribe(SubscriptionParams::Program(params))
+        self.subscribe_from_slot(SubscriptionParams::Program(params), from_slot)
     }
 
     fn program_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
@@ -488,9 +625,15 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
                     LogsSubscriptionKind::Single(param::<Pubkey>(&keys[0], "mentions")?)
                 }
             },
-            commitment: config.and_then(|c| c.commitment).unwrap_or_default(),
+            commitment: config
+                .as_ref()
+                .and_then(|c| c.commitment)
+                .unwrap_or_default(),
         };
-        self.subscribe(SubscriptionParams::Logs(params))
+        self.subscribe_from_slot(
+            SubscriptionParams::Logs(params),
+            config.and_then(|c| c.from_slot),
+        )
     }
 
     fn logs_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
@@ -523,8 +666,14 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         self.unsubscribe(id)
     }
 
-    fn slots_updates_subscribe(&self) -> Result<SubscriptionId> {
-        self.subscribe(SubscriptionParams::SlotsUpdates)
+    fn slots_updates_subscribe(
+        &self,
+        config: Option<RpcSlotsUpdatesSubscribeConfig>,
+    ) -> Result<SubscriptionId> {
+        self.subscribe_from_slot(
+            SubscriptionParams::SlotsUpdates,
+            config.and_then(|c| c.from_slot),
+        )
     }
 
     fn slots_updates_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
@@ -553,6 +702,9 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
                         "mentions_account_or_program",
                     )?)
                 }
+                RpcBlockSubscribeFilter::Transactions(filter) => {
+                    BlockSubscriptionKind::Transactions(transaction_filter(filter)?)
+                }
             },
             transaction_details: config.transaction_details.unwrap_or_default(),
             show_rewards: config.show_rewards.unwrap_or_default(),
@@ -568,6 +720,31 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         self.unsubscribe(id)
     }
 
+    fn transaction_subscribe(
+        &self,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    ) -> Result<SubscriptionId> {
+        if !self.config.enable_transaction_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        let config = config.unwrap_or_default();
+        let params = TransactionSubscriptionParams {
+            commitment: config.commitment.unwrap_or_default(),
+            encoding: config.encoding.unwrap_or(UiTransactionEncoding::Base64),
+            filter: transaction_filter(filter)?,
+            max_supported_transaction_version: config.max_supported_transaction_version,
+        };
+        self.subscribe(SubscriptionParams::Transaction(params))
+    }
+
+    fn transaction_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
+        if !self.config.enable_transaction_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        self.unsubscribe(id)
+    }
+
     fn vote_subscribe(&self) -> Result<SubscriptionId> {
         if !self.config.enable_vote_subscription {
             return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
@@ -887,11 +1064,14 @@ mod tests {
 
         rpc.account_subscribe(
             stake_account.pubkey().to_string(),
-            Some(RpcAccountInfoConfig {
-                commitment: Some(CommitmentConfig::processed()),
-                encoding: Some(encoding),
-                data_slice: None,
-                min_context_slot: None,
+            Some(RpcAccountSubscribeConfig {
+                account_config: RpcAccountInfoConfig {
+                    commitment: Some(CommitmentConfig::processed()),
+                    encoding: Some(encoding),
+                    data_slice: None,
+                    min_context_slot: None,
+                },
+                from_slot: None,
             }),
         )
         .unwrap();
@@ -1011,11 +1191,14 @@ mod tests {
 
         rpc.account_subscribe(
             nonce_account.pubkey().to_string(),
-            Some(RpcAccountInfoConfig {
-                commitment: Some(CommitmentConfig::processed()),
-                encoding: Some(UiAccountEncoding::JsonParsed),
-                data_slice: None,
-                min_context_slot: None,
+            Some(RpcAccountSubscribeConfig {
+                account_config: RpcAccountInfoConfig {
+                    commitment: Some(CommitmentConfig::processed()),
+                    encoding: Some(UiAccountEncoding::JsonParsed),
+                    data_slice: None,
+                    min_context_slot: None,
+                },
+                from_slot: None,
             }),
         )
         .unwrap();
@@ -1144,11 +1327,14 @@ mod tests {
 
         rpc.account_subscribe(
             bob.pubkey().to_string(),
-            Some(RpcAccountInfoConfig {
-                commitment: Some(CommitmentConfig::finalized()),
-                encoding: None,
-                data_slice: None,
-                min_context_slot: None,
+            Some(RpcAccountSubscribeConfig {
+                account_config: RpcAccountInfoConfig {
+                    commitment: Some(CommitmentConfig::finalized()),
+                    encoding: None,
+                    data_slice: None,
+                    min_context_slot: None,
+                },
+                from_slot: None,
             }),
         )
         .unwrap();
@@ -1197,11 +1383,14 @@ mod tests {
 
         rpc.account_subscribe(
             bob.pubkey().to_string(),
-            Some(RpcAccountInfoConfig {
-                commitment: Some(CommitmentConfig::finalized()),
-                encoding: None,
-                data_slice: None,
-                min_context_slot: None,
+            Some(RpcAccountSubscribeConfig {
+                account_config: RpcAccountInfoConfig {
+                    commitment: Some(CommitmentConfig::finalized()),
+                    encoding: None,
+                    data_slice: None,
+                    min_context_slot: None,
+                },
+                from_slot: None,
             }),
         )
         .unwrap();
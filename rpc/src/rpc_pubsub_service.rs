@@ -13,11 +13,13 @@ use {
     soketto::handshake::{server, Server},
     solana_metrics::TokenCounter,
     std::{
+        collections::VecDeque,
         io,
         net::SocketAddr,
         str,
-        sync::Arc,
+        sync::{Arc, Mutex},
         thread::{self, Builder, JoinHandle},
+        time::{Duration, Instant},
     },
     stream_cancel::{Trigger, Tripwire},
     thiserror::Error,
@@ -30,14 +32,24 @@ pub const DEFAULT_QUEUE_CAPACITY_ITEMS: usize = 10_000_000;
 pub const DEFAULT_TEST_QUEUE_CAPACITY_ITEMS: usize = 100;
 pub const DEFAULT_QUEUE_CAPACITY_BYTES: usize = 256 * 1024 * 1024;
 pub const DEFAULT_WORKER_THREADS: usize = 1;
+pub const DEFAULT_REPLAY_CAPACITY_ITEMS: usize = 0;
+pub const DEFAULT_REPLAY_CAPACITY_BYTES: usize = 64 * 1024 * 1024;
+/// How long the subscriptions of a closed connection are kept alive, so a client reconnecting
+/// with `fromSlot` can replay the notifications generated in the meantime
+pub const REPLAY_LINGER_DURATION: Duration = Duration::from_secs(60);
+const LINGER_PRUNE_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Clone)]
 pub struct PubSubConfig {
     pub enable_block_subscription: bool,
+    pub enable_transaction_subscription: bool,
     pub enable_vote_subscription: bool,
     pub max_active_subscriptions: usize,
     pub queue_capacity_items: usize,
     pub queue_capacity_bytes: usize,
+    /// Number of notifications retained for `fromSlot` replay; 0 disables replay
+    pub replay_capacity_items: usize,
+    pub replay_capacity_bytes: usize,
     pub worker_threads: usize,
     pub notification_threads: Option<usize>,
 }
@@ -46,10 +58,13 @@ impl Default for PubSubConfig {
     fn default() -> Self {
         Self {
             enable_block_subscription: false,
+            enable_transaction_subscription: false,
             enable_vote_subscription: false,
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
             queue_capacity_items: DEFAULT_QUEUE_CAPACITY_ITEMS,
             queue_capacity_bytes: DEFAULT_QUEUE_CAPACITY_BYTES,
+            replay_capacity_items: DEFAULT_REPLAY_CAPACITY_ITEMS,
+            replay_capacity_bytes: DEFAULT_REPLAY_CAPACITY_BYTES,
             worker_threads: DEFAULT_WORKER_THREADS,
             notification_threads: None,
         }
@@ -60,10 +75,13 @@ impl PubSubConfig {
     pub fn default_for_tests() -> Self {
         Self {
             enable_block_subscription: false,
+            enable_transaction_subscription: false,
             enable_vote_subscription: false,
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
             queue_capacity_items: DEFAULT_TEST_QUEUE_CAPACITY_ITEMS,
             queue_capacity_bytes: DEFAULT_QUEUE_CAPACITY_BYTES,
+            replay_capacity_items: DEFAULT_REPLAY_CAPACITY_ITEMS,
+            replay_capacity_bytes: DEFAULT_REPLAY_CAPACITY_BYTES,
             worker_threads: DEFAULT_WORKER_THREADS,
             notification_threads: Some(2),
         }
@@ -115,6 +133,32 @@ impl PubSubService {
     }
 }
 
+/// Keeps the replayable subscriptions of closed connections alive for
+/// `REPLAY_LINGER_DURATION`, so their notifications keep being retained for replay
+#[derive(Default)]
+struct LingeringSubscriptions(Mutex<VecDeque<(Instant, SubscriptionToken)>>);
+
+impl LingeringSubscriptions {
+    fn linger(&self, current_subscriptions: &DashMap<SubscriptionId, SubscriptionToken>) {
+        let now = Instant::now();
+        self.0.lock().unwrap().extend(
+            current_subscriptions
+                .iter()
+                .filter(|entry| entry.value().params().is_replayable())
+                .map(|entry| (now, entry.value().clone())),
+        );
+    }
+
+    fn prune(&self) {
+        let mut lingering = self.0.lock().unwrap();
+        while lingering.front().map_or(false, |(since, _)| {
+            since.elapsed() >= REPLAY_LINGER_DURATION
+        }) {
+            lingering.pop_front();
+        }
+    }
+}
+
 struct BroadcastHandler {
     current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
 }
@@ -148,6 +192,9 @@ fn count_final(params: &SubscriptionParams) {
         SubscriptionParams::Block(_) => {
             inc_new_counter_info!("rpc-pubsub-final-slot-txs", 1);
         }
+        SubscriptionParams::Transaction(_) => {
+            inc_new_counter_info!("rpc-pubsub-final-transactions", 1);
+        }
     }
 }
 
@@ -188,6 +235,7 @@ impl BroadcastHandler {
 pub struct TestBroadcastReceiver {
     handler: BroadcastHandler,
     inner: tokio::sync::broadcast::Receiver<RpcNotification>,
+    replay_queue: Arc<Mutex<VecDeque<Arc<String>>>>,
 }
 
 #[cfg(test)]
@@ -204,6 +252,10 @@ impl TestBroadcastReceiver {
 
         let started = std::time::Instant::now();
 
+        if let Some(json) = self.replay_queue.lock().unwrap().pop_front() {
+            return Ok(json.to_string());
+        }
+
         loop {
             match self.inner.try_recv() {
                 Ok(notification) => {
@@ -232,16 +284,19 @@ pub fn test_connection(
     subscriptions: &Arc<RpcSubscriptions>,
 ) -> (RpcSolPubSubImpl, TestBroadcastReceiver) {
     let current_subscriptions = Arc::new(DashMap::new());
+    let replay_queue = Arc::new(Mutex::new(VecDeque::new()));
 
     let rpc_impl = RpcSolPubSubImpl::new(
         PubSubConfig {
             enable_block_subscription: true,
+            enable_transaction_subscription: true,
             enable_vote_subscription: true,
             queue_capacity_items: 100,
             ..PubSubConfig::default()
         },
         subscriptions.control().clone(),
         Arc::clone(&current_subscriptions),
+        Arc::clone(&replay_queue),
     );
     let broadcast_handler = BroadcastHandler {
         current_subscriptions,
@@ -249,6 +304,7 @@ pub fn test_connection(
     let receiver = TestBroadcastReceiver {
         inner: subscriptions.control().broadcast_receiver(),
         handler: broadcast_handler,
+        replay_queue,
     };
     (rpc_impl, receiver)
 }
@@ -269,6 +325,7 @@ async fn handle_connection(
     socket: TcpStream,
     subscription_control: SubscriptionControl,
     config: PubSubConfig,
+    current_subscriptions: Arc<DashMap<SubscriptionId, SubscriptionToken>>,
     mut tripwire: Tripwire,
 ) -> Result<(), Error> {
     let mut server = Server::new(socket.compat());
@@ -282,13 +339,14 @@ async fn handle_connection(
 
     let mut broadcast_receiver = subscription_control.broadcast_receiver();
     let mut data = Vec::new();
-    let current_subscriptions = Arc::new(DashMap::new());
+    let replay_queue = Arc::new(Mutex::new(VecDeque::new()));
 
     let mut json_rpc_handler = IoHandler::new();
     let rpc_impl = RpcSolPubSubImpl::new(
         config,
         subscription_control,
         Arc::clone(&current_subscriptions),
+        Arc::clone(&replay_queue),
     );
     json_rpc_handler.extend_with(rpc_impl.to_delegate());
     let broadcast_handler = BroadcastHandler {
@@ -335,6 +393,12 @@ async fn handle_connection(
         if let Some(response) = json_rpc_handler.handle_request(data_str).await {
             sender.send_text(&response).await?;
         }
+        // Replayed notifications go out right after the subscription response, ahead of
+        // the live ones still waiting in the broadcast channel.
+        let replay = std::mem::take(&mut *replay_queue.lock().unwrap());
+        for json in replay {
+            sender.send_text(&*json).await?;
+        }
         data.clear();
     }
 
@@ -349,6 +413,8 @@ async fn listen(
 ) -> io::Result<()> {
     let listener = tokio::net::TcpListener::bind(&listen_address).await?;
     let counter = TokenCounter::new("rpc_pubsub_connections");
+    let lingering_subscriptions = Arc::new(LingeringSubscriptions::default());
+    let mut prune_interval = tokio::time::interval(LINGER_PRUNE_INTERVAL);
     loop {
         select! {
             result = listener.accept() => match result {
@@ -358,19 +424,30 @@ async fn listen(
                     let config = config.clone();
                     let tripwire = tripwire.clone();
                     let counter_token = counter.create_token();
+                    let lingering_subscriptions = Arc::clone(&lingering_subscriptions);
                     tokio::spawn(async move {
+                        let replay_enabled = subscription_control.is_replay_enabled();
+                        let current_subscriptions = Arc::new(DashMap::new());
                         let handle = handle_connection(
-                            socket, subscription_control, config, tripwire
+                            socket,
+                            subscription_control,
+                            config,
+                            Arc::clone(&current_subscriptions),
+                            tripwire,
                         );
                         match handle.await {
                             Ok(()) => debug!("connection closed ({:?})", addr),
                             Err(err) => warn!("connection handler error ({:?}): {}", addr, err),
                         }
+                        if replay_enabled {
+                            lingering_subscriptions.linger(&current_subscriptions);
+                        }
                         drop(counter_token); // Force moving token into the task.
                     });
                 }
                 Err(e) => error!("couldn't accept connection: {:?}", e),
             },
+            _ = prune_interval.tick() => lingering_subscriptions.prune(),
             _ = &mut tripwire => return Ok(()),
         }
     }
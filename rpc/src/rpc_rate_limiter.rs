@@ -0,0 +1,590 @@
+//! The `rpc_rate_limiter` module implements per-client token bucket limits for the JSON RPC
+//! and gRPC services, applied separately to cheap reads, expensive reads such as account scans
+//! and transaction simulation, and transaction sends.
+
+use {
+    crate::rpc::JsonRpcRequestProcessor,
+    jsonrpc_core::{
+        futures::future::{self, Either},
+        middleware::Middleware,
+        BoxFuture, Call, Error, ErrorCode, Output, Response,
+    },
+    jsonrpc_http_server::hyper::{header::AUTHORIZATION, HeaderMap},
+    solana_rpc_client_api::custom_error::RpcCustomError,
+    std::{
+        collections::{HashMap, HashSet},
+        future::Future,
+        net::SocketAddr,
+        str::FromStr,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    tonic::metadata::MetadataMap,
+};
+
+// Buckets are only pruned once this many are tracked, and then at most once per interval
+const MAX_TRACKED_BUCKETS: usize = 65_536;
+const BUCKET_PRUNE_INTERVAL: Duration = Duration::from_secs(1);
+
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+// Keeps the keys of authorized clients apart from source IPs
+const AUTHORIZATION_KEY_PREFIX: &str = "authorization:";
+
+/// What identifies a client for rate limiting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcRateLimitKey {
+    /// The source IP, as appended to `X-Forwarded-For` by the outermost trusted proxy, or the
+    /// TCP peer address when no proxies are trusted
+    SourceIp,
+    /// The value of the `Authorization` header if it is one of the configured tokens, and the
+    /// source IP otherwise
+    Authorization,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcRateLimit {
+    pub requests_per_second: u64,
+    pub burst: u64,
+}
+
+/// Parses `REQUESTS_PER_SECOND[:BURST]`, the burst defaulting to one second of requests
+impl FromStr for RpcRateLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |value: &str| {
+            value
+                .parse::<u64>()
+                .map_err(|err| format!("Invalid rate limit {s}: {err}"))
+        };
+        let (requests_per_second, burst) = match s.split_once(':') {
+            Some((requests_per_second, burst)) => (parse(requests_per_second)?, parse(burst)?),
+            None => {
+                let requests_per_second = parse(s)?;
+                (requests_per_second, requests_per_second)
+            }
+        };
+        Ok(Self {
+            requests_per_second,
+            burst,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcRateLimitConfig {
+    pub key: RpcRateLimitKey,
+    /// Number of reverse proxies in front of the RPC ports that append the address they
+    /// received a request from to `X-Forwarded-For`
+    pub trusted_proxies: usize,
+    /// `Authorization` header values that identify a client with `RpcRateLimitKey::Authorization`
+    pub authorization_tokens: HashSet<String>,
+    pub reads: Option<RpcRateLimit>,
+    pub scans: Option<RpcRateLimit>,
+    pub sends: Option<RpcRateLimit>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RpcMethodClass {
+    Read,
+    Scan,
+    Send,
+}
+
+impl RpcMethodClass {
+    pub fn from_method(method: &str) -> Self {
+        match method {
+            "getProgramAccounts"
+            | "getProgramAccountsPaginated"
+            | "getTokenAccountsByDelegate"
+            | "getTokenAccountsByOwner"
+            | "getTokenLargestAccounts"
+            | "getLargestAccounts"
+            | "getSupply"
+            | "simulateTransaction"
+            | "simulateBundle" => Self::Scan,
+            "sendTransaction" | "requestAirdrop" => Self::Send,
+            _ => Self::Read,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "reads",
+            Self::Scan => "scans",
+            Self::Send => "sends",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &RpcRateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, limit: &RpcRateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * limit.requests_per_second as f64).min(limit.burst as f64);
+        self.updated = now;
+    }
+
+    /// Takes a token, or returns how long until the next one is available
+    fn take(&mut self, limit: &RpcRateLimit, now: Instant) -> Result<(), Duration> {
+        self.refill(limit, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else if limit.requests_per_second == 0 {
+            Err(Duration::MAX)
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / limit.requests_per_second as f64,
+            ))
+        }
+    }
+
+    fn is_full(&self, limit: &RpcRateLimit) -> bool {
+        self.tokens >= limit.burst as f64
+    }
+}
+
+#[derive(Debug)]
+struct Buckets {
+    buckets: HashMap<(Arc<str>, RpcMethodClass), TokenBucket>,
+    last_prune: Instant,
+}
+
+#[derive(Debug)]
+pub struct RpcRateLimiter {
+    config: RpcRateLimitConfig,
+    buckets: Mutex<Buckets>,
+}
+
+impl RpcRateLimiter {
+    pub fn new(config: RpcRateLimitConfig) -> Result<Self, String> {
+        // The JSON RPC server doesn't expose peer addresses, so only a trusted proxy can tell
+        // its clients apart by source IP. Clients without a configured token are told apart by
+        // source IP too.
+        if config.trusted_proxies == 0 {
+            return Err(
+                "Rate limiting RPC clients requires a trusted proxy that appends to \
+                 X-Forwarded-For"
+                    .to_string(),
+            );
+        }
+        if config.key == RpcRateLimitKey::Authorization && config.authorization_tokens.is_empty() {
+            return Err(
+                "Rate limiting RPC clients by Authorization header requires the accepted tokens"
+                    .to_string(),
+            );
+        }
+        Ok(Self {
+            config,
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                last_prune: Instant::now(),
+            }),
+        })
+    }
+
+    fn limit(&self, class: RpcMethodClass) -> Option<&RpcRateLimit> {
+        match class {
+            RpcMethodClass::Read => self.config.reads.as_ref(),
+            RpcMethodClass::Scan => self.config.scans.as_ref(),
+            RpcMethodClass::Send => self.config.sends.as_ref(),
+        }
+    }
+
+    /// Returns the key identifying the client that sent a JSON RPC request with these headers,
+    /// or None if the client can't be identified
+    pub fn client_key(&self, headers: &HeaderMap) -> Option<Arc<str>> {
+        self.client_key_from(
+            headers
+                .get_all(FORWARDED_FOR_HEADER)
+                .iter()
+                .filter_map(|value| value.to_str().ok()),
+            headers
+                .get(AUTHORIZATION)
+                .and_then(|value| value.to_str().ok()),
+            None,
+        )
+    }
+
+    /// Returns the key identifying the client that sent a gRPC request with this metadata from
+    /// `peer_addr`, or None if the client can't be identified
+    pub fn grpc_client_key(
+        &self,
+        metadata: &MetadataMap,
+        peer_addr: Option<SocketAddr>,
+    ) -> Option<Arc<str>> {
+        self.client_key_from(
+            metadata
+                .get_all(FORWARDED_FOR_HEADER)
+                .iter()
+                .filter_map(|value| value.to_str().ok()),
+            metadata
+                .get(AUTHORIZATION.as_str())
+                .and_then(|value| value.to_str().ok()),
+            peer_addr,
+        )
+    }
+
+    fn client_key_from<'a>(
+        &self,
+        forwarded_for: impl Iterator<Item = &'a str>,
+        authorization: Option<&str>,
+        peer_addr: Option<SocketAddr>,
+    ) -> Option<Arc<str>> {
+        // Any other Authorization header value is client supplied, and could be varied to get
+        // fresh buckets
+        let authorization = authorization.filter(|authorization| {
+            self.config.key == RpcRateLimitKey::Authorization
+                && self.config.authorization_tokens.contains(*authorization)
+        });
+        if let Some(authorization) = authorization {
+            return Some(Arc::from(format!(
+                "{AUTHORIZATION_KEY_PREFIX}{authorization}"
+            )));
+        }
+        if self.config.trusted_proxies == 0 {
+            return peer_addr.map(|peer_addr| Arc::from(peer_addr.ip().to_string()));
+        }
+        // Each trusted proxy appends the address it received the request from, so entries to
+        // the left of the outermost one's are client supplied
+        let forwarded_for = forwarded_for
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect::<Vec<_>>();
+        forwarded_for
+            .len()
+            .checked_sub(self.config.trusted_proxies)
+            .map(|index| forwarded_for[index])
+            .filter(|source_ip| !source_ip.is_empty())
+            .map(Arc::from)
+    }
+
+    /// Takes a token from the client's bucket for the method's class, or returns how long the
+    /// client should wait before retrying
+    pub fn check(&self, client_key: &Arc<str>, method: &str) -> Result<(), Duration> {
+        self.check_at(client_key, method, Instant::now())
+    }
+
+    fn check_at(&self, client_key: &Arc<str>, method: &str, now: Instant) -> Result<(), Duration> {
+        let class = RpcMethodClass::from_method(method);
+        let limit = match self.limit(class) {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.buckets.len() >= MAX_TRACKED_BUCKETS
+            && now.saturating_duration_since(buckets.last_prune) >= BUCKET_PRUNE_INTERVAL
+        {
+            self.prune(&mut buckets, now);
+        }
+        let result = buckets
+            .buckets
+            .entry((client_key.clone(), class))
+            .or_insert_with(|| TokenBucket::new(limit, now))
+            .take(limit, now);
+        drop(buckets);
+
+        if result.is_err() {
+            match class {
+                RpcMethodClass::Read => inc_new_counter_info!("rpc-rate-limited-reads", 1),
+                RpcMethodClass::Scan => inc_new_counter_info!("rpc-rate-limited-scans", 1),
+                RpcMethodClass::Send => inc_new_counter_info!("rpc-rate-limited-sends", 1),
+            }
+        }
+        result
+    }
+
+    // Full buckets are indistinguishable from new ones, so they can be dropped
+    fn prune(&self, buckets: &mut Buckets, now: Instant) {
+        let num_buckets = buckets.buckets.len();
+        buckets.buckets.retain(|(_, class), bucket| {
+            self.limit(*class).map_or(false, |limit| {
+                bucket.refill(limit, now);
+                !bucket.is_full(limit)
+            })
+        });
+        buckets.last_prune = now;
+        datapoint_info!(
+            "rpc-rate-limiter",
+            ("tracked_buckets", buckets.buckets.len(), i64),
+            ("pruned_buckets", num_buckets - buckets.buckets.len(), i64),
+        );
+    }
+}
+
+/// Rejects JSON RPC calls from clients that are over their limit
+#[derive(Default)]
+pub struct RpcRateLimitMiddleware {
+    rate_limiter: Option<Arc<RpcRateLimiter>>,
+}
+
+impl RpcRateLimitMiddleware {
+    pub fn new(rate_limiter: Option<Arc<RpcRateLimiter>>) -> Self {
+        Self { rate_limiter }
+    }
+}
+
+impl Middleware<JsonRpcRequestProcessor> for RpcRateLimitMiddleware {
+    type Future = BoxFuture<Option<Response>>;
+    type CallFuture = BoxFuture<Option<Output>>;
+
+    fn on_call<F, X>(
+        &self,
+        call: Call,
+        meta: JsonRpcRequestProcessor,
+        next: F,
+    ) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, JsonRpcRequestProcessor) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let method = match &call {
+            Call::MethodCall(method_call) => &method_call.method,
+            Call::Notification(notification) => &notification.method,
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+        let rate_limiter = match &self.rate_limiter {
+            Some(rate_limiter) => rate_limiter,
+            None => return Either::Right(next(call, meta)),
+        };
+
+        let checked = meta
+            .rate_limit_key
+            .as_ref()
+            .map(|client_key| rate_limiter.check(client_key, method));
+        let error = match checked {
+            Some(Ok(())) => return Either::Right(next(call, meta)),
+            Some(Err(retry_after)) => RpcCustomError::RateLimited {
+                class: RpcMethodClass::from_method(method).as_str().to_string(),
+                retry_after_ms: u64::try_from(retry_after.as_millis()).unwrap_or(u64::MAX),
+            }
+            .into(),
+            None => unidentified_client_error(),
+        };
+        let output = match call {
+            Call::MethodCall(method_call) => Some(Output::from(
+                Err(error),
+                method_call.id,
+                method_call.jsonrpc,
+            )),
+            _ => None,
+        };
+        Either::Left(Box::pin(future::ready(output)))
+    }
+}
+
+fn unidentified_client_error() -> Error {
+    Error {
+        code: ErrorCode::InvalidRequest,
+        message: format!("Missing {FORWARDED_FOR_HEADER} entry from a trusted proxy"),
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, jsonrpc_http_server::hyper::header::HeaderValue};
+
+    fn limiter(key: RpcRateLimitKey, trusted_proxies: usize) -> Result<RpcRateLimiter, String> {
+        RpcRateLimiter::new(RpcRateLimitConfig {
+            key,
+            trusted_proxies,
+            authorization_tokens: HashSet::from(["Bearer key".to_string()]),
+            reads: None,
+            scans: Some(RpcRateLimit {
+                requests_per_second: 2,
+                burst: 2,
+            }),
+            sends: Some(RpcRateLimit {
+                requests_per_second: 0,
+                burst: 0,
+            }),
+        })
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        assert_eq!(
+            "10".parse::<RpcRateLimit>(),
+            Ok(RpcRateLimit {
+                requests_per_second: 10,
+                burst: 10,
+            })
+        );
+        assert_eq!(
+            "10:50".parse::<RpcRateLimit>(),
+            Ok(RpcRateLimit {
+                requests_per_second: 10,
+                burst: 50,
+            })
+        );
+        assert!("10:".parse::<RpcRateLimit>().is_err());
+        assert!("fast".parse::<RpcRateLimit>().is_err());
+    }
+
+    #[test]
+    fn test_method_class() {
+        assert_eq!(
+            RpcMethodClass::from_method("getAccountInfo"),
+            RpcMethodClass::Read
+        );
+        assert_eq!(
+            RpcMethodClass::from_method("getProgramAccounts"),
+            RpcMethodClass::Scan
+        );
+        assert_eq!(
+            RpcMethodClass::from_method("simulateTransaction"),
+            RpcMethodClass::Scan
+        );
+        assert_eq!(
+            RpcMethodClass::from_method("sendTransaction"),
+            RpcMethodClass::Send
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let rate_limiter = limiter(RpcRateLimitKey::SourceIp, 1).unwrap();
+        let alice: Arc<str> = Arc::from("10.0.0.1");
+        let bob: Arc<str> = Arc::from("10.0.0.2");
+        let now = Instant::now();
+
+        // Unlimited class
+        for _ in 0..10 {
+            assert!(rate_limiter.check_at(&alice, "getBalance", now).is_ok());
+        }
+
+        // Burst, then wait for the bucket to refill
+        assert!(rate_limiter
+            .check_at(&alice, "getProgramAccounts", now)
+            .is_ok());
+        assert!(rate_limiter
+            .check_at(&alice, "getProgramAccounts", now)
+            .is_ok());
+        assert_eq!(
+            rate_limiter.check_at(&alice, "getProgramAccounts", now),
+            Err(Duration::from_millis(500))
+        );
+        assert!(rate_limiter
+            .check_at(&bob, "getProgramAccounts", now)
+            .is_ok());
+        let later = now + Duration::from_millis(500);
+        assert!(rate_limiter
+            .check_at(&alice, "getProgramAccounts", later)
+            .is_ok());
+        assert!(rate_limiter
+            .check_at(&alice, "getProgramAccounts", later)
+            .is_err());
+
+        // A zero limit never refills
+        assert_eq!(
+            rate_limiter.check_at(&alice, "sendTransaction", later),
+            Err(Duration::MAX)
+        );
+    }
+
+    #[test]
+    fn test_client_key() {
+        assert!(limiter(RpcRateLimitKey::SourceIp, 0).is_err());
+        assert!(limiter(RpcRateLimitKey::Authorization, 0).is_err());
+        let mut config = limiter(RpcRateLimitKey::Authorization, 1).unwrap().config;
+        config.authorization_tokens.clear();
+        assert!(RpcRateLimiter::new(config).is_err());
+
+        let mut headers = HeaderMap::new();
+        let rate_limiter = limiter(RpcRateLimitKey::SourceIp, 1).unwrap();
+        assert_eq!(rate_limiter.client_key(&headers), None);
+        headers.insert(
+            FORWARDED_FOR_HEADER,
+            HeaderValue::from_static("10.0.0.1, 192.168.0.1"),
+        );
+        assert_eq!(
+            rate_limiter.client_key(&headers).as_deref(),
+            Some("192.168.0.1")
+        );
+        headers.append(FORWARDED_FOR_HEADER, HeaderValue::from_static("10.0.0.2"));
+        assert_eq!(
+            rate_limiter.client_key(&headers).as_deref(),
+            Some("10.0.0.2")
+        );
+
+        // The entry appended by the outermost of two proxies
+        let rate_limiter = limiter(RpcRateLimitKey::SourceIp, 2).unwrap();
+        assert_eq!(
+            rate_limiter.client_key(&headers).as_deref(),
+            Some("192.168.0.1")
+        );
+        let rate_limiter = limiter(RpcRateLimitKey::SourceIp, 4).unwrap();
+        assert_eq!(rate_limiter.client_key(&headers), None);
+
+        // Clients without an accepted token are told apart by source IP
+        let rate_limiter = limiter(RpcRateLimitKey::Authorization, 1).unwrap();
+        assert_eq!(
+            rate_limiter.client_key(&headers).as_deref(),
+            Some("10.0.0.2")
+        );
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer other"));
+        assert_eq!(
+            rate_limiter.client_key(&headers).as_deref(),
+            Some("10.0.0.2")
+        );
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer key"));
+        assert_eq!(
+            rate_limiter.client_key(&headers).as_deref(),
+            Some("authorization:Bearer key")
+        );
+
+        // Tokens are ignored when limiting by source IP
+        let rate_limiter = limiter(RpcRateLimitKey::SourceIp, 1).unwrap();
+        assert_eq!(
+            rate_limiter.client_key(&headers).as_deref(),
+            Some("10.0.0.2")
+        );
+    }
+
+    #[test]
+    fn test_grpc_client_key() {
+        let peer_addr = "10.0.0.1:8000".parse().ok();
+        let mut metadata = MetadataMap::new();
+        // Not constructible through new, since JSON RPC clients couldn't be identified
+        let mut rate_limiter = limiter(RpcRateLimitKey::SourceIp, 1).unwrap();
+        rate_limiter.config.trusted_proxies = 0;
+        assert_eq!(
+            rate_limiter
+                .grpc_client_key(&metadata, peer_addr)
+                .as_deref(),
+            Some("10.0.0.1")
+        );
+        assert_eq!(rate_limiter.grpc_client_key(&metadata, None), None);
+
+        metadata.insert(FORWARDED_FOR_HEADER, "10.0.0.2, 10.0.0.3".parse().unwrap());
+        assert_eq!(
+            rate_limiter
+                .grpc_client_key(&metadata, peer_addr)
+                .as_deref(),
+            Some("10.0.0.1")
+        );
+        let rate_limiter = limiter(RpcRateLimitKey::SourceIp, 1).unwrap();
+        assert_eq!(
+            rate_limiter
+                .grpc_client_key(&metadata, peer_addr)
+                .as_deref(),
+            Some("10.0.0.3")
+        );
+    }
+}
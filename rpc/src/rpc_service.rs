@@ -10,7 +10,10 @@ use {
             rpc_deprecated_v1_9::*, rpc_full::*, rpc_minimal::*, rpc_obsolete_v1_7::*, *,
         },
         rpc_cache::LargestAccountsCache,
+        rpc_grpc_service::RpcGrpcService,
         rpc_health::*,
+        rpc_rate_limiter::{RpcRateLimitMiddleware, RpcRateLimiter},
+        rpc_subscription_tracker::SubscriptionControl,
     },
     crossbeam_channel::unbounded,
     jsonrpc_core::{futures::prelude::*, MetaIoHandler},
@@ -29,6 +32,7 @@ use {
     solana_metrics::inc_new_counter_info,
     solana_perf::thread::renice_this_thread,
     solana_poh::poh_recorder::PohRecorder,
+    solana_rpc_client_api::request::MAX_MULTIPLE_ACCOUNTS,
     solana_runtime::{
         bank_forks::BankForks, commitment::BlockCommitmentCache,
         prioritization_fee_cache::PrioritizationFeeCache,
@@ -65,6 +69,7 @@ pub struct JsonRpcService {
     pub request_processor: JsonRpcRequestProcessor, // Used only by test_rpc_new()...
 
     close_handle: Option<CloseHandle>,
+    grpc_service: Option<RpcGrpcService>,
 }
 
 struct RpcRequestMiddleware {
@@ -359,6 +364,7 @@ impl JsonRpcService {
         connection_cache: Arc<ConnectionCache>,
         current_transaction_status_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+        subscription_control: Option<SubscriptionControl>,
     ) -> Result<Self, String> {
         info!("rpc bound to {:?}", rpc_addr);
         info!("rpc configuration: {:?}", config);
@@ -451,6 +457,16 @@ impl JsonRpcService {
         let max_request_body_size = config
             .max_request_body_size
             .unwrap_or(MAX_REQUEST_BODY_SIZE);
+        let grpc_config = config.grpc_config.clone();
+        let rate_limiter = config
+            .rate_limit_config
+            .clone()
+            .map(RpcRateLimiter::new)
+            .transpose()?
+            .map(Arc::new);
+        let max_multiple_accounts = config
+            .max_multiple_accounts
+            .unwrap_or(MAX_MULTIPLE_ACCOUNTS);
         let (request_processor, receiver) = JsonRpcRequestProcessor::new(
             config,
             snapshot_config.clone(),
@@ -484,6 +500,17 @@ impl JsonRpcService {
         #[cfg(test)]
         let test_request_processor = request_processor.clone();
 
+        let grpc_service = grpc_config.map(|grpc_config| {
+            RpcGrpcService::new(
+                grpc_config,
+                request_processor.clone(),
+                max_multiple_accounts,
+                rate_limiter.clone(),
+                subscription_control,
+                &validator_exit,
+            )
+        });
+
         let ledger_path = ledger_path.to_path_buf();
 
         let (close_handle_sender, close_handle_receiver) = unbounded();
@@ -492,7 +519,9 @@ impl JsonRpcService {
             .spawn(move || {
                 renice_this_thread(rpc_niceness_adj).unwrap();
 
-                let mut io = MetaIoHandler::default();
+                let mut io = MetaIoHandler::with_middleware(RpcRateLimitMiddleware::new(
+                    rate_limiter.clone(),
+                ));
 
                 io.extend_with(rpc_minimal::MinimalImpl.to_delegate());
                 if full_api {
@@ -515,7 +544,13 @@ impl JsonRpcService {
                 );
                 let server = ServerBuilder::with_meta_extractor(
                     io,
-                    move |_req: &hyper::Request<hyper::Body>| request_processor.clone(),
+                    move |req: &hyper::Request<hyper::Body>| {
+                        let mut request_processor = request_processor.clone();
+                        request_processor.rate_limit_key = rate_limiter
+                            .as_ref()
+                            .and_then(|rate_limiter| rate_limiter.client_key(req.headers()));
+                        request_processor
+                    },
                 )
                 .event_loop_executor(runtime.handle().clone())
                 .threads(1)
@@ -556,6 +591,7 @@ impl JsonRpcService {
             #[cfg(test)]
             request_processor: test_request_processor,
             close_handle: Some(close_handle),
+            grpc_service,
         })
     }
 
@@ -566,6 +602,9 @@ impl JsonRpcService {
     }
 
     pub fn join(self) -> thread::Result<()> {
+        if let Some(grpc_service) = self.grpc_service {
+            grpc_service.join()?;
+        }
         self.thread_hdl.join()
     }
 }
@@ -645,6 +684,7 @@ mod tests {
             connection_cache,
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            None,
         )
         .expect("assume successful JsonRpcService start");
         let thread = rpc_service.thread_hdl.thread();
@@ -1,5 +1,7 @@
 use {
-    crate::rpc_subscriptions::{NotificationEntry, RpcNotification, TimestampedNotificationEntry},
+    crate::rpc_subscriptions::{
+        NotificationEntry, ReplayBuffer, RpcNotification, TimestampedNotificationEntry,
+    },
     dashmap::{mapref::entry::Entry as DashEntry, DashMap},
     solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig},
     solana_metrics::{CounterToken, TokenCounter},
@@ -10,14 +12,17 @@ use {
     },
     solana_sdk::{
         clock::Slot, commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+        transaction::VersionedTransaction,
+    },
+    solana_transaction_status::{
+        TransactionDetails, UiTransactionEncoding, VersionedTransactionWithStatusMeta,
     },
-    solana_transaction_status::{TransactionDetails, UiTransactionEncoding},
     std::{
         collections::hash_map::{Entry, HashMap},
         fmt,
         sync::{
             atomic::{AtomicU64, Ordering},
-            Arc, RwLock, Weak,
+            Arc, Mutex, RwLock, Weak,
         },
     },
     thiserror::Error,
@@ -49,6 +54,7 @@ pub enum SubscriptionParams {
     Slot,
     SlotsUpdates,
     Root,
+    Transaction(TransactionSubscriptionParams),
     Vote,
 }
 
@@ -63,6 +69,7 @@ impl SubscriptionParams {
             SubscriptionParams::SlotsUpdates => "slotsUpdatesNotification",
             SubscriptionParams::Block(_) => "blockNotification",
             SubscriptionParams::Root => "rootNotification",
+            SubscriptionParams::Transaction(_) => "transactionNotification",
             SubscriptionParams::Vote => "voteNotification",
         }
     }
@@ -74,6 +81,7 @@ impl SubscriptionParams {
             SubscriptionParams::Program(params) => Some(params.commitment),
             SubscriptionParams::Signature(params) => Some(params.commitment),
             SubscriptionParams::Block(params) => Some(params.commitment),
+            SubscriptionParams::Transaction(params) => Some(params.commitment),
             SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
             | SubscriptionParams::Root
@@ -81,6 +89,17 @@ impl SubscriptionParams {
         }
     }
 
+    /// Whether notifications of this subscription are retained for `fromSlot` replay
+    pub fn is_replayable(&self) -> bool {
+        matches!(
+            self,
+            SubscriptionParams::Account(_)
+                | SubscriptionParams::Logs(_)
+                | SubscriptionParams::Program(_)
+                | SubscriptionParams::SlotsUpdates
+        )
+    }
+
     fn is_commitment_watcher(&self) -> bool {
         let commitment = match self {
             SubscriptionParams::Account(params) => &params.commitment,
@@ -88,6 +107,7 @@ impl SubscriptionParams {
             SubscriptionParams::Logs(params) => &params.commitment,
             SubscriptionParams::Program(params) => &params.commitment,
             SubscriptionParams::Signature(params) => &params.commitment,
+            SubscriptionParams::Transaction(params) => &params.commitment,
             SubscriptionParams::Root
             | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
@@ -103,6 +123,7 @@ impl SubscriptionParams {
             SubscriptionParams::Logs(params) => &params.commitment,
             SubscriptionParams::Program(params) => &params.commitment,
             SubscriptionParams::Signature(params) => &params.commitment,
+            SubscriptionParams::Transaction(params) => &params.commitment,
             SubscriptionParams::Root
             | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
@@ -144,6 +165,57 @@ pub struct BlockSubscriptionParams {
 pub enum BlockSubscriptionKind {
     All,
     MentionsAccountOrProgram(Pubkey),
+    Transactions(TransactionSubscriptionFilter),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransactionSubscriptionParams {
+    pub commitment: CommitmentConfig,
+    pub encoding: UiTransactionEncoding,
+    pub filter: TransactionSubscriptionFilter,
+    pub max_supported_transaction_version: Option<u8>,
+}
+
+/// Account lists are kept sorted and deduplicated so that equivalent filters share a
+/// subscription
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TransactionSubscriptionFilter {
+    pub vote: Option<bool>,
+    pub failed: Option<bool>,
+    pub account_include: Vec<Pubkey>,
+    pub account_exclude: Vec<Pubkey>,
+    pub account_required: Vec<Pubkey>,
+}
+
+impl TransactionSubscriptionFilter {
+    pub fn matches(&self, transaction: &VersionedTransactionWithStatusMeta) -> bool {
+        if let Some(vote) = self.vote {
+            if is_simple_vote_transaction(&transaction.transaction) != vote {
+                return false;
+            }
+        }
+        if let Some(failed) = self.failed {
+            if transaction.meta.status.is_err() != failed {
+                return false;
+            }
+        }
+        let account_keys = transaction.account_keys();
+        let mentions = |pubkey: &Pubkey| account_keys.iter().any(|key| key == pubkey);
+        (self.account_include.is_empty() || self.account_include.iter().any(mentions))
+            && !self.account_exclude.iter().any(mentions)
+            && self.account_required.iter().all(mentions)
+    }
+}
+
+/// Matches the classification `SanitizedTransaction` uses: the first instruction invokes the
+/// vote program
+fn is_simple_vote_transaction(transaction: &VersionedTransaction) -> bool {
+    let message = &transaction.message;
+    message.instructions().first().and_then(|instruction| {
+        message
+            .static_account_keys()
+            .get(usize::from(instruction.program_id_index))
+    }) == Some(&solana_vote_program::id())
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -186,6 +258,7 @@ struct SubscriptionControlInner {
     max_active_subscriptions: usize,
     sender: crossbeam_channel::Sender<TimestampedNotificationEntry>,
     broadcast_sender: broadcast::Sender<RpcNotification>,
+    replay_buffer: Option<Arc<Mutex<ReplayBuffer>>>,
     counter: TokenCounter,
 }
 
@@ -194,6 +267,7 @@ impl SubscriptionControl {
         max_active_subscriptions: usize,
         sender: crossbeam_channel::Sender<TimestampedNotificationEntry>,
         broadcast_sender: broadcast::Sender<RpcNotification>,
+        replay_buffer: Option<Arc<Mutex<ReplayBuffer>>>,
     ) -> Self {
         Self(Arc::new(SubscriptionControlInner {
             subscriptions: DashMap::new(),
@@ -201,6 +275,7 @@ impl SubscriptionControl {
             max_active_subscriptions,
             sender,
             broadcast_sender,
+            replay_buffer,
             counter: TokenCounter::new("rpc_pubsub_total_subscriptions"),
         }))
     }
@@ -209,7 +284,42 @@ impl SubscriptionControl {
         self.0.broadcast_sender.subscribe()
     }
 
+    pub fn is_replay_enabled(&self) -> bool {
+        self.0.replay_buffer.is_some()
+    }
+
     pub fn subscribe(&self, params: SubscriptionParams) -> Result<SubscriptionToken, Error> {
+        self.subscribe_inner(params).map(|(token, _created)| token)
+    }
+
+    /// Subscribes to an already active subscription and returns its retained notifications
+    /// for `from_slot` and later slots. The subscription must have been kept alive since
+    /// `from_slot`, otherwise notifications may have been missed and an error is returned.
+    pub fn subscribe_from_slot(
+        &self,
+        params: SubscriptionParams,
+        from_slot: Slot,
+    ) -> Result<(SubscriptionToken, Vec<Arc<String>>), Error> {
+        let replay_buffer = match &self.0.replay_buffer {
+            Some(replay_buffer) if params.is_replayable() => replay_buffer,
+            _ => return Err(Error::ReplayNotSupported),
+        };
+        let (token, created) = self.subscribe_inner(params)?;
+        if created {
+            return Err(Error::ReplaySlotNotAvailable(from_slot));
+        }
+        let notifications = replay_buffer
+            .lock()
+            .unwrap()
+            .replay(token.id(), from_slot)
+            .ok_or(Error::ReplaySlotNotAvailable(from_slot))?;
+        Ok((token, notifications))
+    }
+
+    fn subscribe_inner(
+        &self,
+        params: SubscriptionParams,
+    ) -> Result<(SubscriptionToken, bool), Error> {
         debug!(
             "Total existing subscriptions: {}",
             self.0.subscriptions.len()
@@ -230,7 +340,10 @@ impl SubscriptionControl {
 
         match self.0.subscriptions.entry(params) {
             DashEntry::Occupied(mut entry) => match entry.get().0.upgrade() {
-                Some(token_ref) => Ok(SubscriptionToken(token_ref, self.0.counter.create_token())),
+                Some(token_ref) => Ok((
+                    SubscriptionToken(token_ref, self.0.counter.create_token()),
+                    false,
+                )),
                 // This means the last Arc for this Weak pointer entered the drop just before us,
                 // but could not remove the entry since we are holding the write lock.
                 // See `Drop` implementation for `SubscriptionTokenInner` for further info.
@@ -238,7 +351,7 @@ impl SubscriptionControl {
                     let (token, weak_ref) =
                         create_token_and_weak_ref(entry.get().1, entry.key().clone());
                     entry.insert(weak_ref);
-                    Ok(token)
+                    Ok((token, true))
                 }
             },
             DashEntry::Vacant(entry) => {
@@ -257,7 +370,7 @@ impl SubscriptionControl {
                     "rpc-subscription",
                     ("total", self.0.subscriptions.len(), i64)
                 );
-                Ok(token)
+                Ok((token, true))
             }
         }
     }
@@ -345,6 +458,10 @@ impl SubscriptionInfo {
 pub enum Error {
     #[error("node subscription limit reached")]
     TooManySubscriptions,
+    #[error("replay is not supported for this subscription")]
+    ReplayNotSupported,
+    #[error("notifications from slot {0} are no longer available")]
+    ReplaySlotNotAvailable(Slot),
 }
 
 struct LogsSubscriptionsIndex {
@@ -595,6 +712,13 @@ mod tests {
         crate::rpc_pubsub_service::PubSubConfig,
         solana_ledger::genesis_utils::{create_genesis_config, GenesisConfigInfo},
         solana_runtime::bank::Bank,
+        solana_sdk::{
+            instruction::{Instruction, InstructionError},
+            message::Message,
+            system_program,
+            transaction::{Transaction, TransactionError},
+        },
+        solana_transaction_status::TransactionStatusMeta,
         std::str::FromStr,
     };
 
@@ -612,6 +736,7 @@ mod tests {
                 PubSubConfig::default().max_active_subscriptions,
                 sender,
                 broadcast_sender,
+                None,
             );
             Self { control, receiver }
         }
@@ -786,4 +911,77 @@ mod tests {
         tracker.unsubscribe(signature_params, 3.into());
         assert_eq!(counts(&tracker), (0, 0, 0, 0));
     }
+
+    #[test]
+    fn transaction_filter_matches() {
+        let payer = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let transaction = |program_id: Pubkey, failed: bool| {
+            let message = Message::new(
+                &[Instruction::new_with_bytes(program_id, &[], vec![])],
+                Some(&payer),
+            );
+            VersionedTransactionWithStatusMeta {
+                transaction: Transaction::new_unsigned(message).into(),
+                meta: TransactionStatusMeta {
+                    status: if failed {
+                        Err(TransactionError::InstructionError(
+                            0,
+                            InstructionError::InvalidArgument,
+                        ))
+                    } else {
+                        Ok(())
+                    },
+                    ..TransactionStatusMeta::default()
+                },
+            }
+        };
+        let transfer = transaction(system_program::id(), false);
+        let failed_transfer = transaction(system_program::id(), true);
+        let vote = transaction(solana_vote_program::id(), false);
+
+        let filter = TransactionSubscriptionFilter::default();
+        assert!(filter.matches(&transfer));
+        assert!(filter.matches(&failed_transfer));
+        assert!(filter.matches(&vote));
+
+        let filter = TransactionSubscriptionFilter {
+            vote: Some(false),
+            ..TransactionSubscriptionFilter::default()
+        };
+        assert!(filter.matches(&transfer));
+        assert!(!filter.matches(&vote));
+
+        let filter = TransactionSubscriptionFilter {
+            failed: Some(true),
+            ..TransactionSubscriptionFilter::default()
+        };
+        assert!(!filter.matches(&transfer));
+        assert!(filter.matches(&failed_transfer));
+
+        let filter = TransactionSubscriptionFilter {
+            account_include: vec![account, system_program::id()],
+            ..TransactionSubscriptionFilter::default()
+        };
+        assert!(filter.matches(&transfer));
+        assert!(!filter.matches(&vote));
+
+        let filter = TransactionSubscriptionFilter {
+            account_exclude: vec![solana_vote_program::id()],
+            ..TransactionSubscriptionFilter::default()
+        };
+        assert!(filter.matches(&transfer));
+        assert!(!filter.matches(&vote));
+
+        let filter = TransactionSubscriptionFilter {
+            account_required: vec![payer, account],
+            ..TransactionSubscriptionFilter::default()
+        };
+        assert!(!filter.matches(&transfer));
+        let filter = TransactionSubscriptionFilter {
+            account_required: vec![payer, system_program::id()],
+            ..TransactionSubscriptionFilter::default()
+        };
+        assert!(filter.matches(&transfer));
+    }
 }
@@ -9,7 +9,7 @@ use {
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
             LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
             SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionInfo,
-            SubscriptionParams, SubscriptionsTracker,
+            SubscriptionParams, SubscriptionsTracker, TransactionSubscriptionParams,
         },
     },
     crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender},
@@ -23,7 +23,7 @@ use {
     solana_rpc_client_api::response::{
         ProcessedSignatureResult, ReceivedSignatureResult, Response as RpcResponse, RpcBlockUpdate,
         RpcBlockUpdateError, RpcKeyedAccount, RpcLogsResponse, RpcResponseContext,
-        RpcSignatureResult, RpcVote, SlotInfo, SlotUpdate,
+        RpcSignatureResult, RpcTransactionUpdate, RpcVote, SlotInfo, SlotUpdate,
     },
     solana_runtime::{
         bank::{Bank, TransactionLogInfo},
@@ -40,7 +40,8 @@ use {
         transaction,
     },
     solana_transaction_status::{
-        BlockEncodingOptions, ConfirmedBlock, EncodeError, VersionedConfirmedBlock,
+        BlockEncodingOptions, ConfirmedBlock, EncodeError, TransactionWithStatusMeta,
+        VersionedConfirmedBlock,
     },
     std::{
         cell::RefCell,
@@ -145,7 +146,7 @@ where
     B: Fn(&Bank, &P) -> X,
     F: Fn(X, &P, Slot, Arc<Bank>) -> (I, Slot),
     X: Clone + Default,
-    I: IntoIterator<Item = S>,
+    I: IntoIterator<Item = (S, Option<RpcNotificationValue>)>,
 {
     let mut notified = false;
     let bank = bank_forks.read().unwrap().get(slot);
@@ -154,14 +155,16 @@ where
         let mut w_last_notified_slot = subscription.last_notified_slot.write().unwrap();
         let (filter_results, result_slot) =
             filter_results(results, params, *w_last_notified_slot, bank);
-        for result in filter_results {
-            notifier.notify(
+        for (result, typed_result) in filter_results {
+            notifier.notify_at_slot(
                 RpcResponse::from(RpcNotificationResponse {
                     context: RpcNotificationContext { slot },
                     value: result,
                 }),
+                typed_result,
                 subscription,
                 is_final,
+                Some(slot),
             );
             *w_last_notified_slot = result_slot;
             notified = true;
@@ -176,9 +179,33 @@ pub struct RpcNotification {
     pub subscription_id: SubscriptionId,
     pub is_final: bool,
     pub json: Weak<String>,
+    /// The notified value of slot and account subscriptions, for transports that don't use the
+    /// JSON. Lives as long as `json`.
+    pub value: Option<Weak<RpcNotificationValue>>,
     pub created_at: Instant,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcNotificationValue {
+    Slot(SlotInfo),
+    Account {
+        slot: Slot,
+        pubkey: Pubkey,
+        account: AccountSharedData,
+    },
+}
+
+impl RpcNotificationValue {
+    /// Approximate number of bytes held by the value
+    fn size(&self) -> usize {
+        let data_len = match self {
+            Self::Slot(_) => 0,
+            Self::Account { account, .. } => account.data().len(),
+        };
+        std::mem::size_of::<Self>().saturating_add(data_len)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 struct RpcNotificationResponse<T> {
     context: RpcNotificationContext,
@@ -209,7 +236,7 @@ struct RpcNotificationContext {
 const RPC_NOTIFICATIONS_METRICS_SUBMISSION_INTERVAL_MS: Duration = Duration::from_millis(2_000);
 
 struct RecentItems {
-    queue: VecDeque<Arc<String>>,
+    queue: VecDeque<(Arc<String>, Option<Arc<RpcNotificationValue>>)>,
     total_bytes: usize,
     max_len: usize,
     max_total_bytes: usize,
@@ -227,18 +254,24 @@ impl RecentItems {
         }
     }
 
-    fn push(&mut self, item: Arc<String>) {
+    /// Both the JSON and the typed value of a notification count towards `max_total_bytes`
+    fn item_bytes(item: &str, value: Option<&RpcNotificationValue>) -> usize {
+        item.len()
+            .saturating_add(value.map(RpcNotificationValue::size).unwrap_or_default())
+    }
+
+    fn push(&mut self, item: Arc<String>, value: Option<Arc<RpcNotificationValue>>) {
         self.total_bytes = self
             .total_bytes
-            .checked_add(item.len())
+            .checked_add(Self::item_bytes(&item, value.as_deref()))
             .expect("total bytes overflow");
-        self.queue.push_back(item);
+        self.queue.push_back((item, value));
 
         while self.total_bytes > self.max_total_bytes || self.queue.len() > self.max_len {
-            let item = self.queue.pop_front().expect("can't be empty");
+            let (item, value) = self.queue.pop_front().expect("can't be empty");
             self.total_bytes = self
                 .total_bytes
-                .checked_sub(item.len())
+                .checked_sub(Self::item_bytes(&item, value.as_deref()))
                 .expect("total bytes underflow");
         }
 
@@ -261,9 +294,81 @@ impl RecentItems {
     }
 }
 
+struct ReplayItem {
+    subscription_id: SubscriptionId,
+    slot: Slot,
+    json: Arc<String>,
+}
+
+/// Notifications retained so that reconnecting clients can resume a subscription with `fromSlot`
+pub struct ReplayBuffer {
+    queue: VecDeque<ReplayItem>,
+    total_bytes: usize,
+    max_len: usize,
+    max_total_bytes: usize,
+    // Highest slot of all notifications evicted so far; replay can only start after it
+    evicted_slot: Option<Slot>,
+}
+
+impl ReplayBuffer {
+    pub fn new(max_len: usize, max_total_bytes: usize) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            total_bytes: 0,
+            max_len,
+            max_total_bytes,
+            evicted_slot: None,
+        }
+    }
+
+    fn push(&mut self, subscription_id: SubscriptionId, slot: Slot, json: Arc<String>) {
+        self.total_bytes = self
+            .total_bytes
+            .checked_add(json.len())
+            .expect("total bytes overflow");
+        self.queue.push_back(ReplayItem {
+            subscription_id,
+            slot,
+            json,
+        });
+
+        while self.total_bytes > self.max_total_bytes || self.queue.len() > self.max_len {
+            let item = self.queue.pop_front().expect("can't be empty");
+            self.total_bytes = self
+                .total_bytes
+                .checked_sub(item.json.len())
+                .expect("total bytes underflow");
+            self.evicted_slot = self.evicted_slot.max(Some(item.slot));
+        }
+    }
+
+    /// Returns the retained notifications of the subscription for `from_slot` and later slots,
+    /// or `None` if some of them have already been evicted
+    pub fn replay(
+        &self,
+        subscription_id: SubscriptionId,
+        from_slot: Slot,
+    ) -> Option<Vec<Arc<String>>> {
+        if self
+            .evicted_slot
+            .map_or(false, |evicted_slot| evicted_slot >= from_slot)
+        {
+            return None;
+        }
+        Some(
+            self.queue
+                .iter()
+                .filter(|item| item.subscription_id == subscription_id && item.slot >= from_slot)
+                .map(|item| Arc::clone(&item.json))
+                .collect(),
+        )
+    }
+}
+
 struct RpcNotifier {
     sender: broadcast::Sender<RpcNotification>,
     recent_items: Mutex<RecentItems>,
+    replay_buffer: Option<Arc<Mutex<ReplayBuffer>>>,
 }
 
 thread_local! {
@@ -287,6 +392,21 @@ impl RpcNotifier {
     fn notify<T>(&self, value: T, subscription: &SubscriptionInfo, is_final: bool)
     where
         T: serde::Serialize,
+    {
+        self.notify_at_slot(value, None, subscription, is_final, None)
+    }
+
+    /// Like `notify`, but also retains the notification for `fromSlot` replay if the
+    /// subscription supports it, and broadcasts `typed_value` along with the JSON
+    fn notify_at_slot<T>(
+        &self,
+        value: T,
+        typed_value: Option<RpcNotificationValue>,
+        subscription: &SubscriptionInfo,
+        is_final: bool,
+        slot: Option<Slot>,
+    ) where
+        T: serde::Serialize,
     {
         let buf_arc = RPC_NOTIFIER_BUF.with(|buf| {
             let mut buf = buf.borrow_mut();
@@ -305,9 +425,22 @@ impl RpcNotifier {
             Arc::new(String::from(buf_str))
         });
 
+        // Retain the notification before broadcasting it, so a replaying connection receives it
+        // either from the buffer or from the broadcast channel
+        if let (Some(replay_buffer), Some(slot)) = (&self.replay_buffer, slot) {
+            if subscription.params().is_replayable() {
+                replay_buffer
+                    .lock()
+                    .unwrap()
+                    .push(subscription.id(), slot, Arc::clone(&buf_arc));
+            }
+        }
+
+        let typed_value = typed_value.map(Arc::new);
         let notification = RpcNotification {
             subscription_id: subscription.id(),
             json: Arc::downgrade(&buf_arc),
+            value: typed_value.as_ref().map(Arc::downgrade),
             is_final,
             created_at: Instant::now(),
         };
@@ -318,7 +451,7 @@ impl RpcNotifier {
         inc_new_counter_info!("rpc-pubsub-messages", 1);
         inc_new_counter_info!("rpc-pubsub-bytes", buf_arc.len());
 
-        self.recent_items.lock().unwrap().push(buf_arc);
+        self.recent_items.lock().unwrap().push(buf_arc, typed_value);
     }
 }
 
@@ -334,12 +467,15 @@ fn filter_block_result_txs(
             .into_iter()
             .filter(|tx| tx.account_keys().iter().any(|key| key == &pk))
             .collect(),
+        BlockSubscriptionKind::Transactions(ref filter) => block
+            .transactions
+            .into_iter()
+            .filter(|tx| filter.matches(tx))
+            .collect(),
     };
 
-    if block.transactions.is_empty() {
-        if let BlockSubscriptionKind::MentionsAccountOrProgram(_) = params.kind {
-            return Ok(None);
-        }
+    if block.transactions.is_empty() && params.kind != BlockSubscriptionKind::All {
+        return Ok(None);
     }
 
     let block = ConfirmedBlock::from(block)
@@ -367,12 +503,43 @@ fn filter_block_result_txs(
     }))
 }
 
+fn filter_transaction_results(
+    block: VersionedConfirmedBlock,
+    slot: Slot,
+    params: &TransactionSubscriptionParams,
+) -> impl Iterator<Item = RpcTransactionUpdate> + '_ {
+    block
+        .transactions
+        .into_iter()
+        .filter(move |tx| params.filter.matches(tx))
+        .map(move |tx| {
+            let signature = tx.transaction.signatures[0].to_string();
+            let (transaction, err) = match TransactionWithStatusMeta::Complete(tx).encode(
+                params.encoding,
+                params.max_supported_transaction_version,
+                true, // show_rewards
+            ) {
+                Ok(transaction) => (Some(transaction), None),
+                Err(EncodeError::UnsupportedTransactionVersion(version)) => (
+                    None,
+                    Some(RpcBlockUpdateError::UnsupportedTransactionVersion(version)),
+                ),
+            };
+            RpcTransactionUpdate {
+                slot,
+                signature: Some(signature),
+                transaction,
+                err,
+            }
+        })
+}
+
 fn filter_account_result(
     result: Option<(AccountSharedData, Slot)>,
     params: &AccountSubscriptionParams,
     last_notified_slot: Slot,
     bank: Arc<Bank>,
-) -> (Option<UiAccount>, Slot) {
+) -> (Option<(UiAccount, Option<RpcNotificationValue>)>, Slot) {
     // If the account is not found, `last_modified_slot` will default to zero and
     // we will notify clients that the account no longer exists if we haven't already
     let (account, last_modified_slot) = result.unwrap_or_default();
@@ -380,13 +547,19 @@ fn filter_account_result(
     // If last_modified_slot < last_notified_slot this means that we last notified for a fork
     // and should notify that the account state has been reverted.
     let account = (last_modified_slot != last_notified_slot).then(|| {
-        if is_known_spl_token_id(account.owner())
+        let typed_value = RpcNotificationValue::Account {
+            slot: bank.slot(),
+            pubkey: params.pubkey,
+            account: account.clone(),
+        };
+        let ui_account = if is_known_spl_token_id(account.owner())
             && params.encoding == UiAccountEncoding::JsonParsed
         {
             get_parsed_token_account(bank, &params.pubkey, account)
         } else {
             UiAccount::encode(&params.pubkey, &account, params.encoding, None, None)
-        }
+        };
+        (ui_account, Some(typed_value))
     });
     (account, last_modified_slot)
 }
@@ -396,10 +569,18 @@ fn filter_signature_result(
     _params: &SignatureSubscriptionParams,
     last_notified_slot: Slot,
     _bank: Arc<Bank>,
-) -> (Option<RpcSignatureResult>, Slot) {
+) -> (
+    Option<(RpcSignatureResult, Option<RpcNotificationValue>)>,
+    Slot,
+) {
     (
         result.map(|result| {
-            RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err: result.err() })
+            (
+                RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult {
+                    err: result.err(),
+                }),
+                None,
+            )
         }),
         last_notified_slot,
     )
@@ -410,25 +591,39 @@ fn filter_program_results(
     params: &ProgramSubscriptionParams,
     last_notified_slot: Slot,
     bank: Arc<Bank>,
-) -> (impl Iterator<Item = RpcKeyedAccount>, Slot) {
+) -> (
+    impl Iterator<Item = (RpcKeyedAccount, Option<RpcNotificationValue>)>,
+    Slot,
+) {
     let accounts_is_empty = accounts.is_empty();
+    let slot = bank.slot();
     let encoding = params.encoding;
     let filters = params.filters.clone();
+    let rent = bank.rent_collector().rent;
     let keyed_accounts = accounts.into_iter().filter(move |(_, account)| {
         filters
             .iter()
-            .all(|filter_type| filter_type.allows(account))
+            .all(|filter_type| filter_type.allows_with_rent(account, &rent))
     });
     let accounts = if is_known_spl_token_id(&params.pubkey)
         && params.encoding == UiAccountEncoding::JsonParsed
         && !accounts_is_empty
     {
-        let accounts = get_parsed_token_accounts(bank, keyed_accounts);
+        let accounts = get_parsed_token_accounts(bank, keyed_accounts)
+            .map(|keyed_account| (keyed_account, None));
         Either::Left(accounts)
     } else {
-        let accounts = keyed_accounts.map(move |(pubkey, account)| RpcKeyedAccount {
-            pubkey: pubkey.to_string(),
-            account: UiAccount::encode(&pubkey, &account, encoding, None, None),
+        let accounts = keyed_accounts.map(move |(pubkey, account)| {
+            let keyed_account = RpcKeyedAccount {
+                pubkey: pubkey.to_string(),
+                account: UiAccount::encode(&pubkey, &account, encoding, None, None),
+            };
+            let typed_value = RpcNotificationValue::Account {
+                slot,
+                pubkey,
+                account,
+            };
+            (keyed_account, Some(typed_value))
         });
         Either::Right(accounts)
     };
@@ -440,11 +635,17 @@ fn filter_logs_results(
     _params: &LogsSubscriptionParams,
     last_notified_slot: Slot,
     _bank: Arc<Bank>,
-) -> (impl Iterator<Item = RpcLogsResponse>, Slot) {
-    let responses = logs.into_iter().flatten().map(|log| RpcLogsResponse {
-        signature: log.signature.to_string(),
-        err: log.result.err(),
-        logs: log.log_messages,
+) -> (
+    impl Iterator<Item = (RpcLogsResponse, Option<RpcNotificationValue>)>,
+    Slot,
+) {
+    let responses = logs.into_iter().flatten().map(|log| {
+        let response = RpcLogsResponse {
+            signature: log.signature.to_string(),
+            err: log.result.err(),
+            logs: log.log_messages,
+        };
+        (response, None)
     });
     (responses, last_notified_slot)
 }
@@ -617,6 +818,12 @@ impl RpcSubscriptions {
         let subscriptions = SubscriptionsTracker::new(bank_forks.clone());
 
         let (broadcast_sender, _) = broadcast::channel(config.queue_capacity_items);
+        let replay_buffer = (config.replay_capacity_items > 0).then(|| {
+            Arc::new(Mutex::new(ReplayBuffer::new(
+                config.replay_capacity_items,
+                config.replay_capacity_bytes,
+            )))
+        });
 
         let notifier = RpcNotifier {
             sender: broadcast_sender.clone(),
@@ -624,6 +831,7 @@ impl RpcSubscriptions {
                 config.queue_capacity_items,
                 config.queue_capacity_bytes,
             )),
+            replay_buffer: replay_buffer.clone(),
         };
         let notification_threads = config.notification_threads.unwrap_or_else(get_thread_count);
         let t_cleanup = if notification_threads == 0 {
@@ -663,6 +871,7 @@ impl RpcSubscriptions {
             config.max_active_subscriptions,
             notification_sender.clone(),
             broadcast_sender,
+            replay_buffer,
         );
 
         Self {
@@ -801,7 +1010,13 @@ impl RpcSubscriptions {
                             {
                                 debug!("slot notify: {:?}", slot_info);
                                 inc_new_counter_info!("rpc-subscription-notify-slot", 1);
-                                notifier.notify(slot_info, sub, false);
+                                notifier.notify_at_slot(
+                                    slot_info,
+                                    Some(RpcNotificationValue::Slot(slot_info)),
+                                    sub,
+                                    false,
+                                    None,
+                                );
                             }
                         }
                         NotificationEntry::SlotUpdate(slot_update) => {
@@ -810,7 +1025,8 @@ impl RpcSubscriptions {
                                 .get(&SubscriptionParams::SlotsUpdates)
                             {
                                 inc_new_counter_info!("rpc-subscription-notify-slots-updates", 1);
-                                notifier.notify(slot_update, sub, false);
+                                let slot = slot_update.slot();
+                                notifier.notify_at_slot(slot_update, None, sub, false, Some(slot));
                             }
                         }
                         // These notifications are only triggered by votes observed on gossip,
@@ -932,6 +1148,9 @@ impl RpcSubscriptions {
         let num_blocks_found = AtomicUsize::new(0);
         let num_blocks_notified = AtomicUsize::new(0);
 
+        let num_transactions_found = AtomicUsize::new(0);
+        let num_transactions_notified = AtomicUsize::new(0);
+
         let num_logs_found = AtomicUsize::new(0);
         let num_logs_notified = AtomicUsize::new(0);
 
@@ -978,42 +1197,12 @@ impl RpcSubscriptions {
                 SubscriptionParams::Block(params) => {
                     num_blocks_found.fetch_add(1, Ordering::Relaxed);
                     if let Some(slot) = slot {
-                        let bank = bank_forks.read().unwrap().get(slot);
-                        if let Some(bank) = bank {
-                            // We're calling it unnotified in this context
-                            // because, logically, it gets set to `last_notified_slot + 1`
-                            // on the final iteration of the loop down below.
-                            // This is used to notify blocks for slots that were
-                            // potentially missed due to upstream transient errors
-                            // that led to this notification not being triggered for
-                            // a slot.
-                            //
-                            // e.g.
-                            // notify_watchers is triggered for Slot 1
-                            // some time passes
-                            // notify_watchers is triggered for Slot 4
-                            // this will try to fetch blocks for slots 2, 3, and 4
-                            // as long as they are ancestors of `slot`
-                            let mut w_last_unnotified_slot =
-                                subscription.last_notified_slot.write().unwrap();
-                            // would mean it's the first notification for this subscription connection
-                            if *w_last_unnotified_slot == 0 {
-                                *w_last_unnotified_slot = slot;
-                            }
-                            let mut slots_to_notify: Vec<_> =
-                                (*w_last_unnotified_slot..slot).collect();
-                            let ancestors = bank.proper_ancestors_set();
-                            slots_to_notify.retain(|slot| ancestors.contains(slot));
-                            slots_to_notify.push(slot);
-                            for s in slots_to_notify {
-                                // To avoid skipping a slot that fails this condition,
-                                // caused by non-deterministic concurrency accesses, we
-                                // break out of the loop. Besides if the current `s` is
-                                // greater, then any `s + K` is also greater.
-                                if s > max_complete_transaction_status_slot.load(Ordering::SeqCst) {
-                                    break;
-                                }
-
+                        Self::notify_unnotified_blocks(
+                            subscription,
+                            bank_forks,
+                            slot,
+                            &max_complete_transaction_status_slot,
+                            |s| {
                                 let block_update_result = blockstore
                                     .get_complete_block(s, false)
                                     .map_err(|e| {
@@ -1034,14 +1223,14 @@ impl RpcSubscriptions {
                                                 false,
                                             );
                                             num_blocks_notified.fetch_add(1, Ordering::Relaxed);
-                                            // the next time this subscription is notified it will
-                                            // try to fetch all slots between (s + 1) to `slot`, inclusively
-                                            *w_last_unnotified_slot = s + 1;
+                                            true
+                                        } else {
+                                            false
                                         }
                                     }
                                     Err(err) => {
-                                        // we don't advance `w_last_unnotified_slot` so that
-                                        // it'll retry on the next notification trigger
+                                        // the slot isn't marked as notified so that it'll be
+                                        // retried on the next notification trigger
                                         notifier.notify(
                                             RpcResponse::from(RpcNotificationResponse {
                                                 context: RpcNotificationContext { slot: s },
@@ -1054,10 +1243,58 @@ impl RpcSubscriptions {
                                             subscription,
                                             false,
                                         );
+                                        false
                                     }
                                 }
-                            }
-                        }
+                            },
+                        );
+                    }
+                }
+                SubscriptionParams::Transaction(params) => {
+                    num_transactions_found.fetch_add(1, Ordering::Relaxed);
+                    if let Some(slot) = slot {
+                        Self::notify_unnotified_blocks(
+                            subscription,
+                            bank_forks,
+                            slot,
+                            &max_complete_transaction_status_slot,
+                            |s| {
+                                let updates = match blockstore.get_complete_block(s, false) {
+                                    Ok(block) => filter_transaction_results(block, s, params),
+                                    Err(e) => {
+                                        error!("get_complete_block error: {}", e);
+                                        // the slot isn't marked as notified so that it'll be
+                                        // retried on the next notification trigger
+                                        notifier.notify(
+                                            RpcResponse::from(RpcNotificationResponse {
+                                                context: RpcNotificationContext { slot: s },
+                                                value: RpcTransactionUpdate {
+                                                    slot: s,
+                                                    signature: None,
+                                                    transaction: None,
+                                                    err: Some(RpcBlockUpdateError::BlockStoreError),
+                                                },
+                                            }),
+                                            subscription,
+                                            false,
+                                        );
+                                        return false;
+                                    }
+                                };
+                                for update in updates {
+                                    notifier.notify(
+                                        RpcResponse::from(RpcNotificationResponse {
+                                            context: RpcNotificationContext { slot: s },
+                                            value: update,
+                                        }),
+                                        subscription,
+                                        false,
+                                    );
+                                    num_transactions_notified.fetch_add(1, Ordering::Relaxed);
+                                }
+                                true
+                            },
+                        );
                     }
                 }
                 SubscriptionParams::Logs(params) => {
@@ -1210,6 +1447,52 @@ impl RpcSubscriptions {
         }
     }
 
+    /// Calls `notify` for `slot` and for each of its ancestors that were not yet notified to
+    /// the block-based `subscription`. This covers slots whose notifications were missed due
+    /// to upstream transient errors that led to `notify_watchers()` not being triggered for
+    /// them, e.g. if it's triggered for slot 1 and then for slot 4, this will try to notify
+    /// slots 2, 3 and 4 as long as they are ancestors of slot 4.
+    ///
+    /// The subscription's last notified slot is advanced past each slot for which `notify`
+    /// returns `true`.
+    fn notify_unnotified_blocks(
+        subscription: &SubscriptionInfo,
+        bank_forks: &RwLock<BankForks>,
+        slot: Slot,
+        max_complete_transaction_status_slot: &AtomicU64,
+        mut notify: impl FnMut(Slot) -> bool,
+    ) {
+        let Some(bank) = bank_forks.read().unwrap().get(slot) else {
+            return;
+        };
+        // We're calling it unnotified in this context
+        // because, logically, it gets set to `last_notified_slot + 1`
+        // on the final iteration of the loop down below.
+        let mut w_last_unnotified_slot = subscription.last_notified_slot.write().unwrap();
+        // would mean it's the first notification for this subscription connection
+        if *w_last_unnotified_slot == 0 {
+            *w_last_unnotified_slot = slot;
+        }
+        let mut slots_to_notify: Vec<_> = (*w_last_unnotified_slot..slot).collect();
+        let ancestors = bank.proper_ancestors_set();
+        slots_to_notify.retain(|slot| ancestors.contains(slot));
+        slots_to_notify.push(slot);
+        for s in slots_to_notify {
+            // To avoid skipping a slot that fails this condition,
+            // caused by non-deterministic concurrency accesses, we
+            // break out of the loop. Besides if the current `s` is
+            // greater, then any `s + K` is also greater.
+            if s > max_complete_transaction_status_slot.load(Ordering::SeqCst) {
+                break;
+            }
+            if notify(s) {
+                // the next time this subscription is notified it will
+                // try to fetch all slots between (s + 1) to `slot`, inclusively
+                *w_last_unnotified_slot = s + 1;
+            }
+        }
+    }
+
     fn shutdown(&mut self) -> std::thread::Result<()> {
         if self.t_cleanup.is_some() {
             info!("RPC Notification thread - shutting down");
@@ -1240,12 +1523,14 @@ pub(crate) mod tests {
             rpc::{create_test_transaction_entries, populate_blockstore_for_tests},
             rpc_pubsub::RpcSolPubSubInternal,
             rpc_pubsub_service,
+            rpc_subscription_tracker::TransactionSubscriptionFilter,
         },
         serial_test::serial,
         solana_rpc_client_api::config::{
-            RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
-            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcAccountInfoConfig, RpcAccountSubscribeConfig, RpcBlockSubscribeConfig,
+            RpcBlockSubscribeFilter, RpcProgramAccountsConfig, RpcProgramSubscribeConfig,
+            RpcSignatureSubscribeConfig, RpcSlotsUpdatesSubscribeConfig, RpcTransactionLogsConfig,
+            RpcTransactionLogsFilter, RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
         },
         solana_runtime::{
             commitment::BlockCommitment,
@@ -1388,11 +1673,14 @@ pub(crate) mod tests {
             let sub_id = rpc
                 .account_subscribe(
                     pubkey.to_string(),
-                    Some(RpcAccountInfoConfig {
-                        commitment: Some(CommitmentConfig::processed()),
-                        encoding: None,
-                        data_slice: None,
-                        min_context_slot: None,
+                    Some(RpcAccountSubscribeConfig {
+                        account_config: RpcAccountInfoConfig {
+                            commitment: Some(CommitmentConfig::processed()),
+                            encoding: None,
+                            data_slice: None,
+                            min_context_slot: None,
+                        },
+                        from_slot: None,
                     }),
                 )
                 .unwrap();
@@ -1673,6 +1961,131 @@ pub(crate) mod tests {
             .assert_unsubscribed(&SubscriptionParams::Block(params));
     }
 
+    #[test]
+    #[serial]
+    fn test_check_processed_transaction_subscribe() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let ledger_path = get_tmp_ledger_path!();
+        let blockstore = Blockstore::open(&ledger_path).unwrap();
+        let blockstore = Arc::new(blockstore);
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let subscriptions = Arc::new(RpcSubscriptions::new_for_tests_with_blockstore(
+            &exit,
+            max_complete_transaction_status_slot,
+            blockstore.clone(),
+            bank_forks.clone(),
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+        ));
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+        let keypair1 = Keypair::new();
+        let keypair2 = Keypair::new();
+        let keypair3 = Keypair::new();
+        // Only the failed transfer from keypair2 matches; the successful transfer to keypair1
+        // does not
+        let filter = RpcTransactionSubscribeFilter {
+            vote: Some(false),
+            failed: Some(true),
+            account_include: Some(vec![
+                keypair2.pubkey().to_string(),
+                keypair1.pubkey().to_string(),
+            ]),
+            account_exclude: None,
+            account_required: Some(vec![keypair3.pubkey().to_string()]),
+        };
+        let config = RpcTransactionSubscribeConfig {
+            commitment: Some(CommitmentConfig::processed()),
+            encoding: Some(UiTransactionEncoding::Json),
+            max_supported_transaction_version: None,
+        };
+        let mut account_include = vec![keypair1.pubkey(), keypair2.pubkey()];
+        account_include.sort_unstable();
+        let params = TransactionSubscriptionParams {
+            commitment: config.commitment.unwrap(),
+            encoding: config.encoding.unwrap(),
+            filter: TransactionSubscriptionFilter {
+                vote: Some(false),
+                failed: Some(true),
+                account_include,
+                account_exclude: vec![],
+                account_required: vec![keypair3.pubkey()],
+            },
+            max_supported_transaction_version: None,
+        };
+        let sub_id = rpc.transaction_subscribe(filter, Some(config)).unwrap();
+
+        subscriptions
+            .control
+            .assert_subscribed(&SubscriptionParams::Transaction(params.clone()));
+
+        let bank = bank_forks.read().unwrap().working_bank();
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::new(blockstore.max_root()));
+        bank.transfer(rent_exempt_amount, &mint_keypair, &keypair2.pubkey())
+            .unwrap();
+        let (entries, signatures) = create_test_transaction_entries(
+            vec![&mint_keypair, &keypair1, &keypair2, &keypair3],
+            bank.clone(),
+        );
+        populate_blockstore_for_tests(
+            entries,
+            bank,
+            blockstore.clone(),
+            max_complete_transaction_status_slot,
+        );
+
+        let slot = 0;
+        subscriptions.notify_subscribers(CommitmentSlots::default());
+        let actual_resp = receiver.recv();
+        let actual_resp = serde_json::from_str::<serde_json::Value>(&actual_resp).unwrap();
+
+        let transaction = blockstore
+            .get_complete_block(slot, false)
+            .unwrap()
+            .transactions
+            .into_iter()
+            .find(|tx| tx.transaction.signatures[0] == signatures[1])
+            .unwrap();
+        let expected_resp = RpcTransactionUpdate {
+            slot,
+            signature: Some(signatures[1].to_string()),
+            transaction: Some(
+                TransactionWithStatusMeta::Complete(transaction)
+                    .encode(params.encoding, None, true)
+                    .unwrap(),
+            ),
+            err: None,
+        };
+        let expected_resp = json!({
+           "jsonrpc": "2.0",
+           "method": "transactionNotification",
+           "params": {
+               "result": {
+                   "context": { "slot": slot },
+                   "value": expected_resp,
+               },
+               "subscription": 0,
+           }
+        });
+        assert_eq!(expected_resp, actual_resp);
+        let should_err = receiver.recv_timeout(Duration::from_millis(300));
+        assert!(should_err.is_err());
+
+        rpc.transaction_unsubscribe(sub_id).unwrap();
+        subscriptions
+            .control
+            .assert_unsubscribed(&SubscriptionParams::Transaction(params));
+    }
+
     #[test]
     #[serial]
     fn test_check_finalized_block_subscribe() {
@@ -1817,6 +2230,13 @@ pub(crate) mod tests {
             .unwrap()
             .process_transaction(&tx)
             .unwrap();
+        let alice_account = bank_forks
+            .read()
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .get_account(&alice.pubkey())
+            .unwrap();
 
         let exit = Arc::new(AtomicBool::new(false));
         let optimistically_confirmed_bank =
@@ -1833,12 +2253,15 @@ pub(crate) mod tests {
         let sub_id = rpc
             .program_subscribe(
                 stake::program::id().to_string(),
-                Some(RpcProgramAccountsConfig {
-                    account_config: RpcAccountInfoConfig {
-                        commitment: Some(CommitmentConfig::processed()),
-                        ..RpcAccountInfoConfig::default()
+                Some(RpcProgramSubscribeConfig {
+                    program_config: RpcProgramAccountsConfig {
+                        account_config: RpcAccountInfoConfig {
+                            commitment: Some(CommitmentConfig::processed()),
+                            ..RpcAccountInfoConfig::default()
+                        },
+                        ..RpcProgramAccountsConfig::default()
                     },
-                    ..RpcProgramAccountsConfig::default()
+                    from_slot: None,
                 }),
             )
             .unwrap();
@@ -1854,6 +2277,7 @@ pub(crate) mod tests {
                 with_context: false,
             }));
 
+        let mut broadcast_receiver = subscriptions.control.broadcast_receiver();
         subscriptions.notify_subscribers(CommitmentSlots::default());
         let response = receiver.recv();
         let expected = json!({
@@ -1881,6 +2305,16 @@ pub(crate) mod tests {
             expected,
             serde_json::from_str::<serde_json::Value>(&response).unwrap(),
         );
+        // The account is also broadcast as is, for the gRPC service
+        let notification = broadcast_receiver.try_recv().unwrap();
+        assert_eq!(
+            *notification.value.unwrap().upgrade().unwrap(),
+            RpcNotificationValue::Account {
+                slot: 0,
+                pubkey: alice.pubkey(),
+                account: alice_account,
+            }
+        );
 
         rpc.program_unsubscribe(sub_id).unwrap();
         subscriptions
@@ -1985,12 +2419,15 @@ pub(crate) mod tests {
         let sub_id = rpc
             .program_subscribe(
                 stake::program::id().to_string(),
-                Some(RpcProgramAccountsConfig {
-                    account_config: RpcAccountInfoConfig {
-                        commitment: Some(CommitmentConfig::confirmed()),
-                        ..RpcAccountInfoConfig::default()
+                Some(RpcProgramSubscribeConfig {
+                    program_config: RpcProgramAccountsConfig {
+                        account_config: RpcAccountInfoConfig {
+                            commitment: Some(CommitmentConfig::confirmed()),
+                            ..RpcAccountInfoConfig::default()
+                        },
+                        ..RpcProgramAccountsConfig::default()
                     },
-                    ..RpcProgramAccountsConfig::default()
+                    from_slot: None,
                 }),
             )
             .unwrap();
@@ -2153,12 +2590,15 @@ pub(crate) mod tests {
         let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
         rpc.program_subscribe(
             stake::program::id().to_string(),
-            Some(RpcProgramAccountsConfig {
-                account_config: RpcAccountInfoConfig {
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    ..RpcAccountInfoConfig::default()
+            Some(RpcProgramSubscribeConfig {
+                program_config: RpcProgramAccountsConfig {
+                    account_config: RpcAccountInfoConfig {
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        ..RpcAccountInfoConfig::default()
+                    },
+                    ..RpcProgramAccountsConfig::default()
                 },
-                ..RpcProgramAccountsConfig::default()
+                from_slot: None,
             }),
         )
         .unwrap();
@@ -2265,12 +2705,15 @@ pub(crate) mod tests {
         let sub_id = rpc
             .program_subscribe(
                 stake::program::id().to_string(),
-                Some(RpcProgramAccountsConfig {
-                    account_config: RpcAccountInfoConfig {
-                        commitment: Some(CommitmentConfig::confirmed()),
-                        ..RpcAccountInfoConfig::default()
+                Some(RpcProgramSubscribeConfig {
+                    program_config: RpcProgramAccountsConfig {
+                        account_config: RpcAccountInfoConfig {
+                            commitment: Some(CommitmentConfig::confirmed()),
+                            ..RpcAccountInfoConfig::default()
+                        },
+                        ..RpcProgramAccountsConfig::default()
                     },
-                    ..RpcProgramAccountsConfig::default()
+                    from_slot: None,
                 }),
             )
             .unwrap();
@@ -2732,11 +3175,14 @@ pub(crate) mod tests {
         let sub_id0 = rpc0
             .account_subscribe(
                 alice.pubkey().to_string(),
-                Some(RpcAccountInfoConfig {
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    encoding: None,
-                    data_slice: None,
-                    min_context_slot: None,
+                Some(RpcAccountSubscribeConfig {
+                    account_config: RpcAccountInfoConfig {
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        encoding: None,
+                        data_slice: None,
+                        min_context_slot: None,
+                    },
+                    from_slot: None,
                 }),
             )
             .unwrap();
@@ -2823,11 +3269,14 @@ pub(crate) mod tests {
         let sub_id1 = rpc1
             .account_subscribe(
                 alice.pubkey().to_string(),
-                Some(RpcAccountInfoConfig {
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    encoding: None,
-                    data_slice: None,
-                    min_context_slot: None,
+                Some(RpcAccountSubscribeConfig {
+                    account_config: RpcAccountInfoConfig {
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        encoding: None,
+                        data_slice: None,
+                        min_context_slot: None,
+                    },
+                    from_slot: None,
                 }),
             )
             .unwrap();
@@ -2925,6 +3374,7 @@ pub(crate) mod tests {
 
         let sub_config = RpcTransactionLogsConfig {
             commitment: Some(CommitmentConfig::processed()),
+            from_slot: None,
         };
 
         let (rpc_all, mut receiver_all) = rpc_pubsub_service::test_connection(&subscriptions);
@@ -2983,6 +3433,73 @@ pub(crate) mod tests {
         assert!(!subscriptions.control.logs_subscribed(Some(&alice.pubkey())));
     }
 
+    #[test]
+    #[serial]
+    fn test_slots_updates_subscribe_from_slot() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(100);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let ledger_path = get_tmp_ledger_path!();
+        let blockstore = Arc::new(Blockstore::open(&ledger_path).unwrap());
+
+        let exit = Arc::new(AtomicBool::new(false));
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let subscriptions = Arc::new(RpcSubscriptions::new_with_config(
+            &exit,
+            Arc::new(AtomicU64::default()),
+            blockstore,
+            bank_forks,
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+            &PubSubConfig {
+                replay_capacity_items: 2,
+                ..PubSubConfig::default_for_tests()
+            },
+            None,
+        ));
+        let root = |slot| SlotUpdate::Root { slot, timestamp: 0 };
+        let notified_slot = |json: String| {
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()["params"]["result"]["slot"]
+                .as_u64()
+                .unwrap()
+        };
+
+        // The first connection stays subscribed, as if its subscription were lingering after
+        // the client went away.
+        let (rpc0, mut receiver0) = rpc_pubsub_service::test_connection(&subscriptions);
+        let sub_id = rpc0.slots_updates_subscribe(None).unwrap();
+        for slot in 1..=3 {
+            subscriptions.notify_slot_update(root(slot));
+            assert_eq!(notified_slot(receiver0.recv()), slot);
+        }
+
+        let from_slot = |from_slot| {
+            Some(RpcSlotsUpdatesSubscribeConfig {
+                from_slot: Some(from_slot),
+            })
+        };
+        let (rpc1, mut receiver1) = rpc_pubsub_service::test_connection(&subscriptions);
+        // Slot 1 has been evicted from the replay buffer
+        assert!(rpc1.slots_updates_subscribe(from_slot(1)).is_err());
+        assert_eq!(rpc1.slots_updates_subscribe(from_slot(2)).unwrap(), sub_id);
+        assert_eq!(notified_slot(receiver1.recv()), 2);
+        assert_eq!(notified_slot(receiver1.recv()), 3);
+
+        // Notifications of a subscription that was not active can't be replayed
+        let (rpc2, _receiver2) = rpc_pubsub_service::test_connection(&subscriptions);
+        assert!(rpc2
+            .logs_subscribe(
+                RpcTransactionLogsFilter::All,
+                Some(RpcTransactionLogsConfig {
+                    commitment: None,
+                    from_slot: Some(2),
+                }),
+            )
+            .is_err());
+        assert!(!subscriptions.control.logs_subscribed(None));
+    }
+
     #[test]
     fn test_total_subscriptions() {
         let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(100);
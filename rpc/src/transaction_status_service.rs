@@ -387,6 +387,8 @@ pub(crate) mod tests {
             return_data: None,
             executed_units: 0,
             accounts_data_len_delta: 0,
+            account_diffs: None,
+            instruction_trace: None,
         });
 
         let balances = TransactionBalancesSet {
@@ -4,15 +4,16 @@ extern crate test;
 
 use {
     rand::{thread_rng, Rng},
+    rayon::prelude::*,
     solana_runtime::{
         account_info::AccountInfo,
         accounts_index::{
-            AccountSecondaryIndexes, AccountsIndex, UpsertReclaim,
+            AccountIndex, AccountSecondaryIndexes, AccountsIndex, UpsertReclaim,
             ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS,
         },
     },
     solana_sdk::{account::AccountSharedData, pubkey},
-    std::sync::Arc,
+    std::{collections::HashSet, sync::Arc},
     test::Bencher,
 };
 
@@ -65,3 +66,34 @@ fn bench_accounts_index(bencher: &mut Bencher) {
         fork += 1;
     });
 }
+
+#[bench]
+fn bench_accounts_index_program_id_index(bencher: &mut Bencher) {
+    // every account has the same owner, so all threads insert into the same index entry
+    const NUM_PUBKEYS: usize = 100_000;
+    let owner = pubkey::new_rand();
+    let account = AccountSharedData::new(1, 0, &owner);
+    let account_indexes = AccountSecondaryIndexes {
+        keys: None,
+        indexes: HashSet::from([AccountIndex::ProgramId]),
+    };
+
+    bencher.iter(|| {
+        let index = AccountsIndex::<AccountInfo>::new(
+            Some(ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS),
+            &Arc::default(),
+        );
+        (0..NUM_PUBKEYS).into_par_iter().for_each(|_| {
+            index.upsert(
+                0,
+                0,
+                &pubkey::new_rand(),
+                &account,
+                &account_indexes,
+                AccountInfo::default(),
+                &mut vec![],
+                UpsertReclaim::PopulateReclaims,
+            );
+        });
+    });
+}
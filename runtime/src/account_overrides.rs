@@ -1,12 +1,19 @@
 use {
-    solana_sdk::{account::AccountSharedData, pubkey::Pubkey, sysvar},
-    std::collections::HashMap,
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable,
+        feature_set::FeatureSet,
+        pubkey::Pubkey,
+        sysvar,
+    },
+    std::{collections::HashMap, sync::Arc},
 };
 
 /// Encapsulates overridden accounts, typically used for transaction simulations
 #[derive(Default)]
 pub struct AccountOverrides {
     accounts: HashMap<Pubkey, AccountSharedData>,
+    feature_set: Option<Arc<FeatureSet>>,
 }
 
 impl AccountOverrides {
@@ -24,8 +31,33 @@ impl AccountOverrides {
         self.set_account(&sysvar::slot_history::id(), slot_history);
     }
 
+    /// Sets the feature set used to execute transactions in place of the bank's
+    pub fn set_feature_set(&mut self, feature_set: Option<Arc<FeatureSet>>) {
+        self.feature_set = feature_set;
+    }
+
     /// Gets the account if it's found in the list of overrides
     pub fn get(&self, pubkey: &Pubkey) -> Option<&AccountSharedData> {
         self.accounts.get(pubkey)
     }
+
+    /// Gets the overridden feature set, if any
+    pub fn feature_set(&self) -> Option<&Arc<FeatureSet>> {
+        self.feature_set.as_ref()
+    }
+
+    /// Whether any sysvar account is overridden
+    pub fn has_sysvar_overrides(&self) -> bool {
+        self.accounts.keys().any(sysvar::is_sysvar_id)
+    }
+
+    /// Whether any account owned by a program loader is overridden, in which case executors
+    /// cached for the original accounts must not be used
+    pub fn has_program_overrides(&self) -> bool {
+        self.accounts.values().any(|account| {
+            bpf_loader::check_id(account.owner())
+                || bpf_loader_deprecated::check_id(account.owner())
+                || bpf_loader_upgradeable::check_id(account.owner())
+        })
+    }
 }
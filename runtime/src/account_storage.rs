@@ -117,6 +117,21 @@ impl AccountStorage {
             .is_none());
     }
 
+    /// replace the append vec at 'slot' with 'store', which must have the same id
+    /// This is used when the same accounts are moved to a different file, so the accounts index stays valid.
+    /// returns the previous append vec
+    pub(crate) fn replace(
+        &self,
+        slot: Slot,
+        store: Arc<AccountStorageEntry>,
+    ) -> Option<Arc<AccountStorageEntry>> {
+        assert!(self.shrink_in_progress_map.is_empty());
+        self.map.get_mut(&slot).map(|mut entry| {
+            assert_eq!(entry.id, store.append_vec_id());
+            std::mem::replace(&mut entry.storage, store)
+        })
+    }
+
     /// called when shrinking begins on a slot and append vec.
     /// When 'ShrinkInProgress' is dropped by caller, the old store will be removed from the storage map.
     /// Fails if there are no existing stores at the slot.
@@ -254,6 +269,40 @@ impl Default for AccountStorageStatus {
 pub(crate) mod tests {
     use {super::*, std::path::Path};
 
+    #[test]
+    fn test_replace() {
+        let storage = AccountStorage::default();
+        let slot = 0;
+        let id = 0;
+        let store_dir = tempfile::TempDir::new().unwrap();
+        let store_dir2 = tempfile::TempDir::new().unwrap();
+        let entry = Arc::new(AccountStorageEntry::new(store_dir.path(), slot, id, 4000));
+        let entry2 = Arc::new(AccountStorageEntry::new(store_dir2.path(), slot, id, 8000));
+        // nothing to replace
+        assert!(storage.replace(slot, Arc::clone(&entry)).is_none());
+        assert!(storage.get_slot_storage_entry(slot).is_none());
+
+        storage.insert(slot, Arc::clone(&entry));
+        let previous = storage.replace(slot, Arc::clone(&entry2)).unwrap();
+        assert!(Arc::ptr_eq(&previous, &entry));
+        assert!(Arc::ptr_eq(
+            &storage.get_account_storage_entry(slot, id).unwrap(),
+            &entry2
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: `(left == right)`")]
+    fn test_replace_different_id() {
+        let storage = AccountStorage::default();
+        let slot = 0;
+        let store_dir = tempfile::TempDir::new().unwrap();
+        let entry = Arc::new(AccountStorageEntry::new(store_dir.path(), slot, 0, 4000));
+        let entry2 = Arc::new(AccountStorageEntry::new(store_dir.path(), slot, 1, 4000));
+        storage.insert(slot, entry);
+        storage.replace(slot, entry2);
+    }
+
     #[test]
     fn test_shrink_in_progress() {
         // test that we check in order map then shrink_in_progress_map
@@ -58,7 +58,7 @@ use {
         cmp::Reverse,
         collections::{hash_map, BinaryHeap, HashMap, HashSet},
         num::NonZeroUsize,
-        ops::RangeBounds,
+        ops::{Bound, RangeBounds},
         path::PathBuf,
         sync::{
             atomic::{AtomicBool, AtomicUsize, Ordering},
@@ -192,16 +192,19 @@ impl Accounts {
         accounts_update_notifier: Option<AccountsUpdateNotifier>,
         exit: &Arc<AtomicBool>,
     ) -> Self {
+        let accounts_db = AccountsDb::new_with_config(
+            paths,
+            cluster_type,
+            account_indexes,
+            shrink_ratio,
+            accounts_db_config,
+            accounts_update_notifier,
+            exit,
+        );
+        // not started from a snapshot, so a disk index kept from a previous run does not apply
+        accounts_db.discard_restored_disk_index();
         Self {
-            accounts_db: Arc::new(AccountsDb::new_with_config(
-                paths,
-                cluster_type,
-                account_indexes,
-                shrink_ratio,
-                accounts_db_config,
-                accounts_update_notifier,
-                exit,
-            )),
+            accounts_db: Arc::new(accounts_db),
             account_locks: Mutex::new(AccountLocks::default()),
         }
     }
@@ -395,9 +398,14 @@ impl Accounts {
                                 programdata_address,
                             }) = account.state()
                             {
-                                if let Some((programdata_account, _)) = self
-                                    .accounts_db
-                                    .load_with_fixed_root(ancestors, &programdata_address)
+                                if let Some(programdata_account) = account_overrides
+                                    .and_then(|overrides| overrides.get(&programdata_address))
+                                    .cloned()
+                                    .or_else(|| {
+                                        self.accounts_db
+                                            .load_with_fixed_root(ancestors, &programdata_address)
+                                            .map(|(account, _)| account)
+                                    })
                                 {
                                     Self::accumulate_and_check_loaded_account_data_size(
                                         &mut accumulated_accounts_data_size,
@@ -986,6 +994,68 @@ impl Accounts {
         Self::maybe_abort_scan(result, &config)
     }
 
+    /// Loads a page of at most `limit` accounts of `index_key` passing `filter`, in ascending
+    /// pubkey order and starting after `after`. The key must be in an enabled secondary index,
+    /// which is iterated in order and only until the page is full.
+    #[allow(clippy::too_many_arguments)]
+    pub fn load_page_by_index_key_with_filter<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: &IndexKey,
+        filter: F,
+        after: Option<&Pubkey>,
+        limit: usize,
+        config: &ScanConfig,
+        byte_limit_for_scan: Option<usize>,
+    ) -> ScanResult<Vec<TransactionAccount>> {
+        let (account_index, key) = (index_key.account_index(), index_key.key());
+        let account_indexes = &self.accounts_db.account_indexes;
+        if !account_indexes.contains(&account_index) || !account_indexes.include_key(key) {
+            return Err(ScanError::Aborted(format!(
+                "{key} is not in the {account_index:?} secondary index"
+            )));
+        }
+        let sum = AtomicUsize::default();
+        let config = ScanConfig {
+            index_key_start: Some(after.map_or(Bound::Unbounded, |after| Bound::Excluded(*after))),
+            ..config.recreate_with_abort()
+        };
+        let mut collector = Vec::new();
+        self.accounts_db.index_scan_accounts(
+            ancestors,
+            bank_id,
+            *index_key,
+            |some_account_tuple| {
+                Self::load_while_filtering(&mut collector, some_account_tuple, |account| {
+                    let use_account = filter(account);
+                    if use_account
+                        && Self::accumulate_and_check_scan_result_size(
+                            &sum,
+                            account,
+                            &byte_limit_for_scan,
+                        )
+                    {
+                        // total size of results exceeds size limit, so abort scan
+                        config.abort();
+                    }
+                    use_account
+                });
+                if collector.len() >= limit {
+                    // the page is full
+                    config.abort();
+                }
+            },
+            &config,
+        )?;
+        if collector.len() < limit && config.is_aborted() {
+            return Err(ScanError::Aborted(
+                "The accumulated scan results exceeded the limit".to_string(),
+            ));
+        }
+        Ok(collector)
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.accounts_db.account_indexes.include_key(key)
     }
@@ -1229,7 +1299,7 @@ impl Accounts {
     }
 
     #[allow(clippy::too_many_arguments)]
-    fn collect_accounts_to_store<'a>(
+    pub(crate) fn collect_accounts_to_store<'a>(
         &self,
         txs: &'a [SanitizedTransaction],
         execution_results: &'a [TransactionExecutionResult],
@@ -1359,6 +1429,7 @@ mod tests {
     use {
         super::*,
         crate::{
+            accounts_index::AccountIndex,
             bank::{DurableNonceFee, TransactionExecutionDetails},
             rent_collector::RentCollector,
         },
@@ -1413,6 +1484,8 @@ mod tests {
                 return_data: None,
                 executed_units: 0,
                 accounts_data_len_delta: 0,
+                account_diffs: None,
+                instruction_trace: None,
             },
             tx_executor_cache: Rc::new(RefCell::new(TransactionExecutorCache::default())),
         }
@@ -2113,6 +2186,75 @@ mod tests {
         assert_eq!(loaded, vec![]);
     }
 
+    #[test]
+    fn test_load_page_by_index_key_with_filter() {
+        let program_id = Pubkey::new_unique();
+        let mut pubkeys = (0..5).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        pubkeys.sort_unstable();
+        let other_pubkey = Pubkey::new_unique();
+        let ancestors = vec![(0, 0)].into_iter().collect();
+        let index_key = IndexKey::ProgramId(program_id);
+
+        let load_page = |accounts: &Accounts, after: Option<&Pubkey>, limit| {
+            accounts
+                .load_page_by_index_key_with_filter(
+                    &ancestors,
+                    0,
+                    &index_key,
+                    |account| account.owner() == &program_id,
+                    after,
+                    limit,
+                    &ScanConfig::default(),
+                    None,
+                )
+                .map(|accounts| {
+                    accounts
+                        .into_iter()
+                        .map(|(pubkey, _account)| pubkey)
+                        .collect::<Vec<_>>()
+                })
+        };
+
+        // Pages are only loaded from the secondary index
+        let accounts = Accounts::new_with_config_for_tests(
+            Vec::new(),
+            &ClusterType::Development,
+            AccountSecondaryIndexes::default(),
+            AccountShrinkThreshold::default(),
+        );
+        assert!(load_page(&accounts, None, 2).is_err());
+
+        let accounts = Accounts::new_with_config_for_tests(
+            Vec::new(),
+            &ClusterType::Development,
+            AccountSecondaryIndexes {
+                keys: None,
+                indexes: HashSet::from([AccountIndex::ProgramId]),
+            },
+            AccountShrinkThreshold::default(),
+        );
+        for pubkey in pubkeys.iter().rev() {
+            let account = AccountSharedData::new(1, 0, &program_id);
+            accounts.store_slow_uncached(0, pubkey, &account);
+        }
+        let account = AccountSharedData::new(1, 0, &Pubkey::new_unique());
+        accounts.store_slow_uncached(0, &other_pubkey, &account);
+
+        assert_eq!(load_page(&accounts, None, 2).unwrap(), pubkeys[..2]);
+        assert_eq!(
+            load_page(&accounts, Some(&pubkeys[1]), 2).unwrap(),
+            pubkeys[2..4]
+        );
+        assert_eq!(
+            load_page(&accounts, Some(&pubkeys[3]), 2).unwrap(),
+            pubkeys[4..]
+        );
+        assert_eq!(
+            load_page(&accounts, Some(&pubkeys[4]), 2).unwrap(),
+            Vec::<Pubkey>::new()
+        );
+    }
+
     #[test]
     fn test_load_accounts_executable_with_write_lock() {
         let mut accounts: Vec<TransactionAccount> = Vec::new();
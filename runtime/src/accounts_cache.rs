@@ -320,6 +320,10 @@ impl AccountsCache {
         slots
     }
 
+    pub fn slots(&self) -> Vec<Slot> {
+        self.cache.iter().map(|item| *item.key()).collect()
+    }
+
     pub fn contains(&self, slot: Slot) -> bool {
         self.cache.contains_key(&slot)
     }
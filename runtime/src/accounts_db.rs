@@ -24,9 +24,11 @@ use {
         account_storage::{AccountStorage, AccountStorageStatus, ShrinkInProgress},
         accounts_background_service::{DroppedSlotsSender, SendDroppedBankCallback},
         accounts_cache::{AccountsCache, CachedAccount, SlotCache},
+        accounts_db::disk_index_restart::{DiskIndexRestart, PersistedStorage},
         accounts_hash::{
-            AccountsDeltaHash, AccountsHash, AccountsHasher, CalcAccountsHashConfig,
-            CalculateHashIntermediate, HashStats, ZeroLamportAccounts,
+            AccountHashEntry, AccountsDeltaHash, AccountsHash, AccountsHashBins, AccountsHasher,
+            CalcAccountsHashConfig, CalculateHashIntermediate, HashStats, SortedDataByPubkey,
+            ZeroLamportAccounts,
         },
         accounts_index::{
             AccountIndexGetResult, AccountSecondaryIndexes, AccountsIndex, AccountsIndexConfig,
@@ -42,9 +44,9 @@ use {
             get_ancient_append_vec_capacity, is_ancient, AccountsToStore, StorageSelector,
         },
         append_vec::{
-            aligned_stored_size, AppendVec, StorableAccountsWithHashesAndWriteVersions,
-            StoredAccountMeta, StoredMetaWriteVersion, APPEND_VEC_MMAPPED_FILES_OPEN,
-            STORE_META_OVERHEAD,
+            aligned_stored_size, AppendVec, AppendVecCompression, DecompressedBlock,
+            StorableAccountsWithHashesAndWriteVersions, StoredAccountMeta, StoredMetaWriteVersion,
+            APPEND_VEC_MMAPPED_FILES_OPEN, DECOMPRESSED_BLOCK_CACHE, STORE_META_OVERHEAD,
         },
         cache_hash_data::{CacheHashData, CacheHashDataFile},
         contains::Contains,
@@ -85,7 +87,7 @@ use {
     std::{
         borrow::{Borrow, Cow},
         boxed::Box,
-        collections::{BTreeSet, HashMap, HashSet},
+        collections::{BTreeMap, BTreeSet, HashMap, HashSet},
         hash::{Hash as StdHash, Hasher as StdHasher},
         io::{Error as IoError, Result as IoResult},
         ops::{Range, RangeBounds},
@@ -117,6 +119,8 @@ pub const DEFAULT_NUM_DIRS: u32 = 4;
 // When calculating hashes, it is helpful to break the pubkeys found into bins based on the pubkey value.
 // More bins means smaller vectors to sort, copy, etc.
 pub const PUBKEY_BINS_FOR_CALCULATING_HASHES: usize = 65536;
+/// how many of the newest snapshots keep their `AccountsHashBins` summary, see `add_snapshot_accounts_hash_bins`
+pub const MAX_SNAPSHOT_ACCOUNTS_HASH_BINS: usize = 16;
 
 // Without chunks, we end up with 1 output vec for each outer snapshot storage.
 // This results in too many vectors to be efficient.
@@ -305,7 +309,7 @@ impl CurrentAncientAppendVec {
         slot: Slot,
         db: &'a AccountsDb,
     ) -> ShrinkInProgress<'a> {
-        let shrink_in_progress = db.get_store_for_shrink(slot, get_ancient_append_vec_capacity());
+        let shrink_in_progress = db.get_store_for_ancient(slot, get_ancient_append_vec_capacity());
         *self = Self::new(slot, Arc::clone(shrink_in_progress.new_storage()));
         shrink_in_progress
     }
@@ -401,12 +405,13 @@ impl AncientSlotPubkeys {
             // Any overflow accounts will get written into a new append vec AT 'slot', so they don't need to be unrefed
             let accounts = to_store.get(StorageSelector::Primary);
             if Some(current_ancient.slot()) != self.inner.as_ref().map(|ap| ap.slot) {
-                let pubkeys = current_ancient
+                let mut pubkeys = HashSet::default();
+                current_ancient
                     .append_vec()
                     .accounts
-                    .account_iter()
-                    .map(|account| *account.pubkey())
-                    .collect::<HashSet<_>>();
+                    .scan_accounts(|account| {
+                        pubkeys.insert(*account.pubkey());
+                    });
                 self.inner = Some(AncientSlotPubkeysInner {
                     pubkeys,
                     slot: current_ancient.slot(),
@@ -445,6 +450,10 @@ pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     exhaustively_verify_refcounts: false,
     assert_stakes_cache_consistency: true,
     create_ancient_storage: CreateAncientStorage::Append,
+    cold_storage: None,
+    ancient_storage_compression: None,
+    decompressed_block_cache_limit_bytes: None,
+    accounts_hash_bins: None,
 };
 pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig {
     index: Some(ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS),
@@ -456,6 +465,10 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     exhaustively_verify_refcounts: false,
     assert_stakes_cache_consistency: false,
     create_ancient_storage: CreateAncientStorage::Append,
+    cold_storage: None,
+    ancient_storage_compression: None,
+    decompressed_block_cache_limit_bytes: None,
+    accounts_hash_bins: None,
 };
 
 pub type BinnedHashData = Vec<Vec<CalculateHashIntermediate>>;
@@ -502,6 +515,40 @@ impl Default for FillerAccountsConfig {
 
 const ANCIENT_APPEND_VEC_DEFAULT_OFFSET: Option<i64> = Some(-10_000);
 
+pub const DEFAULT_COLD_STORAGE_AGE_EPOCHS: u64 = 4;
+
+/// Where to keep ancient append vecs whose accounts have not been written for a long time.
+/// These are created on 'paths' instead of the accounts paths when slots are squashed into ancient append vecs,
+/// and are compressed once they are no longer appended to. Loads of cold accounts are served from
+/// their decompressed blocks and the read only accounts cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColdStorageConfig {
+    pub paths: Vec<PathBuf>,
+    /// accounts in slots older than this many epochs are moved to 'paths'
+    pub age_epochs: u64,
+    /// format cold ancient append vecs are rewritten in
+    pub compression: AppendVecCompression,
+}
+
+impl ColdStorageConfig {
+    /// the directories in 'paths' that hold the cold append vecs
+    /// Snapshots unpack their accounts files to the same directories.
+    pub fn storage_paths(&self) -> Vec<PathBuf> {
+        self.paths
+            .iter()
+            .map(|path| path.join("accounts"))
+            .collect()
+    }
+
+    /// accounts in slots older than the returned slot belong on the cold storage paths, as of 'slot'
+    pub fn newest_cold_slot(&self, slot: Slot, epoch_schedule: &EpochSchedule) -> Slot {
+        slot.saturating_sub(
+            self.age_epochs
+                .saturating_mul(epoch_schedule.slots_per_epoch),
+        )
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct AccountsDbConfig {
     pub index: Option<AccountsIndexConfig>,
@@ -517,6 +564,14 @@ pub struct AccountsDbConfig {
     pub assert_stakes_cache_consistency: bool,
     /// how to create ancient storages
     pub create_ancient_storage: CreateAncientStorage,
+    /// if Some, ancient append vecs holding old enough accounts are created on separate paths
+    pub cold_storage: Option<ColdStorageConfig>,
+    /// if Some, ancient append vecs that are no longer written to are rewritten read-only in this compressed format
+    pub ancient_storage_compression: Option<AppendVecCompression>,
+    /// if Some, the size limit of DECOMPRESSED_BLOCK_CACHE, which holds the blocks of compressed append vecs
+    pub decompressed_block_cache_limit_bytes: Option<usize>,
+    /// if Some, keep an `AccountsHashBins` summary with this many bins for each new snapshot
+    pub accounts_hash_bins: Option<usize>,
 }
 
 #[cfg(not(test))]
@@ -552,6 +607,14 @@ pub enum ScanStorageResult<R, B> {
     Stored(B),
 }
 
+/// The slot of a snapshot and the ids its storages had when it was taken.
+/// Storages get new ids when they are loaded from a snapshot.
+#[derive(Debug, Default)]
+pub struct SnapshotStorageIds {
+    pub slot: Slot,
+    pub ids: HashMap<Slot, AppendVecId>,
+}
+
 #[derive(Debug, Default)]
 pub struct IndexGenerationInfo {
     pub accounts_data_len: u64,
@@ -753,12 +816,18 @@ pub enum LoadHint {
 #[derive(Debug)]
 pub enum LoadedAccountAccessor<'a> {
     // StoredAccountMeta can't be held directly here due to its lifetime dependency to
-    // AccountStorageEntry
-    Stored(Option<(Arc<AccountStorageEntry>, usize)>),
+    // AccountStorageEntry.
+    // For a compressed AccountStorageEntry, the decompressed block the account is loaded from is
+    // held here instead of in the storage, so the block can be evicted from DECOMPRESSED_BLOCK_CACHE.
+    Stored(
+        Option<(Arc<AccountStorageEntry>, usize)>,
+        Option<Arc<DecompressedBlock>>,
+    ),
     // None value in Cached variant means the cache was flushed
     Cached(Option<Cow<'a, CachedAccount>>),
 }
 
+mod disk_index_restart;
 mod geyser_plugin_utils;
 
 impl<'a> LoadedAccountAccessor<'a> {
@@ -767,7 +836,7 @@ impl<'a> LoadedAccountAccessor<'a> {
         // ideal for representing this as rust type system....
 
         match self {
-            LoadedAccountAccessor::Cached(None) | LoadedAccountAccessor::Stored(None) => {
+            LoadedAccountAccessor::Cached(None) | LoadedAccountAccessor::Stored(None, _) => {
                 panic!("Should have already been taken care of when creating this LoadedAccountAccessor");
             }
             LoadedAccountAccessor::Cached(Some(_cached_account)) => {
@@ -775,7 +844,7 @@ impl<'a> LoadedAccountAccessor<'a> {
                 // it just returns the inner `x` without additional fetches
                 self.get_loaded_account().unwrap()
             }
-            LoadedAccountAccessor::Stored(Some(_maybe_storage_entry)) => {
+            LoadedAccountAccessor::Stored(Some(_maybe_storage_entry), _) => {
                 // If we do find the storage entry, we can guarantee that the storage entry is
                 // safe to read from because we grabbed a reference to the storage entry while it
                 // was still in the storage map. This means even if the storage entry is removed
@@ -795,17 +864,20 @@ impl<'a> LoadedAccountAccessor<'a> {
                 );
                 Some(LoadedAccount::Cached(cached_account))
             }
-            LoadedAccountAccessor::Stored(maybe_storage_entry) => {
+            LoadedAccountAccessor::Stored(maybe_storage_entry, decompressed_block) => {
                 // storage entry may not be present if slot was cleaned up in
                 // between reading the accounts index and calling this function to
                 // get account meta from the storage entry here
-                maybe_storage_entry
-                    .as_ref()
-                    .and_then(|(storage_entry, offset)| {
-                        storage_entry
-                            .get_stored_account_meta(*offset)
-                            .map(LoadedAccount::Stored)
-                    })
+                let (storage_entry, offset) = maybe_storage_entry.as_ref()?;
+                if !storage_entry.accounts.is_compressed() {
+                    return storage_entry
+                        .get_stored_account_meta(*offset)
+                        .map(LoadedAccount::Stored);
+                }
+                *decompressed_block = storage_entry.accounts.get_decompressed_block(*offset);
+                let decompressed_block: &DecompressedBlock = decompressed_block.as_deref()?;
+                AppendVec::get_account_in_block(decompressed_block, *offset)
+                    .map(|(stored_account_meta, _next)| LoadedAccount::Stored(stored_account_meta))
             }
         }
     }
@@ -1000,6 +1072,21 @@ impl AccountStorageEntry {
         }
     }
 
+    /// Return a new entry for the same slot and id as this one which holds 'accounts' instead.
+    /// 'accounts' must contain the same accounts at the same offsets as the current append vec.
+    /// The new entry is marked full so that nothing is appended to it.
+    fn with_accounts(&self, accounts: AppendVec) -> Self {
+        let count = self.count();
+        Self {
+            id: AtomicAppendVecId::new(self.append_vec_id()),
+            slot: AtomicU64::new(self.slot()),
+            accounts,
+            count_and_status: RwLock::new((count, AccountStorageStatus::Full)),
+            approx_store_count: AtomicUsize::new(self.approx_stored_count()),
+            alive_bytes: AtomicUsize::new(self.alive_bytes()),
+        }
+    }
+
     pub fn set_status(&self, mut status: AccountStorageStatus) {
         let mut count_and_status = self.count_and_status.write().unwrap();
 
@@ -1317,6 +1404,18 @@ pub struct AccountsDb {
     /// from AccountsDbConfig
     create_ancient_storage: CreateAncientStorage,
 
+    /// from AccountsDbConfig
+    cold_storage: Option<ColdStorageConfig>,
+
+    /// accounts in slots older than this are squashed into ancient append vecs on the cold storage paths
+    newest_cold_slot: AtomicU64,
+
+    /// from AccountsDbConfig
+    ancient_storage_compression: Option<AppendVecCompression>,
+
+    /// from AccountsDbConfig
+    accounts_hash_bins: Option<usize>,
+
     pub accounts_cache: AccountsCache,
 
     write_cache_limit_bytes: Option<u64>,
@@ -1361,6 +1460,8 @@ pub struct AccountsDb {
 
     accounts_delta_hashes: Mutex<HashMap<Slot, AccountsDeltaHash>>,
     accounts_hashes: Mutex<HashMap<Slot, AccountsHash>>,
+    /// summaries of the newest snapshots, see `add_snapshot_accounts_hash_bins`
+    snapshot_accounts_hash_bins: Mutex<BTreeMap<Slot, AccountsHashBins>>,
     bank_hash_stats: Mutex<HashMap<Slot, BankHashStats>>,
 
     pub stats: AccountsStats,
@@ -1873,6 +1974,10 @@ pub(crate) struct ShrinkAncientStats {
     pub(crate) random_shrink: AtomicU64,
     pub(crate) slots_considered: AtomicU64,
     pub(crate) ancient_scanned: AtomicU64,
+    pub(crate) ancient_moved_to_cold: AtomicU64,
+    pub(crate) ancient_compressed: AtomicU64,
+    pub(crate) compress_us: AtomicU64,
+    pub(crate) compressed_bytes_released: AtomicU64,
 }
 
 #[derive(Debug, Default)]
@@ -2159,6 +2264,26 @@ impl ShrinkAncientStats {
                     self.ancient_scanned.swap(0, Ordering::Relaxed) as i64,
                     i64
                 ),
+                (
+                    "ancient_moved_to_cold",
+                    self.ancient_moved_to_cold.swap(0, Ordering::Relaxed) as i64,
+                    i64
+                ),
+                (
+                    "ancient_compressed",
+                    self.ancient_compressed.swap(0, Ordering::Relaxed) as i64,
+                    i64
+                ),
+                (
+                    "compress_us",
+                    self.compress_us.swap(0, Ordering::Relaxed) as i64,
+                    i64
+                ),
+                (
+                    "compressed_bytes_released",
+                    self.compressed_bytes_released.swap(0, Ordering::Relaxed) as i64,
+                    i64
+                ),
                 (
                     "total_us",
                     self.total_us.swap(0, Ordering::Relaxed) as i64,
@@ -2357,6 +2482,10 @@ impl AccountsDb {
         AccountsDb {
             assert_stakes_cache_consistency: false,
             create_ancient_storage: CreateAncientStorage::Append,
+            cold_storage: None,
+            newest_cold_slot: AtomicU64::default(),
+            ancient_storage_compression: None,
+            accounts_hash_bins: None,
             verify_accounts_hash_in_bg: VerifyAccountsHashInBackground::default(),
             filler_accounts_per_slot: AtomicU64::default(),
             filler_account_slots_remaining: AtomicU64::default(),
@@ -2391,6 +2520,7 @@ impl AccountsDb {
             thread_pool_clean: make_min_priority_thread_pool(),
             accounts_delta_hashes: Mutex::new(HashMap::new()),
             accounts_hashes: Mutex::new(HashMap::new()),
+            snapshot_accounts_hash_bins: Mutex::default(),
             bank_hash_stats: Mutex::new(bank_hash_stats),
             external_purge_slots_stats: PurgeStats::default(),
             clean_accounts_stats: CleanAccountsStats::default(),
@@ -2487,6 +2617,25 @@ impl AccountsDb {
             .map(|config| config.create_ancient_storage)
             .unwrap_or(CreateAncientStorage::Append);
 
+        let cold_storage = accounts_db_config
+            .as_ref()
+            .and_then(|config| config.cold_storage.clone());
+
+        let ancient_storage_compression = accounts_db_config
+            .as_ref()
+            .and_then(|config| config.ancient_storage_compression);
+
+        let accounts_hash_bins = accounts_db_config
+            .as_ref()
+            .and_then(|config| config.accounts_hash_bins);
+
+        if let Some(limit_bytes) = accounts_db_config
+            .as_ref()
+            .and_then(|config| config.decompressed_block_cache_limit_bytes)
+        {
+            DECOMPRESSED_BLOCK_CACHE.set_limit_bytes(limit_bytes);
+        }
+
         let filler_account_suffix = if filler_accounts_config.count > 0 {
             Some(solana_sdk::pubkey::new_rand())
         } else {
@@ -2505,6 +2654,9 @@ impl AccountsDb {
             filler_account_suffix,
             assert_stakes_cache_consistency,
             create_ancient_storage,
+            cold_storage,
+            ancient_storage_compression,
+            accounts_hash_bins,
             write_cache_limit_bytes: accounts_db_config
                 .as_ref()
                 .and_then(|x| x.write_cache_limit_bytes),
@@ -2525,6 +2677,13 @@ impl AccountsDb {
             for path in new.paths.iter() {
                 std::fs::create_dir_all(path).expect("Create directory failed.");
             }
+            for path in new
+                .cold_storage
+                .iter()
+                .flat_map(|config| config.storage_paths())
+            {
+                std::fs::create_dir_all(path).expect("Create directory failed.");
+            }
         }
         new
     }
@@ -2865,6 +3024,13 @@ impl AccountsDb {
             std::cmp::max(*accounts_hash_complete_one_epoch_old, one_epoch_old_slot);
         let accounts_hash_complete_one_epoch_old = *accounts_hash_complete_one_epoch_old;
 
+        if let Some(cold_storage) = &self.cold_storage {
+            self.newest_cold_slot.fetch_max(
+                cold_storage.newest_cold_slot(completed_slot, epoch_schedule),
+                Ordering::Relaxed,
+            );
+        }
+
         // now that accounts hash calculation is complete, we can remove old historical roots
         self.remove_old_historical_roots(accounts_hash_complete_one_epoch_old);
     }
@@ -2959,7 +3125,7 @@ impl AccountsDb {
                             dirty_ancient_stores.fetch_add(1, Ordering::Relaxed);
                         }
                         oldest_dirty_slot = oldest_dirty_slot.min(*slot);
-                        store.accounts.account_iter().for_each(|account| {
+                        store.accounts.scan_accounts(|account| {
                             pubkeys.insert(*account.pubkey());
                         });
                     });
@@ -4117,6 +4283,139 @@ impl AccountsDb {
         self.storage.shrinking_in_progress(slot, shrunken_store)
     }
 
+    /// return a store for an ancient append vec at 'slot' that can contain 'aligned_total' bytes
+    /// The store is created on the cold storage paths if the accounts in 'slot' are old enough.
+    fn get_store_for_ancient(&self, slot: Slot, aligned_total: u64) -> ShrinkInProgress<'_> {
+        match &self.cold_storage {
+            Some(cold_storage) if self.is_cold_slot(slot) => {
+                let cold_store = self.create_store(
+                    slot,
+                    aligned_total,
+                    "ancient-cold",
+                    &cold_storage.storage_paths(),
+                );
+                self.storage.shrinking_in_progress(slot, cold_store)
+            }
+            _ => self.get_store_for_shrink(slot, aligned_total),
+        }
+    }
+
+    /// true if the accounts last written in 'slot' belong on the cold storage paths
+    fn is_cold_slot(&self, slot: Slot) -> bool {
+        self.cold_storage.is_some() && slot < self.newest_cold_slot.load(Ordering::Relaxed)
+    }
+
+    /// true if 'storage' was created on the cold storage paths
+    fn is_cold_storage(&self, storage: &AccountStorageEntry) -> bool {
+        self.cold_storage
+            .as_ref()
+            .map(|cold_storage| {
+                let path = storage.get_path();
+                cold_storage
+                    .paths
+                    .iter()
+                    .any(|cold_path| path.starts_with(cold_path))
+            })
+            .unwrap_or_default()
+    }
+
+    /// the format to rewrite the finished ancient append vec 'storage' in, if any
+    /// Ancient append vecs on the cold storage paths are always compressed.
+    fn ancient_storage_compression_for(
+        &self,
+        storage: &AccountStorageEntry,
+    ) -> Option<AppendVecCompression> {
+        match &self.cold_storage {
+            Some(cold_storage) if self.is_cold_storage(storage) => Some(cold_storage.compression),
+            _ => self.ancient_storage_compression,
+        }
+    }
+
+    /// Compress finished ancient append vecs in 'sorted_slots' and release the decompressed
+    /// blocks held by ones that are already compressed.
+    /// At most one append vec is compressed per call to bound the work done in each pass.
+    fn compress_ancient_storages(
+        &self,
+        sorted_slots: &[Slot],
+        current_ancient: &CurrentAncientAppendVec,
+    ) {
+        if self.ancient_storage_compression.is_none() && self.cold_storage.is_none() {
+            return;
+        }
+        let mut compressed_one = false;
+        for slot in sorted_slots {
+            let Some(storage) = self.storage.get_slot_storage_entry(*slot) else {
+                continue;
+            };
+            if storage.accounts.is_compressed() {
+                self.release_decompressed_accounts(&storage);
+            } else if !compressed_one
+                && is_ancient(&storage.accounts)
+                && current_ancient
+                    .slot_and_append_vec
+                    .as_ref()
+                    .map(|(current_slot, _)| current_slot != slot)
+                    .unwrap_or(true)
+            {
+                // the current ancient append vec may still be appended to on the next pass
+                if let Some(compression) = self.ancient_storage_compression_for(&storage) {
+                    compressed_one = self.compress_storage(&storage, compression);
+                }
+            }
+        }
+    }
+
+    /// Rewrite 'storage' in the compressed format and put it in the storage map in place of the original.
+    /// Accounts keep their offsets and the append vec keeps its id, so the accounts index does not change.
+    /// The compressed file is written next to the original, which is removed once it is no longer referenced.
+    /// return true if 'storage' was compressed
+    fn compress_storage(
+        &self,
+        storage: &Arc<AccountStorageEntry>,
+        compression: AppendVecCompression,
+    ) -> bool {
+        let slot = storage.slot();
+        let path = storage.get_path().with_file_name(format!(
+            "{}.compressed",
+            AppendVec::file_name(slot, storage.append_vec_id())
+        ));
+        let (compressed, compress_us) = measure_us!(storage.accounts.compress(&path, compression));
+        self.shrink_ancient_stats
+            .compress_us
+            .fetch_add(compress_us, Ordering::Relaxed);
+        match compressed {
+            Ok(accounts) => {
+                self.storage
+                    .replace(slot, Arc::new(storage.with_accounts(accounts)));
+                self.shrink_ancient_stats
+                    .ancient_compressed
+                    .fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(err) => {
+                warn!("ancient_append_vec: failed to compress slot {slot}: {err}");
+                false
+            }
+        }
+    }
+
+    /// Blocks of a compressed append vec stay decompressed in memory until the append vec is dropped.
+    /// Release them by putting a freshly opened instance of the same file in the storage map.
+    /// Readers still holding the previous instance keep using it until they are done.
+    fn release_decompressed_accounts(&self, storage: &Arc<AccountStorageEntry>) {
+        let decompressed_bytes = storage.accounts.decompressed_bytes();
+        if decompressed_bytes == 0 {
+            return;
+        }
+        if let Some(accounts) = storage.accounts.reopen_compressed() {
+            self.storage
+                .replace(storage.slot(), Arc::new(storage.with_accounts(accounts)));
+            self.shrink_ancient_stats
+                .compressed_bytes_released
+                .fetch_add(decompressed_bytes as u64, Ordering::Relaxed);
+        }
+    }
+
     // Reads all accounts in given slot's AppendVecs and filter only to alive,
     // then create a minimum AppendVec filled with the alive.
     fn shrink_slot_forced(&self, slot: Slot) {
@@ -4285,11 +4584,11 @@ impl AccountsDb {
         self.storage
             .get_slot_storage_entry(slot)
             .map(|storage| {
+                let mut sizes = Vec::default();
                 storage
                     .accounts
-                    .account_iter()
-                    .map(|account| account.stored_size)
-                    .collect()
+                    .scan_accounts(|account| sizes.push(account.stored_size));
+                sizes
             })
             .unwrap_or_default()
     }
@@ -4379,6 +4678,14 @@ impl AccountsDb {
                 .ancient_scanned
                 .fetch_add(1, Ordering::Relaxed);
 
+            if self.is_cold_slot(slot) && !self.is_cold_storage(storage) {
+                // the accounts in this ancient append vec are old enough to be rewritten to the cold storage paths
+                self.shrink_ancient_stats
+                    .ancient_moved_to_cold
+                    .fetch_add(1, Ordering::Relaxed);
+                return true;
+            }
+
             // randomly shrink ancient slots
             // this exercises the ancient shrink code more often
             let written_bytes = storage.written_bytes();
@@ -4406,6 +4713,11 @@ impl AccountsDb {
                     .fetch_add(1, Ordering::Relaxed);
                 return true;
             }
+            if storage.accounts.is_compressed() {
+                // compressed append vecs are read-only, so accounts from other slots go into a new ancient append vec
+                *current_ancient = CurrentAncientAppendVec::default();
+                return false;
+            }
             // this slot is ancient and can become the 'current' ancient for other slots to be squashed into
             *current_ancient = CurrentAncientAppendVec::new(slot, Arc::clone(storage));
             return false; // we're done with this slot - this slot IS the ancient append vec
@@ -4432,7 +4744,7 @@ impl AccountsDb {
         let mut ancient_slot_pubkeys = AncientSlotPubkeys::default();
 
         let len = sorted_slots.len();
-        for slot in sorted_slots {
+        for slot in sorted_slots.iter().copied() {
             let old_storage = match self.get_storage_to_move_to_ancient_append_vec(
                 slot,
                 &mut current_ancient,
@@ -4465,6 +4777,8 @@ impl AccountsDb {
 
         self.handle_dropped_roots_for_ancient(dropped_roots);
 
+        self.compress_ancient_storages(&sorted_slots, &current_ancient);
+
         total.stop();
         self.shrink_ancient_stats
             .total_us
@@ -4501,6 +4815,14 @@ impl AccountsDb {
             return; // skipping slot with no useful accounts to write
         }
 
+        if current_ancient.slot_and_append_vec.is_some()
+            && self.is_cold_slot(slot) != self.is_cold_storage(current_ancient.append_vec())
+        {
+            // accounts in 'slot' belong on a different set of paths than the current ancient append vec,
+            // so start a new ancient append vec at 'slot'
+            *current_ancient = CurrentAncientAppendVec::default();
+        }
+
         let mut stats_sub = ShrinkStatsSub::default();
         let (mut shrink_in_progress, create_and_insert_store_elapsed_us) =
             measure_us!(current_ancient.create_if_necessary(slot, self));
@@ -4817,12 +5139,7 @@ impl AccountsDb {
     where
         F: FnMut(Option<(&Pubkey, AccountSharedData, Slot)>),
     {
-        let key = match &index_key {
-            IndexKey::ProgramId(key) => key,
-            IndexKey::SplTokenMint(key) => key,
-            IndexKey::SplTokenOwner(key) => key,
-        };
-        if !self.account_indexes.include_key(key) {
+        if !self.account_indexes.include_key(index_key.key()) {
             // the requested key was not indexed in the secondary index, so do a normal scan
             let used_index = false;
             self.scan_accounts(ancestors, bank_id, scan_func, config)?;
@@ -4889,10 +5206,9 @@ impl AccountsDb {
             // been flushed. This is guaranteed because we only remove the rooted slot from
             // the cache *after* we've finished flushing in `flush_slot_cache`.
             if let Some(storage) = self.storage.get_slot_storage_entry(slot) {
-                storage
-                    .accounts
-                    .account_iter()
-                    .for_each(|account| storage_scan_func(&retval, LoadedAccount::Stored(account)));
+                storage.accounts.scan_accounts(|account| {
+                    storage_scan_func(&retval, LoadedAccount::Stored(account))
+                });
             }
 
             ScanStorageResult::Stored(retval)
@@ -5103,7 +5419,8 @@ impl AccountsDb {
         loop {
             let account_accessor = self.get_account_accessor(slot, pubkey, &storage_location);
             match account_accessor {
-                LoadedAccountAccessor::Cached(Some(_)) | LoadedAccountAccessor::Stored(Some(_)) => {
+                LoadedAccountAccessor::Cached(Some(_))
+                | LoadedAccountAccessor::Stored(Some(_), _) => {
                     // Great! There was no race, just return :) This is the most usual situation
                     return Some((account_accessor, slot));
                 }
@@ -5131,7 +5448,7 @@ impl AccountsDb {
                         }
                     }
                 }
-                LoadedAccountAccessor::Stored(None) => {
+                LoadedAccountAccessor::Stored(None, _) => {
                     match load_hint {
                         LoadHint::FixedMaxRoot => {
                             // When running replay on the validator, or banking stage on the leader,
@@ -5400,7 +5717,7 @@ impl AccountsDb {
                     .storage
                     .get_account_storage_entry(slot, *store_id)
                     .map(|account_storage_entry| (account_storage_entry, *offset));
-                LoadedAccountAccessor::Stored(maybe_storage_entry)
+                LoadedAccountAccessor::Stored(maybe_storage_entry, None)
             }
         }
     }
@@ -5427,7 +5744,12 @@ impl AccountsDb {
         let mut avail = 0;
         let mut recycle_stores = self.recycle_stores.write().unwrap();
         for (i, (_recycled_time, store)) in recycle_stores.iter().enumerate() {
-            if Arc::strong_count(store) == 1 {
+            // stores on the cold storage paths are left to expire so they are not reused for new writes
+            // and compressed stores are read-only
+            if Arc::strong_count(store) == 1
+                && !self.is_cold_storage(store)
+                && !store.accounts.is_compressed()
+            {
                 max = std::cmp::max(store.accounts.capacity(), max);
                 min = std::cmp::min(store.accounts.capacity(), min);
                 avail += 1;
@@ -6867,6 +7189,176 @@ impl AccountsDb {
         Ok((accounts_hash, total_lamports))
     }
 
+    /// Calculate the `AccountsHashBins` summary of the accounts at 'slot', with the accounts split into
+    /// 'num_bins' bins by pubkey. 'num_bins' is a power of two up to PUBKEY_BINS_FOR_CALCULATING_HASHES.
+    /// The bins hold the same accounts and account hashes as the full accounts hash, see
+    /// `scan_storages_by_pubkey_bin`.
+    pub fn calculate_accounts_hash_bins(
+        &self,
+        slot: Slot,
+        config: &CalcAccountsHashConfig<'_>,
+        num_bins: usize,
+    ) -> Result<AccountsHashBins, BankHashVerificationError> {
+        let bins = self.scan_storages_by_pubkey_bin(
+            slot,
+            config,
+            |accounts_hasher, data_sections_by_pubkey, stats| {
+                accounts_hasher.calculate_hash_bins(data_sections_by_pubkey, num_bins, stats)
+            },
+        )?;
+        Ok(AccountsHashBins::new(slot, bins))
+    }
+
+    /// Calculate the `AccountsHashBins` summary of the accounts in 'storages', which hold the
+    /// accounts at 'slot', the same way as `calculate_accounts_hash_bins` does.
+    pub fn calculate_accounts_hash_bins_from_storages(
+        &self,
+        slot: Slot,
+        config: &CalcAccountsHashConfig<'_>,
+        storages: &SortedStorages<'_>,
+        num_bins: usize,
+    ) -> Result<AccountsHashBins, BankHashVerificationError> {
+        let bins = self.scan_sorted_storages_by_pubkey_bin(
+            config,
+            storages,
+            |accounts_hasher, data_sections_by_pubkey, stats| {
+                accounts_hasher.calculate_hash_bins(data_sections_by_pubkey, num_bins, stats)
+            },
+        )?;
+        Ok(AccountsHashBins::new(slot, bins))
+    }
+
+    /// The number of bins of the summary kept for each new snapshot, if summaries are kept
+    pub fn num_snapshot_accounts_hash_bins(&self) -> Option<usize> {
+        self.accounts_hash_bins
+    }
+
+    /// Keep the summary of the snapshot at 'accounts_hash_bins.slot', so other nodes loading the
+    /// snapshot can compare their accounts with it.
+    /// The summaries of the MAX_SNAPSHOT_ACCOUNTS_HASH_BINS newest snapshots are kept, along with
+    /// the summary of the full snapshot at 'full_snapshot_slot' that the newest snapshot is based on.
+    pub fn add_snapshot_accounts_hash_bins(
+        &self,
+        accounts_hash_bins: AccountsHashBins,
+        full_snapshot_slot: Slot,
+    ) {
+        let mut snapshot_accounts_hash_bins = self.snapshot_accounts_hash_bins.lock().unwrap();
+        snapshot_accounts_hash_bins.insert(accounts_hash_bins.slot, accounts_hash_bins);
+        let newest_slots = snapshot_accounts_hash_bins
+            .keys()
+            .rev()
+            .take(MAX_SNAPSHOT_ACCOUNTS_HASH_BINS)
+            .copied()
+            .collect::<HashSet<_>>();
+        snapshot_accounts_hash_bins
+            .retain(|slot, _| *slot == full_snapshot_slot || newest_slots.contains(slot));
+    }
+
+    /// Get the summary kept for the snapshot at 'slot'
+    pub fn get_snapshot_accounts_hash_bins(&self, slot: Slot) -> Option<AccountsHashBins> {
+        self.snapshot_accounts_hash_bins
+            .lock()
+            .unwrap()
+            .get(&slot)
+            .cloned()
+    }
+
+    /// return the accounts at 'slot' in bin 'bin' of 'num_bins', sorted by pubkey
+    /// These are the accounts that make up the hash of the bin in `calculate_accounts_hash_bins`.
+    pub fn get_accounts_hash_bin_entries(
+        &self,
+        slot: Slot,
+        config: &CalcAccountsHashConfig<'_>,
+        num_bins: usize,
+        bin: usize,
+    ) -> Result<Vec<AccountHashEntry>, BankHashVerificationError> {
+        self.scan_storages_by_pubkey_bin(
+            slot,
+            config,
+            |accounts_hasher, data_sections_by_pubkey, _stats| {
+                accounts_hasher.get_hash_bin_entries(data_sections_by_pubkey, num_bins, bin)
+            },
+        )
+    }
+
+    /// Scan the storages up to 'slot' the same way as `calculate_accounts_hash_from_storages` does
+    /// for the full accounts hash, and call 'process' with the scanned accounts in
+    /// PUBKEY_BINS_FOR_CALCULATING_HASHES bins.
+    /// The scan goes through the full accounts hash cache, so it reuses the scans of an earlier
+    /// accounts hash calculation. 'slot' has to be flushed from the write cache.
+    fn scan_storages_by_pubkey_bin<T: Send>(
+        &self,
+        slot: Slot,
+        config: &CalcAccountsHashConfig<'_>,
+        process: impl FnOnce(&AccountsHasher, Vec<SortedDataByPubkey<'_>>, &mut HashStats) -> T + Send,
+    ) -> Result<T, BankHashVerificationError> {
+        let (combined_maps, slots) = self.get_snapshot_storages(..=slot, config.ancestors);
+        let storages = SortedStorages::new_with_slots(
+            combined_maps.iter().zip(slots.into_iter()),
+            self.accounts_index.min_alive_root(),
+            Some(slot),
+        );
+        self.scan_sorted_storages_by_pubkey_bin(config, &storages, process)
+    }
+
+    /// Scan 'storages' the same way as `calculate_accounts_hash_from_storages` does, and call
+    /// 'process' with the scanned accounts in PUBKEY_BINS_FOR_CALCULATING_HASHES bins.
+    fn scan_sorted_storages_by_pubkey_bin<T: Send>(
+        &self,
+        config: &CalcAccountsHashConfig<'_>,
+        storages: &SortedStorages<'_>,
+        process: impl FnOnce(&AccountsHasher, Vec<SortedDataByPubkey<'_>>, &mut HashStats) -> T + Send,
+    ) -> Result<T, BankHashVerificationError> {
+        let mut stats = HashStats::default();
+        let scan_and_process = || {
+            let cache_hash_data = Self::get_cache_hash_data(
+                self.full_accounts_hash_cache_path.clone(),
+                config,
+                storages.max_slot_inclusive(),
+            );
+            let bounds = Range {
+                start: 0,
+                end: PUBKEY_BINS_FOR_CALCULATING_HASHES,
+            };
+            let accounts_hasher = AccountsHasher {
+                filler_account_suffix: if self.filler_accounts_config.count > 0 {
+                    self.filler_account_suffix
+                } else {
+                    None
+                },
+                zero_lamport_accounts: CalcAccountsHashFlavor::Full.zero_lamport_accounts(),
+            };
+            let cache_hash_data_files = self.scan_snapshot_stores_with_cache(
+                &cache_hash_data,
+                storages,
+                &mut stats,
+                PUBKEY_BINS_FOR_CALCULATING_HASHES,
+                &bounds,
+                config,
+                accounts_hasher.filler_account_suffix.as_ref(),
+            )?;
+            let cache_hash_intermediates = cache_hash_data_files
+                .iter()
+                .map(|d| d.get_cache_hash_data())
+                .collect::<Vec<_>>();
+            let data_sections_by_pubkey = AccountsHasher::get_binned_data(
+                &cache_hash_intermediates,
+                PUBKEY_BINS_FOR_CALCULATING_HASHES,
+                &bounds,
+            );
+            Ok(process(
+                &accounts_hasher,
+                data_sections_by_pubkey,
+                &mut stats,
+            ))
+        };
+        if config.use_bg_thread_pool {
+            self.thread_pool_clean.install(scan_and_process)
+        } else {
+            scan_and_process()
+        }
+    }
+
     pub fn update_accounts_hash_for_tests(
         &self,
         slot: Slot,
@@ -6891,7 +7383,7 @@ impl AccountsDb {
     where
         S: AppendVecScan,
     {
-        storage.accounts.account_iter().for_each(|account| {
+        storage.accounts.scan_accounts(|account| {
             if scanner.filter(account.pubkey()) {
                 scanner.found_account(&LoadedAccount::Stored(account))
             }
@@ -8137,11 +8629,11 @@ impl AccountsDb {
                     .into_par_iter()
                     .map(|store| {
                         let slot = store.slot();
+                        let mut slot_pubkeys = Vec::default();
                         store
                             .accounts
-                            .account_iter()
-                            .map(|account| (slot, *account.pubkey()))
-                            .collect::<Vec<(Slot, Pubkey)>>()
+                            .scan_accounts(|account| slot_pubkeys.push((slot, *account.pubkey())));
+                        slot_pubkeys
                     })
                     .flatten()
                     .collect::<HashSet<_>>()
@@ -8806,12 +9298,14 @@ impl AccountsDb {
         );
     }
 
+    /// `snapshot_storages` describes the snapshot the storages were loaded from, if any
     #[allow(clippy::needless_collect)]
     pub fn generate_index(
         &self,
         limit_load_slot_count_from_snapshot: Option<usize>,
         verify: bool,
         genesis_config: &GenesisConfig,
+        snapshot_storages: Option<&SnapshotStorageIds>,
     ) -> IndexGenerationInfo {
         let mut slots = self.storage.all_slots();
         #[allow(clippy::stable_sort_primitive)]
@@ -8827,7 +9321,24 @@ impl AccountsDb {
             genesis_config.slots_per_year(),
             genesis_config.rent,
         );
-        let accounts_data_len = AtomicU64::new(0);
+        // If the disk index persisted on the previous shutdown can be reused, it already refers to
+        // every storage of the snapshot, so there is nothing to index.
+        let restart = match snapshot_storages {
+            Some(snapshot_storages) if limit_load_slot_count_from_snapshot.is_none() && !verify => {
+                self.reuse_restored_disk_index(&slots, snapshot_storages)
+            }
+            _ => {
+                self.discard_restored_disk_index();
+                None
+            }
+        };
+        let slots_to_index: &[Slot] = if restart.is_some() { &[] } else { &slots };
+        let accounts_data_len = AtomicU64::new(
+            restart
+                .as_ref()
+                .map(|restart| restart.accounts_data_len)
+                .unwrap_or_default(),
+        );
 
         let rent_paying_accounts_by_partition =
             Mutex::new(RentPayingAccountsByPartition::new(&schedule));
@@ -8842,8 +9353,20 @@ impl AccountsDb {
                     .set_startup(Startup::StartupWithExtraThreads);
             }
             let storage_info = StorageSizeAndCountMap::default();
+            if let Some(restart) = restart.as_ref().filter(|_| pass == 0) {
+                // the reused storages are not scanned, so use what was persisted with the index
+                restart.storages.values().for_each(|storage| {
+                    storage_info.insert(
+                        storage.id,
+                        StorageSizeAndCount {
+                            stored_size: storage.alive_bytes,
+                            count: storage.count,
+                        },
+                    );
+                });
+            }
             let total_processed_slots_across_all_threads = AtomicU64::new(0);
-            let outer_slots_len = slots.len();
+            let outer_slots_len = slots_to_index.len();
             let threads = if self.accounts_index.is_disk_index_enabled() {
                 // these write directly to disk, so the more threads, the better
                 num_cpus::get()
@@ -8858,7 +9381,7 @@ impl AccountsDb {
             let amount_to_top_off_rent = AtomicU64::new(0);
             let total_duplicates = AtomicU64::new(0);
             let storage_info_timings = Mutex::new(GenerateIndexTimings::default());
-            let scan_time: u64 = slots
+            let scan_time: u64 = slots_to_index
                 .par_chunks(chunk_size)
                 .map(|slots| {
                     let mut log_status = MultiThreadProgress::new(
@@ -8941,6 +9464,10 @@ impl AccountsDb {
                             lookup_time.as_us()
                         };
                         insertion_time_us.fetch_add(insert_us, Ordering::Relaxed);
+                        if let Some(storage) = &storage {
+                            // the accounts just indexed were loaded from decompressed blocks of compressed storages
+                            self.release_decompressed_accounts(storage);
+                        }
                     }
                     scan_time_sum
                 })
@@ -9061,10 +9588,204 @@ impl AccountsDb {
 
         IndexGenerationInfo {
             accounts_data_len: accounts_data_len.load(Ordering::Relaxed),
-            rent_paying_accounts_by_partition: rent_paying_accounts_by_partition
-                .into_inner()
-                .unwrap(),
+            rent_paying_accounts_by_partition: if restart.is_some() {
+                // the rent paying accounts in the reused storages are unknown
+                RentPayingAccountsByPartition::default()
+            } else {
+                rent_paying_accounts_by_partition.into_inner().unwrap()
+            },
+        }
+    }
+
+    /// Keep the disk index for the next start of the validator.
+    /// 'root' is the latest root, of which a full snapshot has just been taken, and
+    /// 'accounts_data_len' the total data len of all accounts as of 'root'.
+    /// All unrooted slots are purged and every index entry is written to disk. Then the snapshot
+    /// and the storages the index refers to are recorded next to it, so the index is only reused
+    /// when exactly that snapshot is loaded.
+    /// Nothing may be stored or cleaned afterwards.
+    /// Does nothing unless the disk index is configured to be persisted.
+    pub fn persist_disk_index(&self, root: Slot, accounts_data_len: u64) -> IoResult<()> {
+        let Some(restart_file) = self.accounts_index.disk_index_restart_file() else {
+            return Ok(());
+        };
+        if !self.is_latest_root(root) {
+            return Err(IoError::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "slot {root} is not the latest root {}",
+                    self.accounts_index.max_root_inclusive()
+                ),
+            ));
+        }
+        let accounts_hash = self.get_accounts_hash(root).ok_or_else(|| {
+            IoError::new(
+                std::io::ErrorKind::Other,
+                format!("there is no full snapshot at slot {root}"),
+            )
+        })?;
+        self.flush_accounts_cache(true, Some(root));
+        let mut unrooted_slots = self
+            .accounts_cache
+            .slots()
+            .into_iter()
+            .chain(self.storage.all_slots())
+            .filter(|slot| *slot > root || !self.accounts_index.is_alive_root(*slot))
+            .collect::<Vec<_>>();
+        unrooted_slots.sort_unstable();
+        unrooted_slots.dedup();
+        self.purge_slots_from_cache_and_store(unrooted_slots.iter(), &PurgeStats::default(), false);
+        if self.accounts_cache.num_slots() > 0 {
+            return Err(IoError::new(
+                std::io::ErrorKind::Other,
+                "accounts write cache could not be emptied",
+            ));
+        }
+
+        self.accounts_index.persist_disk_index()?;
+        let restart = DiskIndexRestart {
+            slot: root,
+            accounts_hash,
+            accounts_data_len,
+            storages: self
+                .storage
+                .iter()
+                .map(|(slot, storage)| {
+                    (
+                        slot,
+                        PersistedStorage {
+                            id: storage.append_vec_id(),
+                            len: storage.accounts.len(),
+                            stored_count: storage.approx_stored_count(),
+                            count: storage.count(),
+                            alive_bytes: storage.alive_bytes(),
+                        },
+                    )
+                })
+                .collect(),
+        };
+        restart.write(restart_file)?;
+        info!(
+            "persisted accounts index for the snapshot at slot {root} with {} storages",
+            restart.storages.len()
+        );
+        Ok(())
+    }
+
+    /// true if `slot` is the latest root, the only one the disk index can be persisted at
+    pub fn is_latest_root(&self, slot: Slot) -> bool {
+        slot == self.accounts_index.max_root_inclusive()
+    }
+
+    /// true if the disk index is kept on shutdown. See `persist_disk_index`.
+    pub fn is_disk_index_persisted(&self) -> bool {
+        self.accounts_index.disk_index_restart_file().is_some()
+    }
+
+    /// Clear the disk index if it was reopened from the files persisted on the previous shutdown.
+    /// Must be called before anything is stored if the index is not generated from a snapshot.
+    pub fn discard_restored_disk_index(&self) {
+        if let Some(restart_file) = self.accounts_index.disk_index_restart_file() {
+            let _ = std::fs::remove_file(restart_file);
+        }
+        if self.accounts_index.is_disk_index_restored() {
+            self.accounts_index.clear_disk_index();
+        }
+    }
+
+    /// Reuse the disk index persisted by `persist_disk_index` on the previous shutdown.
+    /// This is only possible if the storages were loaded from the snapshot the index was persisted
+    /// with. They get back the ids the index refers to.
+    /// Returns what was persisted with the index, or None if the disk index was cleared instead.
+    fn reuse_restored_disk_index(
+        &self,
+        slots: &[Slot],
+        snapshot_storages: &SnapshotStorageIds,
+    ) -> Option<DiskIndexRestart> {
+        let restart_file = self.accounts_index.disk_index_restart_file()?;
+        if !self.accounts_index.is_disk_index_restored() {
+            return None;
+        }
+        let restart = DiskIndexRestart::read(restart_file).map_err(|err| err.to_string());
+        let _ = std::fs::remove_file(restart_file);
+        if let Err(err) = restart
+            .as_ref()
+            .map_err(Clone::clone)
+            .and_then(|restart| self.check_restored_disk_index(restart, slots, snapshot_storages))
+        {
+            info!("not reusing the persisted accounts index: {err}");
+            self.accounts_index.clear_disk_index();
+            return None;
+        }
+        let restart = restart.unwrap();
+
+        let max_persisted_id = restart
+            .storages
+            .values()
+            .map(|storage| storage.id)
+            .max()
+            .unwrap_or_default();
+        self.next_id
+            .fetch_max(max_persisted_id.saturating_add(1), Ordering::AcqRel);
+        for slot in slots {
+            let storage = self.storage.remove(slot).unwrap();
+            storage
+                .id
+                .store(restart.storages[slot].id, Ordering::Release);
+            self.storage.insert(*slot, storage);
+        }
+        info!(
+            "reusing the persisted accounts index for the snapshot at slot {}",
+            restart.slot
+        );
+        Some(restart)
+    }
+
+    /// return an error describing why the restored disk index does not match the snapshot the
+    /// storages were loaded from
+    fn check_restored_disk_index(
+        &self,
+        restart: &DiskIndexRestart,
+        slots: &[Slot],
+        snapshot_storages: &SnapshotStorageIds,
+    ) -> Result<(), String> {
+        if !self.account_indexes.is_empty() {
+            return Err("secondary indexes are not persisted".to_string());
+        }
+        if restart.slot != snapshot_storages.slot {
+            return Err(format!(
+                "the index was persisted with the snapshot at slot {}, not {}",
+                restart.slot, snapshot_storages.slot
+            ));
+        }
+        if self.get_accounts_hash(restart.slot) != Some(restart.accounts_hash) {
+            return Err(format!(
+                "the snapshot at slot {} is not the one the index was persisted with",
+                restart.slot
+            ));
+        }
+        if slots.len() != restart.storages.len() {
+            return Err(format!(
+                "{} storages were loaded, but the index refers to {}",
+                slots.len(),
+                restart.storages.len()
+            ));
+        }
+        for slot in slots {
+            let storage = self.storage.get_slot_storage_entry(*slot);
+            match (
+                storage,
+                restart.storages.get(slot),
+                snapshot_storages.ids.get(slot),
+            ) {
+                (Some(storage), Some(persisted), Some(snapshot_id))
+                    if *snapshot_id == persisted.id
+                        && storage.accounts.len() == persisted.len
+                        && storage.approx_stored_count() == persisted.stored_count => {}
+                _ => return Err(format!("the storage for slot {slot} has changed")),
+            }
         }
+        Ok(())
     }
 
     /// Startup processes can consume large amounts of memory while inserting accounts into the index as fast as possible.
@@ -9128,6 +9849,7 @@ impl AccountsDb {
                         .get_account_storage_entry(*slot, account_info.store_id());
                     let mut accessor = LoadedAccountAccessor::Stored(
                         maybe_storage_entry.map(|entry| (entry, account_info.offset())),
+                        None,
                     );
                     let loaded_account = accessor.check_and_get_loaded_account();
                     accounts_data_len_from_duplicates += loaded_account.data().len();
@@ -9418,10 +10140,10 @@ pub mod tests {
         super::*,
         crate::{
             accounts::Accounts,
-            accounts_hash::MERKLE_FANOUT,
+            accounts_hash::{AccountsHashBin, MERKLE_FANOUT},
             accounts_index::{
                 tests::*, AccountIndex, AccountSecondaryIndexes,
-                AccountSecondaryIndexesIncludeExclude, ReadAccountMapEntry, RefCount,
+                AccountSecondaryIndexesIncludeExclude, IndexLimitMb, ReadAccountMapEntry, RefCount,
             },
             append_vec::{test_utils::TempFile, AccountMeta, StoredMeta},
             cache_hash_data_stats::CacheHashDataStats,
@@ -11665,6 +12387,82 @@ pub mod tests {
         assert_eq!(accounts.accounts_index.uncleaned_roots_len(), 0);
     }
 
+    #[test]
+    fn test_persist_disk_index() {
+        solana_logger::setup();
+        // the storages of the restored snapshot get new ids, like when unpacking an archive
+        let id_offset = 1000;
+        for matching_snapshot in [true, false] {
+            let index_dir = TempDir::new().unwrap();
+            let accounts_db_config = AccountsDbConfig {
+                index: Some(AccountsIndexConfig {
+                    drives: Some(vec![index_dir.path().to_path_buf()]),
+                    index_limit_mb: IndexLimitMb::Limit(1),
+                    persist_disk_index: true,
+                    ..ACCOUNTS_INDEX_CONFIG_FOR_TESTING
+                }),
+                ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+            };
+            let db = AccountsDb::new_with_config(
+                Vec::new(),
+                &ClusterType::Development,
+                AccountSecondaryIndexes::default(),
+                AccountShrinkThreshold::default(),
+                Some(accounts_db_config.clone()),
+                None,
+                &Arc::default(),
+            );
+            assert!(db.is_disk_index_persisted());
+
+            let pubkeys = (0..10).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+            let root = 2;
+            for slot in 0..=root {
+                // every slot updates fewer accounts, so loads have to pick the latest root
+                for (i, pubkey) in pubkeys.iter().enumerate().skip(slot as usize * 4) {
+                    let account =
+                        AccountSharedData::new(slot * 100 + i as u64 + 1, 0, &Pubkey::default());
+                    db.store_for_tests(slot, &[(pubkey, &account)]);
+                }
+                db.calculate_accounts_delta_hash(slot);
+                db.add_root_and_flush_write_cache(slot);
+            }
+            db.update_accounts_hash_for_tests(root, &linear_ancestors(root + 1), false, false);
+            db.persist_disk_index(root, 0).unwrap();
+            if !matching_snapshot {
+                // a snapshot other than the one the index was persisted with
+                db.set_accounts_hash_for_tests(root, AccountsHash(Hash::new_unique()));
+            }
+
+            let restored =
+                crate::serde_snapshot::reconstruct_accounts_db_via_serialization_with_config(
+                    &db,
+                    root,
+                    accounts_db_config,
+                    id_offset,
+                );
+            for slot in 0..=root {
+                let id = db.get_storage_for_slot(slot).unwrap().append_vec_id();
+                let expected_id = if matching_snapshot {
+                    // the index refers to the ids the storages had when it was persisted
+                    id
+                } else {
+                    // the index was generated from the storages
+                    id + id_offset
+                };
+                assert_eq!(
+                    restored.get_storage_for_slot(slot).unwrap().append_vec_id(),
+                    expected_id
+                );
+            }
+            let ancestors = Ancestors::default();
+            for pubkey in &pubkeys {
+                let loaded = restored.load_without_fixed_root(&ancestors, pubkey);
+                assert!(loaded.is_some());
+                assert_eq!(loaded, db.load_without_fixed_root(&ancestors, pubkey));
+            }
+        }
+    }
+
     #[test]
     fn test_accounts_db_serialize1() {
         for pass in 0..2 {
@@ -12467,6 +13265,168 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_calculate_accounts_hash_bins() {
+        solana_logger::setup();
+        let db = AccountsDb::new(Vec::new(), &ClusterType::Development);
+
+        let some_slot: Slot = 0;
+        let ancestors = vec![(some_slot, 0)].into_iter().collect();
+        let mut keys = (0..100)
+            .map(|_| solana_sdk::pubkey::new_rand())
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        // the first account has zero lamports, so it is not part of the accounts hash
+        let accounts = keys
+            .iter()
+            .enumerate()
+            .map(|(lamports, key)| AccountSharedData::new(lamports as u64, 0, key))
+            .collect::<Vec<_>>();
+        db.store_for_tests(
+            some_slot,
+            &keys.iter().zip(accounts.iter()).collect::<Vec<_>>(),
+        );
+        db.add_root_and_flush_write_cache(some_slot);
+        let config = CalcAccountsHashConfig {
+            ancestors: Some(&ancestors),
+            ..CalcAccountsHashConfig::default()
+        };
+        let (accounts_hash, total_lamports) = db
+            .calculate_accounts_hash(CalcAccountsHashDataSource::Storages, some_slot, &config)
+            .unwrap();
+
+        let num_bins = 4;
+        let bins = db
+            .calculate_accounts_hash_bins(some_slot, &config, num_bins)
+            .unwrap();
+        assert_eq!(bins.slot, some_slot);
+        assert_eq!(bins.bins.len(), num_bins);
+        assert_eq!(bins.num_accounts(), keys.len() - 1);
+        assert_eq!(bins.lamports(), total_lamports);
+        assert_eq!(bins.mismatched_bins(&bins), Some(vec![]));
+
+        // the bins hold the accounts of the accounts hash, in pubkey order
+        let entries = (0..num_bins)
+            .map(|bin| {
+                db.get_accounts_hash_bin_entries(some_slot, &config, num_bins, bin)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        for (bin, entries) in entries.iter().enumerate() {
+            assert_eq!(bins.bins[bin], AccountsHashBin::new(entries));
+        }
+        let hashes = entries
+            .iter()
+            .map(|entries| entries.iter().map(|entry| entry.hash).collect())
+            .collect();
+        assert_eq!(AccountsHasher::calculate_hash(hashes).0, accounts_hash.0);
+        assert_eq!(
+            entries
+                .concat()
+                .iter()
+                .map(|entry| entry.pubkey)
+                .collect::<Vec<_>>(),
+            keys[1..]
+        );
+
+        // changing an account only changes the bin holding it
+        let changed_slot = some_slot + 1;
+        let changed_key = keys[50];
+        db.store_for_tests(
+            changed_slot,
+            &[(
+                &changed_key,
+                &AccountSharedData::new(1_000, 0, &changed_key),
+            )],
+        );
+        db.add_root_and_flush_write_cache(changed_slot);
+        let changed_ancestors = vec![(some_slot, 0), (changed_slot, 1)]
+            .into_iter()
+            .collect();
+        let changed_config = CalcAccountsHashConfig {
+            ancestors: Some(&changed_ancestors),
+            ..CalcAccountsHashConfig::default()
+        };
+        let changed_bins = db
+            .calculate_accounts_hash_bins(changed_slot, &changed_config, num_bins)
+            .unwrap();
+        assert_ne!(bins.root, changed_bins.root);
+        let changed_bin = PubkeyBinCalculator24::new(num_bins).bin_from_pubkey(&changed_key);
+        assert_eq!(bins.mismatched_bins(&changed_bins), Some(vec![changed_bin]));
+        let (storages, _slots) = db.get_snapshot_storages(..=changed_slot, None);
+        assert_eq!(
+            db.calculate_accounts_hash_bins_from_storages(
+                changed_slot,
+                &changed_config,
+                &SortedStorages::new(&storages),
+                num_bins,
+            )
+            .unwrap(),
+            changed_bins
+        );
+        assert_eq!(
+            bins.mismatched_bins(
+                &db.calculate_accounts_hash_bins(changed_slot, &changed_config, num_bins * 2)
+                    .unwrap()
+            ),
+            None
+        );
+
+        let changed_entries = db
+            .get_accounts_hash_bin_entries(changed_slot, &changed_config, num_bins, changed_bin)
+            .unwrap();
+        let mismatched =
+            AccountsHashBins::mismatched_accounts(&entries[changed_bin], &changed_entries);
+        assert_eq!(mismatched.len(), 1);
+        let (ours, theirs) = mismatched[0];
+        assert_eq!(ours.unwrap().pubkey, changed_key);
+        assert_eq!(ours.unwrap().lamports, 50);
+        assert_eq!(theirs.unwrap().pubkey, changed_key);
+        assert_eq!(theirs.unwrap().lamports, 1_000);
+    }
+
+    #[test]
+    fn test_add_snapshot_accounts_hash_bins() {
+        let db = AccountsDb::new(Vec::new(), &ClusterType::Development);
+        let summary = |slot| AccountsHashBins::new(slot, vec![AccountsHashBin::new(&[])]);
+
+        let full_snapshot_slot = 100;
+        db.add_snapshot_accounts_hash_bins(summary(full_snapshot_slot), full_snapshot_slot);
+        let incremental_snapshot_slots = (1..=MAX_SNAPSHOT_ACCOUNTS_HASH_BINS as Slot)
+            .map(|i| full_snapshot_slot + i * 10)
+            .collect::<Vec<_>>();
+        for slot in &incremental_snapshot_slots {
+            db.add_snapshot_accounts_hash_bins(summary(*slot), full_snapshot_slot);
+        }
+        // the full snapshot the incremental snapshots are based on is kept
+        assert_eq!(
+            db.get_snapshot_accounts_hash_bins(full_snapshot_slot),
+            Some(summary(full_snapshot_slot))
+        );
+        for slot in &incremental_snapshot_slots {
+            assert_eq!(
+                db.get_snapshot_accounts_hash_bins(*slot),
+                Some(summary(*slot))
+            );
+        }
+
+        // a new full snapshot replaces the oldest summaries
+        let new_full_snapshot_slot = incremental_snapshot_slots.last().unwrap() + 10;
+        db.add_snapshot_accounts_hash_bins(summary(new_full_snapshot_slot), new_full_snapshot_slot);
+        assert_eq!(db.get_snapshot_accounts_hash_bins(full_snapshot_slot), None);
+        assert_eq!(
+            db.get_snapshot_accounts_hash_bins(incremental_snapshot_slots[0]),
+            None
+        );
+        assert!(db
+            .get_snapshot_accounts_hash_bins(incremental_snapshot_slots[1])
+            .is_some());
+        assert!(db
+            .get_snapshot_accounts_hash_bins(new_full_snapshot_slot)
+            .is_some());
+        assert_eq!(db.get_snapshot_accounts_hash_bins(1), None);
+    }
+
     #[test]
     fn test_verify_bank_hash() {
         use BankHashVerificationError::*;
@@ -17269,6 +18229,181 @@ pub mod tests {
             .is_none());
     }
 
+    #[test]
+    fn test_combine_ancient_slots_cold_storage() {
+        solana_logger::setup();
+
+        let num_normal_slots = 2;
+        // build an ancient append vec at slot 'ancient_slot' on the accounts paths
+        let (mut db, ancient_slot) = get_one_ancient_append_vec_and_others(true, num_normal_slots);
+        let cold_dir = TempDir::new().unwrap();
+        let cold_storage = ColdStorageConfig {
+            paths: vec![cold_dir.path().to_path_buf()],
+            age_epochs: 1,
+            compression: AppendVecCompression::Lz4,
+        };
+        for path in cold_storage.storage_paths() {
+            std::fs::create_dir_all(path).unwrap();
+        }
+        db.cold_storage = Some(cold_storage);
+        let max_slot_inclusive = ancient_slot + (num_normal_slots as Slot);
+        let initial_accounts = get_all_accounts(&db, ancient_slot..(max_slot_inclusive + 1));
+        let ancient = db.storage.get_slot_storage_entry(ancient_slot).unwrap();
+        assert!(!db.is_cold_storage(&ancient));
+
+        // only 'ancient_slot' is at least one epoch older than the completed hash calculation
+        let epoch_schedule = EpochSchedule::default();
+        db.notify_accounts_hash_calculated_complete(
+            ancient_slot + 1 + epoch_schedule.slots_per_epoch,
+            &epoch_schedule,
+        );
+        assert!(db.is_cold_slot(ancient_slot));
+        assert!(!db.is_cold_slot(ancient_slot + 1));
+
+        db.combine_ancient_slots(
+            (ancient_slot..=max_slot_inclusive).collect(),
+            CAN_RANDOMLY_SHRINK_FALSE,
+        );
+        compare_all_accounts(
+            &initial_accounts,
+            &get_all_accounts(&db, ancient_slot..(max_slot_inclusive + 1)),
+        );
+        assert_eq!(
+            db.shrink_ancient_stats
+                .ancient_moved_to_cold
+                .load(Ordering::Relaxed),
+            1
+        );
+
+        // the ancient append vec was rewritten to the cold storage paths and compressed,
+        // since it is no longer appended to
+        let ancient = db.storage.get_slot_storage_entry(ancient_slot).unwrap();
+        assert!(is_ancient(&ancient.accounts));
+        assert!(db.is_cold_storage(&ancient));
+        assert!(ancient.get_path().starts_with(cold_dir.path()));
+        assert!(ancient.accounts.is_compressed());
+        assert_eq!(
+            db.shrink_ancient_stats
+                .ancient_compressed
+                .load(Ordering::Relaxed),
+            1
+        );
+
+        // the newer slots were squashed into a new ancient append vec on the accounts paths
+        let hot = db.storage.get_slot_storage_entry(ancient_slot + 1).unwrap();
+        assert!(is_ancient(&hot.accounts));
+        assert!(!db.is_cold_storage(&hot));
+        assert!(!hot.accounts.is_compressed());
+        assert!(db
+            .storage
+            .get_slot_storage_entry(max_slot_inclusive)
+            .is_none());
+
+        // cold stores are not recycled for writes to the accounts paths
+        drop(db.storage.remove(&ancient_slot));
+        db.drop_or_recycle_stores(vec![ancient], &ShrinkStats::default());
+        assert!(db
+            .try_recycle_store(max_slot_inclusive + 1, 0, u64::MAX)
+            .is_none());
+    }
+
+    #[test]
+    fn test_compress_ancient_storages() {
+        solana_logger::setup();
+
+        let num_normal_slots = 2;
+        let (mut db, ancient_slot) = get_one_ancient_append_vec_and_others(true, num_normal_slots);
+        db.ancient_storage_compression = Some(AppendVecCompression::Zstd);
+        let max_slot_inclusive = ancient_slot + (num_normal_slots as Slot);
+        let sorted_slots = (ancient_slot..=max_slot_inclusive).collect::<Vec<_>>();
+        let initial_accounts = get_all_accounts(&db, ancient_slot..(max_slot_inclusive + 1));
+        let ancient = db.storage.get_slot_storage_entry(ancient_slot).unwrap();
+        let ancient_path = ancient.get_path();
+
+        // the current ancient append vec can still be appended to, so it is not compressed
+        db.compress_ancient_storages(
+            &sorted_slots,
+            &CurrentAncientAppendVec::new(ancient_slot, Arc::clone(&ancient)),
+        );
+        assert!(!db
+            .storage
+            .get_slot_storage_entry(ancient_slot)
+            .unwrap()
+            .accounts
+            .is_compressed());
+
+        db.compress_ancient_storages(&sorted_slots, &CurrentAncientAppendVec::default());
+        assert_eq!(
+            db.shrink_ancient_stats
+                .ancient_compressed
+                .load(Ordering::Relaxed),
+            1
+        );
+        let compressed = db.storage.get_slot_storage_entry(ancient_slot).unwrap();
+        assert!(compressed.accounts.is_compressed());
+        assert!(is_ancient(&compressed.accounts));
+        assert_eq!(compressed.append_vec_id(), ancient.append_vec_id());
+        assert_eq!(compressed.written_bytes(), ancient.written_bytes());
+        assert_eq!(compressed.count(), ancient.count());
+        assert_eq!(compressed.alive_bytes(), ancient.alive_bytes());
+        assert_eq!(compressed.status(), AccountStorageStatus::Full);
+        assert!(Arc::ptr_eq(
+            &compressed,
+            &db.storage
+                .get_account_storage_entry(ancient_slot, ancient.append_vec_id())
+                .unwrap()
+        ));
+        // normal slots are not ancient
+        for slot in (ancient_slot + 1)..=max_slot_inclusive {
+            assert!(!db
+                .storage
+                .get_slot_storage_entry(slot)
+                .unwrap()
+                .accounts
+                .is_compressed());
+        }
+
+        // the original file is removed once it is no longer referenced
+        drop(ancient);
+        assert!(!ancient_path.exists());
+
+        // reading keeps blocks decompressed until the next pass releases them
+        compare_all_accounts(
+            &initial_accounts,
+            &get_all_accounts(&db, ancient_slot..(max_slot_inclusive + 1)),
+        );
+        assert!(compressed.accounts.decompressed_bytes() > 0);
+        db.compress_ancient_storages(&sorted_slots, &CurrentAncientAppendVec::default());
+        let released = db.storage.get_slot_storage_entry(ancient_slot).unwrap();
+        assert!(!Arc::ptr_eq(&released, &compressed));
+        assert!(released.accounts.is_compressed());
+        assert_eq!(released.accounts.decompressed_bytes(), 0);
+        assert_eq!(
+            db.shrink_ancient_stats
+                .compressed_bytes_released
+                .load(Ordering::Relaxed),
+            compressed.accounts.decompressed_bytes() as u64
+        );
+        drop(compressed);
+        drop(released);
+
+        // compressed ancient append vecs are never appended to
+        db.combine_ancient_slots(sorted_slots, CAN_RANDOMLY_SHRINK_FALSE);
+        compare_all_accounts(
+            &initial_accounts,
+            &get_all_accounts(&db, ancient_slot..(max_slot_inclusive + 1)),
+        );
+        assert!(db
+            .storage
+            .get_slot_storage_entry(ancient_slot)
+            .unwrap()
+            .accounts
+            .is_compressed());
+        let new_ancient = db.storage.get_slot_storage_entry(ancient_slot + 1).unwrap();
+        assert!(is_ancient(&new_ancient.accounts));
+        assert!(!new_ancient.accounts.is_compressed());
+    }
+
     #[test]
     fn test_shrink_ancient() {
         solana_logger::setup();
@@ -0,0 +1,217 @@
+//! Marker written next to the disk accounts index when it is persisted on a clean shutdown,
+//! together with a full snapshot of the last root.
+//! It records the snapshot the index is valid for and the storages the index refers to, so the
+//! next start can tell whether it is loading exactly that snapshot.
+//!
+//! The format is line based text:
+//! ```text
+//! solana-accounts-index-restart v2
+//! slot <snapshot slot>
+//! accounts_hash <accounts hash of the snapshot>
+//! accounts_data_len <total data len of all accounts as of the snapshot>
+//! storage <slot> <append vec id> <len> <stored count> <count> <alive bytes>
+//! ...
+//! ```
+use {
+    crate::{accounts_db::AppendVecId, accounts_hash::AccountsHash},
+    solana_sdk::{clock::Slot, hash::Hash},
+    std::{
+        collections::HashMap,
+        fs::{self, File},
+        io::{self, BufRead, BufReader, BufWriter, Write},
+        path::Path,
+        str::FromStr,
+    },
+};
+
+const HEADER: &str = "solana-accounts-index-restart v2";
+
+/// a storage as it was when the disk index was persisted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PersistedStorage {
+    pub(crate) id: AppendVecId,
+    /// number of bytes written to the append vec
+    pub(crate) len: usize,
+    /// number of accounts written to the append vec
+    pub(crate) stored_count: usize,
+    pub(crate) count: usize,
+    pub(crate) alive_bytes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiskIndexRestart {
+    /// the disk index contains exactly the accounts of the full snapshot at this slot
+    pub(crate) slot: Slot,
+    pub(crate) accounts_hash: AccountsHash,
+    pub(crate) accounts_data_len: u64,
+    pub(crate) storages: HashMap<Slot, PersistedStorage>,
+}
+
+fn invalid_data(error: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn parse<T: FromStr>(value: Option<&str>, line: &str) -> io::Result<T> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| invalid_data(format!("invalid accounts index restart line: {line}")))
+}
+
+impl DiskIndexRestart {
+    /// write to 'path', replacing any previous file only once the new contents are complete
+    pub(crate) fn write(&self, path: &Path) -> io::Result<()> {
+        let temp_path = path.with_extension("tmp");
+        {
+            let mut file = BufWriter::new(File::create(&temp_path)?);
+            writeln!(file, "{HEADER}")?;
+            writeln!(file, "slot {}", self.slot)?;
+            writeln!(file, "accounts_hash {}", self.accounts_hash.0)?;
+            writeln!(file, "accounts_data_len {}", self.accounts_data_len)?;
+            let mut slots = self.storages.keys().collect::<Vec<_>>();
+            slots.sort_unstable();
+            for slot in slots {
+                let storage = &self.storages[slot];
+                writeln!(
+                    file,
+                    "storage {slot} {} {} {} {} {}",
+                    storage.id,
+                    storage.len,
+                    storage.stored_count,
+                    storage.count,
+                    storage.alive_bytes
+                )?;
+            }
+            file.into_inner()
+                .map_err(|err| err.into_error())?
+                .sync_all()?;
+        }
+        fs::rename(temp_path, path)
+    }
+
+    pub(crate) fn read(path: &Path) -> io::Result<Self> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let mut next_line = || {
+            lines.next().unwrap_or_else(|| {
+                Err(invalid_data(
+                    "accounts index restart file is truncated".to_string(),
+                ))
+            })
+        };
+        if next_line()? != HEADER {
+            return Err(invalid_data(
+                "unknown accounts index restart file version".to_string(),
+            ));
+        }
+        let mut header_value = |name: &str| -> io::Result<(String, String)> {
+            let line = next_line()?;
+            match line.split_once(' ') {
+                Some((key, value)) if key == name => Ok((value.to_string(), line)),
+                _ => Err(invalid_data(format!("expected {name}, found: {line}"))),
+            }
+        };
+        let (value, line) = header_value("slot")?;
+        let slot: Slot = parse(Some(&value), &line)?;
+        let (value, line) = header_value("accounts_hash")?;
+        let accounts_hash = AccountsHash(parse::<Hash>(Some(&value), &line)?);
+        let (value, line) = header_value("accounts_data_len")?;
+        let accounts_data_len = parse(Some(&value), &line)?;
+
+        let mut storages = HashMap::default();
+        for line in lines {
+            let line = line?;
+            let mut fields = line.split(' ');
+            if fields.next() != Some("storage") {
+                return Err(invalid_data(format!(
+                    "invalid accounts index restart line: {line}"
+                )));
+            }
+            let storage_slot: Slot = parse(fields.next(), &line)?;
+            let storage = PersistedStorage {
+                id: parse(fields.next(), &line)?,
+                len: parse(fields.next(), &line)?,
+                stored_count: parse(fields.next(), &line)?,
+                count: parse(fields.next(), &line)?,
+                alive_bytes: parse(fields.next(), &line)?,
+            };
+            if fields.next().is_some()
+                || storage_slot > slot
+                || storages.insert(storage_slot, storage).is_some()
+            {
+                return Err(invalid_data(format!(
+                    "invalid accounts index restart line: {line}"
+                )));
+            }
+        }
+        Ok(Self {
+            slot,
+            accounts_hash,
+            accounts_data_len,
+            storages,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    #[test]
+    fn test_disk_index_restart_write_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("restart");
+        let accounts_hash = AccountsHash(Hash::new_unique());
+        let restart = DiskIndexRestart {
+            slot: 10,
+            accounts_hash,
+            accounts_data_len: 1234,
+            storages: HashMap::from([
+                (
+                    10,
+                    PersistedStorage {
+                        id: 4,
+                        len: 1000,
+                        stored_count: 4,
+                        count: 3,
+                        alive_bytes: 500,
+                    },
+                ),
+                (
+                    2,
+                    PersistedStorage {
+                        id: 1,
+                        len: 64,
+                        stored_count: 1,
+                        count: 0,
+                        alive_bytes: 0,
+                    },
+                ),
+            ]),
+        };
+        restart.write(&path).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+        assert_eq!(DiskIndexRestart::read(&path).unwrap(), restart);
+
+        let valid_header = format!(
+            "{HEADER}\nslot 10\naccounts_hash {}\naccounts_data_len 1234\n",
+            accounts_hash.0
+        );
+        for contents in [
+            format!("{HEADER}\nslot 10\n"),
+            format!(
+                "unknown\nslot 10\naccounts_hash {}\naccounts_data_len 1234\n",
+                accounts_hash.0
+            ),
+            format!("{HEADER}\nslot 10\naccounts_hash x\naccounts_data_len 1234\n"),
+            // storage above the slot of the index
+            format!("{valid_header}storage 11 1 2 3 4 5\n"),
+            // duplicate slot
+            format!("{valid_header}storage 1 1 2 3 4 5\nstorage 1 2 2 3 4 5\n"),
+            format!("{valid_header}storage 1 1 2 3 4\n"),
+            format!("{valid_header}storage 1 1 2 3 4 5 6\n"),
+            format!("{valid_header}storage 1 x 2 3 4 5\n"),
+        ] {
+            fs::write(&path, contents.as_bytes()).unwrap();
+            assert!(DiskIndexRestart::read(&path).is_err(), "{contents}");
+        }
+    }
+}
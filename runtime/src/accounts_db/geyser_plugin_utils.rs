@@ -1,6 +1,6 @@
 use {
     crate::{
-        accounts_db::AccountsDb,
+        accounts_db::{AccountsDb, LoadedAccountAccessor},
         append_vec::{StoredAccountMeta, StoredMeta},
     },
     solana_measure::measure::Measure,
@@ -71,9 +71,15 @@ impl AccountsDb {
     {
         if let Some(accounts_update_notifier) = &self.accounts_update_notifier {
             let notifier = &accounts_update_notifier.read().unwrap();
+            let previous_account = if notifier.previous_account_notifications_enabled() {
+                self.load_previous_account_version(slot, pubkey)
+            } else {
+                None
+            };
             notifier.notify_account_update(
                 slot,
                 account,
+                previous_account.as_ref(),
                 txn_signature,
                 pubkey,
                 write_version_producer.next().unwrap(),
@@ -81,6 +87,36 @@ impl AccountsDb {
         }
     }
 
+    /// Load the version of the account which a store at `slot` replaces: either an earlier write
+    /// in the same slot, or the version stored in the most recent slot before it. The accounts
+    /// index doesn't know about forks, so across forks this may be the version of a sibling slot.
+    fn load_previous_account_version(
+        &self,
+        slot: Slot,
+        pubkey: &Pubkey,
+    ) -> Option<AccountSharedData> {
+        if let Some(cached_account) = self.accounts_cache.load(slot, pubkey) {
+            return Some(cached_account.account.clone());
+        }
+
+        // hold the index entry while loading, so that the version can't be cleaned in between
+        let entry = self.accounts_index.get_account_read_entry(pubkey)?;
+        let (previous_slot, storage_location) = entry
+            .slot_list()
+            .iter()
+            .filter(|(stored_slot, _)| *stored_slot < slot)
+            .max_by_key(|(stored_slot, _)| *stored_slot)
+            .map(|(stored_slot, account_info)| (*stored_slot, account_info.storage_location()))?;
+        let mut accessor = self.get_account_accessor(previous_slot, pubkey, &storage_location);
+        match accessor {
+            // flushed or shrunk since the index was updated; not worth a retry
+            LoadedAccountAccessor::Cached(None) | LoadedAccountAccessor::Stored(None, _) => None,
+            _ => accessor
+                .get_loaded_account()
+                .map(|loaded_account| loaded_account.take_account()),
+        }
+    }
+
     fn notify_accounts_in_slot(
         &self,
         slot: Slot,
@@ -194,6 +230,8 @@ pub mod tests {
     #[derive(Debug, Default)]
     struct GeyserTestPlugin {
         pub accounts_notified: DashMap<Pubkey, Vec<(Slot, AccountSharedData)>>,
+        pub previous_accounts_notified: DashMap<Pubkey, Vec<Option<AccountSharedData>>>,
+        pub previous_account_notifications_enabled: bool,
         pub is_startup_done: AtomicBool,
     }
 
@@ -203,6 +241,7 @@ pub mod tests {
             &self,
             slot: Slot,
             account: &AccountSharedData,
+            previous_account: Option<&AccountSharedData>,
             _txn_signature: &Option<&Signature>,
             pubkey: &Pubkey,
             _write_version: u64,
@@ -211,6 +250,14 @@ pub mod tests {
                 .entry(*pubkey)
                 .or_default()
                 .push((slot, account.clone()));
+            self.previous_accounts_notified
+                .entry(*pubkey)
+                .or_default()
+                .push(previous_account.cloned());
+        }
+
+        fn previous_account_notifications_enabled(&self) -> bool {
+            self.previous_account_notifications_enabled
         }
 
         /// Notified when the AccountsDb is initialized at start when restored
@@ -410,4 +457,37 @@ pub mod tests {
         );
         assert_eq!(notifier.accounts_notified.get(&key3).unwrap()[0].0, slot1);
     }
+
+    #[test]
+    fn test_notify_account_at_accounts_update_with_previous_account() {
+        let mut accounts = AccountsDb::new_single_for_tests_with_caching();
+
+        let notifier = GeyserTestPlugin {
+            previous_account_notifications_enabled: true,
+            ..GeyserTestPlugin::default()
+        };
+
+        let notifier = Arc::new(RwLock::new(notifier));
+        accounts.set_geyser_plugin_notifer(Some(notifier.clone()));
+
+        // Account with key1 is updated once in slot0 and twice in slot1
+        let key1 = solana_sdk::pubkey::new_rand();
+        let owner = AccountSharedData::default().owner().to_owned();
+        let account1_slot0 = AccountSharedData::new(1, 1, &owner);
+        let slot0 = 0;
+        accounts.store_cached((slot0, &[(&key1, &account1_slot0)][..]), None);
+
+        let slot1 = 1;
+        let account1_slot1 = AccountSharedData::new(2, 1, &owner);
+        accounts.store_cached((slot1, &[(&key1, &account1_slot1)][..]), None);
+        let account1_slot1_again = AccountSharedData::new(3, 1, &owner);
+        accounts.store_cached((slot1, &[(&key1, &account1_slot1_again)][..]), None);
+
+        let notifier = notifier.write().unwrap();
+        let previous_accounts = notifier.previous_accounts_notified.get(&key1).unwrap();
+        assert_eq!(
+            *previous_accounts,
+            vec![None, Some(account1_slot0), Some(account1_slot1)]
+        );
+    }
 }
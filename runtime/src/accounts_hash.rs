@@ -968,6 +968,46 @@ impl AccountsHasher {
         pubkey_division: &'a [SortedDataByPubkey<'a>],
         pubkey_bin: usize,
     ) -> (AccountHashesFile, u64, usize) {
+        let mut hashes = AccountHashesFile::default();
+        let mut overall_sum = 0;
+        let unreduced_count = self.de_dup_accounts_in_bin(pubkey_division, pubkey_bin, |item| {
+            // add lamports and get hash
+            if let Some(hash) = self.hash_in_accounts_hash(item) {
+                overall_sum = Self::checked_cast_for_capitalization(
+                    item.lamports as u128 + overall_sum as u128,
+                );
+                hashes.write(&hash);
+            }
+        });
+        (hashes, overall_sum, unreduced_count)
+    }
+
+    /// return the hash 'item' contributes to the accounts hash, None if it is not included
+    fn hash_in_accounts_hash(&self, item: &CalculateHashIntermediate) -> Option<Hash> {
+        if item.lamports != 0 {
+            // do not include filler accounts in the hash
+            (!(self.filler_accounts_enabled() && self.is_filler_account(&item.pubkey)))
+                .then_some(item.hash)
+        } else if self.zero_lamport_accounts == ZeroLamportAccounts::Included {
+            // if lamports == 0, check if they should be included
+            // For incremental accounts hash, the hash of a zero lamport account is
+            // the hash of its pubkey
+            let hash = blake3::hash(bytemuck::bytes_of(&item.pubkey));
+            Some(Hash::new_from_array(hash.into()))
+        } else {
+            None
+        }
+    }
+
+    // go through: [..][pubkey_bin][..] and call 'callback' with the highest slot or (slot = and
+    // highest version) item of each pubkey, in pubkey order
+    // return the unreduced count (ie. including duplicates and zero lamport)
+    fn de_dup_accounts_in_bin<'a>(
+        &self,
+        pubkey_division: &'a [SortedDataByPubkey<'a>],
+        pubkey_bin: usize,
+        mut callback: impl FnMut(&'a CalculateHashIntermediate),
+    ) -> usize {
         let len = pubkey_division.len();
         let mut unreduced_count = 0;
         let mut indexes = vec![0; len];
@@ -975,7 +1015,6 @@ impl AccountsHasher {
         // map from index of an item in first_items[] to index of the corresponding item in pubkey_division[]
         // this will change as items in pubkey_division[] are exhausted
         let mut first_item_to_pubkey_division = Vec::with_capacity(len);
-        let mut hashes = AccountHashesFile::default();
         // initialize 'first_items', which holds the current lowest item in each slot group
         pubkey_division.iter().enumerate().for_each(|(i, bins)| {
             // check to make sure we can do bins[pubkey_bin]
@@ -988,9 +1027,7 @@ impl AccountsHasher {
                 }
             }
         });
-        let mut overall_sum = 0;
         let mut duplicate_pubkey_indexes = Vec::with_capacity(len);
-        let filler_accounts_enabled = self.filler_accounts_enabled();
 
         // this loop runs once per unique pubkey contained in any slot group
         while !first_items.is_empty() {
@@ -1031,25 +1068,7 @@ impl AccountsHasher {
                 &mut first_item_to_pubkey_division,
             );
 
-            // add lamports and get hash
-            if item.lamports != 0 {
-                // do not include filler accounts in the hash
-                if !(filler_accounts_enabled && self.is_filler_account(&item.pubkey)) {
-                    overall_sum = Self::checked_cast_for_capitalization(
-                        item.lamports as u128 + overall_sum as u128,
-                    );
-                    hashes.write(&item.hash);
-                }
-            } else {
-                // if lamports == 0, check if they should be included
-                if self.zero_lamport_accounts == ZeroLamportAccounts::Included {
-                    // For incremental accounts hash, the hash of a zero lamport account is
-                    // the hash of its pubkey
-                    let hash = blake3::hash(bytemuck::bytes_of(&item.pubkey));
-                    let hash = Hash::new_from_array(hash.into());
-                    hashes.write(&hash);
-                }
-            }
+            callback(item);
 
             if !duplicate_pubkey_indexes.is_empty() {
                 // skip past duplicate keys in earlier slots
@@ -1068,7 +1087,7 @@ impl AccountsHasher {
                 duplicate_pubkey_indexes.clear();
             }
         }
-        (hashes, overall_sum, unreduced_count)
+        unreduced_count
     }
 
     fn is_filler_account(&self, pubkey: &Pubkey) -> bool {
@@ -1107,6 +1126,84 @@ impl AccountsHasher {
         stats.hash_time_total_us += hash_time.as_us();
         (hash, total_lamports)
     }
+
+    /// Group the PUBKEY_BINS_FOR_CALCULATING_HASHES bins of 'data_sections_by_pubkey' into 'num_bins'
+    /// bins and return the `AccountsHashBin` of each. The accounts are handled the same way as in
+    /// `rest_of_hash_calculation`.
+    /// 'data_sections_by_pubkey' is the same input as for `rest_of_hash_calculation`.
+    pub fn calculate_hash_bins(
+        &self,
+        data_sections_by_pubkey: Vec<SortedDataByPubkey<'_>>,
+        num_bins: usize,
+        stats: &mut HashStats,
+    ) -> Vec<AccountsHashBin> {
+        let mut hash_time = Measure::start("hash");
+        let bins = (0..num_bins)
+            .into_par_iter()
+            .map(|bin| {
+                let (hashes, lamports): (Vec<_>, Vec<_>) = Self::pubkey_bins_in_bin(num_bins, bin)
+                    .into_par_iter()
+                    .map(|pubkey_bin| {
+                        let (hashes, lamports_bin, _unreduced_entries_count) =
+                            self.de_dup_accounts_in_parallel(&data_sections_by_pubkey, pubkey_bin);
+                        (hashes, lamports_bin)
+                    })
+                    .unzip();
+                let lamports = lamports.into_iter().fold(0, |lamports, lamports_bin| {
+                    Self::checked_cast_for_capitalization(lamports as u128 + lamports_bin as u128)
+                });
+                let cumulative = CumulativeHashesFromFiles::from_files(hashes);
+                let (hash, _) = Self::compute_merkle_root_from_slices(
+                    cumulative.total_count(),
+                    MERKLE_FANOUT,
+                    None,
+                    |start| cumulative.get_slice(start),
+                    None,
+                );
+                AccountsHashBin {
+                    hash,
+                    num_accounts: cumulative.total_count(),
+                    lamports,
+                }
+            })
+            .collect();
+        hash_time.stop();
+        stats.hash_time_total_us += hash_time.as_us();
+        bins
+    }
+
+    /// return the accounts in bin 'bin' of 'num_bins' bins, sorted by pubkey
+    /// These are the accounts that make up the bin in `calculate_hash_bins`.
+    /// 'data_sections_by_pubkey' is the same input as for `rest_of_hash_calculation`.
+    pub fn get_hash_bin_entries(
+        &self,
+        data_sections_by_pubkey: Vec<SortedDataByPubkey<'_>>,
+        num_bins: usize,
+        bin: usize,
+    ) -> Vec<AccountHashEntry> {
+        let mut entries = vec![];
+        for pubkey_bin in Self::pubkey_bins_in_bin(num_bins, bin) {
+            self.de_dup_accounts_in_bin(&data_sections_by_pubkey, pubkey_bin, |item| {
+                if let Some(hash) = self.hash_in_accounts_hash(item) {
+                    entries.push(AccountHashEntry {
+                        pubkey: item.pubkey,
+                        hash,
+                        lamports: item.lamports,
+                    });
+                }
+            });
+        }
+        entries
+    }
+
+    /// The PUBKEY_BINS_FOR_CALCULATING_HASHES bins that hold the pubkeys of bin 'bin' of 'num_bins' bins.
+    /// Both bin counts are powers of two, so each bin of 'num_bins' is a range of these bins.
+    fn pubkey_bins_in_bin(num_bins: usize, bin: usize) -> Range<usize> {
+        assert!(num_bins.is_power_of_two() && num_bins <= PUBKEY_BINS_FOR_CALCULATING_HASHES);
+        assert!(bin < num_bins);
+        let pubkey_bins_per_bin = PUBKEY_BINS_FOR_CALCULATING_HASHES / num_bins;
+        bin * pubkey_bins_per_bin..(bin + 1) * pubkey_bins_per_bin
+    }
 }
 
 /// How should zero-lamport accounts be treated by the accounts hasher?
@@ -1124,6 +1221,126 @@ pub struct AccountsHash(pub Hash);
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize, AbiExample)]
 pub struct AccountsDeltaHash(pub Hash);
 
+/// An account as it contributes to the accounts hash
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccountHashEntry {
+    pub pubkey: Pubkey,
+    pub hash: Hash,
+    pub lamports: u64,
+}
+
+/// The accounts with pubkeys in one bin of `AccountsHashBins`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccountsHashBin {
+    /// merkle root of the hashes of the accounts in the bin, in pubkey order
+    pub hash: Hash,
+    pub num_accounts: usize,
+    pub lamports: u64,
+}
+
+impl AccountsHashBin {
+    /// 'accounts' are sorted by pubkey
+    pub fn new(accounts: &[AccountHashEntry]) -> Self {
+        let hashes = accounts.iter().map(|account| account.hash).collect();
+        let lamports = accounts.iter().fold(0, |lamports, account| {
+            AccountsHasher::checked_cast_for_capitalization(
+                lamports as u128 + account.lamports as u128,
+            )
+        });
+        Self {
+            // the same merkle tree as `AccountsHasher::calculate_hash_bins`
+            hash: AccountsHasher::compute_merkle_root_from_slices_recurse(
+                hashes,
+                MERKLE_FANOUT,
+                None,
+                None,
+            )
+            .0,
+            num_accounts: accounts.len(),
+            lamports,
+        }
+    }
+}
+
+/// Merkle summary of the accounts at a slot, with the accounts split into bins by pubkey the same
+/// way as the accounts hash calculation does, see `PubkeyBinCalculator24`.
+/// When the accounts of two nodes or snapshots differ, comparing their bins narrows the difference
+/// down to the accounts in the mismatched bins.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccountsHashBins {
+    pub slot: Slot,
+    /// merkle root of the bin hashes
+    pub root: Hash,
+    pub bins: Vec<AccountsHashBin>,
+}
+
+impl AccountsHashBins {
+    /// 'bins' is a power of two
+    pub fn new(slot: Slot, bins: Vec<AccountsHashBin>) -> Self {
+        assert!(bins.len().is_power_of_two());
+        let root = AccountsHasher::compute_merkle_root_recurse(
+            bins.iter().map(|bin| bin.hash).collect(),
+            MERKLE_FANOUT,
+        );
+        Self { slot, root, bins }
+    }
+
+    pub fn num_accounts(&self) -> usize {
+        self.bins.iter().map(|bin| bin.num_accounts).sum()
+    }
+
+    pub fn lamports(&self) -> u64 {
+        self.bins.iter().fold(0, |lamports, bin| {
+            AccountsHasher::checked_cast_for_capitalization(lamports as u128 + bin.lamports as u128)
+        })
+    }
+
+    /// return the indexes of the bins that differ from 'other'
+    /// return None if 'other' was calculated with a different number of bins
+    pub fn mismatched_bins(&self, other: &Self) -> Option<Vec<usize>> {
+        (self.bins.len() == other.bins.len()).then(|| {
+            if self.root == other.root {
+                return vec![];
+            }
+            self.bins
+                .iter()
+                .zip(other.bins.iter())
+                .enumerate()
+                .filter_map(|(index, (bin, other_bin))| (bin != other_bin).then_some(index))
+                .collect()
+        })
+    }
+
+    /// Compare the accounts of the same bin from two nodes or snapshots, both sorted by pubkey.
+    /// return (ours, theirs) for each pubkey whose account differs or only exists on one side
+    pub fn mismatched_accounts<'a>(
+        ours: &'a [AccountHashEntry],
+        theirs: &'a [AccountHashEntry],
+    ) -> Vec<(Option<&'a AccountHashEntry>, Option<&'a AccountHashEntry>)> {
+        let mut mismatched = vec![];
+        let mut ours = ours.iter().peekable();
+        let mut theirs = theirs.iter().peekable();
+        loop {
+            let (our_account, their_account) = match (ours.peek(), theirs.peek()) {
+                (None, None) => break,
+                (Some(_), None) => (ours.next(), None),
+                (None, Some(_)) => (None, theirs.next()),
+                (Some(our_account), Some(their_account)) => {
+                    match our_account.pubkey.cmp(&their_account.pubkey) {
+                        std::cmp::Ordering::Less => (ours.next(), None),
+                        std::cmp::Ordering::Greater => (None, theirs.next()),
+                        std::cmp::Ordering::Equal => (ours.next(), theirs.next()),
+                    }
+                }
+            };
+            if our_account != their_account {
+                mismatched.push((our_account, their_account));
+            }
+        }
+        mismatched
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use {super::*, std::str::FromStr};
@@ -1219,6 +1436,52 @@ pub mod tests {
         });
     }
 
+    #[test]
+    fn test_accounts_hash_bins() {
+        let entry = |pubkey: u8, hash: u8, lamports: u64| AccountHashEntry {
+            pubkey: Pubkey::from([pubkey; 32]),
+            hash: Hash::new_from_array([hash; 32]),
+            lamports,
+        };
+        let ours = vec![entry(1, 1, 10), entry(2, 2, 20), entry(4, 4, 40)];
+        let theirs = vec![entry(2, 2, 20), entry(3, 3, 30), entry(4, 5, 40)];
+
+        let bin = AccountsHashBin::new(&ours);
+        assert_eq!(bin.num_accounts, 3);
+        assert_eq!(bin.lamports, 70);
+        assert_eq!(
+            bin.hash,
+            AccountsHasher::compute_merkle_root_recurse(
+                ours.iter().map(|entry| entry.hash).collect(),
+                MERKLE_FANOUT
+            )
+        );
+        let empty_bin = AccountsHashBin::new(&[]);
+        assert_eq!(empty_bin.num_accounts, 0);
+        assert_eq!(empty_bin.lamports, 0);
+
+        let our_bins = AccountsHashBins::new(1, vec![bin, empty_bin]);
+        assert_eq!(our_bins.num_accounts(), 3);
+        assert_eq!(our_bins.lamports(), 70);
+        let their_bins = AccountsHashBins::new(1, vec![AccountsHashBin::new(&theirs), empty_bin]);
+        assert_ne!(our_bins.root, their_bins.root);
+        assert_eq!(our_bins.mismatched_bins(&their_bins), Some(vec![0]));
+        assert_eq!(our_bins.mismatched_bins(&our_bins), Some(vec![]));
+        let other_num_bins = AccountsHashBins::new(1, vec![bin]);
+        assert_eq!(our_bins.mismatched_bins(&other_num_bins), None);
+
+        // pubkey 2 matches, pubkey 1 and 3 exist on one side only, pubkey 4 has a different hash
+        assert_eq!(
+            AccountsHashBins::mismatched_accounts(&ours, &theirs),
+            vec![
+                (Some(&ours[0]), None),
+                (None, Some(&theirs[1])),
+                (Some(&ours[2]), Some(&theirs[2])),
+            ]
+        );
+        assert!(AccountsHashBins::mismatched_accounts(&ours, &ours).is_empty());
+    }
+
     #[test]
     fn test_accountsdb_div_ceil() {
         assert_eq!(AccountsHasher::div_ceil(10, 3), 4);
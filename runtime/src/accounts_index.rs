@@ -12,6 +12,7 @@ use {
         rolling_bit_field::RollingBitField,
         secondary_index::*,
     },
+    dashmap::DashMap,
     log::*,
     once_cell::sync::OnceCell,
     ouroboros::self_referencing,
@@ -24,7 +25,7 @@ use {
     solana_sdk::{
         account::ReadableAccount,
         clock::{BankId, Slot},
-        pubkey::Pubkey,
+        pubkey::{Pubkey, PUBKEY_BYTES},
     },
     std::{
         collections::{btree_map::BTreeMap, HashSet},
@@ -34,7 +35,8 @@ use {
             Bound::{Excluded, Included, Unbounded},
             Range, RangeBounds,
         },
-        path::PathBuf,
+        path::{Path, PathBuf},
+        str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering},
             Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard,
@@ -56,6 +58,7 @@ pub const ACCOUNTS_INDEX_CONFIG_FOR_TESTING: AccountsIndexConfig = AccountsIndex
     ages_to_stay_in_cache: None,
     scan_results_limit_bytes: None,
     started_from_validator: false,
+    persist_disk_index: false,
 };
 pub const ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS: AccountsIndexConfig = AccountsIndexConfig {
     bins: Some(BINS_FOR_BENCHMARKS),
@@ -65,6 +68,7 @@ pub const ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS: AccountsIndexConfig = AccountsIn
     ages_to_stay_in_cache: None,
     scan_results_limit_bytes: None,
     started_from_validator: false,
+    persist_disk_index: false,
 };
 pub type ScanResult<T> = Result<T, ScanError>;
 pub type SlotList<T> = Vec<(Slot, T)>;
@@ -92,6 +96,10 @@ pub struct ScanConfig {
     /// true to allow return of all matching items and allow them to be unsorted.
     /// This is more efficient.
     pub collect_all_unsorted: bool,
+
+    /// when set, secondary index scans visit the keys in ascending order, starting from this
+    /// bound. Used to page through a secondary index.
+    pub index_key_start: Option<Bound<Pubkey>>,
 }
 
 impl ScanConfig {
@@ -114,6 +122,7 @@ impl ScanConfig {
         ScanConfig {
             abort: Some(self.abort.as_ref().map(Arc::clone).unwrap_or_default()),
             collect_all_unsorted: self.collect_all_unsorted,
+            index_key_start: self.index_key_start,
         }
     }
 
@@ -156,6 +165,28 @@ pub enum IndexKey {
     ProgramId(Pubkey),
     SplTokenMint(Pubkey),
     SplTokenOwner(Pubkey),
+    /// Key built from the indexed data bytes by `DataOffsetIndex::key_from_bytes()`
+    DataOffset(DataOffsetIndex, Pubkey),
+}
+
+impl IndexKey {
+    pub fn account_index(&self) -> AccountIndex {
+        match self {
+            Self::ProgramId(_) => AccountIndex::ProgramId,
+            Self::SplTokenMint(_) => AccountIndex::SplTokenMint,
+            Self::SplTokenOwner(_) => AccountIndex::SplTokenOwner,
+            Self::DataOffset(index, _) => AccountIndex::DataOffset(*index),
+        }
+    }
+
+    pub fn key(&self) -> &Pubkey {
+        match self {
+            Self::ProgramId(key)
+            | Self::SplTokenMint(key)
+            | Self::SplTokenOwner(key)
+            | Self::DataOffset(_, key) => key,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -163,6 +194,78 @@ pub enum AccountIndex {
     ProgramId,
     SplTokenMint,
     SplTokenOwner,
+    DataOffset(DataOffsetIndex),
+}
+
+/// Index of the accounts owned by `program_id`, keyed by the `length` bytes of account data
+/// at `offset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataOffsetIndex {
+    pub program_id: Pubkey,
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl DataOffsetIndex {
+    pub const MAX_LENGTH: usize = PUBKEY_BYTES;
+
+    pub fn new(program_id: Pubkey, offset: usize, length: usize) -> Option<Self> {
+        (1..=Self::MAX_LENGTH).contains(&length).then_some(Self {
+            program_id,
+            offset,
+            length,
+        })
+    }
+
+    /// Returns the index key of an account owned by `program_id`, if its data is long enough
+    pub fn key_from_data(&self, data: &[u8]) -> Option<Pubkey> {
+        let end = self.offset.checked_add(self.length)?;
+        data.get(self.offset..end)
+            .and_then(|bytes| self.key_from_bytes(bytes))
+    }
+
+    /// Returns the index key of the indexed bytes, which are zero-padded to a pubkey
+    pub fn key_from_bytes(&self, bytes: &[u8]) -> Option<Pubkey> {
+        (bytes.len() == self.length && bytes.len() <= PUBKEY_BYTES).then(|| {
+            let mut key = [0u8; PUBKEY_BYTES];
+            key[..bytes.len()].copy_from_slice(bytes);
+            Pubkey::from(key)
+        })
+    }
+}
+
+/// Parses `PROGRAM_ID:OFFSET:LENGTH`
+impl FromStr for DataOffsetIndex {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let (program_id, offset, length) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(program_id), Some(offset), Some(length)) if parts.next().is_none() => {
+                (program_id, offset, length)
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid data offset index {s}: expected PROGRAM_ID:OFFSET:LENGTH"
+                ))
+            }
+        };
+        let program_id = program_id
+            .parse::<Pubkey>()
+            .map_err(|err| format!("Invalid data offset index program id {program_id}: {err}"))?;
+        let offset = offset
+            .parse::<usize>()
+            .map_err(|err| format!("Invalid data offset index offset {offset}: {err}"))?;
+        let length = length
+            .parse::<usize>()
+            .map_err(|err| format!("Invalid data offset index length {length}: {err}"))?;
+        Self::new(program_id, offset, length).ok_or_else(|| {
+            format!(
+                "Invalid data offset index length {length}: must be between 1 and {}",
+                Self::MAX_LENGTH
+            )
+        })
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -198,6 +301,9 @@ pub struct AccountsIndexConfig {
     pub scan_results_limit_bytes: Option<usize>,
     /// true if the accounts index is being created as a result of being started as a validator (as opposed to test, etc.)
     pub started_from_validator: bool,
+    /// true to keep the files of the disk index on a clean shutdown, so the next start can reuse them
+    /// instead of generating the index from all storages
+    pub persist_disk_index: bool,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -219,6 +325,12 @@ impl AccountSecondaryIndexes {
             None => true, // include all keys
         }
     }
+    pub fn data_offset_indexes(&self) -> impl Iterator<Item = &DataOffsetIndex> {
+        self.indexes.iter().filter_map(|index| match index {
+            AccountIndex::DataOffset(index) => Some(index),
+            _ => None,
+        })
+    }
 }
 
 #[derive(Debug, Default)]
@@ -668,9 +780,11 @@ pub enum AccountsIndexScanResult {
 pub struct AccountsIndex<T: IndexValue> {
     pub account_maps: LockMapType<T>,
     pub bin_calculator: PubkeyBinCalculator24,
-    program_id_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
+    program_id_index: SecondaryIndex<ShardedBTreeSecondaryIndexEntry>,
     spl_token_mint_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
     spl_token_owner_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
+    // Created on first insert, as the configured indexes are only known at update time
+    data_offset_indexes: DashMap<DataOffsetIndex, Arc<SecondaryIndex<DashMapSecondaryIndexEntry>>>,
     pub(crate) roots_tracker: RwLock<RootsTracker>,
     ongoing_scan_roots: RwLock<BTreeMap<Slot, u64>>,
     // Each scan has some latest slot `S` that is the tip of the fork the scan
@@ -716,7 +830,7 @@ impl<T: IndexValue> AccountsIndex<T> {
         Self {
             account_maps,
             bin_calculator,
-            program_id_index: SecondaryIndex::<DashMapSecondaryIndexEntry>::new(
+            program_id_index: SecondaryIndex::<ShardedBTreeSecondaryIndexEntry>::new(
                 "program_id_index_stats",
             ),
             spl_token_mint_index: SecondaryIndex::<DashMapSecondaryIndexEntry>::new(
@@ -725,6 +839,7 @@ impl<T: IndexValue> AccountsIndex<T> {
             spl_token_owner_index: SecondaryIndex::<RwLockSecondaryIndexEntry>::new(
                 "spl_token_owner_index_stats",
             ),
+            data_offset_indexes: DashMap::default(),
             roots_tracker: RwLock::<RootsTracker>::default(),
             ongoing_scan_roots: RwLock::<BTreeMap<Slot, u64>>::default(),
             removed_bank_ids: Mutex::<HashSet<BankId>>::default(),
@@ -771,6 +886,45 @@ impl<T: IndexValue> AccountsIndex<T> {
         self.storage.storage.is_disk_index_enabled()
     }
 
+    /// true if the disk index was reopened from the files persisted on the previous shutdown
+    pub(crate) fn is_disk_index_restored(&self) -> bool {
+        self.storage
+            .storage
+            .disk
+            .as_ref()
+            .map(|disk| disk.is_restored())
+            .unwrap_or_default()
+    }
+
+    /// file describing what the persisted disk index was generated from.
+    /// None if the disk index is not persisted.
+    pub(crate) fn disk_index_restart_file(&self) -> Option<&Path> {
+        self.storage
+            .storage
+            .disk
+            .as_ref()
+            .and(self.storage.storage.restart_file.as_deref())
+    }
+
+    /// Write every entry to the disk index and keep its files for the next start.
+    /// The index must not be modified afterwards.
+    pub(crate) fn persist_disk_index(&self) -> std::io::Result<()> {
+        let disk = self.storage.storage.disk.as_ref().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::Other, "disk index is not enabled")
+        })?;
+        self.account_maps
+            .iter()
+            .for_each(|map| map.write_dirty_entries_to_disk());
+        disk.persist()
+    }
+
+    /// remove all entries from the disk index
+    pub(crate) fn clear_disk_index(&self) {
+        if let Some(disk) = self.storage.storage.disk.as_ref() {
+            disk.clear();
+        }
+    }
+
     fn min_ongoing_scan_root_from_btree(ongoing_scan_roots: &BTreeMap<Slot, u64>) -> Option<Slot> {
         ongoing_scan_roots.keys().next().cloned()
     }
@@ -977,6 +1131,18 @@ impl<T: IndexValue> AccountsIndex<T> {
                     config,
                 );
             }
+            ScanTypes::Indexed(IndexKey::DataOffset(index, index_key)) => {
+                if let Some(data_offset_index) = self.data_offset_index(&index) {
+                    self.do_scan_secondary_index(
+                        ancestors,
+                        func,
+                        &data_offset_index,
+                        &index_key,
+                        Some(max_root),
+                        config,
+                    );
+                }
+            }
         }
 
         {
@@ -1098,6 +1264,12 @@ impl<T: IndexValue> AccountsIndex<T> {
     ) where
         F: FnMut(&Pubkey, (&T, Slot)),
     {
+        if let Some(start) = config.index_key_start {
+            self.do_ordered_scan_secondary_index(
+                ancestors, func, index, index_key, start, max_root, config,
+            );
+            return;
+        }
         for pubkey in index.get(index_key) {
             // Maybe these reads from the AccountsIndex can be batched every time it
             // grabs the read lock as well...
@@ -1113,6 +1285,44 @@ impl<T: IndexValue> AccountsIndex<T> {
         }
     }
 
+    /// Visits the keys of `index_key` in ascending order from `start`, a batch at a time, until
+    /// the scan is aborted
+    #[allow(clippy::too_many_arguments)]
+    fn do_ordered_scan_secondary_index<
+        F,
+        SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
+    >(
+        &self,
+        ancestors: &Ancestors,
+        mut func: F,
+        index: &SecondaryIndex<SecondaryIndexEntryType>,
+        index_key: &Pubkey,
+        mut start: Bound<Pubkey>,
+        max_root: Option<Slot>,
+        config: &ScanConfig,
+    ) where
+        F: FnMut(&Pubkey, (&T, Slot)),
+    {
+        loop {
+            let pubkeys = index.get_from(index_key, start, ITER_BATCH_SIZE);
+            for pubkey in &pubkeys {
+                if let AccountIndexGetResult::Found(list_r, index) =
+                    self.get(pubkey, Some(ancestors), max_root)
+                {
+                    let entry = &list_r.slot_list()[index];
+                    func(pubkey, (&entry.1, entry.0));
+                }
+                if config.is_aborted() {
+                    return;
+                }
+            }
+            match pubkeys.last() {
+                Some(last) if pubkeys.len() == ITER_BATCH_SIZE => start = Bound::Excluded(*last),
+                _ => return,
+            }
+        }
+    }
+
     pub fn get_account_read_entry(&self, pubkey: &Pubkey) -> Option<ReadAccountMapEntry<T>> {
         let lock = self.get_bin(pubkey);
         self.get_account_read_entry_with_lock(pubkey, &lock)
@@ -1471,6 +1681,40 @@ impl<T: IndexValue> AccountsIndex<T> {
         }
     }
 
+    fn data_offset_index(
+        &self,
+        index: &DataOffsetIndex,
+    ) -> Option<Arc<SecondaryIndex<DashMapSecondaryIndexEntry>>> {
+        self.data_offset_indexes
+            .get(index)
+            .map(|data_offset_index| Arc::clone(&data_offset_index))
+    }
+
+    fn update_data_offset_secondary_indexes(
+        &self,
+        pubkey: &Pubkey,
+        account_owner: &Pubkey,
+        account_data: &[u8],
+        account_indexes: &AccountSecondaryIndexes,
+    ) {
+        for index in account_indexes.data_offset_indexes() {
+            if index.program_id != *account_owner {
+                continue;
+            }
+            if let Some(index_key) = index.key_from_data(account_data) {
+                if account_indexes.include_key(&index_key) {
+                    self.data_offset_index(index)
+                        .unwrap_or_else(|| {
+                            Arc::clone(self.data_offset_indexes.entry(*index).or_insert_with(
+                                || Arc::new(SecondaryIndex::new("data_offset_index_stats")),
+                            ))
+                        })
+                        .insert(&index_key, pubkey);
+                }
+            }
+        }
+    }
+
     pub fn get_index_key_size(&self, index: &AccountIndex, index_key: &Pubkey) -> Option<usize> {
         match index {
             AccountIndex::ProgramId => self.program_id_index.index.get(index_key).map(|x| x.len()),
@@ -1484,6 +1728,11 @@ impl<T: IndexValue> AccountsIndex<T> {
                 .index
                 .get(index_key)
                 .map(|x| x.len()),
+            AccountIndex::DataOffset(index) => {
+                self.data_offset_index(index).and_then(|data_offset_index| {
+                    data_offset_index.index.get(index_key).map(|x| x.len())
+                })
+            }
         }
     }
 
@@ -1505,6 +1754,14 @@ impl<T: IndexValue> AccountsIndex<T> {
                 .spl_token_mint_index
                 .key_size_index
                 .get_largest_keys(max_entries),
+            AccountIndex::DataOffset(index) => self
+                .data_offset_index(index)
+                .map(|data_offset_index| {
+                    data_offset_index
+                        .key_size_index
+                        .get_largest_keys(max_entries)
+                })
+                .unwrap_or_default(),
         }
     }
 
@@ -1522,6 +1779,15 @@ impl<T: IndexValue> AccountsIndex<T> {
             info!("secondary index: {:?}", AccountIndex::SplTokenOwner);
             self.spl_token_owner_index.log_contents();
         }
+        for data_offset_index in self.data_offset_indexes.iter() {
+            if !data_offset_index.index.is_empty() {
+                info!(
+                    "secondary index: {:?}",
+                    AccountIndex::DataOffset(*data_offset_index.key())
+                );
+                data_offset_index.log_contents();
+            }
+        }
     }
 
     pub(crate) fn update_secondary_indexes(
@@ -1570,6 +1836,12 @@ impl<T: IndexValue> AccountsIndex<T> {
             account_data,
             account_indexes,
         );
+        self.update_data_offset_secondary_indexes(
+            pubkey,
+            account_owner,
+            account_data,
+            account_indexes,
+        );
     }
 
     pub(crate) fn get_bin(&self, pubkey: &Pubkey) -> AccountMaps<T> {
@@ -1728,6 +2000,12 @@ impl<T: IndexValue> AccountsIndex<T> {
         if account_indexes.contains(&AccountIndex::SplTokenMint) {
             self.spl_token_mint_index.remove_by_inner_key(inner_key);
         }
+
+        for index in account_indexes.data_offset_indexes() {
+            if let Some(data_offset_index) = self.data_offset_index(index) {
+                data_offset_index.remove_by_inner_key(inner_key);
+            }
+        }
     }
 
     fn purge_older_root_entries(
@@ -3795,6 +4073,129 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_sharded_btree_secondary_index_get_from() {
+        let index = AccountsIndex::<bool>::default_for_tests();
+        let program_id = Pubkey::new_unique();
+        let mut pubkeys = (0..100).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        for pubkey in &pubkeys {
+            index.program_id_index.insert(&program_id, pubkey);
+        }
+        pubkeys.sort_unstable();
+
+        // pages continue across shards in key order
+        let mut paged = vec![];
+        let mut start = Bound::Unbounded;
+        loop {
+            let page = index.program_id_index.get_from(&program_id, start, 7);
+            assert!(page.len() <= 7);
+            match page.last() {
+                Some(last) => start = Bound::Excluded(*last),
+                None => break,
+            }
+            paged.extend(page);
+        }
+        assert_eq!(paged, pubkeys);
+        assert_eq!(
+            index
+                .program_id_index
+                .get_from(&program_id, Bound::Included(pubkeys[50]), 3),
+            pubkeys[50..53]
+        );
+
+        index.program_id_index.remove_by_inner_key(&pubkeys[51]);
+        assert_eq!(
+            index
+                .program_id_index
+                .get_from(&program_id, Bound::Excluded(pubkeys[50]), 2),
+            [pubkeys[52], pubkeys[53]]
+        );
+    }
+
+    #[test]
+    fn test_data_offset_secondary_index() {
+        let program_id = Pubkey::new_unique();
+        let data_offset_index = DataOffsetIndex::new(program_id, 8, 32).unwrap();
+        let secondary_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::DataOffset(data_offset_index)]),
+        };
+        let index = AccountsIndex::<bool>::default_for_tests();
+        let account_key = Pubkey::new_unique();
+        let index_key = Pubkey::new_unique();
+        let mut account_data = vec![0; 64];
+        account_data[8..40].copy_from_slice(index_key.as_ref());
+
+        // Wrong program id
+        index.update_secondary_indexes(
+            &account_key,
+            &AccountSharedData::create(0, account_data.clone(), Pubkey::new_unique(), false, 0),
+            &secondary_indexes,
+        );
+        // Data too short to hold the indexed bytes
+        index.update_secondary_indexes(
+            &account_key,
+            &AccountSharedData::create(0, account_data[..39].to_vec(), program_id, false, 0),
+            &secondary_indexes,
+        );
+        assert!(index.data_offset_index(&data_offset_index).is_none());
+
+        index.upsert(
+            0,
+            0,
+            &account_key,
+            &AccountSharedData::create(0, account_data, program_id, false, 0),
+            &secondary_indexes,
+            true,
+            &mut vec![],
+            UPSERT_POPULATE_RECLAIMS,
+        );
+        let secondary_index = index.data_offset_index(&data_offset_index).unwrap();
+        check_secondary_index_mapping_correct(&secondary_index, &[index_key], &account_key);
+        assert_eq!(
+            index.get_index_key_size(&AccountIndex::DataOffset(data_offset_index), &index_key),
+            Some(1)
+        );
+
+        index.slot_list_mut(&account_key, |slot_list| slot_list.clear());
+        let _ = index.handle_dead_keys(&[&account_key], &secondary_indexes);
+        assert!(secondary_index.index.is_empty());
+        assert!(secondary_index.reverse_index.is_empty());
+    }
+
+    #[test]
+    fn test_data_offset_index_keys() {
+        let program_id = Pubkey::new_unique();
+        assert!(DataOffsetIndex::new(program_id, 0, 0).is_none());
+        assert!(DataOffsetIndex::new(program_id, 0, 33).is_none());
+
+        let index = DataOffsetIndex::new(program_id, 2, 3).unwrap();
+        let mut expected_key = [0u8; PUBKEY_BYTES];
+        expected_key[..3].copy_from_slice(&[3, 4, 5]);
+        assert_eq!(
+            index.key_from_data(&[1, 2, 3, 4, 5, 6]),
+            Some(Pubkey::from(expected_key))
+        );
+        assert_eq!(
+            index.key_from_bytes(&[3, 4, 5]),
+            Some(Pubkey::from(expected_key))
+        );
+        assert_eq!(index.key_from_data(&[1, 2, 3, 4]), None);
+        assert_eq!(index.key_from_bytes(&[3, 4]), None);
+
+        assert_eq!(
+            format!("{program_id}:2:3").parse::<DataOffsetIndex>(),
+            Ok(index)
+        );
+        assert!(format!("{program_id}:2")
+            .parse::<DataOffsetIndex>()
+            .is_err());
+        assert!(format!("{program_id}:2:33")
+            .parse::<DataOffsetIndex>()
+            .is_err());
+        assert!("program:2:3".parse::<DataOffsetIndex>().is_err());
+    }
+
     fn run_test_secondary_indexes_same_slot_and_forks<
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
@@ -5,16 +5,25 @@ use {
 };
 
 pub trait AccountsUpdateNotifierInterface: std::fmt::Debug {
-    /// Notified when an account is updated at runtime, due to transaction activities
+    /// Notified when an account is updated at runtime, due to transaction activities.
+    /// `previous_account` is the version of the account replaced by the update, which is only
+    /// looked up when `previous_account_notifications_enabled` returns true.
     fn notify_account_update(
         &self,
         slot: Slot,
         account: &AccountSharedData,
+        previous_account: Option<&AccountSharedData>,
         txn_signature: &Option<&Signature>,
         pubkey: &Pubkey,
         write_version: u64,
     );
 
+    /// Check if the previous version of an updated account should be loaded for
+    /// `notify_account_update`. Loading it costs an extra account load per update.
+    fn previous_account_notifications_enabled(&self) -> bool {
+        false
+    }
+
     /// Notified when the AccountsDb is initialized at start when restored
     /// from a snapshot.
     fn notify_account_restore_from_snapshot(&self, slot: Slot, account: &StoredAccountMeta);
@@ -5,7 +5,13 @@
 //! <https://docs.solana.com/implemented-proposals/persistent-account-storage>
 
 use {
-    crate::storable_accounts::StorableAccounts,
+    crate::{
+        append_vec::compressed::{
+            is_compressed_file, write_compressed_file, CompressedAccounts, CompressedFile,
+            TARGET_BLOCK_SIZE,
+        },
+        storable_accounts::StorableAccounts,
+    },
     log::*,
     memmap2::MmapMut,
     serde::{Deserialize, Serialize},
@@ -18,20 +24,26 @@ use {
     std::{
         borrow::Borrow,
         convert::TryFrom,
-        fs::{remove_file, OpenOptions},
+        fs::{remove_file, File, OpenOptions},
         io::{self, Seek, SeekFrom, Write},
         marker::PhantomData,
         mem,
         path::{Path, PathBuf},
         sync::{
             atomic::{AtomicU64, AtomicUsize, Ordering},
-            Mutex,
+            Arc, Mutex,
         },
     },
 };
 
+mod compressed;
 pub mod test_utils;
 
+pub use compressed::{
+    AppendVecCompression, DecompressedBlock, DecompressedBlockCache, DECOMPRESSED_BLOCK_CACHE,
+    DEFAULT_DECOMPRESSED_BLOCK_CACHE_LIMIT_BYTES,
+};
+
 // Data placement should be aligned at the next boundary. Without alignment accessing the memory may
 // crash on some architectures.
 pub const ALIGN_BOUNDARY_OFFSET: usize = mem::size_of::<u64>();
@@ -275,17 +287,92 @@ impl<'a> Iterator for AppendVecAccountsIter<'a> {
     }
 }
 
+/// accounts laid out as in an append vec, where 'bytes' starts at offset 'start'
+struct AccountsBytes<'a> {
+    bytes: &'a [u8],
+    start: usize,
+}
+
+impl<'a> From<&'a DecompressedBlock> for AccountsBytes<'a> {
+    fn from(block: &'a DecompressedBlock) -> Self {
+        Self {
+            bytes: block.bytes(),
+            start: block.start(),
+        }
+    }
+}
+
+impl<'a> AccountsBytes<'a> {
+    /// Get a reference to the data at `offset` of `size` bytes if that slice
+    /// doesn't overrun the bytes. Otherwise return None.
+    /// Also return the offset of the first byte after the requested data that
+    /// falls on a 64-byte boundary.
+    fn get_slice(&self, offset: usize, size: usize) -> Option<(&'a [u8], usize)> {
+        let (next, overflow) = offset.overflowing_add(size);
+        if overflow || offset < self.start || next > self.start + self.bytes.len() {
+            return None;
+        }
+        let data = &self.bytes[(offset - self.start)..(next - self.start)];
+        let next = u64_align!(next);
+
+        Some((data, next))
+    }
+
+    /// Return a reference to the type at `offset` if its data doesn't overrun the bytes.
+    /// Otherwise return None. Also return the offset of the first byte after the requested data
+    /// that falls on a 64-byte boundary.
+    fn get_type<T>(&self, offset: usize) -> Option<(&'a T, usize)> {
+        let (data, next) = self.get_slice(offset, mem::size_of::<T>())?;
+        let ptr: *const T = data.as_ptr() as *const T;
+        //UNSAFE: The cast is safe because the slice is aligned and fits into the memory
+        //and the lifetime of the &T is tied to the bytes, which hold the underlying memory
+        Some((unsafe { &*ptr }, next))
+    }
+
+    /// Return account metadata for the account at `offset` if its data doesn't overrun
+    /// the bytes. Otherwise return None. Also return the offset of the first byte
+    /// after the requested data that falls on a 64-byte boundary.
+    fn get_account(&self, offset: usize) -> Option<(StoredAccountMeta<'a>, usize)> {
+        let (meta, next): (&'a StoredMeta, _) = self.get_type(offset)?;
+        let (account_meta, next): (&'a AccountMeta, _) = self.get_type(next)?;
+        let (hash, next): (&'a Hash, _) = self.get_type(next)?;
+        let (data, next) = self.get_slice(next, meta.data_len as usize)?;
+        let stored_size = next - offset;
+        Some((
+            StoredAccountMeta {
+                meta,
+                account_meta,
+                data,
+                offset,
+                stored_size,
+                hash,
+            },
+            next,
+        ))
+    }
+}
+
+/// where the accounts of an append vec are held
+#[derive(Debug)]
+enum AppendVecData {
+    /// A file-backed block of memory that is used to store the data for each appended item.
+    Mmap(MmapMut),
+    /// Read-only accounts in the compressed format, decompressed a block at a time.
+    Compressed(CompressedAccounts),
+}
+
 /// A thread-safe, file-backed block of memory used to store `Account` instances. Append operations
 /// are serialized such that only one thread updates the internal `append_lock` at a time. No
 /// restrictions are placed on reading. That is, one may read items from one thread while another
 /// is appending new items.
+/// An append vec can also be a read-only, compressed copy of another append vec. See `compress`.
 #[derive(Debug, AbiExample)]
 pub struct AppendVec {
     /// The file path where the data is stored.
     path: PathBuf,
 
-    /// A file-backed block of memory that is used to store the data for each appended item.
-    map: MmapMut,
+    /// The memory map or the compressed blocks holding the data for each appended item.
+    data: AppendVecData,
 
     /// A lock used to serialize append operations.
     append_lock: Mutex<()>,
@@ -306,7 +393,8 @@ lazy_static! {
 
 impl Drop for AppendVec {
     fn drop(&mut self) {
-        if self.remove_on_drop {
+        // a compressed file is removed once no append vec reads it anymore
+        if self.remove_on_drop && matches!(self.data, AppendVecData::Mmap(_)) {
             APPEND_VEC_MMAPPED_FILES_OPEN.fetch_sub(1, Ordering::Relaxed);
             if let Err(_e) = remove_file(&self.path) {
                 // promote this to panic soon.
@@ -366,7 +454,7 @@ impl AppendVec {
 
         AppendVec {
             path: file.to_path_buf(),
-            map,
+            data: AppendVecData::Mmap(map),
             // This mutex forces append to be single threaded, but concurrent with reads
             // See UNSAFE usage in `append_ptr`
             append_lock: Mutex::new(()),
@@ -378,6 +466,130 @@ impl AppendVec {
 
     pub fn set_no_remove_on_drop(&mut self) {
         self.remove_on_drop = false;
+        if let AppendVecData::Compressed(compressed) = &self.data {
+            compressed.file().set_no_remove_on_drop();
+        }
+    }
+
+    fn with_compressed_file(file: Arc<CompressedFile>) -> Self {
+        AppendVec {
+            path: file.path().to_path_buf(),
+            current_len: AtomicUsize::new(file.len()),
+            file_size: file.capacity(),
+            data: AppendVecData::Compressed(CompressedAccounts::new(file)),
+            append_lock: Mutex::new(()),
+            remove_on_drop: true,
+        }
+    }
+
+    /// Write the accounts of this append vec to 'path' in the compressed format and open the result.
+    /// The accounts keep their offsets, so the result can take the place of this append vec.
+    pub fn compress(&self, path: &Path, compression: AppendVecCompression) -> io::Result<Self> {
+        let AppendVecData::Mmap(map) = &self.data else {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "append vec is already compressed",
+            ));
+        };
+        let len = self.len();
+        // blocks hold whole accounts
+        let mut block_offsets = Vec::default();
+        let mut offset = 0;
+        while let Some((_account, next)) = self.get_account(offset) {
+            if block_offsets
+                .last()
+                .map(|block_offset| offset - block_offset >= TARGET_BLOCK_SIZE)
+                .unwrap_or(true)
+            {
+                block_offsets.push(offset);
+            }
+            offset = next;
+        }
+        if offset != u64_align!(len) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!(
+                    "incorrect layout in the appendvec at path {}",
+                    self.path.display()
+                ),
+            ));
+        }
+
+        write_compressed_file(
+            path,
+            compression,
+            self.capacity(),
+            &map[..len],
+            &block_offsets,
+        )
+        .and_then(|_| Self::open_compressed(path, len))
+        .map_err(|err| {
+            let _ignored = remove_file(path);
+            err
+        })
+    }
+
+    /// Write the accounts of this compressed append vec to 'path' as an uncompressed append vec file
+    /// of the same capacity. Nodes that do not know the compressed format can load that file, so
+    /// this is used when a compressed append vec is put in a snapshot archive.
+    pub fn decompress(&self, path: &Path) -> io::Result<()> {
+        let AppendVecData::Compressed(compressed) = &self.data else {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "append vec is not compressed",
+            ));
+        };
+        let mut file = File::create(path)?;
+        let mut result = Ok(());
+        compressed.scan_blocks(|bytes, _start| {
+            if result.is_ok() {
+                result = file.write_all(bytes);
+            }
+        });
+        result?;
+        file.set_len(self.capacity())?;
+        file.sync_all()
+    }
+
+    fn open_compressed(path: &Path, current_len: usize) -> io::Result<Self> {
+        let file = CompressedFile::open(path)?;
+        Self::sanitize_len_and_size(file.len(), file.capacity() as usize)?;
+        if file.len() != current_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "current_len {current_len} does not match the compressed appendvec at path {}",
+                    path.display()
+                ),
+            ));
+        }
+        Ok(Self::with_compressed_file(Arc::new(file)))
+    }
+
+    /// true if this append vec is read-only and compressed
+    pub fn is_compressed(&self) -> bool {
+        matches!(self.data, AppendVecData::Compressed(_))
+    }
+
+    /// number of bytes of a compressed append vec that are currently held decompressed
+    pub fn decompressed_bytes(&self) -> usize {
+        match &self.data {
+            AppendVecData::Mmap(_) => 0,
+            AppendVecData::Compressed(compressed) => compressed.decompressed_bytes(),
+        }
+    }
+
+    /// Return a new instance of a compressed append vec reading the same file, with no blocks decompressed.
+    /// Accounts loaded from a compressed append vec reference its decompressed blocks, so the blocks
+    /// are only released when the append vec is dropped.
+    /// Return None if this append vec is not compressed.
+    pub fn reopen_compressed(&self) -> Option<Self> {
+        match &self.data {
+            AppendVecData::Mmap(_) => None,
+            AppendVecData::Compressed(compressed) => {
+                Some(Self::with_compressed_file(Arc::clone(compressed.file())))
+            }
+        }
     }
 
     fn sanitize_len_and_size(current_len: usize, file_size: usize) -> io::Result<()> {
@@ -405,7 +617,10 @@ impl AppendVec {
     }
 
     pub fn flush(&self) -> io::Result<()> {
-        self.map.flush()
+        match &self.data {
+            AppendVecData::Mmap(map) => map.flush(),
+            AppendVecData::Compressed(_) => Ok(()),
+        }
     }
 
     pub fn reset(&self) {
@@ -417,6 +632,9 @@ impl AppendVec {
 
     /// how many more bytes can be stored in this append vec
     pub fn remaining_bytes(&self) -> u64 {
+        if self.is_compressed() {
+            return 0;
+        }
         (self.capacity()).saturating_sub(self.len() as u64)
     }
 
@@ -458,6 +676,9 @@ impl AppendVec {
         path: P,
         current_len: usize,
     ) -> io::Result<Self> {
+        if is_compressed_file(path.as_ref())? {
+            return Self::open_compressed(path.as_ref(), current_len);
+        }
         let file_size = std::fs::metadata(&path)?.len();
         Self::sanitize_len_and_size(current_len, file_size as usize)?;
 
@@ -479,7 +700,7 @@ impl AppendVec {
 
         Ok(AppendVec {
             path: path.as_ref().to_path_buf(),
-            map,
+            data: AppendVecData::Mmap(map),
             append_lock: Mutex::new(()),
             current_len: AtomicUsize::new(current_len),
             file_size,
@@ -495,44 +716,44 @@ impl AppendVec {
         // This code should not reuse AppendVec.accounts() method as the current form or
         // extend it to be reused here because it would allow attackers to accumulate
         // some measurable amount of memory needlessly.
+        // It also does not keep the blocks of a compressed append vec decompressed.
         let mut num_accounts = 0;
-        while let Some((account, next_offset)) = self.get_account(offset) {
-            if !account.sanitize() {
-                return (false, num_accounts);
+        let mut sanitized = true;
+        self.scan_accounts(|account| {
+            // accounts must follow each other, also across the blocks of a compressed append vec
+            if sanitized && account.offset == offset && account.sanitize() {
+                offset = account.offset + account.stored_size;
+                num_accounts += 1;
+            } else {
+                sanitized = false;
             }
-            offset = next_offset;
-            num_accounts += 1;
-        }
+        });
         let aligned_current_len = u64_align!(self.current_len.load(Ordering::Acquire));
 
-        (offset == aligned_current_len, num_accounts)
+        (sanitized && offset == aligned_current_len, num_accounts)
     }
 
-    /// Get a reference to the data at `offset` of `size` bytes if that slice
-    /// doesn't overrun the internal buffer. Otherwise return None.
-    /// Also return the offset of the first byte after the requested data that
-    /// falls on a 64-byte boundary.
-    fn get_slice(&self, offset: usize, size: usize) -> Option<(&[u8], usize)> {
-        let (next, overflow) = offset.overflowing_add(size);
-        if overflow || next > self.len() {
-            return None;
+    /// the accounts bytes holding `offset`
+    fn get_bytes(&self, offset: usize) -> Option<AccountsBytes<'_>> {
+        match &self.data {
+            AppendVecData::Mmap(map) => Some(AccountsBytes {
+                bytes: &map[..self.len()],
+                start: 0,
+            }),
+            AppendVecData::Compressed(compressed) => compressed
+                .get_block(offset)
+                .map(|(bytes, start)| AccountsBytes { bytes, start }),
         }
-        let data = &self.map[offset..next];
-        let next = u64_align!(next);
-
-        Some((
-            //UNSAFE: This unsafe creates a slice that represents a chunk of self.map memory
-            //The lifetime of this slice is tied to &self, since it points to self.map memory
-            unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, size) },
-            next,
-        ))
     }
 
     /// Copy `len` bytes from `src` to the first 64-byte boundary after position `offset` of
     /// the internal buffer. Then update `offset` to the first byte after the copied data.
     fn append_ptr(&self, offset: &mut usize, src: *const u8, len: usize) {
+        let AppendVecData::Mmap(map) = &self.data else {
+            unreachable!("compressed append vecs are read-only");
+        };
         let pos = u64_align!(*offset);
-        let data = &self.map[pos..(pos + len)];
+        let data = &map[pos..(pos + len)];
         //UNSAFE: This mut append is safe because only 1 thread can append at a time
         //Mutex<()> guarantees exclusive write access to the memory occupied in
         //the range.
@@ -566,37 +787,47 @@ impl AppendVec {
         Some(pos)
     }
 
-    /// Return a reference to the type at `offset` if its data doesn't overrun the internal buffer.
-    /// Otherwise return None. Also return the offset of the first byte after the requested data
-    /// that falls on a 64-byte boundary.
-    fn get_type<'a, T>(&self, offset: usize) -> Option<(&'a T, usize)> {
-        let (data, next) = self.get_slice(offset, mem::size_of::<T>())?;
-        let ptr: *const T = data.as_ptr() as *const T;
-        //UNSAFE: The cast is safe because the slice is aligned and fits into the memory
-        //and the lifetime of the &T is tied to self, which holds the underlying memory map
-        Some((unsafe { &*ptr }, next))
-    }
-
     /// Return account metadata for the account at `offset` if its data doesn't overrun
     /// the internal buffer. Otherwise return None. Also return the offset of the first byte
     /// after the requested data that falls on a 64-byte boundary.
     pub fn get_account<'a>(&'a self, offset: usize) -> Option<(StoredAccountMeta<'a>, usize)> {
-        let (meta, next): (&'a StoredMeta, _) = self.get_type(offset)?;
-        let (account_meta, next): (&'a AccountMeta, _) = self.get_type(next)?;
-        let (hash, next): (&'a Hash, _) = self.get_type(next)?;
-        let (data, next) = self.get_slice(next, meta.data_len as usize)?;
-        let stored_size = next - offset;
-        Some((
-            StoredAccountMeta {
-                meta,
-                account_meta,
-                data,
-                offset,
-                stored_size,
-                hash,
-            },
-            next,
-        ))
+        self.get_bytes(offset)?.get_account(offset)
+    }
+
+    /// For a compressed append vec, the decompressed block holding the account at `offset`.
+    /// Unlike `get_account`, this does not keep the block decompressed in this append vec, so it is
+    /// preferred for loads that are done before the block is dropped. See `get_account_in_block`.
+    /// Return None if this append vec is not compressed or `offset` is past its end.
+    pub fn get_decompressed_block(&self, offset: usize) -> Option<Arc<DecompressedBlock>> {
+        match &self.data {
+            AppendVecData::Mmap(_) => None,
+            AppendVecData::Compressed(compressed) => compressed.load_block(offset),
+        }
+    }
+
+    /// Return account metadata for the account at `offset` in `block`, like `get_account`.
+    pub fn get_account_in_block(
+        block: &DecompressedBlock,
+        offset: usize,
+    ) -> Option<(StoredAccountMeta<'_>, usize)> {
+        AccountsBytes::from(block).get_account(offset)
+    }
+
+    /// Call `callback` with each account, in order.
+    /// Unlike `account_iter`, this does not keep the blocks of a compressed append vec decompressed,
+    /// so it is preferred for passes over all the accounts that do not hold on to them.
+    pub fn scan_accounts(&self, mut callback: impl FnMut(StoredAccountMeta<'_>)) {
+        match &self.data {
+            AppendVecData::Mmap(_) => self.account_iter().for_each(callback),
+            AppendVecData::Compressed(compressed) => compressed.scan_blocks(|bytes, start| {
+                let accounts = AccountsBytes { bytes, start };
+                let mut offset = start;
+                while let Some((account, next)) = accounts.get_account(offset) {
+                    callback(account);
+                    offset = next;
+                }
+            }),
+        }
     }
 
     #[cfg(test)]
@@ -643,6 +874,9 @@ impl AppendVec {
         accounts: &StorableAccountsWithHashesAndWriteVersions<'a, 'b, T, U, V>,
         skip: usize,
     ) -> Option<Vec<usize>> {
+        if self.is_compressed() {
+            return None;
+        }
         let _lock = self.append_lock.lock().unwrap();
         let mut offset = self.len();
 
@@ -1216,6 +1450,106 @@ pub mod tests {
         assert_matches!(result, Err(ref message) if message.to_string().starts_with("incorrect layout/length/data"));
     }
 
+    #[test]
+    fn test_compress() {
+        let file = get_append_vec_path("test_compress");
+        let av = AppendVec::new(&file.path, true, 4 * 1024 * 1024);
+        // enough accounts for several blocks
+        let accounts = (0..2000)
+            .map(|sample| {
+                let account = create_test_account(sample);
+                let index = av.append_account_test(&account).unwrap();
+                (index, account)
+            })
+            .collect::<Vec<_>>();
+
+        for compression in [AppendVecCompression::Lz4, AppendVecCompression::Zstd] {
+            let compressed_file = get_append_vec_path("test_compress_compressed");
+            let compressed = av.compress(&compressed_file.path, compression).unwrap();
+            assert!(compressed.is_compressed());
+            assert!(std::fs::metadata(&compressed_file.path).unwrap().len() < av.len() as u64 / 2);
+            assert_eq!(compressed.len(), av.len());
+            assert_eq!(compressed.capacity(), av.capacity());
+            assert_eq!(compressed.remaining_bytes(), 0);
+            assert!(compressed.append_account_test(&accounts[0].1).is_none());
+
+            // scanning does not keep blocks decompressed
+            let mut scanned = vec![];
+            compressed.scan_accounts(|account| {
+                scanned.push((account.offset, account.stored_size, account.clone_account()))
+            });
+            assert_eq!(compressed.decompressed_bytes(), 0);
+            assert_eq!(
+                scanned,
+                av.account_iter()
+                    .map(|account| (account.offset, account.stored_size, account.clone_account()))
+                    .collect::<Vec<_>>()
+            );
+
+            // accounts are at the same offsets
+            for (index, (meta, account)) in &accounts {
+                let (stored, _next) = compressed.get_account(*index).unwrap();
+                assert_eq!(stored.meta, meta);
+                assert_eq!(&stored.clone_account(), account);
+            }
+            assert!(compressed.decompressed_bytes() > 0);
+            assert_eq!(compressed.account_iter().count(), accounts.len());
+            let reopened = compressed.reopen_compressed().unwrap();
+            assert_eq!(reopened.decompressed_bytes(), 0);
+            // loading from a decompressed block does not keep the block in the append vec
+            let block = reopened.get_decompressed_block(accounts[1].0).unwrap();
+            let (stored, _next) = AppendVec::get_account_in_block(&block, accounts[1].0).unwrap();
+            assert_eq!(&stored.clone_account(), &accounts[1].1 .1);
+            assert_eq!(reopened.decompressed_bytes(), 0);
+            assert!(reopened.get_decompressed_block(av.len()).is_none());
+            assert!(av.get_decompressed_block(accounts[1].0).is_none());
+            assert_eq!(
+                reopened.get_account_test(accounts[1].0).unwrap().0,
+                accounts[1].1 .0
+            );
+            assert!(compressed.compress(&file.path, compression).is_err());
+
+            // the decompressed file is a regular append vec with the same accounts
+            let decompressed_file = get_append_vec_path("test_compress_decompressed");
+            compressed.decompress(&decompressed_file.path).unwrap();
+            assert_eq!(
+                std::fs::metadata(&decompressed_file.path).unwrap().len(),
+                av.capacity()
+            );
+            let (decompressed, num_accounts) =
+                AppendVec::new_from_file(&decompressed_file.path, av.len()).unwrap();
+            assert!(!decompressed.is_compressed());
+            assert_eq!(num_accounts, accounts.len());
+            assert_eq!(
+                decompressed
+                    .account_iter()
+                    .map(|account| (account.offset, account.clone_account()))
+                    .collect::<Vec<_>>(),
+                av.account_iter()
+                    .map(|account| (account.offset, account.clone_account()))
+                    .collect::<Vec<_>>()
+            );
+            assert!(av.decompress(&decompressed_file.path).is_err());
+
+            // a compressed append vec is loaded like any other, such as from a snapshot
+            let (mut loaded, num_accounts) =
+                AppendVec::new_from_file(&compressed_file.path, av.len()).unwrap();
+            loaded.set_no_remove_on_drop();
+            assert!(loaded.is_compressed());
+            assert_eq!(num_accounts, accounts.len());
+            assert_eq!(loaded.decompressed_bytes(), 0);
+            let result = AppendVec::new_from_file(&compressed_file.path, av.len() - 1);
+            assert_matches!(result, Err(ref message) if message.to_string().starts_with("current_len"));
+
+            // the file is removed once the last append vec reading it is dropped
+            drop(compressed);
+            drop(loaded);
+            assert!(compressed_file.path.exists());
+            drop(reopened);
+            assert!(!compressed_file.path.exists());
+        }
+    }
+
     #[test]
     fn test_new_from_file_crafted_executable() {
         let file = get_append_vec_path("test_new_from_crafted_executable");
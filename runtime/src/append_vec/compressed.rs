@@ -0,0 +1,651 @@
+//! A read-only, compressed file format for the accounts of an append vec.
+//!
+//! The accounts keep exactly the layout they have in an uncompressed append vec, so the offset of
+//! an account (and therefore the accounts index) does not change when its append vec is
+//! compressed. That layout is cut into blocks at account boundaries and each block is compressed
+//! on its own, so loading one account only decompresses the block holding it.
+//! Decompressed blocks are shared by all compressed append vecs through `DECOMPRESSED_BLOCK_CACHE`,
+//! which is limited in size.
+//!
+//! File layout, all integers little endian:
+//! ```text
+//! magic        8 bytes, MAGIC
+//! version      u32
+//! compression  u32, AppendVecCompression
+//! len          u64, number of bytes used by the uncompressed accounts
+//! capacity     u64, capacity of the append vec the accounts were compressed from
+//! num_blocks   u64
+//! offset table num_blocks * (uncompressed offset u64, compressed len u64)
+//! blocks       the compressed blocks, back to back, in offset table order
+//! ```
+use {
+    log::*,
+    lru::LruCache,
+    once_cell::sync::OnceCell,
+    std::{
+        fs::{remove_file, File},
+        io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+        path::{Path, PathBuf},
+        str::FromStr,
+        sync::{
+            atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+    },
+};
+
+const MAGIC: [u8; 8] = *b"solavcmp";
+const VERSION: u32 = 1;
+const HEADER_SIZE: u64 = 40;
+const BLOCK_ENTRY_SIZE: u64 = 16;
+
+/// blocks are cut at the first account boundary at or after this many uncompressed bytes
+pub(crate) const TARGET_BLOCK_SIZE: usize = 64 * 1024;
+
+pub const DEFAULT_DECOMPRESSED_BLOCK_CACHE_LIMIT_BYTES: usize = 1024 * 1024 * 1024;
+
+lazy_static! {
+    /// decompressed blocks of every compressed append vec
+    pub static ref DECOMPRESSED_BLOCK_CACHE: DecompressedBlockCache =
+        DecompressedBlockCache::new(DEFAULT_DECOMPRESSED_BLOCK_CACHE_LIMIT_BYTES);
+}
+
+/// identifies a `CompressedFile` in `DECOMPRESSED_BLOCK_CACHE`
+static NEXT_COMPRESSED_FILE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// how the blocks of a compressed append vec are compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppendVecCompression {
+    Lz4,
+    Zstd,
+}
+
+impl AppendVecCompression {
+    fn to_u32(self) -> u32 {
+        match self {
+            Self::Lz4 => 1,
+            Self::Zstd => 2,
+        }
+    }
+
+    fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(Self::Lz4),
+            2 => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    fn compress(self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Lz4 => lz4::block::compress(data, None, false),
+            Self::Zstd => zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL),
+        }
+    }
+
+    fn decompress(self, data: &[u8], len: usize) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Lz4 => lz4::block::decompress(
+                data,
+                Some(i32::try_from(len).map_err(|_| invalid_data("block is too large"))?),
+            ),
+            Self::Zstd => zstd::bulk::decompress(data, len),
+        }
+    }
+}
+
+impl FromStr for AppendVecCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lz4" => Ok(Self::Lz4),
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(format!("unknown append vec compression: {s}")),
+        }
+    }
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+/// true if the file at 'path' is in the compressed format
+pub(crate) fn is_compressed_file(path: &Path) -> io::Result<bool> {
+    let mut magic = [0u8; MAGIC.len()];
+    match File::open(path)?.read_exact(&mut magic) {
+        Ok(()) => Ok(magic == MAGIC),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Write the accounts in 'accounts', laid out as in an uncompressed append vec, to 'path'.
+/// 'block_offsets' are the offsets of the accounts each block starts with. The first one must be 0.
+pub(crate) fn write_compressed_file(
+    path: &Path,
+    compression: AppendVecCompression,
+    capacity: u64,
+    accounts: &[u8],
+    block_offsets: &[usize],
+) -> io::Result<()> {
+    assert_eq!(block_offsets.first().copied().unwrap_or_default(), 0);
+    let block_ends = block_offsets
+        .iter()
+        .skip(1)
+        .copied()
+        .chain(std::iter::once(accounts.len()));
+    let blocks = block_offsets
+        .iter()
+        .zip(block_ends)
+        .map(|(start, end)| compression.compress(&accounts[*start..end]))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(&MAGIC)?;
+    file.write_all(&VERSION.to_le_bytes())?;
+    file.write_all(&compression.to_u32().to_le_bytes())?;
+    file.write_all(&(accounts.len() as u64).to_le_bytes())?;
+    file.write_all(&capacity.to_le_bytes())?;
+    file.write_all(&(blocks.len() as u64).to_le_bytes())?;
+    for (offset, block) in block_offsets.iter().zip(blocks.iter()) {
+        file.write_all(&(*offset as u64).to_le_bytes())?;
+        file.write_all(&(block.len() as u64).to_le_bytes())?;
+    }
+    for block in &blocks {
+        file.write_all(block)?;
+    }
+    file.flush()
+}
+
+#[derive(Debug)]
+struct BlockInfo {
+    /// offset of the first account in the block, as in an uncompressed append vec
+    offset: usize,
+    /// where the compressed block starts in the file
+    file_offset: u64,
+    compressed_len: usize,
+}
+
+/// the file of a compressed append vec, shared by every `CompressedAccounts` reading it
+#[derive(Debug)]
+pub(crate) struct CompressedFile {
+    id: u64,
+    path: PathBuf,
+    file: Mutex<File>,
+    compression: AppendVecCompression,
+    len: usize,
+    capacity: u64,
+    blocks: Vec<BlockInfo>,
+    remove_on_drop: AtomicBool,
+}
+
+impl Drop for CompressedFile {
+    fn drop(&mut self) {
+        DECOMPRESSED_BLOCK_CACHE.remove_file(self);
+        if self.remove_on_drop.load(Ordering::Acquire) {
+            if let Err(err) = remove_file(&self.path) {
+                debug!(
+                    "failed to remove compressed append vec {}: {err}",
+                    self.path.display()
+                );
+            }
+        }
+    }
+}
+
+impl CompressedFile {
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let mut header = [0u8; HEADER_SIZE as usize];
+        file.read_exact(&mut header)?;
+        if header[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data("not a compressed append vec"));
+        }
+        let version = read_u32(&header[8..]);
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported compressed append vec version: {version}"
+            )));
+        }
+        let compression = AppendVecCompression::from_u32(read_u32(&header[12..]))
+            .ok_or_else(|| invalid_data("unknown append vec compression"))?;
+        let len = read_u64(&header[16..]);
+        let capacity = read_u64(&header[24..]);
+        let num_blocks = read_u64(&header[32..]);
+        let table_len = num_blocks
+            .checked_mul(BLOCK_ENTRY_SIZE)
+            .filter(|table_len| *table_len <= file_len.saturating_sub(HEADER_SIZE))
+            .ok_or_else(|| invalid_data("offset table is larger than the file"))?;
+        if len > capacity {
+            return Err(invalid_data("len is larger than capacity"));
+        }
+
+        let mut table = vec![0u8; table_len as usize];
+        file.read_exact(&mut table)?;
+        let mut file_offset = HEADER_SIZE + table_len;
+        let mut blocks = Vec::with_capacity(num_blocks as usize);
+        for entry in table.chunks_exact(BLOCK_ENTRY_SIZE as usize) {
+            let offset = read_u64(entry);
+            let compressed_len = read_u64(&entry[8..]);
+            let valid_offset = match blocks.last() {
+                None => offset == 0,
+                Some(BlockInfo {
+                    offset: previous, ..
+                }) => offset > *previous as u64 && offset < len,
+            };
+            if !valid_offset || offset % 8 != 0 {
+                return Err(invalid_data(format!("invalid block offset: {offset}")));
+            }
+            blocks.push(BlockInfo {
+                offset: offset as usize,
+                file_offset,
+                compressed_len: compressed_len as usize,
+            });
+            file_offset = file_offset
+                .checked_add(compressed_len)
+                .filter(|end| *end <= file_len)
+                .ok_or_else(|| invalid_data("block is past the end of the file"))?;
+        }
+        if file_offset != file_len {
+            return Err(invalid_data("unexpected data after the last block"));
+        }
+        if blocks.is_empty() != (len == 0) {
+            return Err(invalid_data("blocks do not match len"));
+        }
+
+        Ok(Self {
+            id: NEXT_COMPRESSED_FILE_ID.fetch_add(1, Ordering::Relaxed),
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+            compression,
+            len: len as usize,
+            capacity,
+            blocks,
+            remove_on_drop: AtomicBool::new(true),
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn set_no_remove_on_drop(&self) {
+        self.remove_on_drop.store(false, Ordering::Release);
+    }
+
+    /// uncompressed offset of the first and one past the last byte of block 'index'
+    fn block_range(&self, index: usize) -> (usize, usize) {
+        let end = self
+            .blocks
+            .get(index + 1)
+            .map(|block| block.offset)
+            .unwrap_or(self.len);
+        (self.blocks[index].offset, end)
+    }
+
+    /// index of the block holding 'offset'
+    fn block_index(&self, offset: usize) -> Option<usize> {
+        if offset >= self.len {
+            return None;
+        }
+        Some(
+            self.blocks
+                .partition_point(|block| block.offset <= offset)
+                .saturating_sub(1),
+        )
+    }
+
+    /// Read and decompress block 'index'.
+    fn decompress_block(&self, index: usize) -> io::Result<DecompressedBlock> {
+        let block = &self.blocks[index];
+        let mut compressed = vec![0u8; block.compressed_len];
+        {
+            let mut file = self.file.lock().unwrap();
+            file.seek(SeekFrom::Start(block.file_offset))?;
+            file.read_exact(&mut compressed)?;
+        }
+        let (start, end) = self.block_range(index);
+        let len = end - start;
+        let decompressed = self.compression.decompress(&compressed, len)?;
+        if decompressed.len() != len {
+            return Err(invalid_data("decompressed block has the wrong size"));
+        }
+        let mut words = vec![0u64; (len + 7) / 8].into_boxed_slice();
+        bytemuck::cast_slice_mut::<u64, u8>(&mut words)[..len].copy_from_slice(&decompressed);
+        Ok(DecompressedBlock { words, len, start })
+    }
+
+    /// block 'index', decompressed. Panics if it cannot be read.
+    fn expect_decompress_block(&self, index: usize) -> DecompressedBlock {
+        self.decompress_block(index).unwrap_or_else(|err| {
+            panic!(
+                "unable to decompress block {index} of {}: {err}",
+                self.path.display()
+            )
+        })
+    }
+}
+
+/// a decompressed block of a compressed append vec
+#[derive(Debug)]
+pub struct DecompressedBlock {
+    /// The contents are held in u64s so accounts can be referenced in place with their alignment.
+    words: Box<[u64]>,
+    len: usize,
+    /// offset of the first byte of the block, as in an uncompressed append vec
+    start: usize,
+}
+
+impl DecompressedBlock {
+    pub(crate) fn bytes(&self) -> &[u8] {
+        &bytemuck::cast_slice::<u64, u8>(&self.words)[..self.len]
+    }
+
+    pub(crate) fn start(&self) -> usize {
+        self.start
+    }
+}
+
+/// Decompressed blocks shared by all compressed append vecs, up to a total of `limit_bytes`.
+/// The least recently used blocks are evicted first.
+/// An evicted block is freed once no account loaded from it is referenced anymore.
+#[derive(Debug)]
+pub struct DecompressedBlockCache {
+    limit_bytes: AtomicUsize,
+    inner: Mutex<DecompressedBlockCacheInner>,
+}
+
+#[derive(Debug)]
+struct DecompressedBlockCacheInner {
+    /// keyed by the id of the file and the index of the block in it
+    blocks: LruCache<(u64, usize), Arc<DecompressedBlock>>,
+    bytes: usize,
+}
+
+impl DecompressedBlockCacheInner {
+    fn evict(&mut self, limit_bytes: usize) {
+        while self.bytes > limit_bytes {
+            match self.blocks.pop_lru() {
+                Some((_key, block)) => self.bytes -= block.len,
+                None => break,
+            }
+        }
+    }
+}
+
+impl DecompressedBlockCache {
+    pub fn new(limit_bytes: usize) -> Self {
+        Self {
+            limit_bytes: AtomicUsize::new(limit_bytes),
+            inner: Mutex::new(DecompressedBlockCacheInner {
+                blocks: LruCache::unbounded(),
+                bytes: 0,
+            }),
+        }
+    }
+
+    pub fn set_limit_bytes(&self, limit_bytes: usize) {
+        self.limit_bytes.store(limit_bytes, Ordering::Relaxed);
+        self.inner.lock().unwrap().evict(limit_bytes);
+    }
+
+    /// number of bytes of the blocks in the cache
+    pub fn bytes(&self) -> usize {
+        self.inner.lock().unwrap().bytes
+    }
+
+    /// block 'index' of 'file', decompressed if it is not in the cache
+    fn get_or_decompress(&self, file: &CompressedFile, index: usize) -> Arc<DecompressedBlock> {
+        let key = (file.id, index);
+        if let Some(block) = self.inner.lock().unwrap().blocks.get(&key) {
+            return Arc::clone(block);
+        }
+        // decompress without holding the lock. Another thread may decompress the same block meanwhile.
+        let block = Arc::new(file.expect_decompress_block(index));
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(previous) = inner.blocks.put(key, Arc::clone(&block)) {
+            inner.bytes -= previous.len;
+        }
+        inner.bytes += block.len;
+        inner.evict(self.limit_bytes.load(Ordering::Relaxed));
+        block
+    }
+
+    /// block 'index' of 'file' if it is in the cache
+    fn peek(&self, file: &CompressedFile, index: usize) -> Option<Arc<DecompressedBlock>> {
+        self.inner
+            .lock()
+            .unwrap()
+            .blocks
+            .peek(&(file.id, index))
+            .cloned()
+    }
+
+    /// remove the blocks of 'file', which is no longer read
+    fn remove_file(&self, file: &CompressedFile) {
+        let mut inner = self.inner.lock().unwrap();
+        for index in 0..file.blocks.len() {
+            if let Some(block) = inner.blocks.pop(&(file.id, index)) {
+                inner.bytes -= block.len;
+            }
+        }
+    }
+}
+
+/// Accounts read from a `CompressedFile`.
+/// Blocks come from `DECOMPRESSED_BLOCK_CACHE`. Accounts borrowed from here through `get_block`
+/// reference the block they are in, so such blocks are also kept by this instance until it is
+/// dropped. `load_block` and `scan_blocks` do not keep the blocks.
+#[derive(Debug)]
+pub(crate) struct CompressedAccounts {
+    file: Arc<CompressedFile>,
+    pinned_blocks: Vec<OnceCell<Arc<DecompressedBlock>>>,
+    pinned_bytes: AtomicUsize,
+}
+
+impl CompressedAccounts {
+    pub(crate) fn new(file: Arc<CompressedFile>) -> Self {
+        Self {
+            pinned_blocks: (0..file.blocks.len()).map(|_| OnceCell::new()).collect(),
+            file,
+            pinned_bytes: AtomicUsize::default(),
+        }
+    }
+
+    pub(crate) fn file(&self) -> &Arc<CompressedFile> {
+        &self.file
+    }
+
+    /// number of bytes of the blocks kept by this instance
+    pub(crate) fn decompressed_bytes(&self) -> usize {
+        self.pinned_bytes.load(Ordering::Relaxed)
+    }
+
+    /// the decompressed block holding 'offset', which is not kept by this instance
+    pub(crate) fn load_block(&self, offset: usize) -> Option<Arc<DecompressedBlock>> {
+        let index = self.file.block_index(offset)?;
+        Some(
+            self.pinned_blocks[index]
+                .get()
+                .cloned()
+                .unwrap_or_else(|| DECOMPRESSED_BLOCK_CACHE.get_or_decompress(&self.file, index)),
+        )
+    }
+
+    /// the decompressed block holding 'offset' and the offset of its first byte
+    /// The block is kept by this instance, since the returned bytes borrow from it.
+    pub(crate) fn get_block(&self, offset: usize) -> Option<(&[u8], usize)> {
+        let index = self.file.block_index(offset)?;
+        let block = self.pinned_blocks[index].get_or_init(|| {
+            let block = DECOMPRESSED_BLOCK_CACHE.get_or_decompress(&self.file, index);
+            self.pinned_bytes.fetch_add(block.len, Ordering::Relaxed);
+            block
+        });
+        Some((block.bytes(), block.start))
+    }
+
+    /// call 'callback' with each block and the offset of its first byte, in order.
+    /// Blocks that are not already decompressed are decompressed just for the call.
+    pub(crate) fn scan_blocks(&self, mut callback: impl FnMut(&[u8], usize)) {
+        for (index, pinned) in self.pinned_blocks.iter().enumerate() {
+            match pinned
+                .get()
+                .cloned()
+                .or_else(|| DECOMPRESSED_BLOCK_CACHE.peek(&self.file, index))
+            {
+                Some(block) => callback(block.bytes(), block.start),
+                None => {
+                    let block = self.file.expect_decompress_block(index);
+                    callback(block.bytes(), block.start);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    #[test]
+    fn test_compressed_file_write_open() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("compressed");
+        let accounts = (0..1000u64)
+            .flat_map(|i| (i % 7).to_le_bytes())
+            .collect::<Vec<_>>();
+        for compression in [AppendVecCompression::Lz4, AppendVecCompression::Zstd] {
+            write_compressed_file(&path, compression, 10_000, &accounts, &[0, 800, 4000]).unwrap();
+            assert!(is_compressed_file(&path).unwrap());
+            let file = Arc::new(CompressedFile::open(&path).unwrap());
+            file.set_no_remove_on_drop();
+            assert_eq!(file.len(), accounts.len());
+            assert_eq!(file.capacity(), 10_000);
+            assert_eq!(file.compression, compression);
+
+            let compressed = CompressedAccounts::new(Arc::clone(&file));
+            let mut scanned = vec![];
+            compressed.scan_blocks(|block, start| {
+                assert_eq!(start, scanned.len());
+                scanned.extend_from_slice(block);
+            });
+            assert_eq!(scanned, accounts);
+            assert_eq!(compressed.decompressed_bytes(), 0);
+
+            let (block, start) = compressed.get_block(900).unwrap();
+            assert_eq!(start, 800);
+            assert_eq!(block, &accounts[800..4000]);
+            assert_eq!(compressed.decompressed_bytes(), 3200);
+            assert_eq!(compressed.get_block(7999).unwrap().1, 4000);
+            assert!(compressed.get_block(8000).is_none());
+        }
+
+        assert!(is_compressed_file(&temp_dir.path().join("missing")).is_err());
+        let uncompressed = temp_dir.path().join("uncompressed");
+        std::fs::write(&uncompressed, [0u8; 4]).unwrap();
+        assert!(!is_compressed_file(&uncompressed).unwrap());
+    }
+
+    #[test]
+    fn test_decompressed_block_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("compressed");
+        let accounts = (0..1000u64)
+            .flat_map(|i| (i % 7).to_le_bytes())
+            .collect::<Vec<_>>();
+        write_compressed_file(
+            &path,
+            AppendVecCompression::Lz4,
+            10_000,
+            &accounts,
+            &[0, 800, 4000],
+        )
+        .unwrap();
+        let file = CompressedFile::open(&path).unwrap();
+        file.set_no_remove_on_drop();
+
+        // room for the last two blocks, which hold 3200 and 4000 bytes
+        let cache = DecompressedBlockCache::new(7200);
+        let first = cache.get_or_decompress(&file, 0);
+        assert_eq!(first.bytes(), &accounts[..800]);
+        assert_eq!(first.start(), 0);
+        assert_eq!(cache.bytes(), 800);
+        let second = cache.get_or_decompress(&file, 1);
+        assert_eq!(second.start(), 800);
+        assert_eq!(cache.bytes(), 4000);
+        // a cached block is shared
+        assert!(Arc::ptr_eq(&first, &cache.get_or_decompress(&file, 0)));
+
+        // the least recently used block is evicted, but stays valid while it is referenced
+        let third = cache.get_or_decompress(&file, 2);
+        assert_eq!(third.bytes(), &accounts[4000..]);
+        assert_eq!(cache.bytes(), 4800);
+        assert!(cache.peek(&file, 1).is_none());
+        assert_eq!(second.bytes(), &accounts[800..4000]);
+        assert!(cache.peek(&file, 0).is_some());
+
+        cache.set_limit_bytes(4000);
+        assert_eq!(cache.bytes(), 4000);
+        assert!(cache.peek(&file, 0).is_none());
+        assert!(cache.peek(&file, 2).is_some());
+
+        cache.remove_file(&file);
+        assert_eq!(cache.bytes(), 0);
+        assert!(cache.peek(&file, 2).is_none());
+    }
+
+    #[test]
+    fn test_compressed_file_open_invalid() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("compressed");
+        let accounts = vec![1u8; 1024];
+        write_compressed_file(&path, AppendVecCompression::Lz4, 2048, &accounts, &[0, 512])
+            .unwrap();
+        let contents = std::fs::read(&path).unwrap();
+
+        let corruptions: Vec<Box<dyn Fn(&mut Vec<u8>)>> = vec![
+            // magic
+            Box::new(|contents| contents[0] = b'x'),
+            // version
+            Box::new(|contents| contents[8] = 2),
+            // compression
+            Box::new(|contents| contents[12] = 9),
+            // len larger than capacity
+            Box::new(|contents| contents[16..24].copy_from_slice(&4096u64.to_le_bytes())),
+            // too many blocks
+            Box::new(|contents| contents[32..40].copy_from_slice(&u64::MAX.to_le_bytes())),
+            // unaligned block offset
+            Box::new(|contents| contents[56..64].copy_from_slice(&513u64.to_le_bytes())),
+            // truncated
+            Box::new(|contents| {
+                contents.pop();
+            }),
+            // trailing data
+            Box::new(|contents| contents.push(0)),
+        ];
+        for (i, corrupt) in corruptions.into_iter().enumerate() {
+            let mut corrupted = contents.clone();
+            corrupt(&mut corrupted);
+            std::fs::write(&path, corrupted).unwrap();
+            assert!(CompressedFile::open(&path).is_err(), "corruption {i}");
+        }
+    }
+}
@@ -48,7 +48,7 @@ use {
             CalcAccountsHashDataSource, IncludeSlotInHash, ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
         },
-        accounts_hash::AccountsHash,
+        accounts_hash::{AccountHashEntry, AccountsHash, AccountsHashBins, CalcAccountsHashConfig},
         accounts_index::{AccountSecondaryIndexes, IndexKey, ScanConfig, ScanResult, ZeroLamport},
         accounts_update_notifier_interface::AccountsUpdateNotifier,
         ancestors::{Ancestors, AncestorsForSerialization},
@@ -96,6 +96,7 @@ use {
             BankExecutorCache, TransactionExecutorCache, TxBankExecutorCacheDiff,
             MAX_CACHED_EXECUTORS,
         },
+        instruction_tracer::{InstructionTrace, InstructionTracing},
         invoke_context::{BuiltinProgram, ProcessInstructionWithContext},
         log_collector::LogCollector,
         sysvar_cache::SysvarCache,
@@ -369,6 +370,23 @@ pub struct TransactionExecutionDetails {
     /// The change in accounts data len for this transaction.
     /// NOTE: This value is valid IFF `status` is `Ok`.
     pub accounts_data_len_delta: i64,
+    /// The accounts writable by this transaction, before and after executing it. Only recorded
+    /// when `RuntimeConfig::record_transaction_account_diffs` is set.
+    pub account_diffs: Option<Vec<TransactionAccountDiff>>,
+    /// The instructions executed, with the compute units each consumed. Only recorded when
+    /// instruction tracing is enabled, e.g. for simulations.
+    pub instruction_trace: Option<InstructionTrace>,
+}
+
+/// The state of an account writable by a transaction before and after executing it.
+/// `pre_account` is the account as loaded for the transaction, so the fee has already been
+/// deducted from the fee payer. `post_account` is the output of the execution, which is
+/// discarded if the transaction fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionAccountDiff {
+    pub pubkey: Pubkey,
+    pub pre_account: AccountSharedData,
+    pub post_account: AccountSharedData,
 }
 
 /// Type safe representation of a transaction execution attempt which
@@ -464,9 +482,18 @@ impl DurableNonceFee {
 pub struct TransactionSimulationResult {
     pub result: Result<()>,
     pub logs: TransactionLogMessages,
+    /// The transaction's accounts as stored in the bank before the simulation, in the order of
+    /// its account keys
+    pub pre_simulation_accounts: Vec<TransactionAccount>,
     pub post_simulation_accounts: Vec<TransactionAccount>,
     pub units_consumed: u64,
     pub return_data: Option<TransactionReturnData>,
+    pub inner_instructions: Option<InnerInstructionsList>,
+    pub instruction_trace: Option<InstructionTrace>,
+    /// The accounts the transaction would store if it were committed: its writable accounts if
+    /// it succeeded, or only the fee payer charged the fee and the advanced nonce account if it
+    /// failed
+    pub written_accounts: Vec<TransactionAccount>,
 }
 pub struct TransactionBalancesSet {
     pub pre_balances: TransactionBalances,
@@ -3724,10 +3751,12 @@ impl Bank {
     pub fn simulate_transaction(
         &self,
         transaction: SanitizedTransaction,
+        enable_cpi_recording: bool,
+        instruction_tracing: InstructionTracing,
     ) -> TransactionSimulationResult {
         assert!(self.is_frozen(), "simulation bank must be frozen");
 
-        self.simulate_transaction_unchecked(transaction)
+        self.simulate_transaction_unchecked(transaction, enable_cpi_recording, instruction_tracing)
     }
 
     /// Run transactions against a bank without committing the results; does not check if the bank
@@ -3735,15 +3764,144 @@ impl Bank {
     pub fn simulate_transaction_unchecked(
         &self,
         transaction: SanitizedTransaction,
+        enable_cpi_recording: bool,
+        instruction_tracing: InstructionTracing,
+    ) -> TransactionSimulationResult {
+        let mut account_overrides = AccountOverrides::default();
+        self.add_account_overrides_for_simulation(
+            &transaction.message().account_keys(),
+            &mut account_overrides,
+        );
+        self.simulate_transaction_with_account_overrides(
+            transaction,
+            enable_cpi_recording,
+            instruction_tracing,
+            &account_overrides,
+        )
+    }
+
+    /// Run a transaction against a frozen bank without committing the results, replacing the
+    /// accounts, sysvars and feature set found in `account_overrides`.
+    ///
+    /// Only execution is affected: fees and the age of the recent blockhash are checked against
+    /// the bank itself.
+    pub fn simulate_transaction_with_overrides(
+        &self,
+        transaction: SanitizedTransaction,
+        enable_cpi_recording: bool,
+        instruction_tracing: InstructionTracing,
+        mut account_overrides: AccountOverrides,
+    ) -> TransactionSimulationResult {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        self.add_account_overrides_for_simulation(
+            &transaction.message().account_keys(),
+            &mut account_overrides,
+        );
+        self.simulate_transaction_with_account_overrides(
+            transaction,
+            enable_cpi_recording,
+            instruction_tracing,
+            &account_overrides,
+        )
+    }
+
+    /// Run a bundle of transactions in order against a frozen bank without committing the
+    /// results, each transaction seeing the accounts written by the previous ones
+    pub fn simulate_bundle(
+        &self,
+        transactions: Vec<SanitizedTransaction>,
+        enable_cpi_recording: bool,
+        instruction_tracing: InstructionTracing,
+    ) -> Vec<TransactionSimulationResult> {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        self.simulate_bundle_unchecked(transactions, enable_cpi_recording, instruction_tracing)
+    }
+
+    /// Run a bundle of transactions in order against a bank without committing the results; does
+    /// not check if the bank is frozen, enabling use in single-Bank test frameworks.
+    ///
+    /// The accounts a transaction would store if it were committed are passed on to the next ones
+    /// as account overrides, so the bank is left untouched. Like when committing, failed
+    /// transactions still pass on their fee payer, charged the fee, and their advanced nonce.
+    ///
+    /// The bundle runs on this bank rather than on a child bank, so the fees and the age of the
+    /// recent blockhashes are checked against this bank, and its status cache only holds the
+    /// transactions committed to it. A transaction repeating the signature of an earlier one in
+    /// the bundle is therefore rejected here with `AlreadyProcessed`, without being executed.
+    pub fn simulate_bundle_unchecked(
+        &self,
+        transactions: Vec<SanitizedTransaction>,
+        enable_cpi_recording: bool,
+        instruction_tracing: InstructionTracing,
+    ) -> Vec<TransactionSimulationResult> {
+        let mut account_overrides = AccountOverrides::default();
+        let mut signatures = HashSet::new();
+        transactions
+            .into_iter()
+            .map(|transaction| {
+                if !signatures.insert(*transaction.signature()) {
+                    return TransactionSimulationResult {
+                        result: Err(TransactionError::AlreadyProcessed),
+                        logs: TransactionLogMessages::default(),
+                        pre_simulation_accounts: vec![],
+                        post_simulation_accounts: vec![],
+                        units_consumed: 0,
+                        return_data: None,
+                        inner_instructions: None,
+                        instruction_trace: None,
+                        written_accounts: vec![],
+                    };
+                }
+                self.add_account_overrides_for_simulation(
+                    &transaction.message().account_keys(),
+                    &mut account_overrides,
+                );
+                let simulation_result = self.simulate_transaction_with_account_overrides(
+                    transaction,
+                    enable_cpi_recording,
+                    instruction_tracing,
+                    &account_overrides,
+                );
+                for (pubkey, account) in &simulation_result.written_accounts {
+                    // the bank loads an account closed to zero lamports as a default account
+                    let account = if account.lamports() == 0 {
+                        AccountSharedData::default()
+                    } else {
+                        account.clone()
+                    };
+                    account_overrides.set_account(pubkey, Some(account));
+                }
+                simulation_result
+            })
+            .collect()
+    }
+
+    fn simulate_transaction_with_account_overrides(
+        &self,
+        transaction: SanitizedTransaction,
+        enable_cpi_recording: bool,
+        instruction_tracing: InstructionTracing,
+        account_overrides: &AccountOverrides,
     ) -> TransactionSimulationResult {
         let account_keys = transaction.message().account_keys();
         let number_of_accounts = account_keys.len();
-        let account_overrides = self.get_account_overrides_for_simulation(&account_keys);
+        let pre_simulation_accounts: Vec<_> = account_keys
+            .iter()
+            .map(|pubkey| {
+                let account = account_overrides
+                    .get(pubkey)
+                    .cloned()
+                    .or_else(|| self.get_account(pubkey));
+                (*pubkey, account.unwrap_or_default())
+            })
+            .collect();
         let batch = self.prepare_simulation_batch(transaction);
         let mut timings = ExecuteTimings::default();
 
         let LoadAndExecuteTransactionsOutput {
-            loaded_transactions,
+            mut loaded_transactions,
             mut execution_results,
             ..
         } = self.load_and_execute_transactions(
@@ -3752,14 +3910,21 @@ impl Bank {
             // for processing. During forwarding, the transaction could expire if the
             // delay is not accounted for.
             MAX_PROCESSING_AGE - MAX_TRANSACTION_FORWARDING_DELAY,
-            false,
-            true,
-            true,
+            enable_cpi_recording,
+            true, // enable_log_recording
+            true, // enable_return_data_recording
+            instruction_tracing,
             &mut timings,
-            Some(&account_overrides),
+            Some(account_overrides),
             None,
         );
 
+        let written_accounts = self.simulation_written_accounts(
+            batch.sanitized_transactions(),
+            &execution_results,
+            &mut loaded_transactions,
+            &pre_simulation_accounts,
+        );
         let post_simulation_accounts = loaded_transactions
             .into_iter()
             .next()
@@ -3787,27 +3952,94 @@ impl Bank {
 
         let execution_result = execution_results.pop().unwrap();
         let flattened_result = execution_result.flattened_result();
-        let (logs, return_data) = match execution_result {
-            TransactionExecutionResult::Executed { details, .. } => {
-                (details.log_messages, details.return_data)
-            }
-            TransactionExecutionResult::NotExecuted(_) => (None, None),
+        let (logs, return_data, inner_instructions, instruction_trace) = match execution_result {
+            TransactionExecutionResult::Executed { details, .. } => (
+                details.log_messages,
+                details.return_data,
+                details.inner_instructions,
+                details.instruction_trace,
+            ),
+            TransactionExecutionResult::NotExecuted(_) => (None, None, None, None),
         };
         let logs = logs.unwrap_or_default();
 
         TransactionSimulationResult {
             result: flattened_result,
             logs,
+            pre_simulation_accounts,
             post_simulation_accounts,
             units_consumed,
             return_data,
+            inner_instructions,
+            instruction_trace,
+            written_accounts,
         }
     }
 
-    fn get_account_overrides_for_simulation(&self, account_keys: &AccountKeys) -> AccountOverrides {
-        let mut account_overrides = AccountOverrides::default();
+    /// The accounts a simulated transaction would store if it were committed, following
+    /// `Accounts::collect_accounts_to_store()` and, for the fee of failed transactions without a
+    /// durable nonce, `Bank::filter_program_errors_and_collect_fee()`
+    fn simulation_written_accounts(
+        &self,
+        sanitized_txs: &[SanitizedTransaction],
+        execution_results: &[TransactionExecutionResult],
+        loaded_txs: &mut [TransactionLoadResult],
+        pre_simulation_accounts: &[TransactionAccount],
+    ) -> Vec<TransactionAccount> {
+        let (transaction, details, nonce) = match (
+            sanitized_txs.first(),
+            execution_results.first(),
+            loaded_txs.first(),
+        ) {
+            (
+                Some(transaction),
+                Some(TransactionExecutionResult::Executed { details, .. }),
+                Some((_, nonce)),
+            ) => (transaction, details, nonce),
+            // Transactions which aren't executed store nothing and aren't charged a fee
+            _ => return vec![],
+        };
+        if details.status.is_err() && nonce.is_none() {
+            let message = transaction.message();
+            let fee = self.get_fee_for_message(message).unwrap_or_default();
+            return pre_simulation_accounts
+                .iter()
+                .filter(|(pubkey, _)| pubkey == message.fee_payer())
+                .take(1)
+                .map(|(pubkey, account)| {
+                    let mut account = account.clone();
+                    account.set_lamports(account.lamports().saturating_sub(fee));
+                    (*pubkey, account)
+                })
+                .collect();
+        }
+
+        let (last_blockhash, lamports_per_signature) =
+            self.last_blockhash_and_lamports_per_signature();
+        let durable_nonce = DurableNonce::from_blockhash(&last_blockhash);
+        let (accounts_to_store, _signatures) = self.rc.accounts.collect_accounts_to_store(
+            sanitized_txs,
+            execution_results,
+            loaded_txs,
+            &self.rent_collector,
+            &durable_nonce,
+            lamports_per_signature,
+        );
+        accounts_to_store
+            .into_iter()
+            .map(|(pubkey, account)| (*pubkey, account.clone()))
+            .collect()
+    }
+
+    fn add_account_overrides_for_simulation(
+        &self,
+        account_keys: &AccountKeys,
+        account_overrides: &mut AccountOverrides,
+    ) {
         let slot_history_id = sysvar::slot_history::id();
-        if account_keys.iter().any(|pubkey| *pubkey == slot_history_id) {
+        if account_overrides.get(&slot_history_id).is_none()
+            && account_keys.iter().any(|pubkey| *pubkey == slot_history_id)
+        {
             let current_account = self.get_account_with_fixed_root(&slot_history_id);
             let slot_history = current_account
                 .as_ref()
@@ -3822,7 +4054,6 @@ impl Bank {
                 }
             }
         }
-        account_overrides
     }
 
     pub fn unlock_accounts(&self, batch: &mut TransactionBatch) {
@@ -4084,6 +4315,7 @@ impl Bank {
             &program,
             programdata.as_ref().unwrap_or(&program),
             self.runtime_config.bpf_jit,
+            false, // debugging_features
         )
         .map(|(executor, _create_executor_metrics)| executor)
         .map_err(|err| TransactionError::InstructionError(0, err))
@@ -4110,12 +4342,26 @@ impl Bank {
         enable_cpi_recording: bool,
         enable_log_recording: bool,
         enable_return_data_recording: bool,
+        instruction_tracing: InstructionTracing,
         timings: &mut ExecuteTimings,
         error_counters: &mut TransactionErrorMetrics,
         log_messages_bytes_limit: Option<usize>,
+        account_overrides: Option<&AccountOverrides>,
     ) -> TransactionExecutionResult {
+        // Executors cached for overridden programs would run the original code, and executors
+        // built from overridden programs must not leak into the bank's cache
+        let overrides_programs =
+            account_overrides.map_or(false, |overrides| overrides.has_program_overrides());
+        let feature_set = account_overrides
+            .and_then(|overrides| overrides.feature_set())
+            .unwrap_or(&self.feature_set);
+
         let mut get_tx_executor_cache_time = Measure::start("get_tx_executor_cache_time");
-        let tx_executor_cache = self.get_tx_executor_cache(&loaded_transaction.accounts);
+        let tx_executor_cache = if overrides_programs {
+            Rc::new(RefCell::new(TransactionExecutorCache::default()))
+        } else {
+            self.get_tx_executor_cache(&loaded_transaction.accounts)
+        };
         get_tx_executor_cache_time.stop();
         saturating_add_assign!(
             timings.execute_accessories.get_executors_us,
@@ -4123,30 +4369,34 @@ impl Bank {
         );
 
         let prev_accounts_data_len = self.load_accounts_data_size();
+        let pre_writable_accounts =
+            self.runtime_config
+                .record_transaction_account_diffs
+                .then(|| {
+                    loaded_transaction
+                        .accounts
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| tx.message().is_writable(*index))
+                        .map(|(index, (pubkey, account))| (index, *pubkey, account.clone()))
+                        .collect::<Vec<_>>()
+                });
         let transaction_accounts = std::mem::take(&mut loaded_transaction.accounts);
         let mut transaction_context = TransactionContext::new(
             transaction_accounts,
-            if self
-                .feature_set
-                .is_active(&enable_early_verification_of_account_modifications::id())
-            {
+            if feature_set.is_active(&enable_early_verification_of_account_modifications::id()) {
                 Some(self.rent_collector.rent)
             } else {
                 None
             },
             compute_budget.max_invoke_stack_height,
-            if self
-                .feature_set
-                .is_active(&feature_set::limit_max_instruction_trace_length::id())
-            {
+            if feature_set.is_active(&feature_set::limit_max_instruction_trace_length::id()) {
                 compute_budget.max_instruction_trace_length
             } else {
                 std::usize::MAX
             },
         );
-        if self
-            .feature_set
-            .is_active(&feature_set::cap_accounts_data_allocations_per_transaction::id())
+        if feature_set.is_active(&feature_set::cap_accounts_data_allocations_per_transaction::id())
         {
             transaction_context.enable_cap_accounts_data_allocations_per_transaction();
         }
@@ -4167,10 +4417,16 @@ impl Bank {
             None
         };
 
+        let instruction_tracer = instruction_tracing.new_tracer();
+
         let (blockhash, lamports_per_signature) = self.last_blockhash_and_lamports_per_signature();
 
         let mut executed_units = 0u64;
 
+        let overridden_sysvar_cache =
+            account_overrides.and_then(|overrides| self.get_sysvar_cache_with_overrides(overrides));
+        let sysvar_cache = self.sysvar_cache.read().unwrap();
+
         let mut process_message_time = Measure::start("process_message_time");
         let process_result = MessageProcessor::process_message(
             &self.builtin_programs.vec,
@@ -4179,17 +4435,19 @@ impl Bank {
             &mut transaction_context,
             self.rent_collector.rent,
             log_collector.clone(),
+            instruction_tracer.clone(),
             tx_executor_cache.clone(),
-            self.feature_set.clone(),
+            feature_set.clone(),
             compute_budget,
             timings,
-            &self.sysvar_cache.read().unwrap(),
+            overridden_sysvar_cache.as_ref().unwrap_or(&sysvar_cache),
             blockhash,
             lamports_per_signature,
             prev_accounts_data_len,
             &mut executed_units,
         );
         process_message_time.stop();
+        drop(sysvar_cache);
 
         saturating_add_assign!(
             timings.execute_accessories.process_message_us,
@@ -4197,7 +4455,9 @@ impl Bank {
         );
 
         let mut store_missing_executors_time = Measure::start("store_missing_executors_time");
-        self.store_missing_executors(&tx_executor_cache);
+        if !overrides_programs {
+            self.store_missing_executors(&tx_executor_cache);
+        }
         store_missing_executors_time.stop();
         saturating_add_assign!(
             timings.execute_accessories.update_executors_us,
@@ -4242,6 +4502,12 @@ impl Bank {
                     .ok()
             });
 
+        let instruction_trace = instruction_tracer.and_then(|instruction_tracer| {
+            Rc::try_unwrap(instruction_tracer)
+                .map(|instruction_tracer| instruction_tracer.into_inner().into_trace())
+                .ok()
+        });
+
         let inner_instructions = if enable_cpi_recording {
             Some(inner_instructions_list_from_instruction_trace(
                 &transaction_context,
@@ -4257,10 +4523,17 @@ impl Bank {
             accounts_resize_delta,
         } = transaction_context.into();
         loaded_transaction.accounts = accounts;
-        if self
-            .feature_set
-            .is_active(&enable_early_verification_of_account_modifications::id())
-        {
+        let account_diffs = pre_writable_accounts.map(|pre_writable_accounts| {
+            pre_writable_accounts
+                .into_iter()
+                .map(|(index, pubkey, pre_account)| TransactionAccountDiff {
+                    pubkey,
+                    pre_account,
+                    post_account: loaded_transaction.accounts[index].1.clone(),
+                })
+                .collect()
+        });
+        if feature_set.is_active(&enable_early_verification_of_account_modifications::id()) {
             saturating_add_assign!(
                 timings.details.total_account_count,
                 loaded_transaction.accounts.len() as u64
@@ -4290,6 +4563,8 @@ impl Bank {
                 return_data,
                 executed_units,
                 accounts_data_len_delta,
+                account_diffs,
+                instruction_trace,
             },
             tx_executor_cache,
         }
@@ -4303,6 +4578,7 @@ impl Bank {
         enable_cpi_recording: bool,
         enable_log_recording: bool,
         enable_return_data_recording: bool,
+        instruction_tracing: InstructionTracing,
         timings: &mut ExecuteTimings,
         account_overrides: Option<&AccountOverrides>,
         log_messages_bytes_limit: Option<usize>,
@@ -4417,9 +4693,11 @@ impl Bank {
                         enable_cpi_recording,
                         enable_log_recording,
                         enable_return_data_recording,
+                        instruction_tracing,
                         timings,
                         &mut error_counters,
                         log_messages_bytes_limit,
+                        account_overrides,
                     )
                 }
             })
@@ -5988,6 +6266,7 @@ impl Bank {
             enable_cpi_recording,
             enable_log_recording,
             enable_return_data_recording,
+            InstructionTracing::Disabled,
             timings,
             None,
             log_messages_bytes_limit,
@@ -6209,6 +6488,25 @@ impl Bank {
             .flush_accounts_cache(false, Some(self.slot()))
     }
 
+    /// Keep the disk accounts index on shutdown, so a restart from the full snapshot just taken of
+    /// this bank can reuse it. This bank must be the latest root. See `AccountsDb::persist_disk_index`.
+    pub fn persist_accounts_index(&self) -> std::io::Result<()> {
+        self.rc
+            .accounts
+            .accounts_db
+            .persist_disk_index(self.slot(), self.load_accounts_data_size())
+    }
+
+    /// true if this bank is the latest root
+    pub fn is_latest_root(&self) -> bool {
+        self.rc.accounts.accounts_db.is_latest_root(self.slot())
+    }
+
+    /// true if the disk accounts index is kept on shutdown
+    pub fn is_accounts_index_persisted(&self) -> bool {
+        self.rc.accounts.accounts_db.is_disk_index_persisted()
+    }
+
     #[cfg(test)]
     pub fn flush_accounts_cache_slot_for_tests(&self) {
         self.rc
@@ -6455,6 +6753,29 @@ impl Bank {
         )
     }
 
+    /// Get a page of the accounts of `index_key` passing `filter`, in ascending pubkey order and
+    /// starting after `after`
+    pub fn get_filtered_indexed_accounts_page<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        index_key: &IndexKey,
+        filter: F,
+        after: Option<&Pubkey>,
+        limit: usize,
+        config: &ScanConfig,
+        byte_limit_for_scan: Option<usize>,
+    ) -> ScanResult<Vec<TransactionAccount>> {
+        self.rc.accounts.load_page_by_index_key_with_filter(
+            &self.ancestors,
+            self.bank_id,
+            index_key,
+            filter,
+            after,
+            limit,
+            config,
+            byte_limit_for_scan,
+        )
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.rc.accounts.account_indexes_include_key(key)
     }
@@ -6495,6 +6816,17 @@ impl Bank {
         self.rc.accounts.load_by_program_slot(self.slot(), None)
     }
 
+    /// Get the pubkey and hash of each account modified by this bank, which together make up
+    /// the accounts delta hash of the bank
+    pub fn get_accounts_delta_hashes(&self) -> Vec<(Pubkey, Hash)> {
+        let (hashes, ..) = self
+            .rc
+            .accounts
+            .accounts_db
+            .get_pubkey_hash_for_slot(self.slot());
+        hashes
+    }
+
     // if you want get_account_modified_since_parent without fixed_root, please define so...
     fn get_account_modified_since_parent_with_fixed_root(
         &self,
@@ -6943,6 +7275,66 @@ impl Bank {
         self.rc.accounts.accounts_db.get_accounts_hash(self.slot())
     }
 
+    /// The accounts hash bins summary kept for the snapshot at 'slot', see
+    /// `AccountsDb::add_snapshot_accounts_hash_bins`
+    pub fn get_snapshot_accounts_hash_bins(&self, slot: Slot) -> Option<AccountsHashBins> {
+        self.rc
+            .accounts
+            .accounts_db
+            .get_snapshot_accounts_hash_bins(slot)
+    }
+
+    /// Summary of the accounts of this bank split into 'num_bins' bins by pubkey, to locate
+    /// the accounts responsible for an accounts hash mismatch with another node or snapshot.
+    /// This bank has to be rooted and flushed from the write cache, since the storages are scanned.
+    pub fn calculate_accounts_hash_bins(&self, num_bins: usize) -> AccountsHashBins {
+        // the scan shares the accounts hash cache with the startup verification
+        self.rc
+            .accounts
+            .accounts_db
+            .verify_accounts_hash_in_bg
+            .wait_for_complete();
+        self.rc
+            .accounts
+            .accounts_db
+            .calculate_accounts_hash_bins(self.slot(), &self.accounts_hash_bins_config(), num_bins)
+            .unwrap() // unwrap here will never fail since check_hash = false
+    }
+
+    /// The accounts of this bank in bin 'bin' of `calculate_accounts_hash_bins`, sorted by pubkey
+    pub fn get_accounts_hash_bin_entries(
+        &self,
+        num_bins: usize,
+        bin: usize,
+    ) -> Vec<AccountHashEntry> {
+        self.rc
+            .accounts
+            .accounts_db
+            .verify_accounts_hash_in_bg
+            .wait_for_complete();
+        self.rc
+            .accounts
+            .accounts_db
+            .get_accounts_hash_bin_entries(
+                self.slot(),
+                &self.accounts_hash_bins_config(),
+                num_bins,
+                bin,
+            )
+            .unwrap() // unwrap here will never fail since check_hash = false
+    }
+
+    fn accounts_hash_bins_config(&self) -> CalcAccountsHashConfig<'_> {
+        CalcAccountsHashConfig {
+            use_bg_thread_pool: false,
+            check_hash: false,
+            ancestors: Some(&self.ancestors),
+            epoch_schedule: self.epoch_schedule(),
+            rent_collector: &self.rent_collector,
+            store_detailed_debug_info_on_failure: false,
+        }
+    }
+
     pub fn get_snapshot_hash(&self) -> SnapshotHash {
         let accounts_hash = self
             .get_accounts_hash()
@@ -8007,6 +8399,8 @@ pub(crate) mod tests {
                 return_data: None,
                 executed_units: 0,
                 accounts_data_len_delta: 0,
+                account_diffs: None,
+                instruction_trace: None,
             },
             tx_executor_cache: Rc::new(RefCell::new(TransactionExecutorCache::default())),
         }
@@ -14058,6 +14452,244 @@ pub(crate) mod tests {
         assert_eq!(balances[1], vec![8, 11, 1]);
     }
 
+    #[test]
+    fn test_record_transaction_account_diffs() {
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1.));
+        let bank = Bank::new_with_runtime_config_for_tests(
+            &genesis_config,
+            Arc::new(RuntimeConfig {
+                record_transaction_account_diffs: true,
+                ..RuntimeConfig::default()
+            }),
+        );
+        let amount = genesis_config.rent.minimum_balance(0);
+        let alice = solana_sdk::pubkey::new_rand();
+        let tx = system_transaction::transfer(&mint_keypair, &alice, amount, bank.last_blockhash());
+
+        let batch = bank.prepare_batch_for_tests(vec![tx]);
+        let (transaction_results, _) = bank.load_execute_and_commit_transactions(
+            &batch,
+            MAX_PROCESSING_AGE,
+            false,
+            false,
+            false,
+            false,
+            &mut ExecuteTimings::default(),
+            None,
+        );
+        let account_diffs = transaction_results.execution_results[0]
+            .details()
+            .unwrap()
+            .account_diffs
+            .as_ref()
+            .unwrap();
+
+        // Only the writable mint and alice accounts are recorded, not the system program
+        assert_eq!(account_diffs.len(), 2);
+        let mint_diff = &account_diffs[0];
+        assert_eq!(mint_diff.pubkey, mint_keypair.pubkey());
+        assert_eq!(
+            mint_diff.post_account.lamports(),
+            mint_diff.pre_account.lamports() - amount
+        );
+        let alice_diff = &account_diffs[1];
+        assert_eq!(alice_diff.pubkey, alice);
+        assert_eq!(alice_diff.pre_account.lamports(), 0);
+        assert_eq!(alice_diff.post_account.lamports(), amount);
+        assert_eq!(bank.get_balance(&alice), amount);
+    }
+
+    #[test]
+    fn test_simulate_bundle() {
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1.));
+        let bank = Bank::new_for_tests(&genesis_config);
+        let rent_exempt_amount = genesis_config.rent.minimum_balance(0);
+        let alice = Keypair::new();
+        let bob = solana_sdk::pubkey::new_rand();
+        let fund_alice =
+            SanitizedTransaction::from_transaction_for_tests(system_transaction::transfer(
+                &mint_keypair,
+                &alice.pubkey(),
+                3 * rent_exempt_amount,
+                bank.last_blockhash(),
+            ));
+        let alice_to_bob = SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(&alice, &bob, rent_exempt_amount, bank.last_blockhash()),
+        );
+
+        // Alice doesn't exist until the first transaction of the bundle funds her
+        assert_eq!(
+            bank.simulate_transaction_unchecked(
+                alice_to_bob.clone(),
+                false,
+                InstructionTracing::Disabled,
+            )
+            .result,
+            Err(TransactionError::AccountNotFound)
+        );
+        let results = bank.simulate_bundle_unchecked(
+            vec![fund_alice.clone(), alice_to_bob.clone()],
+            false,
+            InstructionTracing::Disabled,
+        );
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].result, Ok(()));
+        assert_eq!(results[1].result, Ok(()));
+        assert_eq!(
+            results[1].pre_simulation_accounts[0].1.lamports(),
+            3 * rent_exempt_amount
+        );
+        assert_eq!(results[1].post_simulation_accounts[1].0, bob);
+        assert_eq!(
+            results[1].post_simulation_accounts[1].1.lamports(),
+            rent_exempt_amount
+        );
+
+        // Nothing was committed
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+        assert_eq!(bank.get_balance(&bob), 0);
+
+        // A failed transaction still charges its fee to the next ones
+        let alice_overdraft =
+            SanitizedTransaction::from_transaction_for_tests(system_transaction::transfer(
+                &alice,
+                &bob,
+                10 * rent_exempt_amount,
+                bank.last_blockhash(),
+            ));
+        let results = bank.simulate_bundle_unchecked(
+            vec![fund_alice, alice_overdraft, alice_to_bob],
+            false,
+            InstructionTracing::Disabled,
+        );
+        let fee = bank.get_lamports_per_signature();
+        assert_eq!(results[0].result, Ok(()));
+        assert!(matches!(
+            results[1].result,
+            Err(TransactionError::InstructionError(0, _))
+        ));
+        assert_eq!(results[1].written_accounts.len(), 1);
+        assert_eq!(results[1].written_accounts[0].0, alice.pubkey());
+        assert_eq!(
+            results[1].written_accounts[0].1.lamports(),
+            3 * rent_exempt_amount - fee
+        );
+        assert_eq!(results[2].result, Ok(()));
+        assert_eq!(
+            results[2].pre_simulation_accounts[0].1.lamports(),
+            3 * rent_exempt_amount - fee
+        );
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+    }
+
+    #[test]
+    fn test_simulate_bundle_closed_account() {
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1.));
+        let bank = Bank::new_for_tests(&genesis_config);
+        let rent_exempt_amount = genesis_config.rent.minimum_balance(0);
+        let alice = Keypair::new();
+        let bob = solana_sdk::pubkey::new_rand();
+        let fund_alice =
+            SanitizedTransaction::from_transaction_for_tests(system_transaction::transfer(
+                &mint_keypair,
+                &alice.pubkey(),
+                3 * rent_exempt_amount,
+                bank.last_blockhash(),
+            ));
+        // the mint pays the fee, so alice is closed to zero lamports
+        let close_alice =
+            SanitizedTransaction::from_transaction_for_tests(Transaction::new_signed_with_payer(
+                &[system_instruction::transfer(
+                    &alice.pubkey(),
+                    &bob,
+                    3 * rent_exempt_amount,
+                )],
+                Some(&mint_keypair.pubkey()),
+                &[&mint_keypair, &alice],
+                bank.last_blockhash(),
+            ));
+        let refund_alice =
+            SanitizedTransaction::from_transaction_for_tests(system_transaction::transfer(
+                &mint_keypair,
+                &alice.pubkey(),
+                rent_exempt_amount,
+                bank.last_blockhash(),
+            ));
+
+        let results = bank.simulate_bundle_unchecked(
+            vec![fund_alice.clone(), close_alice, refund_alice, fund_alice],
+            false,
+            InstructionTracing::Disabled,
+        );
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].result, Ok(()));
+        assert_eq!(results[1].result, Ok(()));
+        assert_eq!(results[1].post_simulation_accounts[1].1.lamports(), 0);
+        // the closed account is loaded the way the bank would load it
+        assert_eq!(results[2].result, Ok(()));
+        assert_eq!(results[2].pre_simulation_accounts[1].0, alice.pubkey());
+        assert_eq!(
+            results[2].pre_simulation_accounts[1].1,
+            AccountSharedData::default()
+        );
+        assert_eq!(
+            results[2].post_simulation_accounts[1].1.lamports(),
+            rent_exempt_amount
+        );
+        // the status cache of the bank does not know the earlier transactions of the bundle
+        assert_eq!(results[3].result, Err(TransactionError::AlreadyProcessed));
+        assert!(results[3].written_accounts.is_empty());
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+    }
+
+    #[test]
+    fn test_simulate_transaction_with_overrides() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(sol_to_lamports(1.));
+        let bank = Bank::new_for_tests(&genesis_config);
+        bank.freeze();
+        let rent_exempt_amount = genesis_config.rent.minimum_balance(0);
+        let alice = Keypair::new();
+        let bob = solana_sdk::pubkey::new_rand();
+        let alice_to_bob = SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(&alice, &bob, rent_exempt_amount, bank.last_blockhash()),
+        );
+
+        assert_eq!(
+            bank.simulate_transaction(alice_to_bob.clone(), false, InstructionTracing::Disabled)
+                .result,
+            Err(TransactionError::AccountNotFound)
+        );
+
+        let mut account_overrides = AccountOverrides::default();
+        account_overrides.set_account(
+            &alice.pubkey(),
+            Some(AccountSharedData::new(
+                3 * rent_exempt_amount,
+                0,
+                &system_program::id(),
+            )),
+        );
+        let mut feature_set = FeatureSet::clone(&bank.feature_set);
+        feature_set.deactivate(&feature_set::cap_accounts_data_allocations_per_transaction::id());
+        account_overrides.set_feature_set(Some(Arc::new(feature_set)));
+        let result = bank.simulate_transaction_with_overrides(
+            alice_to_bob,
+            false,
+            InstructionTracing::Disabled,
+            account_overrides,
+        );
+        assert_eq!(result.result, Ok(()));
+        assert_eq!(
+            result.pre_simulation_accounts[0].1.lamports(),
+            3 * rent_exempt_amount
+        );
+        assert_eq!(
+            result.post_simulation_accounts[1].1.lamports(),
+            rent_exempt_amount
+        );
+        assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+    }
+
     #[test]
     fn test_pre_post_transaction_balances() {
         let (mut genesis_config, _mint_keypair) = create_genesis_config(500_000);
@@ -1,6 +1,6 @@
 use {
-    super::Bank, solana_program_runtime::sysvar_cache::SysvarCache,
-    solana_sdk::account::ReadableAccount,
+    super::Bank, crate::account_overrides::AccountOverrides,
+    solana_program_runtime::sysvar_cache::SysvarCache, solana_sdk::account::ReadableAccount,
 };
 
 impl Bank {
@@ -18,6 +18,26 @@ impl Bank {
         sysvar_cache.reset();
     }
 
+    /// Builds a sysvar cache in which the sysvar accounts found in `account_overrides` replace
+    /// the bank's, or returns `None` if no sysvar is overridden
+    pub(crate) fn get_sysvar_cache_with_overrides(
+        &self,
+        account_overrides: &AccountOverrides,
+    ) -> Option<SysvarCache> {
+        if !account_overrides.has_sysvar_overrides() {
+            return None;
+        }
+        let mut sysvar_cache = SysvarCache::default();
+        sysvar_cache.fill_missing_entries(|pubkey, callback| {
+            if let Some(account) = account_overrides.get(pubkey) {
+                callback(account.data());
+            } else if let Some(account) = self.get_account_with_fixed_root(pubkey) {
+                callback(account.data());
+            }
+        });
+        Some(sysvar_cache)
+    }
+
     pub fn get_sysvar_cache_for_tests(&self) -> SysvarCache {
         self.sysvar_cache.read().unwrap().clone()
     }
@@ -27,7 +47,10 @@ impl Bank {
 mod tests {
     use {
         super::*,
-        solana_sdk::{genesis_config::create_genesis_config, pubkey::Pubkey},
+        solana_sdk::{
+            account::create_account_shared_data_for_test, clock::Clock,
+            genesis_config::create_genesis_config, pubkey::Pubkey, sysvar,
+        },
         std::sync::Arc,
     };
 
@@ -142,4 +165,36 @@ mod tests {
             bank1_cached_slot_hashes
         );
     }
+
+    #[test]
+    fn test_get_sysvar_cache_with_overrides() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(100_000);
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        let bank1 = Bank::new_from_parent(&bank0, &Pubkey::default(), bank0.slot() + 1);
+
+        let mut account_overrides = AccountOverrides::default();
+        assert!(bank1
+            .get_sysvar_cache_with_overrides(&account_overrides)
+            .is_none());
+
+        let clock = Clock {
+            unix_timestamp: 1_700_000_000,
+            ..Clock::default()
+        };
+        account_overrides.set_account(
+            &sysvar::clock::id(),
+            Some(create_account_shared_data_for_test(&clock)),
+        );
+
+        let sysvar_cache = bank1
+            .get_sysvar_cache_with_overrides(&account_overrides)
+            .unwrap();
+        assert_eq!(*sysvar_cache.get_clock().unwrap(), clock);
+        let bank1_sysvar_cache = bank1.sysvar_cache.read().unwrap();
+        assert_eq!(
+            sysvar_cache.get_slot_hashes(),
+            bank1_sysvar_cache.get_slot_hashes()
+        );
+        assert_ne!(sysvar_cache.get_clock(), bank1_sysvar_cache.get_clock());
+    }
 }
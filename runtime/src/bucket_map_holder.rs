@@ -13,6 +13,7 @@ use {
     },
     std::{
         fmt::Debug,
+        path::PathBuf,
         sync::{
             atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
             Arc,
@@ -27,9 +28,17 @@ const AGE_MS: u64 = DEFAULT_MS_PER_SLOT; // match one age per slot time
 // 10 GB limit for in-mem idx. In practice, we don't get this high. This tunes how aggressively to save items we expect to use soon.
 pub const DEFAULT_DISK_INDEX: Option<usize> = Some(10_000);
 
+/// files written to the first index drive when the disk index is persisted.
+/// Both are erased with the drive whenever the disk index cannot be reopened.
+const BUCKET_MAP_RESTART_FILE: &str = "bucket_map_restart";
+const ACCOUNTS_INDEX_RESTART_FILE: &str = "accounts_index_restart";
+
 pub struct BucketMapHolder<T: IndexValue> {
     pub disk: Option<BucketMap<(Slot, T)>>,
 
+    /// Some if the disk index is kept across restarts. See `AccountsIndexConfig::persist_disk_index`.
+    pub restart_file: Option<PathBuf>,
+
     pub count_buckets_flushed: AtomicUsize,
 
     /// These three ages are individual atomics because their values are read many times from code during runtime.
@@ -201,6 +210,14 @@ impl<T: IndexValue> BucketMapHolder<T> {
 
         let mut bucket_config = BucketMapConfig::new(bins);
         bucket_config.drives = config.as_ref().and_then(|config| config.drives.clone());
+        let restart_drive = config
+            .as_ref()
+            .filter(|config| config.persist_disk_index)
+            .and_then(|config| config.drives.as_ref())
+            .and_then(|drives| drives.first());
+        bucket_config.restart_config_file =
+            restart_drive.map(|drive| drive.join(BUCKET_MAP_RESTART_FILE));
+        let restart_file = restart_drive.map(|drive| drive.join(ACCOUNTS_INDEX_RESTART_FILE));
         let mem_budget_mb = match config
             .as_ref()
             .map(|config| &config.index_limit_mb)
@@ -240,6 +257,7 @@ impl<T: IndexValue> BucketMapHolder<T> {
         let disk = mem_budget_mb.map(|_| BucketMap::new(bucket_config));
         Self {
             disk,
+            restart_file,
             ages_to_stay_in_cache,
             count_buckets_flushed: AtomicUsize::default(),
             // age = 0
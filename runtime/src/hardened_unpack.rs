@@ -2,7 +2,10 @@ use {
     bzip2::bufread::BzDecoder,
     log::*,
     rand::{thread_rng, Rng},
-    solana_sdk::genesis_config::{GenesisConfig, DEFAULT_GENESIS_ARCHIVE, DEFAULT_GENESIS_FILE},
+    solana_sdk::{
+        clock::Slot,
+        genesis_config::{GenesisConfig, DEFAULT_GENESIS_ARCHIVE, DEFAULT_GENESIS_FILE},
+    },
     std::{
         collections::HashMap,
         fs::{self, File},
@@ -304,6 +307,27 @@ impl ParallelSelector {
     }
 }
 
+/// Accounts files of slots older than 'newest_cold_slot' are unpacked to 'paths' instead of the
+/// account paths
+#[derive(Debug, Clone)]
+pub struct ColdAccountPaths {
+    pub paths: Vec<PathBuf>,
+    pub newest_cold_slot: Slot,
+}
+
+impl ColdAccountPaths {
+    /// true if the accounts file named 'file' belongs on the cold account paths
+    fn is_cold(&self, file: &str) -> bool {
+        !self.paths.is_empty()
+            && file
+                .split('.')
+                .next()
+                .and_then(|slot| slot.parse::<Slot>().ok())
+                .map(|slot| slot < self.newest_cold_slot)
+                .unwrap_or_default()
+    }
+}
+
 /// Unpacks snapshot and collects AppendVec file names & paths
 pub fn unpack_snapshot<A: Read>(
     archive: &mut Archive<A>,
@@ -317,6 +341,7 @@ pub fn unpack_snapshot<A: Read>(
         archive,
         ledger_dir,
         account_paths,
+        None,
         parallel_selector,
         |file, path| {
             unpacked_append_vec_map.insert(file.to_string(), path.join("accounts").join(file));
@@ -331,6 +356,7 @@ pub fn streaming_unpack_snapshot<A: Read>(
     archive: &mut Archive<A>,
     ledger_dir: &Path,
     account_paths: &[PathBuf],
+    cold_account_paths: Option<&ColdAccountPaths>,
     parallel_selector: Option<ParallelSelector>,
     sender: &crossbeam_channel::Sender<PathBuf>,
 ) -> Result<()> {
@@ -338,6 +364,7 @@ pub fn streaming_unpack_snapshot<A: Read>(
         archive,
         ledger_dir,
         account_paths,
+        cold_account_paths,
         parallel_selector,
         |_, _| {},
         |entry_path_buf| {
@@ -352,6 +379,7 @@ fn unpack_snapshot_with_processors<A, F, G>(
     archive: &mut Archive<A>,
     ledger_dir: &Path,
     account_paths: &[PathBuf],
+    cold_account_paths: Option<&ColdAccountPaths>,
     parallel_selector: Option<ParallelSelector>,
     mut accounts_path_processor: F,
     entry_processor: G,
@@ -381,6 +409,12 @@ where
                     None => {}
                 };
                 if let ["accounts", file] = parts {
+                    let account_paths = match cold_account_paths {
+                        Some(cold_account_paths) if cold_account_paths.is_cold(file) => {
+                            &cold_account_paths.paths[..]
+                        }
+                        _ => account_paths,
+                    };
                     // Randomly distribute the accounts files about the available `account_paths`,
                     let path_index = thread_rng().gen_range(0, account_paths.len());
                     match account_paths
@@ -787,7 +821,7 @@ mod tests {
 
     fn finalize_and_unpack_snapshot(archive: tar::Builder<Vec<u8>>) -> Result<()> {
         with_finalize_and_unpack(archive, |a, b| {
-            unpack_snapshot_with_processors(a, b, &[PathBuf::new()], None, |_, _| {}, |_| {})
+            unpack_snapshot_with_processors(a, b, &[PathBuf::new()], None, None, |_, _| {}, |_| {})
         })
     }
 
@@ -813,6 +847,52 @@ mod tests {
         assert_matches!(result, Ok(()));
     }
 
+    #[test]
+    fn test_archive_unpack_snapshot_cold_account_paths() {
+        let mut archive = Builder::new(Vec::new());
+        let data: &[u8] = &[1, 2, 3, 4];
+        for file in ["accounts/1.0", "accounts/100.1"] {
+            let mut header = Header::new_gnu();
+            header.set_path(file).unwrap();
+            header.set_size(4);
+            header.set_cksum();
+            archive.append(&header, data).unwrap();
+        }
+        let data = archive.into_inner().unwrap();
+        let mut archive = Archive::new(BufReader::new(&data[..]));
+
+        let ledger_dir = tempfile::TempDir::new().unwrap();
+        let account_dir = tempfile::TempDir::new().unwrap();
+        let cold_account_dir = tempfile::TempDir::new().unwrap();
+        let cold_account_paths = ColdAccountPaths {
+            paths: vec![cold_account_dir.path().to_path_buf()],
+            newest_cold_slot: 50,
+        };
+        let mut unpacked = HashMap::new();
+        unpack_snapshot_with_processors(
+            &mut archive,
+            ledger_dir.path(),
+            &[account_dir.path().to_path_buf()],
+            Some(&cold_account_paths),
+            None,
+            |file, path| {
+                unpacked.insert(file.to_string(), path.to_path_buf());
+            },
+            |_| {},
+        )
+        .unwrap();
+
+        // only the slot older than 'newest_cold_slot' is unpacked to the cold account paths
+        assert_eq!(unpacked["1.0"], cold_account_dir.path());
+        assert_eq!(unpacked["100.1"], account_dir.path());
+        assert!(cold_account_dir
+            .path()
+            .join("accounts")
+            .join("1.0")
+            .is_file());
+        assert!(account_dir.path().join("accounts").join("100.1").is_file());
+    }
+
     #[test]
     fn test_archive_unpack_genesis_ok() {
         let mut header = Header::new_gnu();
@@ -910,6 +910,30 @@ impl<T: IndexValue> InMemAccountsIndex<T> {
         }
     }
 
+    /// write every dirty in-mem entry to disk, regardless of age.
+    /// Used before the disk index is persisted, when no more updates are expected.
+    pub(crate) fn write_dirty_entries_to_disk(&self) {
+        let Some(disk) = self.bucket.as_ref() else {
+            return;
+        };
+        let map = self.map_internal.read().unwrap();
+        for (k, v) in map.iter() {
+            if v.clear_dirty() {
+                // may have to loop if disk has to grow and we have to retry the write
+                loop {
+                    let disk_resize = {
+                        let slot_list = v.slot_list.read().unwrap();
+                        disk.try_write(k, (&slot_list, v.ref_count()))
+                    };
+                    match disk_resize {
+                        Ok(_) => break,
+                        Err(err) => disk.grow(err),
+                    }
+                }
+            }
+        }
+    }
+
     /// returns true if a dice roll indicates this call should result in a random eviction.
     /// This causes non-determinism in cache contents per validator.
     fn random_chance_of_eviction() -> bool {
@@ -76,6 +76,7 @@ mod storable_accounts;
 mod system_instruction_processor;
 pub mod transaction_batch;
 pub mod transaction_error_metrics;
+pub mod transaction_fixture;
 pub mod transaction_priority_details;
 mod verify_accounts_hash_in_background;
 pub mod vote_account;
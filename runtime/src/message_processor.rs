@@ -4,6 +4,7 @@ use {
     solana_program_runtime::{
         compute_budget::ComputeBudget,
         executor_cache::TransactionExecutorCache,
+        instruction_tracer::InstructionTracer,
         invoke_context::{BuiltinProgram, InvokeContext},
         log_collector::LogCollector,
         sysvar_cache::SysvarCache,
@@ -57,6 +58,7 @@ impl MessageProcessor {
         transaction_context: &mut TransactionContext,
         rent: Rent,
         log_collector: Option<Rc<RefCell<LogCollector>>>,
+        instruction_tracer: Option<Rc<RefCell<InstructionTracer>>>,
         tx_executor_cache: Rc<RefCell<TransactionExecutorCache>>,
         feature_set: Arc<FeatureSet>,
         compute_budget: ComputeBudget,
@@ -80,6 +82,7 @@ impl MessageProcessor {
             lamports_per_signature,
             current_accounts_data_len,
         );
+        invoke_context.set_instruction_tracer(instruction_tracer);
 
         debug_assert_eq!(program_indices.len(), message.instructions().len());
         for (instruction_index, ((program_id, instruction), program_indices)) in message
@@ -311,6 +314,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache.clone(),
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -361,6 +365,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache.clone(),
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -401,6 +406,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache,
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -538,6 +544,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache.clone(),
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -572,6 +579,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache.clone(),
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -603,6 +611,7 @@ mod tests {
             &mut transaction_context,
             rent_collector.rent,
             None,
+            None,
             tx_executor_cache,
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -683,6 +692,7 @@ mod tests {
             &mut transaction_context,
             RentCollector::default().rent,
             None,
+            None,
             Rc::new(RefCell::new(TransactionExecutorCache::default())),
             Arc::new(FeatureSet::all_enabled()),
             ComputeBudget::default(),
@@ -7,4 +7,7 @@ pub struct RuntimeConfig {
     pub compute_budget: Option<ComputeBudget>,
     pub log_messages_bytes_limit: Option<usize>,
     pub transaction_account_lock_limit: Option<usize>,
+    /// Record the state of each account written by a transaction before and after executing it,
+    /// in `TransactionExecutionDetails::account_diffs`. Meant for debugging tools only.
+    pub record_transaction_account_diffs: bool,
 }
@@ -3,8 +3,9 @@ use {
     log::*,
     solana_sdk::{pubkey::Pubkey, timing::AtomicInterval},
     std::{
-        collections::HashSet,
+        collections::{BTreeSet, HashSet},
         fmt::Debug,
+        ops::{Bound, RangeBounds},
         sync::{
             atomic::{AtomicU64, Ordering},
             RwLock,
@@ -28,6 +29,15 @@ pub trait SecondaryIndexEntry: Debug {
     fn is_empty(&self) -> bool;
     fn keys(&self) -> Vec<Pubkey>;
     fn len(&self) -> usize;
+    // Returns at most `limit` keys starting from `start`, in ascending order. Unordered entries
+    // have to collect and sort all of their keys.
+    fn keys_from(&self, start: Bound<Pubkey>, limit: usize) -> Vec<Pubkey> {
+        let mut keys = self.keys();
+        keys.retain(|key| (start, Bound::Unbounded).contains(key));
+        keys.sort_unstable();
+        keys.truncate(limit);
+        keys
+    }
 }
 
 #[derive(Debug, Default)]
@@ -102,6 +112,83 @@ impl SecondaryIndexEntry for RwLockSecondaryIndexEntry {
     }
 }
 
+/// Number of ordered sets the keys of a `ShardedBTreeSecondaryIndexEntry` are split into
+const NUM_ORDERED_SHARDS: usize = 16;
+
+/// Keeps the keys in ordered sets, so they can be paged through without collecting and sorting
+/// all of them. The keys are split into shards by their leading bits: concurrent inserts and
+/// removes mostly take different locks, and the shards themselves are in key order.
+#[derive(Debug, Default)]
+pub struct ShardedBTreeSecondaryIndexEntry {
+    shards: [RwLock<BTreeSet<Pubkey>>; NUM_ORDERED_SHARDS],
+}
+
+impl ShardedBTreeSecondaryIndexEntry {
+    fn shard_index(key: &Pubkey) -> usize {
+        key.as_ref()[0] as usize * NUM_ORDERED_SHARDS / (u8::MAX as usize + 1)
+    }
+
+    fn shard(&self, key: &Pubkey) -> &RwLock<BTreeSet<Pubkey>> {
+        &self.shards[Self::shard_index(key)]
+    }
+}
+
+impl SecondaryIndexEntry for ShardedBTreeSecondaryIndexEntry {
+    fn insert_if_not_exists(&self, key: &Pubkey, inner_keys_count: &AtomicU64) {
+        let shard = self.shard(key);
+        let exists = shard.read().unwrap().contains(key);
+        if !exists && shard.write().unwrap().insert(*key) {
+            inner_keys_count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn remove_inner_key(&self, key: &Pubkey) -> bool {
+        self.shard(key).write().unwrap().remove(key)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.shards
+            .iter()
+            .all(|shard| shard.read().unwrap().is_empty())
+    }
+
+    fn keys(&self) -> Vec<Pubkey> {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.read().unwrap().iter().cloned().collect::<Vec<_>>())
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().unwrap().len())
+            .sum()
+    }
+
+    fn keys_from(&self, start: Bound<Pubkey>, limit: usize) -> Vec<Pubkey> {
+        let first_shard = match start {
+            Bound::Included(key) | Bound::Excluded(key) => Self::shard_index(&key),
+            Bound::Unbounded => 0,
+        };
+        let mut keys = vec![];
+        for shard in &self.shards[first_shard..] {
+            if keys.len() >= limit {
+                break;
+            }
+            keys.extend(
+                shard
+                    .read()
+                    .unwrap()
+                    .range((start, Bound::Unbounded))
+                    .take(limit - keys.len())
+                    .cloned(),
+            );
+        }
+        keys
+    }
+}
+
 #[derive(Debug, Default)]
 struct HierarchicalOrderedMap<K, V>
 where
@@ -331,6 +418,15 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
         }
     }
 
+    /// Returns at most `limit` inner keys of `key` starting from `start`, in ascending order
+    pub fn get_from(&self, key: &Pubkey, start: Bound<Pubkey>, limit: usize) -> Vec<Pubkey> {
+        if let Some(inner_keys_map) = self.index.get(key) {
+            inner_keys_map.keys_from(start, limit)
+        } else {
+            vec![]
+        }
+    }
+
     /// log top 20 (owner, # accounts) in descending order of # accounts
     pub fn log_contents(&self) {
         let mut entries = self
@@ -3,7 +3,7 @@ use {
         accounts::Accounts,
         accounts_db::{
             AccountShrinkThreshold, AccountStorageEntry, AccountsDb, AccountsDbConfig, AppendVecId,
-            AtomicAppendVecId, BankHashInfo, IndexGenerationInfo,
+            AtomicAppendVecId, BankHashInfo, IndexGenerationInfo, SnapshotStorageIds,
         },
         accounts_hash::AccountsHash,
         accounts_index::AccountSecondaryIndexes,
@@ -57,7 +57,10 @@ mod utils;
 pub(crate) use storage::SerializedAppendVecId;
 // a number of test cases in accounts_db use this
 #[cfg(test)]
-pub(crate) use tests::reconstruct_accounts_db_via_serialization;
+pub(crate) use tests::{
+    reconstruct_accounts_db_via_serialization,
+    reconstruct_accounts_db_via_serialization_with_config,
+};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub(crate) enum SerdeStyle {
@@ -680,7 +683,7 @@ where
     }
 
     let AccountsDbFields(
-        _snapshot_storages,
+        snapshot_storages,
         snapshot_version,
         snapshot_slot,
         snapshot_bank_hash_info,
@@ -738,6 +741,17 @@ where
         })
         .unwrap();
 
+    let snapshot_storage_ids = SnapshotStorageIds {
+        slot: snapshot_slot,
+        ids: snapshot_storages
+            .iter()
+            .filter_map(|(slot, storages)| {
+                storages
+                    .first()
+                    .map(|storage| (*slot, storage.id() as AppendVecId))
+            })
+            .collect(),
+    };
     let IndexGenerationInfo {
         accounts_data_len,
         rent_paying_accounts_by_partition,
@@ -745,6 +759,7 @@ where
         limit_load_slot_count_from_snapshot,
         verify_index,
         genesis_config,
+        Some(&snapshot_storage_ids),
     );
     accounts_db
         .accounts_index
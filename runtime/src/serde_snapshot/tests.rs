@@ -7,6 +7,7 @@ use {
         accounts::Accounts,
         accounts_db::{
             get_temp_accounts_paths, test_utils::create_test_accounts, AccountShrinkThreshold,
+            ACCOUNTS_DB_CONFIG_FOR_TESTING,
         },
         accounts_hash::AccountsHash,
         append_vec::AppendVec,
@@ -41,6 +42,15 @@ use {
 fn copy_append_vecs<P: AsRef<Path>>(
     accounts_db: &AccountsDb,
     output_dir: P,
+) -> std::io::Result<StorageAndNextAppendVecId> {
+    copy_append_vecs_with_id_offset(accounts_db, output_dir, 0)
+}
+
+/// Like `copy_append_vecs`, but the copies get new ids, as they may when a snapshot is unpacked
+fn copy_append_vecs_with_id_offset<P: AsRef<Path>>(
+    accounts_db: &AccountsDb,
+    output_dir: P,
+    id_offset: AppendVecId,
 ) -> std::io::Result<StorageAndNextAppendVecId> {
     let storage_entries = accounts_db.get_snapshot_storages(RangeFull, None).0;
     let storage: AccountStorageMap = AccountStorageMap::with_capacity(storage_entries.len());
@@ -48,7 +58,8 @@ fn copy_append_vecs<P: AsRef<Path>>(
     for storage_entry in storage_entries.into_iter() {
         // Copy file to new directory
         let storage_path = storage_entry.get_path();
-        let file_name = AppendVec::file_name(storage_entry.slot(), storage_entry.append_vec_id());
+        let append_vec_id = storage_entry.append_vec_id() + id_offset;
+        let file_name = AppendVec::file_name(storage_entry.slot(), append_vec_id);
         let output_path = output_dir.as_ref().join(file_name);
         std::fs::copy(storage_path, &output_path)?;
 
@@ -57,7 +68,7 @@ fn copy_append_vecs<P: AsRef<Path>>(
             AppendVec::new_from_file(output_path, storage_entry.accounts.len())?;
         let new_storage_entry = AccountStorageEntry::new_existing(
             storage_entry.slot(),
-            storage_entry.append_vec_id(),
+            append_vec_id,
             append_vec,
             num_accounts,
         );
@@ -94,6 +105,7 @@ fn context_accountsdb_from_stream<'a, C, R>(
     stream: &mut BufReader<R>,
     account_paths: &[PathBuf],
     storage_and_next_append_vec_id: StorageAndNextAppendVecId,
+    accounts_db_config: AccountsDbConfig,
 ) -> Result<AccountsDb, Error>
 where
     C: TypeContext<'a>,
@@ -117,7 +129,7 @@ where
         None,
         AccountShrinkThreshold::default(),
         false,
-        Some(crate::accounts_db::ACCOUNTS_DB_CONFIG_FOR_TESTING),
+        Some(accounts_db_config),
         None,
         &Arc::default(),
         None,
@@ -130,6 +142,7 @@ fn accountsdb_from_stream<R>(
     stream: &mut BufReader<R>,
     account_paths: &[PathBuf],
     storage_and_next_append_vec_id: StorageAndNextAppendVecId,
+    accounts_db_config: AccountsDbConfig,
 ) -> Result<AccountsDb, Error>
 where
     R: Read,
@@ -139,6 +152,7 @@ where
             stream,
             account_paths,
             storage_and_next_append_vec_id,
+            accounts_db_config,
         ),
     }
 }
@@ -207,6 +221,7 @@ fn test_accounts_serialize_style(serde_style: SerdeStyle) {
             &mut reader,
             &daccounts_paths,
             storage_and_next_append_vec_id,
+            ACCOUNTS_DB_CONFIG_FOR_TESTING,
         )
         .unwrap(),
     );
@@ -424,6 +439,22 @@ fn test_bank_serialize_style(
 pub(crate) fn reconstruct_accounts_db_via_serialization(
     accounts: &AccountsDb,
     slot: Slot,
+) -> AccountsDb {
+    reconstruct_accounts_db_via_serialization_with_config(
+        accounts,
+        slot,
+        ACCOUNTS_DB_CONFIG_FOR_TESTING,
+        0,
+    )
+}
+
+/// Like `reconstruct_accounts_db_via_serialization`, with `accounts_db_config` and with
+/// `id_offset` added to the ids of the storages
+pub(crate) fn reconstruct_accounts_db_via_serialization_with_config(
+    accounts: &AccountsDb,
+    slot: Slot,
+    accounts_db_config: AccountsDbConfig,
+    id_offset: AppendVecId,
 ) -> AccountsDb {
     let mut writer = Cursor::new(vec![]);
     let snapshot_storages = accounts.get_snapshot_storages(..=slot, None).0;
@@ -442,12 +473,13 @@ pub(crate) fn reconstruct_accounts_db_via_serialization(
 
     // Simulate obtaining a copy of the AppendVecs from a tarball
     let storage_and_next_append_vec_id =
-        copy_append_vecs(accounts, copied_accounts.path()).unwrap();
+        copy_append_vecs_with_id_offset(accounts, copied_accounts.path(), id_offset).unwrap();
     let mut accounts_db = accountsdb_from_stream(
         SerdeStyle::Newer,
         &mut reader,
         &[],
         storage_and_next_append_vec_id,
+        accounts_db_config,
     )
     .unwrap();
 
@@ -43,6 +43,10 @@ pub struct SnapshotConfig {
 
     // Thread niceness adjustment for snapshot packager service
     pub packager_thread_niceness_adj: i8,
+
+    /// Put compressed AppendVecs into snapshot archives as they are, instead of decompressing
+    /// them. Only nodes that support compressed AppendVecs can load such archives.
+    pub archive_compressed_storages: bool,
 }
 
 impl Default for SnapshotConfig {
@@ -64,6 +68,7 @@ impl Default for SnapshotConfig {
                 snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
             accounts_hash_debug_verify: false,
             packager_thread_niceness_adj: 0,
+            archive_compressed_storages: false,
         }
     }
 }
@@ -7,11 +7,12 @@ use {
         },
         accounts_index::AccountSecondaryIndexes,
         accounts_update_notifier_interface::AccountsUpdateNotifier,
+        append_vec::StoredAccountMeta,
         bank::{Bank, BankFieldsToDeserialize, BankSlotDelta},
         builtins::Builtins,
         hardened_unpack::{
-            streaming_unpack_snapshot, unpack_snapshot, ParallelSelector, UnpackError,
-            UnpackedAppendVecMap,
+            streaming_unpack_snapshot, unpack_snapshot, ColdAccountPaths, ParallelSelector,
+            UnpackError, UnpackedAppendVecMap,
         },
         runtime_config::RuntimeConfig,
         serde_snapshot::{
@@ -21,6 +22,7 @@ use {
         snapshot_archive_info::{
             FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
         },
+        snapshot_config::SnapshotConfig,
         snapshot_hash::SnapshotHash,
         snapshot_package::{AccountsPackage, AccountsPackageType, SnapshotPackage, SnapshotType},
         snapshot_utils::snapshot_storage_rebuilder::{
@@ -38,6 +40,7 @@ use {
     regex::Regex,
     solana_measure::{measure, measure::Measure},
     solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
         clock::Slot,
         genesis_config::GenesisConfig,
         hash::Hash,
@@ -433,6 +436,7 @@ pub fn archive_snapshot_package(
     incremental_snapshot_archives_dir: impl AsRef<Path>,
     maximum_full_snapshot_archives_to_retain: usize,
     maximum_incremental_snapshot_archives_to_retain: usize,
+    archive_compressed_storages: bool,
 ) -> Result<()> {
     info!(
         "Generating snapshot archive for slot {}",
@@ -483,6 +487,15 @@ pub fn archive_snapshot_package(
             storage.append_vec_id(),
         ));
 
+        if storage.accounts.is_compressed() && !archive_compressed_storages {
+            // Unless configured otherwise, archives hold uncompressed AppendVecs so that any node
+            // can load them. Compressed ones are detected by their header when loading.
+            storage.accounts.decompress(&output_path).map_err(|e| {
+                SnapshotError::IoWithSourceAndFile(e, "decompress storage", output_path)
+            })?;
+            continue;
+        }
+
         // `storage_path` - The file path where the AppendVec itself is located
         // `output_path` - The file path where the AppendVec will be placed in the staging directory.
         let storage_path =
@@ -1014,6 +1027,7 @@ fn verify_and_unarchive_snapshots(
     full_snapshot_archive_info: &FullSnapshotArchiveInfo,
     incremental_snapshot_archive_info: Option<&IncrementalSnapshotArchiveInfo>,
     account_paths: &[PathBuf],
+    cold_account_paths: Option<&ColdAccountPaths>,
 ) -> Result<(UnarchivedSnapshot, Option<UnarchivedSnapshot>, AtomicU32)> {
     check_are_snapshots_compatible(
         full_snapshot_archive_info,
@@ -1029,6 +1043,7 @@ fn verify_and_unarchive_snapshots(
         full_snapshot_archive_info.path(),
         "snapshot untar",
         account_paths,
+        cold_account_paths,
         full_snapshot_archive_info.archive_format(),
         parallel_divisions,
         next_append_vec_id.clone(),
@@ -1042,6 +1057,7 @@ fn verify_and_unarchive_snapshots(
                 incremental_snapshot_archive_info.path(),
                 "incremental snapshot untar",
                 account_paths,
+                cold_account_paths,
                 incremental_snapshot_archive_info.archive_format(),
                 parallel_divisions,
                 next_append_vec_id.clone(),
@@ -1086,6 +1102,7 @@ pub fn bank_fields_from_snapshot_archives(
             &full_snapshot_archive_info,
             incremental_snapshot_archive_info.as_ref(),
             &account_paths,
+            None,
         )?;
 
     bank_fields_from_snapshots(
@@ -1098,6 +1115,63 @@ pub fn bank_fields_from_snapshot_archives(
     )
 }
 
+/// Load the latest version of the accounts in a full snapshot archive, and optionally an
+/// incremental snapshot archive on top of it, without rebuilding a bank. Only the accounts
+/// `filter` selects are loaded, and accounts whose latest version has zero lamports are skipped.
+pub fn accounts_from_snapshot_archives(
+    full_snapshot_archive_path: impl AsRef<Path>,
+    incremental_snapshot_archive_path: Option<impl AsRef<Path>>,
+    filter: impl Fn(&StoredAccountMeta) -> bool,
+) -> Result<Vec<(Pubkey, AccountSharedData)>> {
+    let full_snapshot_archive_info =
+        FullSnapshotArchiveInfo::new_from_path(full_snapshot_archive_path.as_ref().to_path_buf())?;
+    let incremental_snapshot_archive_info = incremental_snapshot_archive_path
+        .map(|path| IncrementalSnapshotArchiveInfo::new_from_path(path.as_ref().to_path_buf()))
+        .transpose()?;
+
+    let temp_dir = tempfile::Builder::new()
+        .prefix("accounts-from-snapshot-archives")
+        .tempdir()?;
+    let account_paths = vec![temp_dir.path().join("accounts")];
+    fs::create_dir_all(&account_paths[0])?;
+
+    let (unarchived_full_snapshot, unarchived_incremental_snapshot, _next_append_vec_id) =
+        verify_and_unarchive_snapshots(
+            temp_dir.path(),
+            &full_snapshot_archive_info,
+            incremental_snapshot_archive_info.as_ref(),
+            &account_paths,
+            None,
+        )?;
+
+    // there is a single storage per slot, and later versions of an account within a storage
+    // are appended after the earlier ones
+    let mut accounts = HashMap::<Pubkey, (Slot, Option<AccountSharedData>)>::new();
+    for unarchived_snapshot in
+        std::iter::once(&unarchived_full_snapshot).chain(unarchived_incremental_snapshot.as_ref())
+    {
+        for storage in unarchived_snapshot.storage.iter() {
+            let slot = *storage.key();
+            for stored_account in storage.storage.all_accounts() {
+                let pubkey = *stored_account.pubkey();
+                if let Some((latest_slot, _)) = accounts.get(&pubkey) {
+                    if *latest_slot > slot {
+                        continue;
+                    }
+                }
+                let account = (stored_account.lamports() != 0 && filter(&stored_account))
+                    .then(|| stored_account.clone_account());
+                accounts.insert(pubkey, (slot, account));
+            }
+        }
+    }
+
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(pubkey, (_slot, account))| Some((pubkey, account?)))
+        .collect())
+}
+
 /// Rebuild bank from snapshot archives.  Handles either just a full snapshot, or both a full
 /// snapshot and an incremental snapshot.
 #[allow(clippy::too_many_arguments)]
@@ -1120,12 +1194,30 @@ pub fn bank_from_snapshot_archives(
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     exit: &Arc<AtomicBool>,
 ) -> Result<(Bank, BankFromArchiveTimings)> {
+    let cold_account_paths = accounts_db_config
+        .as_ref()
+        .and_then(|config| config.cold_storage.as_ref())
+        .map(|cold_storage| {
+            // the cold storages from before the restart are replaced by the ones in the snapshot
+            for path in cold_storage.storage_paths() {
+                move_and_async_delete_path(&path);
+            }
+            let snapshot_slot = incremental_snapshot_archive_info
+                .map(|info| info.slot())
+                .unwrap_or_else(|| full_snapshot_archive_info.slot());
+            ColdAccountPaths {
+                paths: cold_storage.paths.clone(),
+                newest_cold_slot: cold_storage
+                    .newest_cold_slot(snapshot_slot, &genesis_config.epoch_schedule),
+            }
+        });
     let (unarchived_full_snapshot, mut unarchived_incremental_snapshot, next_append_vec_id) =
         verify_and_unarchive_snapshots(
             bank_snapshots_dir,
             full_snapshot_archive_info,
             incremental_snapshot_archive_info,
             account_paths,
+            cold_account_paths.as_ref(),
         )?;
 
     let mut storage = unarchived_full_snapshot.storage;
@@ -1322,6 +1414,7 @@ fn verify_bank_against_expected_slot_hash(
 fn spawn_unpack_snapshot_thread(
     file_sender: Sender<PathBuf>,
     account_paths: Arc<Vec<PathBuf>>,
+    cold_account_paths: Option<Arc<ColdAccountPaths>>,
     ledger_dir: Arc<PathBuf>,
     mut archive: Archive<SharedBufferReader>,
     parallel_selector: Option<ParallelSelector>,
@@ -1334,6 +1427,7 @@ fn spawn_unpack_snapshot_thread(
                 &mut archive,
                 ledger_dir.as_path(),
                 &account_paths,
+                cold_account_paths.as_deref(),
                 parallel_selector,
                 &file_sender,
             )
@@ -1346,12 +1440,14 @@ fn spawn_unpack_snapshot_thread(
 fn streaming_unarchive_snapshot(
     file_sender: Sender<PathBuf>,
     account_paths: Vec<PathBuf>,
+    cold_account_paths: Option<ColdAccountPaths>,
     ledger_dir: PathBuf,
     snapshot_archive_path: PathBuf,
     archive_format: ArchiveFormat,
     num_threads: usize,
 ) -> Vec<JoinHandle<()>> {
     let account_paths = Arc::new(account_paths);
+    let cold_account_paths = cold_account_paths.map(Arc::new);
     let ledger_dir = Arc::new(ledger_dir);
     let shared_buffer = untar_snapshot_create_shared_buffer(&snapshot_archive_path, archive_format);
 
@@ -1376,6 +1472,7 @@ fn streaming_unarchive_snapshot(
             spawn_unpack_snapshot_thread(
                 file_sender.clone(),
                 account_paths.clone(),
+                cold_account_paths.clone(),
                 ledger_dir.clone(),
                 archive,
                 parallel_selector,
@@ -1394,6 +1491,7 @@ fn unarchive_snapshot<P, Q>(
     snapshot_archive_path: Q,
     measure_name: &'static str,
     account_paths: &[PathBuf],
+    cold_account_paths: Option<&ColdAccountPaths>,
     archive_format: ArchiveFormat,
     parallel_divisions: usize,
     next_append_vec_id: Arc<AtomicU32>,
@@ -1411,6 +1509,7 @@ where
     streaming_unarchive_snapshot(
         file_sender,
         account_paths.to_vec(),
+        cold_account_paths.cloned(),
         unpack_dir.path().to_path_buf(),
         snapshot_archive_path.as_ref().to_path_buf(),
         archive_format,
@@ -2312,6 +2411,38 @@ pub fn bank_to_full_snapshot_archive(
     )
 }
 
+/// Create a full snapshot archive of `bank`, which must be the latest root, and keep the disk
+/// accounts index so the next start can reuse it when it loads exactly this snapshot.
+/// Called on a clean shutdown, once nothing else can change the accounts.
+/// Does nothing and returns None if the disk accounts index is not persisted.
+pub fn bank_to_full_snapshot_archive_with_accounts_index(
+    bank: &Bank,
+    snapshot_config: &SnapshotConfig,
+) -> Result<Option<FullSnapshotArchiveInfo>> {
+    if !bank.is_accounts_index_persisted() {
+        return Ok(None);
+    }
+    // checked before the snapshot is taken, as the index cannot be persisted with it otherwise
+    if !bank.is_latest_root() {
+        return Err(get_io_error(&format!(
+            "slot {} is not the latest root",
+            bank.slot()
+        )));
+    }
+    let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+        &snapshot_config.bank_snapshots_dir,
+        bank,
+        Some(snapshot_config.snapshot_version),
+        &snapshot_config.full_snapshot_archives_dir,
+        &snapshot_config.incremental_snapshot_archives_dir,
+        snapshot_config.archive_format,
+        snapshot_config.maximum_full_snapshot_archives_to_retain,
+        snapshot_config.maximum_incremental_snapshot_archives_to_retain,
+    )?;
+    bank.persist_accounts_index()?;
+    Ok(Some(full_snapshot_archive_info))
+}
+
 /// Convenience function to create an incremental snapshot archive out of any Bank, regardless of
 /// state.  The Bank will be frozen during the process.
 /// This is only called from ledger-tool or tests. Warping is a special case as well.
@@ -2410,6 +2541,7 @@ pub fn package_and_archive_full_snapshot(
         incremental_snapshot_archives_dir,
         maximum_full_snapshot_archives_to_retain,
         maximum_incremental_snapshot_archives_to_retain,
+        false, // archive_compressed_storages
     )?;
 
     Ok(FullSnapshotArchiveInfo::new(
@@ -2464,6 +2596,7 @@ pub fn package_and_archive_incremental_snapshot(
         incremental_snapshot_archives_dir,
         maximum_full_snapshot_archives_to_retain,
         maximum_incremental_snapshot_archives_to_retain,
+        false, // archive_compressed_storages
     )?;
 
     Ok(IncrementalSnapshotArchiveInfo::new(
@@ -3454,6 +3587,76 @@ mod tests {
         assert_eq!(original_bank, roundtrip_bank);
     }
 
+    #[test]
+    fn test_accounts_from_snapshot_archives() {
+        let collector = Pubkey::new_unique();
+        let key1 = Keypair::new();
+        let key2 = Keypair::new();
+        let key3 = Keypair::new();
+
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        bank0
+            .transfer(sol_to_lamports(1.), &mint_keypair, &key1.pubkey())
+            .unwrap();
+        bank0
+            .transfer(sol_to_lamports(3.), &mint_keypair, &key2.pubkey())
+            .unwrap();
+        while !bank0.is_complete() {
+            bank0.register_tick(&Hash::new_unique());
+        }
+
+        let bank1 = Arc::new(Bank::new_from_parent(&bank0, &collector, 1));
+        bank1
+            .transfer(sol_to_lamports(1.), &mint_keypair, &key1.pubkey())
+            .unwrap();
+        bank1
+            .transfer(sol_to_lamports(3.), &mint_keypair, &key3.pubkey())
+            .unwrap();
+        while !bank1.is_complete() {
+            bank1.register_tick(&Hash::new_unique());
+        }
+
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_info = bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &bank1,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            ArchiveFormat::Tar,
+            DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+
+        let mut accounts = accounts_from_snapshot_archives(
+            snapshot_archive_info.path(),
+            None::<&Path>,
+            |stored_account| [key1.pubkey(), key2.pubkey()].contains(stored_account.pubkey()),
+        )
+        .unwrap();
+        accounts.sort_by_key(|(pubkey, _account)| bank1.get_balance(pubkey));
+        assert_eq!(
+            accounts,
+            vec![
+                (key1.pubkey(), bank1.get_account(&key1.pubkey()).unwrap()),
+                (key2.pubkey(), bank1.get_account(&key2.pubkey()).unwrap()),
+            ]
+        );
+
+        let accounts =
+            accounts_from_snapshot_archives(snapshot_archive_info.path(), None::<&Path>, |_| true)
+                .unwrap();
+        assert!(accounts.contains(&(key3.pubkey(), bank1.get_account(&key3.pubkey()).unwrap())));
+        assert!(accounts.contains(&(
+            mint_keypair.pubkey(),
+            bank1.get_account(&mint_keypair.pubkey()).unwrap()
+        )));
+    }
+
     /// Test roundtrip of bank to a full snapshot, then back again.  This test is more involved
     /// than the simple version above; creating multiple banks over multiple slots and doing
     /// multiple transfers.  So this full snapshot should contain more data.
@@ -0,0 +1,257 @@
+//! A single transaction together with the state it was executed against, captured from a bank
+//! so that the transaction can be executed again deterministically outside of the cluster.
+use {
+    crate::{
+        accounts_db::CalcAccountsHashDataSource,
+        bank::{Bank, TransactionExecutionResult},
+    },
+    serde::{Deserialize, Serialize},
+    solana_program_runtime::timings::ExecuteTimings,
+    solana_sdk::{
+        account::{Account, AccountSharedData, ReadableAccount},
+        account_utils::StateMut,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::{Slot, MAX_PROCESSING_AGE},
+        epoch_schedule::EpochSchedule,
+        feature::{self, Feature},
+        fee_calculator::FeeRateGovernor,
+        genesis_config::GenesisConfig,
+        inflation::Inflation,
+        pubkey::Pubkey,
+        rent::Rent,
+        sysvar,
+        transaction::{self, MessageHash, SanitizedTransaction, VersionedTransaction},
+    },
+    std::{
+        collections::BTreeMap,
+        fs::File,
+        io::{self, BufReader, BufWriter},
+        path::Path,
+        sync::Arc,
+    },
+    thiserror::Error,
+};
+
+#[derive(Error, Debug)]
+pub enum TransactionFixtureError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("serialization error: {0}")]
+    Serialize(#[from] bincode::Error),
+
+    #[error("invalid transaction: {0}")]
+    InvalidTransaction(#[from] transaction::TransactionError),
+
+    #[error("missing sysvar account {0}")]
+    MissingSysvar(Pubkey),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransactionFixture {
+    /// The slot the transaction was executed in
+    pub slot: Slot,
+    pub transaction: VersionedTransaction,
+    /// Every account the transaction loads, including the program data of upgradeable
+    /// programs and the address lookup tables, as well as all sysvars
+    pub accounts: BTreeMap<Pubkey, Account>,
+    /// The active features, with the slots they were activated at
+    pub active_features: BTreeMap<Pubkey, Slot>,
+    pub lamports_per_signature: u64,
+    /// The result of executing the transaction when it was captured, if it was executed
+    pub expected_result: Option<transaction::Result<()>>,
+}
+
+impl TransactionFixture {
+    /// Capture the state `transaction` would execute against in `bank`
+    pub fn capture(
+        bank: &Bank,
+        transaction: VersionedTransaction,
+    ) -> Result<Self, TransactionFixtureError> {
+        let mut pubkeys = sysvar::ALL_IDS.clone();
+        if let Some(address_table_lookups) = transaction.message.address_table_lookups() {
+            pubkeys.extend(
+                address_table_lookups
+                    .iter()
+                    .map(|lookup| lookup.account_key),
+            );
+        }
+        let sanitized_transaction = SanitizedTransaction::try_create(
+            transaction.clone(),
+            MessageHash::Compute,
+            None,
+            bank,
+        )?;
+        pubkeys.extend(sanitized_transaction.message().account_keys().iter());
+
+        let mut accounts = BTreeMap::new();
+        for pubkey in pubkeys {
+            let Some(account) = bank.get_account_with_fixed_root(&pubkey) else {
+                continue;
+            };
+            if bpf_loader_upgradeable::check_id(account.owner()) {
+                if let Ok(UpgradeableLoaderState::Program {
+                    programdata_address,
+                }) = account.state()
+                {
+                    if let Some(programdata) =
+                        bank.get_account_with_fixed_root(&programdata_address)
+                    {
+                        accounts.insert(programdata_address, Account::from(programdata));
+                    }
+                }
+            }
+            accounts.insert(pubkey, Account::from(account));
+        }
+
+        Ok(Self {
+            slot: bank.slot(),
+            transaction,
+            accounts,
+            active_features: bank
+                .feature_set
+                .active
+                .iter()
+                .map(|(feature_id, slot)| (*feature_id, *slot))
+                .collect(),
+            lamports_per_signature: bank.get_lamports_per_signature(),
+            expected_result: None,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, TransactionFixtureError> {
+        let file = File::open(path)?;
+        Ok(bincode::deserialize_from(BufReader::new(file))?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), TransactionFixtureError> {
+        let file = File::create(path)?;
+        Ok(bincode::serialize_into(BufWriter::new(file), self)?)
+    }
+
+    fn sysvar<T: sysvar::Sysvar + sysvar::SysvarId>(&self) -> Result<T, TransactionFixtureError> {
+        self.accounts
+            .get(&T::id())
+            .and_then(|account| bincode::deserialize(&account.data).ok())
+            .ok_or(TransactionFixtureError::MissingSysvar(T::id()))
+    }
+
+    /// Create a bank at the slot of the fixture, holding its accounts, sysvars and features,
+    /// which the transaction can be executed in again, e.g. through `BankClient`
+    pub fn create_bank(&self) -> Result<Bank, TransactionFixtureError> {
+        let genesis_config = GenesisConfig {
+            accounts: self
+                .active_features
+                .keys()
+                .map(|feature_id| {
+                    let feature = Feature {
+                        activated_at: Some(0),
+                    };
+                    (
+                        *feature_id,
+                        Account::from(feature::create_account(&feature, 1)),
+                    )
+                })
+                .collect(),
+            fee_rate_governor: FeeRateGovernor::new(self.lamports_per_signature, 0),
+            rent: self.sysvar::<Rent>()?,
+            epoch_schedule: self.sysvar::<EpochSchedule>()?,
+            inflation: Inflation::new_disabled(),
+            ..GenesisConfig::default()
+        };
+
+        let mut bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        if self.slot > 1 {
+            bank = Arc::new(Bank::warp_from_parent(
+                &bank,
+                &Pubkey::default(),
+                self.slot - 1,
+                CalcAccountsHashDataSource::IndexForTests,
+            ));
+        }
+        let bank = if self.slot > 0 {
+            Bank::new_from_parent(&bank, &Pubkey::default(), self.slot)
+        } else {
+            Arc::try_unwrap(bank).unwrap()
+        };
+
+        // registering the blockhash updates the recent blockhashes sysvar, so do it before
+        // storing the captured sysvars
+        bank.register_recent_blockhash(self.transaction.message.recent_blockhash());
+        for (pubkey, account) in &self.accounts {
+            bank.store_account(pubkey, &AccountSharedData::from(account.clone()));
+        }
+        bank.reset_sysvar_cache();
+        bank.fill_missing_sysvar_cache_entries();
+        Ok(bank)
+    }
+
+    /// Execute the transaction in `bank`, which was created by `create_bank`
+    pub fn execute(
+        &self,
+        bank: &Bank,
+    ) -> Result<TransactionExecutionResult, TransactionFixtureError> {
+        let batch = bank.prepare_entry_batch(vec![self.transaction.clone()])?;
+        let (mut transaction_results, _balances) = bank.load_execute_and_commit_transactions(
+            &batch,
+            MAX_PROCESSING_AGE,
+            false,
+            true,
+            true,
+            true,
+            &mut ExecuteTimings::default(),
+            None,
+        );
+        Ok(transaction_results.execution_results.remove(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::genesis_utils::create_genesis_config,
+        solana_sdk::{
+            native_token::sol_to_lamports,
+            signature::{Keypair, Signer},
+            system_transaction,
+        },
+    };
+
+    #[test]
+    fn test_transaction_fixture_round_trip() {
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1.));
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        let bank = Bank::new_from_parent(&bank, &Pubkey::default(), 3);
+        let alice = Keypair::new();
+        let amount = genesis_config.rent.minimum_balance(0);
+        let transaction = VersionedTransaction::from(system_transaction::transfer(
+            &mint_keypair,
+            &alice.pubkey(),
+            amount,
+            bank.last_blockhash(),
+        ));
+
+        let mut fixture = TransactionFixture::capture(&bank, transaction).unwrap();
+        assert_eq!(fixture.slot, 3);
+        assert!(fixture.accounts.contains_key(&mint_keypair.pubkey()));
+        assert!(fixture.accounts.contains_key(&sysvar::clock::id()));
+        assert!(!fixture.accounts.contains_key(&alice.pubkey()));
+        fixture.expected_result = Some(fixture.execute(&bank).unwrap().flattened_result());
+        assert_eq!(fixture.expected_result, Some(Ok(())));
+
+        let fixture_file = tempfile::NamedTempFile::new().unwrap();
+        fixture.save(fixture_file.path()).unwrap();
+        let fixture = TransactionFixture::load(fixture_file.path()).unwrap();
+
+        let fixture_bank = fixture.create_bank().unwrap();
+        assert_eq!(fixture_bank.slot(), 3);
+        assert_eq!(
+            fixture_bank.get_balance(&mint_keypair.pubkey()),
+            fixture.accounts[&mint_keypair.pubkey()].lamports
+        );
+        let result = fixture.execute(&fixture_bank).unwrap();
+        assert_eq!(Some(result.flattened_result()), fixture.expected_result);
+        assert_eq!(fixture_bank.get_balance(&alice.pubkey()), amount);
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::integer_arithmetic)]
 
 use {
+    crossbeam_channel::Sender,
     log::*,
     solana_cli_output::CliAccount,
     solana_client::rpc_request::MAX_MULTIPLE_ACCOUNTS,
@@ -8,6 +9,7 @@ use {
         tower_storage::TowerStorage,
         validator::{Validator, ValidatorConfig, ValidatorStartProgress},
     },
+    solana_geyser_plugin_manager::geyser_plugin_manager::GeyserPluginManagerRequest,
     solana_gossip::{
         cluster_info::{ClusterInfo, Node},
         gossip_service::discover_cluster,
@@ -788,6 +790,7 @@ impl TestValidator {
                 }),
             log_messages_bytes_limit: config.log_messages_bytes_limit,
             transaction_account_lock_limit: config.transaction_account_lock_limit,
+            ..RuntimeConfig::default()
         };
 
         let mut validator_config = ValidatorConfig {
@@ -982,6 +985,15 @@ impl TestValidator {
     pub fn repair_whitelist(&self) -> Arc<RwLock<HashSet<Pubkey>>> {
         Arc::new(RwLock::new(HashSet::default()))
     }
+
+    pub fn geyser_plugin_manager_request_sender(
+        &self,
+    ) -> Option<Sender<GeyserPluginManagerRequest>> {
+        self.validator
+            .as_ref()
+            .unwrap()
+            .geyser_plugin_manager_request_sender()
+    }
 }
 
 impl Drop for TestValidator {
@@ -1,4 +1,5 @@
 use {
+    crossbeam_channel::{unbounded, Sender},
     jsonrpc_core::{MetaIoHandler, Metadata, Result},
     jsonrpc_core_client::{transports::ipc, RpcError},
     jsonrpc_derive::rpc,
@@ -9,6 +10,7 @@ use {
     solana_core::{
         consensus::Tower, tower_storage::TowerStorage, validator::ValidatorStartProgress,
     },
+    solana_geyser_plugin_manager::geyser_plugin_manager::GeyserPluginManagerRequest,
     solana_gossip::{
         cluster_info::ClusterInfo, legacy_contact_info::LegacyContactInfo as ContactInfo,
     },
@@ -38,6 +40,7 @@ pub struct AdminRpcRequestMetadataPostInit {
     pub bank_forks: Arc<RwLock<BankForks>>,
     pub vote_account: Pubkey,
     pub repair_whitelist: Arc<RwLock<HashSet<Pubkey>>>,
+    pub geyser_plugin_manager_request_sender: Option<Sender<GeyserPluginManagerRequest>>,
 }
 
 #[derive(Clone)]
@@ -66,6 +69,31 @@ impl AdminRpcRequestMetadata {
             ))
         }
     }
+
+    fn send_geyser_plugin_manager_request<F, R>(&self, make_request: F) -> Result<R>
+    where
+        F: FnOnce(Sender<std::result::Result<R, String>>) -> GeyserPluginManagerRequest,
+    {
+        self.with_post_init(|post_init| {
+            let request_sender = post_init
+                .geyser_plugin_manager_request_sender
+                .as_ref()
+                .ok_or_else(|| {
+                    jsonrpc_core::error::Error::invalid_params(
+                        "Geyser plugin service is not enabled, restart the validator with \
+                         --geyser-plugin-config or --geyser-plugin-always-enabled",
+                    )
+                })?;
+            let (response_sender, response_receiver) = unbounded();
+            request_sender
+                .send(make_request(response_sender))
+                .map_err(|_| jsonrpc_core::error::Error::internal_error())?;
+            response_receiver
+                .recv()
+                .map_err(|_| jsonrpc_core::error::Error::internal_error())?
+                .map_err(jsonrpc_core::error::Error::invalid_params)
+        })
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -220,6 +248,18 @@ pub trait AdminRpc {
         secondary_index: RpcAccountIndex,
         max_entries: usize,
     ) -> Result<Vec<(String, usize)>>;
+
+    #[rpc(meta, name = "listPlugins")]
+    fn list_plugins(&self, meta: Self::Metadata) -> Result<Vec<String>>;
+
+    #[rpc(meta, name = "loadPlugin")]
+    fn load_plugin(&self, meta: Self::Metadata, config_file: String) -> Result<String>;
+
+    #[rpc(meta, name = "unloadPlugin")]
+    fn unload_plugin(&self, meta: Self::Metadata, name: String) -> Result<()>;
+
+    #[rpc(meta, name = "reloadPlugin")]
+    fn reload_plugin(&self, meta: Self::Metadata, name: String, config_file: String) -> Result<()>;
 }
 
 pub struct AdminRpcImpl;
@@ -426,9 +466,10 @@ impl AdminRpc for AdminRpcImpl {
                 .indexes
                 .iter()
                 .filter_map(|index| {
+                    let rpc_account_index = rpc_account_index_from_account_index(index)?;
                     accounts_index
                         .get_index_key_size(index, &index_key)
-                        .map(|size| (rpc_account_index_from_account_index(index), size))
+                        .map(|size| (rpc_account_index, size))
                 })
                 .collect::<HashMap<_, _>>();
 
@@ -467,6 +508,44 @@ impl AdminRpc for AdminRpcImpl {
             Ok(largest_keys)
         })
     }
+
+    fn list_plugins(&self, meta: Self::Metadata) -> Result<Vec<String>> {
+        debug!("list_plugins request received");
+        meta.send_geyser_plugin_manager_request(|response_sender| {
+            GeyserPluginManagerRequest::ListPlugins { response_sender }
+        })
+    }
+
+    fn load_plugin(&self, meta: Self::Metadata, config_file: String) -> Result<String> {
+        debug!("load_plugin request received: {}", config_file);
+        meta.send_geyser_plugin_manager_request(|response_sender| {
+            GeyserPluginManagerRequest::LoadPlugin {
+                config_file,
+                response_sender,
+            }
+        })
+    }
+
+    fn unload_plugin(&self, meta: Self::Metadata, name: String) -> Result<()> {
+        debug!("unload_plugin request received: {}", name);
+        meta.send_geyser_plugin_manager_request(|response_sender| {
+            GeyserPluginManagerRequest::UnloadPlugin {
+                name,
+                response_sender,
+            }
+        })
+    }
+
+    fn reload_plugin(&self, meta: Self::Metadata, name: String, config_file: String) -> Result<()> {
+        debug!("reload_plugin request received: {} {}", name, config_file);
+        meta.send_geyser_plugin_manager_request(|response_sender| {
+            GeyserPluginManagerRequest::ReloadPlugin {
+                name,
+                config_file,
+                response_sender,
+            }
+        })
+    }
 }
 
 impl AdminRpcImpl {
@@ -516,11 +595,13 @@ impl AdminRpcImpl {
     }
 }
 
-fn rpc_account_index_from_account_index(account_index: &AccountIndex) -> RpcAccountIndex {
+// Data offset indexes are configured by the operator and have no admin RPC counterpart
+fn rpc_account_index_from_account_index(account_index: &AccountIndex) -> Option<RpcAccountIndex> {
     match account_index {
-        AccountIndex::ProgramId => RpcAccountIndex::ProgramId,
-        AccountIndex::SplTokenOwner => RpcAccountIndex::SplTokenOwner,
-        AccountIndex::SplTokenMint => RpcAccountIndex::SplTokenMint,
+        AccountIndex::ProgramId => Some(RpcAccountIndex::ProgramId),
+        AccountIndex::SplTokenOwner => Some(RpcAccountIndex::SplTokenOwner),
+        AccountIndex::SplTokenMint => Some(RpcAccountIndex::SplTokenMint),
+        AccountIndex::DataOffset(_) => None,
     }
 }
 
@@ -720,6 +801,7 @@ mod tests {
                     bank_forks: bank_forks.clone(),
                     vote_account,
                     repair_whitelist,
+                    geyser_plugin_manager_request_sender: None,
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
             };
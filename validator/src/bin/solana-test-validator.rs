@@ -422,6 +422,8 @@ fn main() {
         rpc_bigtable_config,
         faucet_addr: Some(faucet_addr),
         account_indexes,
+        enable_simulation_instruction_tracing: true,
+        enable_simulation_program_overrides: true,
         ..JsonRpcConfig::default_for_test()
     });
 
@@ -506,6 +508,8 @@ fn main() {
                     cluster_info: test_validator.cluster_info(),
                     vote_account: test_validator.vote_account_address(),
                     repair_whitelist: test_validator.repair_whitelist(),
+                    geyser_plugin_manager_request_sender: test_validator
+                        .geyser_plugin_manager_request_sender(),
                 });
             if let Some(dashboard) = dashboard {
                 dashboard.run(Duration::from_millis(250));
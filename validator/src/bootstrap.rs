@@ -2,6 +2,7 @@ use {
     log::*,
     rand::{seq::SliceRandom, thread_rng, Rng},
     rayon::prelude::*,
+    serde_json::json,
     solana_core::validator::{ValidatorConfig, ValidatorStartProgress},
     solana_download_utils::{download_snapshot_archive, DownloadProgressRecord},
     solana_genesis_utils::download_then_check_genesis_hash,
@@ -12,7 +13,9 @@ use {
         legacy_contact_info::LegacyContactInfo as ContactInfo,
     },
     solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::request::RpcRequest,
     solana_runtime::{
+        accounts_hash::AccountsHashBins,
         snapshot_archive_info::SnapshotArchiveInfoGetter,
         snapshot_package::SnapshotType,
         snapshot_utils::{self},
@@ -52,6 +55,7 @@ pub struct RpcBootstrapConfig {
     pub max_genesis_archive_unpacked_size: u64,
     pub check_vote_account: Option<String>,
     pub incremental_snapshot_fetch: bool,
+    pub compare_accounts_hash_bins: bool,
 }
 
 fn verify_reachable_ports(
@@ -422,6 +426,11 @@ pub fn attempt_download_genesis_and_snapshot(
         rpc_contact_info,
     )?;
 
+    if bootstrap_config.compare_accounts_hash_bins {
+        validator_config.rpc_peer_accounts_hash_bins = snapshot_hash
+            .and_then(|snapshot_hash| get_accounts_hash_bins(rpc_client, snapshot_hash));
+    }
+
     if let Some(url) = bootstrap_config.check_vote_account.as_ref() {
         let rpc_client = RpcClient::new(url);
         check_vote_account(
@@ -449,6 +458,37 @@ pub fn attempt_download_genesis_and_snapshot(
     Ok(())
 }
 
+/// Get the accounts hash bins summary of the snapshot from the RPC node, to compare with the
+/// accounts of the snapshot once it is loaded
+fn get_accounts_hash_bins(
+    rpc_client: &RpcClient,
+    snapshot_hash: SnapshotHash,
+) -> Option<AccountsHashBins> {
+    let slot = snapshot_hash
+        .incr
+        .map(|(slot, _hash)| slot)
+        .unwrap_or(snapshot_hash.full.0);
+    match rpc_client.send::<Option<AccountsHashBins>>(
+        RpcRequest::Custom {
+            method: "getAccountsHashBins",
+        },
+        json!([slot]),
+    ) {
+        Ok(Some(accounts_hash_bins)) => Some(accounts_hash_bins),
+        Ok(None) => {
+            warn!(
+                "RPC node has no accounts hash bins for slot {slot}, it needs to run with \
+                 --accounts-hash-bins"
+            );
+            None
+        }
+        Err(err) => {
+            warn!("Failed to get accounts hash bins for slot {slot} from the RPC node: {err}");
+            None
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn rpc_bootstrap(
     node: &Node,
@@ -13,12 +13,18 @@ use {
     solana_core::banking_trace::{DirByteLimit, BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT},
     solana_faucet::faucet::{self, FAUCET_PORT},
     solana_net_utils::{MINIMUM_VALIDATOR_PORT_RANGE_WIDTH, VALIDATOR_PORT_RANGE},
-    solana_rpc::{rpc::MAX_REQUEST_BODY_SIZE, rpc_pubsub_service::PubSubConfig},
+    solana_rpc::{
+        rpc::{DEFAULT_ACCOUNT_HISTORY_RETENTION_SLOTS, MAX_REQUEST_BODY_SIZE},
+        rpc_grpc_service::DEFAULT_GRPC_WORKER_THREADS,
+        rpc_pubsub_service::PubSubConfig,
+        rpc_rate_limiter::RpcRateLimit,
+    },
     solana_rpc_client_api::request::MAX_MULTIPLE_ACCOUNTS,
     solana_runtime::{
         accounts_db::{
             DEFAULT_ACCOUNTS_SHRINK_OPTIMIZE_TOTAL_SPACE, DEFAULT_ACCOUNTS_SHRINK_RATIO,
         },
+        accounts_index::DataOffsetIndex,
         hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
         snapshot_utils::{
             SnapshotVersion, DEFAULT_ARCHIVE_COMPRESSION,
@@ -228,6 +234,26 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("Include CPI inner instructions, logs, and return data in \
                        the historical transaction info stored"),
         )
+        .arg(
+            Arg::with_name("enable_rpc_account_history")
+                .long("enable-rpc-account-history")
+                .takes_value(false)
+                .help("Record the accounts written in each slot to serve historical \
+                       account state over JSON RPC with the 'getAccountInfoAtSlot' API.  \
+                       Only slots replayed since this was enabled are covered.  \
+                       This will cause a significant increase in disk usage and IOPS"),
+        )
+        .arg(
+            Arg::with_name("rpc_account_history_retention_slots")
+                .long("rpc-account-history-retention-slots")
+                .value_name("SLOTS")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .requires("enable_rpc_account_history")
+                .default_value(&default_args.rpc_account_history_retention_slots)
+                .help("Number of slots behind the highest root for which historical \
+                       account state is retained"),
+        )
         .arg(
             Arg::with_name("rpc_max_multiple_accounts")
                 .long("rpc-max-multiple-accounts")
@@ -401,6 +427,17 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                        slots behind the highest snapshot available for \
                        download from other validators"),
         )
+        .arg(
+            Arg::with_name("compare_accounts_hash_bins")
+                .long("compare-accounts-hash-bins")
+                .takes_value(false)
+                .conflicts_with("no_snapshot_fetch")
+                .help("At startup, compare the accounts of the loaded snapshot with the \
+                       summary of the same snapshot on the RPC node it was fetched from, and log \
+                       the pubkey bins that differ. The RPC node must run with \
+                       --accounts-hash-bins. Startup waits for the comparison, which scans all \
+                       the accounts"),
+        )
         .arg(
             Arg::with_name("no_incremental_snapshots")
                 .long("no-incremental-snapshots")
@@ -862,6 +899,13 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .takes_value(false)
                 .help("Enable the unstable RPC PubSub `blockSubscribe` subscription"),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_transaction_subscription")
+                .long("rpc-pubsub-enable-transaction-subscription")
+                .requires("enable_rpc_transaction_history")
+                .takes_value(false)
+                .help("Enable the unstable RPC PubSub `transactionSubscribe` subscription"),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_enable_vote_subscription")
                 .long("rpc-pubsub-enable-vote-subscription")
@@ -939,6 +983,27 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("The maximum total size of notifications that RPC PubSub will store \
                        across all connections."),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_replay_capacity_items")
+                .long("rpc-pubsub-replay-capacity-items")
+                .takes_value(true)
+                .value_name("NUMBER")
+                .validator(is_parsable::<usize>)
+                .default_value(&default_args.rpc_pubsub_replay_capacity_items)
+                .help("The maximum number of account, program, logs and slots updates \
+                       notifications that RPC PubSub will retain for clients resuming their \
+                       subscriptions with `fromSlot`. 0 disables replay"),
+        )
+        .arg(
+            Arg::with_name("rpc_pubsub_replay_capacity_bytes")
+                .long("rpc-pubsub-replay-capacity-bytes")
+                .takes_value(true)
+                .value_name("BYTES")
+                .validator(is_parsable::<usize>)
+                .default_value(&default_args.rpc_pubsub_replay_capacity_bytes)
+                .help("The maximum total size of notifications that RPC PubSub will retain \
+                       for replay."),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_notification_threads")
                 .long("rpc-pubsub-notification-threads")
@@ -1011,6 +1076,24 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .requires("enable_rpc_transaction_history")
                 .help("Verifies blockstore roots on boot and fixes any gaps"),
         )
+        .arg(
+            Arg::with_name("rpc_enable_simulation_instruction_tracing")
+                .long("rpc-enable-simulation-instruction-tracing")
+                .takes_value(false)
+                .help(
+                    "Allow simulateTransaction and simulateBundle requests to trace the \
+                     instructions executed and the compute units they consumed",
+                ),
+        )
+        .arg(
+            Arg::with_name("rpc_enable_simulation_program_overrides")
+                .long("rpc-enable-simulation-program-overrides")
+                .takes_value(false)
+                .help(
+                    "Allow simulateTransaction requests to override program accounts and \
+                     features. Such simulations compile their programs on every request",
+                ),
+        )
         .arg(
             Arg::with_name("rpc_max_request_body_size")
                 .long("rpc-max-request-body-size")
@@ -1020,6 +1103,81 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(&default_args.rpc_max_request_body_size)
                 .help("The maximum request body size accepted by rpc service"),
         )
+        .arg(
+            Arg::with_name("rpc_grpc_port")
+                .long("rpc-grpc-port")
+                .value_name("PORT")
+                .takes_value(true)
+                .validator(port_validator)
+                .requires("full_rpc_api")
+                .help("Enable the gRPC transport for account, block and transaction queries \
+                       and subscriptions on this port"),
+        )
+        .arg(
+            Arg::with_name("rpc_grpc_threads")
+                .long("rpc-grpc-threads")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .default_value(&default_args.rpc_grpc_threads)
+                .help("Number of threads to use for servicing gRPC requests"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_key")
+                .long("rpc-rate-limit-key")
+                .value_name("KEY")
+                .takes_value(true)
+                .possible_values(&["ip", "authorization"])
+                .default_value("ip")
+                .help("Identify RPC clients for rate limiting by source IP, as appended to \
+                       the X-Forwarded-For header by the outermost trusted proxy, or by the \
+                       Authorization header. Clients without an accepted Authorization header \
+                       are identified by source IP"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_authorization_tokens")
+                .long("rpc-rate-limit-authorization-tokens")
+                .value_name("FILE")
+                .takes_value(true)
+                .required_if("rpc_rate_limit_key", "authorization")
+                .help("File with the accepted Authorization header values, one per line. \
+                       Required to rate limit RPC clients by Authorization header"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_trusted_proxies")
+                .long("rpc-rate-limit-trusted-proxies")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .help("Number of reverse proxies in front of the RPC ports that append the \
+                       address they received a request from to the X-Forwarded-For header. \
+                       Required to rate limit RPC clients"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_reads")
+                .long("rpc-rate-limit-reads")
+                .value_name("REQUESTS_PER_SECOND[:BURST]")
+                .takes_value(true)
+                .validator(is_parsable::<RpcRateLimit>)
+                .help("Limit each RPC client's requests to methods that are not scans or sends"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_scans")
+                .long("rpc-rate-limit-scans")
+                .value_name("REQUESTS_PER_SECOND[:BURST]")
+                .takes_value(true)
+                .validator(is_parsable::<RpcRateLimit>)
+                .help("Limit each RPC client's requests to expensive read methods, \
+                       such as getProgramAccounts and simulateTransaction"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_sends")
+                .long("rpc-rate-limit-sends")
+                .value_name("REQUESTS_PER_SECOND[:BURST]")
+                .takes_value(true)
+                .validator(is_parsable::<RpcRateLimit>)
+                .help("Limit each RPC client's requests to transaction sending methods"),
+        )
         .arg(
             Arg::with_name("enable_accountsdb_repl")
                 .long("enable-accountsdb-repl")
@@ -1064,6 +1222,13 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .multiple(true)
                 .help("Specify the configuration file for the Geyser plugin."),
         )
+        .arg(
+            Arg::with_name("geyser_plugin_always_enabled")
+                .long("geyser-plugin-always-enabled")
+                .takes_value(false)
+                .help("Enable Geyser interface even if no Geyser configs are specified, \
+                       so that plugins can be loaded later through the admin RPC service."),
+        )
         .arg(
             Arg::with_name("halt_on_known_validators_accounts_hash_mismatch")
                 .alias("halt-on-trusted-validators-accounts-hash-mismatch")
@@ -1082,6 +1247,14 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .takes_value(true)
                 .help("Snapshot archive format to use."),
         )
+        .arg(
+            Arg::with_name("snapshot_archive_compressed_storages")
+                .long("snapshot-archive-compressed-storages")
+                .takes_value(false)
+                .help("Put compressed AppendVecs into snapshot archives as they are. \
+                       By default, they are decompressed so that any node can load the archives.")
+                .hidden(true),
+        )
         .arg(
             Arg::with_name("max_genesis_archive_unpacked_size")
                 .long("max-genesis-archive-unpacked-size")
@@ -1151,6 +1324,16 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .value_name("INDEX")
                 .help("Enable an accounts index, indexed by the selected account field"),
         )
+        .arg(
+            Arg::with_name("account_index_data_offset")
+                .long("account-index-data-offset")
+                .takes_value(true)
+                .multiple(true)
+                .validator(is_parsable::<DataOffsetIndex>)
+                .value_name("PROGRAM_ID:OFFSET:LENGTH")
+                .help("Enable an accounts index of the accounts owned by PROGRAM_ID, indexed by \
+                       the LENGTH bytes of account data at OFFSET. LENGTH is at most 32"),
+        )
         .arg(
             Arg::with_name("account_index_exclude_key")
                 .long(EXCLUDE_KEY)
@@ -1197,6 +1380,74 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("AppendVecs that are older than (slots_per_epoch - SLOT-OFFSET) are squashed together.")
                 .hidden(true),
         )
+        .arg(
+            Arg::with_name("accounts_db_cold_path")
+                .long("accounts-db-cold-path")
+                .value_name("PATH")
+                .takes_value(true)
+                .multiple(true)
+                .requires("accounts_db_ancient_append_vecs")
+                .help("Path for compressed ancient AppendVecs holding accounts that have not \
+                       been written for --accounts-db-cold-age-epochs epochs. \
+                       Meant for a larger, slower disk than the accounts paths. \
+                       Kept across restarts, and its storages are replaced when a snapshot is loaded.")
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("accounts_db_cold_age_epochs")
+                .long("accounts-db-cold-age-epochs")
+                .value_name("EPOCHS")
+                .validator(is_parsable::<u64>)
+                .takes_value(true)
+                .requires("accounts_db_cold_path")
+                .help("Number of epochs an account has to be untouched for before it is \
+                       moved to --accounts-db-cold-path [default: 4]")
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("accounts_db_cold_compression")
+                .long("accounts-db-cold-compression")
+                .value_name("CODEC")
+                .takes_value(true)
+                .possible_values(&["lz4", "zstd"])
+                .requires("accounts_db_cold_path")
+                .help("Block compression of the ancient AppendVecs on --accounts-db-cold-path \
+                       [default: zstd]")
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("accounts_db_compress_ancient_storages")
+                .long("accounts-db-compress-ancient-storages")
+                .value_name("CODEC")
+                .takes_value(true)
+                .possible_values(&["lz4", "zstd"])
+                .requires("accounts_db_ancient_append_vecs")
+                .help("Rewrite ancient AppendVecs that are no longer written to in a read-only, \
+                       block-compressed format. Trades CPU on account loads for disk space.")
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("accounts_db_decompressed_cache_limit_mb")
+                .long("accounts-db-decompressed-cache-limit-mb")
+                .value_name("MEGABYTES")
+                .validator(is_parsable::<usize>)
+                .takes_value(true)
+                .help("How much memory the decompressed blocks of compressed AppendVecs can use \
+                       [default: 1024]")
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("accounts_hash_bins")
+                .long("accounts-hash-bins")
+                .value_name("NUMBER")
+                .validator(is_pow2)
+                .takes_value(true)
+                .help("Summarize the accounts of each new snapshot in NUMBER bins by pubkey, a \
+                       power of two up to 65536, and serve the summaries of the newest snapshots \
+                       with the getAccountsHashBins RPC method. Nodes that fetch a snapshot from \
+                       this node can compare their accounts with it \
+                       using --compare-accounts-hash-bins"),
+        )
         .arg(
             Arg::with_name("accounts_db_cache_limit_mb")
                 .long("accounts-db-cache-limit-mb")
@@ -1227,6 +1478,19 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("Disable the disk-based accounts index if it is enabled by default.")
                 .conflicts_with("accounts_index_memory_limit_mb")
         )
+        .arg(
+            Arg::with_name("accounts_index_persist")
+                .long("accounts-index-persist")
+                .conflicts_with("disable_accounts_disk_index")
+                .help("Keep the disk-based accounts index on a clean shutdown, together with \
+                       a full snapshot archive of the last root taken at shutdown. \
+                       At the next start, the index is reused if exactly that snapshot is \
+                       loaded. Otherwise, the index is generated as usual, also when a newer \
+                       snapshot is loaded, e.g. one downloaded from a peer or an incremental \
+                       snapshot on top of the one taken at shutdown. \
+                       Not used when secondary account indexes are enabled or snapshots are \
+                       not generated.")
+        )
         .arg(
             Arg::with_name("accounts_index_bins")
                 .long("accounts-index-bins")
@@ -1450,6 +1714,48 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                                     running validator instance")
                 )
         )
+        .subcommand(
+            SubCommand::with_name("plugin")
+                .about("Manage and view Geyser plugins")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .setting(AppSettings::InferSubcommands)
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List all current running Geyser plugins")
+                )
+                .subcommand(
+                    SubCommand::with_name("unload")
+                        .about("Unload a particular Geyser plugin. You must specify the Geyser plugin name")
+                        .arg(
+                            Arg::with_name("name")
+                                .required(true)
+                                .takes_value(true)
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("reload")
+                        .about("Reload a particular Geyser plugin. You must specify the Geyser plugin name and the new config path")
+                        .arg(
+                            Arg::with_name("name")
+                                .required(true)
+                                .takes_value(true)
+                        )
+                        .arg(
+                            Arg::with_name("config")
+                                .required(true)
+                                .takes_value(true)
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("load")
+                        .about("Load a new Geyser plugin. You must specify the config path. Fails if overwriting (use reload)")
+                        .arg(
+                            Arg::with_name("config")
+                                .required(true)
+                                .takes_value(true)
+                        )
+                )
+        )
         .subcommand(
             SubCommand::with_name("init")
                 .about("Initialize the ledger directory then exit")
@@ -1661,6 +1967,8 @@ pub struct DefaultArgs {
     pub rpc_pubsub_max_active_subscriptions: String,
     pub rpc_pubsub_queue_capacity_items: String,
     pub rpc_pubsub_queue_capacity_bytes: String,
+    pub rpc_pubsub_replay_capacity_items: String,
+    pub rpc_pubsub_replay_capacity_bytes: String,
     pub rpc_send_transaction_retry_ms: String,
     pub rpc_send_transaction_batch_ms: String,
     pub rpc_send_transaction_leader_forward_count: String,
@@ -1672,6 +1980,8 @@ pub struct DefaultArgs {
     pub rpc_bigtable_instance_name: String,
     pub rpc_bigtable_app_profile_id: String,
     pub rpc_max_request_body_size: String,
+    pub rpc_grpc_threads: String,
+    pub rpc_account_history_retention_slots: String,
     pub rpc_pubsub_worker_threads: String,
 
     pub maximum_local_snapshot_age: String,
@@ -1735,6 +2045,12 @@ impl DefaultArgs {
             rpc_pubsub_queue_capacity_bytes: PubSubConfig::default()
                 .queue_capacity_bytes
                 .to_string(),
+            rpc_pubsub_replay_capacity_items: PubSubConfig::default()
+                .replay_capacity_items
+                .to_string(),
+            rpc_pubsub_replay_capacity_bytes: PubSubConfig::default()
+                .replay_capacity_bytes
+                .to_string(),
             send_transaction_service_config: send_transaction_service::Config::default(),
             rpc_send_transaction_retry_ms: default_send_transaction_service_config
                 .retry_rate_ms
@@ -1784,6 +2100,9 @@ impl DefaultArgs {
             accounts_shrink_ratio: DEFAULT_ACCOUNTS_SHRINK_RATIO.to_string(),
             tpu_connection_pool_size: DEFAULT_TPU_CONNECTION_POOL_SIZE.to_string(),
             rpc_max_request_body_size: MAX_REQUEST_BODY_SIZE.to_string(),
+            rpc_grpc_threads: DEFAULT_GRPC_WORKER_THREADS.to_string(),
+            rpc_account_history_retention_slots: DEFAULT_ACCOUNT_HISTORY_RETENTION_SLOTS
+                .to_string(),
             exit_min_idle_time: "10".to_string(),
             exit_max_delinquent_stake: "5".to_string(),
             wait_for_restart_window_min_idle_time: "10".to_string(),
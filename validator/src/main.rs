@@ -23,19 +23,23 @@ use {
     solana_poh::poh_service,
     solana_rpc::{
         rpc::{JsonRpcConfig, RpcBigtableConfig},
+        rpc_grpc_service::RpcGrpcConfig,
         rpc_pubsub_service::PubSubConfig,
+        rpc_rate_limiter::{RpcRateLimit, RpcRateLimitConfig, RpcRateLimitKey},
     },
     solana_rpc_client::rpc_client::RpcClient,
     solana_rpc_client_api::config::RpcLeaderScheduleConfig,
     solana_runtime::{
         accounts_db::{
-            AccountShrinkThreshold, AccountsDb, AccountsDbConfig, CreateAncientStorage,
-            FillerAccountsConfig,
+            AccountShrinkThreshold, AccountsDb, AccountsDbConfig, ColdStorageConfig,
+            CreateAncientStorage, FillerAccountsConfig, DEFAULT_COLD_STORAGE_AGE_EPOCHS,
+            PUBKEY_BINS_FOR_CALCULATING_HASHES,
         },
         accounts_index::{
             AccountIndex, AccountSecondaryIndexes, AccountSecondaryIndexesIncludeExclude,
-            AccountsIndexConfig, IndexLimitMb,
+            AccountsIndexConfig, DataOffsetIndex, IndexLimitMb,
         },
+        append_vec::AppendVecCompression,
         runtime_config::RuntimeConfig,
         snapshot_config::{SnapshotConfig, SnapshotUsage},
         snapshot_utils::{
@@ -757,6 +761,85 @@ pub fn main() {
                 _ => unreachable!(),
             }
         }
+        ("plugin", Some(plugin_subcommand_matches)) => {
+            match plugin_subcommand_matches.subcommand() {
+                ("list", _) => {
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    let plugins = admin_rpc_service::runtime()
+                        .block_on(async move { admin_client.await?.list_plugins().await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to list plugins: {err}");
+                            exit(1);
+                        });
+                    if !plugins.is_empty() {
+                        println!("Currently the following plugins are loaded:");
+                        for (plugin, i) in plugins.into_iter().zip(1..) {
+                            println!("  {i}) {plugin}");
+                        }
+                    } else {
+                        println!("There are currently no plugins loaded");
+                    }
+                    return;
+                }
+                ("unload", Some(subcommand_matches)) => {
+                    let name = value_t_or_exit!(subcommand_matches, "name", String);
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    admin_rpc_service::runtime()
+                        .block_on(async { admin_client.await?.unload_plugin(name.clone()).await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to unload plugin {name}: {err:?}");
+                            exit(1);
+                        });
+                    println!("Successfully unloaded plugin: {name}");
+                    return;
+                }
+                ("load", Some(subcommand_matches)) => {
+                    let config = value_t_or_exit!(subcommand_matches, "config", String);
+                    // the validator resolves the path relative to its own working directory
+                    let config = fs::canonicalize(&config)
+                        .map(|config| config.display().to_string())
+                        .unwrap_or_else(|err| {
+                            println!("Unable to access path: {config}: {err:?}");
+                            exit(1);
+                        });
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    let name = admin_rpc_service::runtime()
+                        .block_on(async { admin_client.await?.load_plugin(config.clone()).await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to load plugin {config}: {err:?}");
+                            exit(1);
+                        });
+                    println!("Successfully loaded plugin: {name}");
+                    return;
+                }
+                ("reload", Some(subcommand_matches)) => {
+                    let name = value_t_or_exit!(subcommand_matches, "name", String);
+                    let config = value_t_or_exit!(subcommand_matches, "config", String);
+                    // the validator resolves the path relative to its own working directory
+                    let config = fs::canonicalize(&config)
+                        .map(|config| config.display().to_string())
+                        .unwrap_or_else(|err| {
+                            println!("Unable to access path: {config}: {err:?}");
+                            exit(1);
+                        });
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    admin_rpc_service::runtime()
+                        .block_on(async {
+                            admin_client
+                                .await?
+                                .reload_plugin(name.clone(), config.clone())
+                                .await
+                        })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to reload plugin {name}: {err:?}");
+                            exit(1);
+                        });
+                    println!("Successfully reloaded plugin: {name}");
+                    return;
+                }
+                _ => unreachable!(),
+            }
+        }
         _ => unreachable!(),
     };
 
@@ -837,6 +920,7 @@ pub fn main() {
             u64
         ),
         incremental_snapshot_fetch: !matches.is_present("no_incremental_snapshots"),
+        compare_accounts_hash_bins: matches.is_present("compare_accounts_hash_bins"),
     };
 
     let private_rpc = matches.is_present("private_rpc");
@@ -1013,6 +1097,7 @@ pub fn main() {
         } else {
             IndexLimitMb::Unspecified
         };
+    accounts_index_config.persist_disk_index = matches.is_present("accounts_index_persist");
 
     {
         let mut accounts_index_paths: Vec<PathBuf> = if matches.is_present("accounts_index_path") {
@@ -1040,6 +1125,17 @@ pub fn main() {
         size: value_t_or_exit!(matches, "accounts_filler_size", usize),
     };
 
+    let accounts_hash_bins = value_t!(matches, "accounts_hash_bins", usize).ok();
+    if let Some(num_bins) = accounts_hash_bins {
+        if num_bins > PUBKEY_BINS_FOR_CALCULATING_HASHES {
+            eprintln!(
+                "--accounts-hash-bins {num_bins} is more than the \
+                 {PUBKEY_BINS_FOR_CALCULATING_HASHES} bins of the accounts hash calculation"
+            );
+            exit(1);
+        }
+    }
+
     let accounts_db_config = AccountsDbConfig {
         index: Some(accounts_index_config),
         accounts_hash_cache_path: Some(ledger_path.join(AccountsDb::ACCOUNTS_HASH_CACHE_DIR)),
@@ -1048,6 +1144,33 @@ pub fn main() {
             .ok()
             .map(|mb| mb * MB as u64),
         ancient_append_vec_offset: value_t!(matches, "accounts_db_ancient_append_vecs", i64).ok(),
+        cold_storage: values_t!(matches, "accounts_db_cold_path", String)
+            .ok()
+            .map(|paths| ColdStorageConfig {
+                paths: paths.into_iter().map(PathBuf::from).collect(),
+                age_epochs: value_t!(matches, "accounts_db_cold_age_epochs", u64)
+                    .unwrap_or(DEFAULT_COLD_STORAGE_AGE_EPOCHS),
+                compression: value_t!(
+                    matches,
+                    "accounts_db_cold_compression",
+                    AppendVecCompression
+                )
+                .unwrap_or(AppendVecCompression::Zstd),
+            }),
+        ancient_storage_compression: value_t!(
+            matches,
+            "accounts_db_compress_ancient_storages",
+            AppendVecCompression
+        )
+        .ok(),
+        decompressed_block_cache_limit_bytes: value_t!(
+            matches,
+            "accounts_db_decompressed_cache_limit_mb",
+            usize
+        )
+        .ok()
+        .map(|mb| mb * MB),
+        accounts_hash_bins,
         exhaustively_verify_refcounts: matches.is_present("accounts_db_verify_refcounts"),
         create_ancient_storage: matches
             .is_present("accounts_db_create_ancient_storage_packed")
@@ -1113,6 +1236,37 @@ pub fn main() {
     }
     let full_api = matches.is_present("full_rpc_api");
 
+    let rpc_rate_limit_config = {
+        let reads = value_t!(matches, "rpc_rate_limit_reads", RpcRateLimit).ok();
+        let scans = value_t!(matches, "rpc_rate_limit_scans", RpcRateLimit).ok();
+        let sends = value_t!(matches, "rpc_rate_limit_sends", RpcRateLimit).ok();
+        (reads.is_some() || scans.is_some() || sends.is_some()).then(|| RpcRateLimitConfig {
+            key: match matches.value_of("rpc_rate_limit_key") {
+                Some("authorization") => RpcRateLimitKey::Authorization,
+                _ => RpcRateLimitKey::SourceIp,
+            },
+            trusted_proxies: value_t!(matches, "rpc_rate_limit_trusted_proxies", usize)
+                .unwrap_or(0),
+            authorization_tokens: matches
+                .value_of("rpc_rate_limit_authorization_tokens")
+                .map(|file| {
+                    fs::read_to_string(file).unwrap_or_else(|err| {
+                        eprintln!("Unable to read {file}: {err}");
+                        exit(1)
+                    })
+                })
+                .unwrap_or_default()
+                .lines()
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+                .map(str::to_string)
+                .collect(),
+            reads,
+            scans,
+            sends,
+        })
+    };
+
     let mut validator_config = ValidatorConfig {
         require_tower: matches.is_present("require_tower"),
         tower_storage,
@@ -1129,6 +1283,12 @@ pub fn main() {
             enable_rpc_transaction_history: matches.is_present("enable_rpc_transaction_history"),
             enable_extended_tx_metadata_storage: matches.is_present("enable_cpi_and_log_storage")
                 || matches.is_present("enable_extended_tx_metadata_storage"),
+            enable_rpc_account_history: matches.is_present("enable_rpc_account_history"),
+            account_history_retention_slots: value_t_or_exit!(
+                matches,
+                "rpc_account_history_retention_slots",
+                u64
+            ),
             rpc_bigtable_config,
             faucet_addr: matches.value_of("rpc_faucet_addr").map(|address| {
                 solana_net_utils::parse_host_port(address).expect("failed to parse faucet address")
@@ -1149,13 +1309,25 @@ pub fn main() {
             rpc_niceness_adj: value_t_or_exit!(matches, "rpc_niceness_adj", i8),
             account_indexes: account_indexes.clone(),
             rpc_scan_and_fix_roots: matches.is_present("rpc_scan_and_fix_roots"),
+            enable_simulation_instruction_tracing: matches
+                .is_present("rpc_enable_simulation_instruction_tracing"),
+            enable_simulation_program_overrides: matches
+                .is_present("rpc_enable_simulation_program_overrides"),
             max_request_body_size: Some(value_t_or_exit!(
                 matches,
                 "rpc_max_request_body_size",
                 usize
             )),
+            grpc_config: value_t!(matches, "rpc_grpc_port", u16)
+                .ok()
+                .map(|grpc_port| RpcGrpcConfig {
+                    grpc_addr: SocketAddr::new(rpc_bind_address, grpc_port),
+                    worker_threads: value_t_or_exit!(matches, "rpc_grpc_threads", usize),
+                }),
+            rate_limit_config: rpc_rate_limit_config,
         },
         geyser_plugin_config_files,
+        geyser_plugin_always_enabled: matches.is_present("geyser_plugin_always_enabled"),
         rpc_addrs: value_t!(matches, "rpc_port", u16).ok().map(|rpc_port| {
             (
                 SocketAddr::new(rpc_bind_address, rpc_port),
@@ -1167,6 +1339,8 @@ pub fn main() {
         }),
         pubsub_config: PubSubConfig {
             enable_block_subscription: matches.is_present("rpc_pubsub_enable_block_subscription"),
+            enable_transaction_subscription: matches
+                .is_present("rpc_pubsub_enable_transaction_subscription"),
             enable_vote_subscription: matches.is_present("rpc_pubsub_enable_vote_subscription"),
             max_active_subscriptions: value_t_or_exit!(
                 matches,
@@ -1183,6 +1357,16 @@ pub fn main() {
                 "rpc_pubsub_queue_capacity_bytes",
                 usize
             ),
+            replay_capacity_items: value_t_or_exit!(
+                matches,
+                "rpc_pubsub_replay_capacity_items",
+                usize
+            ),
+            replay_capacity_bytes: value_t_or_exit!(
+                matches,
+                "rpc_pubsub_replay_capacity_bytes",
+                usize
+            ),
             worker_threads: value_t_or_exit!(matches, "rpc_pubsub_worker_threads", usize),
             notification_threads: if full_api {
                 value_of(&matches, "rpc_pubsub_notification_threads")
@@ -1414,6 +1598,7 @@ pub fn main() {
         maximum_incremental_snapshot_archives_to_retain,
         accounts_hash_debug_verify: validator_config.accounts_db_test_hash_calculation,
         packager_thread_niceness_adj: snapshot_packager_niceness_adj,
+        archive_compressed_storages: matches.is_present("snapshot_archive_compressed_storages"),
     };
 
     validator_config.accounts_hash_interval_slots =
@@ -1686,6 +1871,7 @@ pub fn main() {
             cluster_info: validator.cluster_info.clone(),
             vote_account,
             repair_whitelist,
+            geyser_plugin_manager_request_sender: validator.geyser_plugin_manager_request_sender(),
         });
 
     if let Some(filename) = init_complete_file {
@@ -1709,6 +1895,12 @@ fn process_account_indexes(matches: &ArgMatches) -> AccountSecondaryIndexes {
             "spl-token-owner" => AccountIndex::SplTokenOwner,
             _ => unreachable!(),
         })
+        .chain(
+            values_t!(matches, "account_index_data_offset", DataOffsetIndex)
+                .unwrap_or_default()
+                .into_iter()
+                .map(AccountIndex::DataOffset),
+        )
         .collect();
 
     let account_indexes_include_keys: HashSet<Pubkey> =